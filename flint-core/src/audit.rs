@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use crate::plugin::exec::eval::TestCaseOutput;
+
+/// How severe a vulnerability found by an audit plugin is, read from the severity of
+/// its finding. Severities are ordered from the most severe, which reports list first.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    High,
+    Moderate,
+    Low,
+    /// Tools like cargo audit report advisories without a severity
+    Unknown,
+}
+
+impl Severity {
+    /// Reads the severity a tool reported. Some tools call moderate severity "medium".
+    pub fn parse(severity: Option<&str>) -> Self {
+        match severity.map(str::to_lowercase).as_deref() {
+            Some("critical") => Severity::Critical,
+            Some("high") => Severity::High,
+            Some("moderate") | Some("medium") => Severity::Moderate,
+            Some("low") | Some("info") | Some("informational") => Severity::Low,
+            _ => Severity::Unknown,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::High => "high",
+            Severity::Moderate => "moderate",
+            Severity::Low => "low",
+            Severity::Unknown => "unknown",
+        }
+    }
+
+    /// The level of a lint finding as severe as the vulnerability: critical and high
+    /// vulnerabilities are errors, and a vulnerability of unknown severity is a warning
+    pub fn level(&self) -> &'static str {
+        match self {
+            Severity::Critical | Severity::High => "error",
+            Severity::Moderate | Severity::Unknown => "warning",
+            Severity::Low => "note",
+        }
+    }
+
+    /// The lowest CVSS score of the severity, which code scanning tools like GitHub's
+    /// rank security findings by
+    pub fn score(&self) -> Option<&'static str> {
+        match self {
+            Severity::Critical => Some("9.0"),
+            Severity::High => Some("7.0"),
+            Severity::Moderate => Some("4.0"),
+            Severity::Low => Some("0.1"),
+            Severity::Unknown => None,
+        }
+    }
+}
+
+/// A vulnerability found by one or more audit plugins
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vulnerability<'a> {
+    pub severity: Severity,
+    /// The advisory, like `GHSA-29mw-wpgm-hmr9` or `RUSTSEC-2021-0145`, if the plugin
+    /// reported one
+    pub advisory: Option<&'a str>,
+    /// The manifest or lockfile the vulnerable package is in
+    pub file: &'a str,
+    pub message: Option<&'a str>,
+    /// The plugins that found it
+    pub plugins: Vec<&'a str>,
+}
+
+/// Merges the findings of audit plugins, with the plugin that found them, into the
+/// vulnerabilities they found, ranked from the most severe. An advisory found in a file
+/// by several plugins is listed once, with the highest severity any of them reported.
+pub fn rank<'a>(
+    findings: impl IntoIterator<Item = (&'a str, &'a TestCaseOutput)>,
+) -> Vec<Vulnerability<'a>> {
+    let mut vulnerabilities: Vec<Vulnerability> = Vec::new();
+    for (plugin, finding) in findings {
+        if finding.success {
+            continue;
+        }
+        let severity = Severity::parse(finding.severity.as_deref());
+        let advisory = finding.rule_id.as_deref();
+
+        let known = vulnerabilities.iter_mut().find(|vulnerability| {
+            advisory.is_some()
+                && vulnerability.advisory == advisory
+                && vulnerability.file == finding.file_name
+        });
+        match known {
+            Some(vulnerability) => {
+                vulnerability.severity = vulnerability.severity.min(severity);
+                if !vulnerability.plugins.contains(&plugin) {
+                    vulnerability.plugins.push(plugin);
+                }
+            }
+            None => vulnerabilities.push(Vulnerability {
+                severity,
+                advisory,
+                file: &finding.file_name,
+                message: finding.error_message.as_deref(),
+                plugins: vec![plugin],
+            }),
+        }
+    }
+
+    vulnerabilities
+        .sort_by(|a, b| (a.severity, a.advisory, a.file).cmp(&(b.severity, b.advisory, b.file)));
+    vulnerabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(advisory: &str, severity: &str) -> TestCaseOutput {
+        TestCaseOutput {
+            file_name: "package-lock.json".to_string(),
+            line_no: None,
+            column_no: None,
+            success: false,
+            error_message: Some(format!("{} in lodash", advisory)),
+            rule_id: Some(advisory.to_string()),
+            severity: Some(severity.to_string()),
+            fixes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_severities() {
+        assert_eq!(Severity::parse(Some("CRITICAL")), Severity::Critical);
+        assert_eq!(Severity::parse(Some("medium")), Severity::Moderate);
+        assert_eq!(Severity::parse(None), Severity::Unknown);
+    }
+
+    #[test]
+    fn ranks_and_merges_vulnerabilities() {
+        let low = finding("GHSA-low", "low");
+        let high = finding("GHSA-high", "high");
+        let critical = finding("GHSA-high", "critical");
+        let unknown = finding("RUSTSEC-2021-0145", "");
+        let vulnerabilities = rank([
+            ("npm-audit", &low),
+            ("npm-audit", &high),
+            ("osv", &critical),
+            ("osv", &unknown),
+        ]);
+
+        let ranked = vulnerabilities
+            .iter()
+            .map(|vulnerability| (vulnerability.advisory.unwrap(), vulnerability.severity))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranked,
+            [
+                ("GHSA-high", Severity::Critical),
+                ("GHSA-low", Severity::Low),
+                ("RUSTSEC-2021-0145", Severity::Unknown),
+            ]
+        );
+        assert_eq!(vulnerabilities[0].plugins, ["npm-audit", "osv"]);
+    }
+}
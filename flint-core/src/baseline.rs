@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppResult;
+use crate::plugin::exec::eval::{PluginEvalOutput, TestCaseOutput};
+use crate::{get_flag, warn};
+
+/// The file the baseline is kept in, relative to the project root
+pub const BASELINE_FILE: &str = ".flint/baseline.json";
+
+pub fn baseline_file() -> PathBuf {
+    get_flag!(current_dir).join(BASELINE_FILE)
+}
+
+/// A lint finding in the baseline. Findings are matched without their line and
+/// column, so they stay suppressed when the code around them changes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BaselineFinding {
+    pub plugin: String,
+    /// The file, relative to the project root if it's in the project
+    pub file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl BaselineFinding {
+    pub fn new(plugin: &str, result: &TestCaseOutput) -> Self {
+        Self {
+            plugin: plugin.to_string(),
+            file: relative(&result.file_name),
+            rule: result.rule_id.clone(),
+            message: result.error_message.clone(),
+        }
+    }
+}
+
+/// The lint findings a project already had when it adopted flint, which runs leave
+/// out so only new findings fail them
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    /// Sorted, with a finding repeated as many times as it was found
+    pub findings: Vec<BaselineFinding>,
+}
+
+impl Baseline {
+    pub fn new(mut findings: Vec<BaselineFinding>) -> Self {
+        findings.sort();
+        Self { findings }
+    }
+
+    /// Loads the baseline of the project, if it has one. An invalid baseline is
+    /// ignored with a warning, so every finding is reported.
+    pub fn load() -> Option<Self> {
+        let contents = fs::read_to_string(baseline_file()).ok()?;
+        serde_json::from_str(&contents)
+            .inspect_err(|err| warn!("Ignoring {}: {}", BASELINE_FILE, err))
+            .ok()
+    }
+
+    pub fn save(&self) -> AppResult<()> {
+        let path = baseline_file();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).expect("the baseline is always valid JSON");
+        fs::write(path, json + "\n")?;
+        Ok(())
+    }
+
+    /// Removes the findings of the plugin that are in the baseline from its output,
+    /// returning how many were removed. A finding found more often than in the
+    /// baseline is kept for every extra time.
+    pub fn subtract(&self, plugin: &str, output: &mut PluginEvalOutput) -> usize {
+        let mut remaining = self
+            .findings
+            .iter()
+            .filter(|finding| finding.plugin == plugin)
+            .collect::<Vec<_>>();
+        let count = output.test_results.len();
+
+        output.test_results.retain(|result| {
+            if result.success {
+                return true;
+            }
+            let finding = BaselineFinding::new(plugin, result);
+            match remaining.iter().position(|known| **known == finding) {
+                Some(index) => {
+                    remaining.swap_remove(index);
+                    false
+                }
+                None => true,
+            }
+        });
+        count - output.test_results.len()
+    }
+}
+
+/// The path relative to the project root, so the baseline works wherever the project
+/// is checked out. Plugins like ESLint report absolute paths.
+fn relative(file: &str) -> String {
+    let current_dir = get_flag!(current_dir);
+    match Path::new(file).strip_prefix(current_dir) {
+        Ok(path) => path.to_string_lossy().into_owned(),
+        Err(_) => file.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(file: &str, line: u32, message: &str) -> TestCaseOutput {
+        TestCaseOutput {
+            file_name: file.to_string(),
+            line_no: Some(line),
+            column_no: None,
+            success: false,
+            error_message: Some(message.to_string()),
+            rule_id: Some("no-unused-vars".to_string()),
+            severity: Some("error".to_string()),
+            fixes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn subtracts_known_findings_only() {
+        let baseline = Baseline::new(vec![
+            BaselineFinding::new("eslint", &finding("a.js", 1, "'x' is unused")),
+            BaselineFinding::new("other", &finding("a.js", 2, "'y' is unused")),
+        ]);
+        let mut output = PluginEvalOutput {
+            tests_passed: 0,
+            total_tests: 1,
+            passing_percentage: 0.0,
+            test_results: vec![
+                // Moved since the baseline was created
+                finding("a.js", 5, "'x' is unused"),
+                finding("a.js", 9, "'x' is unused"),
+                finding("a.js", 6, "'y' is unused"),
+            ],
+        };
+
+        assert_eq!(baseline.subtract("eslint", &mut output), 1);
+        let lines = output
+            .test_results
+            .iter()
+            .map(|result| result.line_no)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [Some(9), Some(6)]);
+    }
+}
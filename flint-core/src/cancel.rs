@@ -0,0 +1,15 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once flint quits while plugins are running, so they stop instead of writing
+/// files after the terminal is restored
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Cancels the running plugins. Their Lua scripts fail at the next interrupt, and
+/// workers check it before writing files and starting plugins.
+pub fn cancel() {
+    CANCELLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::Relaxed)
+}
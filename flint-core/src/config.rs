@@ -0,0 +1,251 @@
+use crate::{
+    error::{AppResult, FlintError},
+    network::NetworkConfig,
+    plugin::{ci::CiConfig, remote_cache::CacheConfig},
+    workspace::WorkspaceConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use toml;
+use toml_edit::{DocumentMut, InlineTable, Item, Table};
+
+pub fn default_plugins_branch() -> String {
+    "main".into()
+}
+
+pub fn default_mouse() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FlintConfig {
+    pub version: u8,
+    #[serde(default = "default_plugins_branch")]
+    pub plugins_branch: String,
+    /// Key bindings overriding the defaults, by action name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub keys: HashMap<String, KeyBinding>,
+    /// Whether the TUI captures the mouse, for scrolling and clicking
+    #[serde(default = "default_mouse")]
+    pub mouse: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ThemeConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkConfig>,
+    /// How many logs the UI keeps, with older ones only in the log file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_logs: Option<usize>,
+    /// Which lint findings make test and lint runs exit with an error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on: Option<FailOn>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<FixConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheConfig>,
+    /// The locale messages are shown in, like "es", instead of the system's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Whether output is written for screen readers, like with `--accessible`
+    #[serde(default)]
+    pub accessible: bool,
+    /// The memory in megabytes the Lua VM of a plugin may use, unless its section sets
+    /// a `memory_limit` of its own. 0 is no limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit: Option<u64>,
+}
+
+/// How `flint fix` applies the fixes plugins return, in `[flint.fix]`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FixConfig {
+    /// Rules whose fixes aren't applied, like `"eslint:semi"`, or whole plugins,
+    /// like `"sqlfluff"`
+    #[serde(default)]
+    pub skip_rules: Vec<String>,
+}
+
+/// The lint findings that fail a run in `[flint] fail_on`: `"error"`, `"warning"`,
+/// which fails on errors too, or `"never"`. Failed plugins and tests always fail it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FailOn {
+    #[default]
+    Error,
+    Warning,
+    Never,
+}
+
+/// The sections of `[report]` configuring the reports flint writes itself, rather
+/// than report plugins
+pub const REPORT_FORMATS: [&str; 2] = ["html", "markdown"];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Config {
+    pub flint: FlintConfig,
+    pub common: HashMap<String, toml::Value>,
+    pub rules: HashMap<String, toml::Value>,
+    pub tests: HashMap<String, toml::Value>,
+    pub config: HashMap<String, toml::Value>,
+    #[serde(default)]
+    pub ci: CiConfig,
+    pub report: HashMap<String, toml::Value>,
+    /// The formatter plugins, run by `flint fmt`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fmt: HashMap<String, toml::Value>,
+    /// The audit plugins, which check the dependencies for known vulnerabilities
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub audit: HashMap<String, toml::Value>,
+    /// The members of a monorepo, or the members a member depends on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<WorkspaceConfig>,
+}
+
+impl Config {
+    /// The ids of the report plugins, leaving out the built-in report formats
+    pub fn report_plugin_ids(&self) -> Vec<&String> {
+        self.report
+            .keys()
+            .filter(|id| !REPORT_FORMATS.contains(&id.as_str()))
+            .collect()
+    }
+
+    /// The options of a report plugin in `[report.<id>]`
+    pub fn report_plugin_config(&self, id: &str) -> Option<&toml::Value> {
+        match REPORT_FORMATS.contains(&id) {
+            true => None,
+            false => self.report.get(id),
+        }
+    }
+
+    pub fn load(path: &PathBuf) -> AppResult<Self> {
+        let _span = tracing::info_span!("config", path = %path.display()).entered();
+        let parse = || -> AppResult<Self> {
+            let toml_str = std::fs::read_to_string(path)?;
+            Ok(toml::from_str(&toml_str)?)
+        };
+        parse().map_err(|source| FlintError::Config {
+            path: path.clone(),
+            source: Box::new(source),
+        })
+    }
+
+    pub fn create(path: PathBuf, config: Config) -> AppResult<()> {
+        let toml_str = toml::to_string(&config)?;
+        std::fs::write(path, toml_str)?;
+        Ok(())
+    }
+
+    /// Sets a value in the config file, keeping its comments and formatting. The keys
+    /// lead to the value, like `["rules", "eslint", "semi"]`. Missing tables are
+    /// created, with the first two keys as a section and the others as dotted keys.
+    pub fn set_value(path: &Path, keys: &[&str], value: &toml::Value) -> AppResult<()> {
+        let Some((last, tables)) = keys.split_last() else {
+            return Ok(());
+        };
+        let mut document = std::fs::read_to_string(path)?.parse::<DocumentMut>()?;
+
+        let mut item = document.as_item_mut();
+        for (depth, key) in tables.iter().enumerate() {
+            let missing = if item.is_inline_table() {
+                let mut table = InlineTable::new();
+                table.set_dotted(true);
+                Item::Value(table.into())
+            } else {
+                let mut table = Table::new();
+                table.set_implicit(depth == 0);
+                table.set_dotted(depth >= 2);
+                Item::Table(table)
+            };
+            item = item
+                .as_table_like_mut()
+                .ok_or_else(|| {
+                    FlintError::Err(format!("{} isn't a table", keys[..depth].join(".")))
+                })?
+                .entry(key)
+                .or_insert(missing);
+        }
+
+        let table = item
+            .as_table_like_mut()
+            .ok_or_else(|| FlintError::Err(format!("{} isn't a table", tables.join("."))))?;
+        let mut value = value.to_string().parse::<toml_edit::Value>()?;
+        match table.get_mut(last) {
+            // The comment after the old value is kept
+            Some(Item::Value(old)) => {
+                *value.decor_mut() = old.decor().clone();
+                *old = value;
+            }
+            Some(old) => *old = Item::Value(value),
+            None => {
+                table.insert(last, Item::Value(value));
+            }
+        }
+
+        std::fs::write(path, document.to_string())?;
+        Ok(())
+    }
+
+    pub fn create_default(path: PathBuf) -> AppResult<()> {
+        let config = Config {
+            flint: FlintConfig {
+                version: 1,
+                plugins_branch: "main".into(),
+                keys: HashMap::new(),
+                mouse: true,
+                theme: None,
+                network: None,
+                max_logs: None,
+                fail_on: None,
+                fix: None,
+                cache: None,
+                locale: None,
+                accessible: false,
+                memory_limit: None,
+            },
+            common: HashMap::new(),
+            rules: HashMap::new(),
+            tests: HashMap::new(),
+            config: HashMap::new(),
+            ci: CiConfig::default(),
+            report: HashMap::new(),
+            fmt: HashMap::new(),
+            audit: HashMap::new(),
+            workspace: None,
+        };
+        Self::create(path, config)
+    }
+}
+
+/// One or more key chords bound to an action in `[flint.keys]`, like
+/// `quit = "q"` or `quit = ["q", "ctrl+c"]`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl KeyBinding {
+    /// The chords bound, which frontends parse into their key events
+    pub fn chords(&self) -> Vec<&str> {
+        match self {
+            KeyBinding::Single(chord) => vec![chord.as_str()],
+            KeyBinding::Multiple(chords) => chords.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// The theme in `[flint.theme]`: a preset, with any of its colors overridden like
+/// `error = "light-red"` or `border = "#5f5f5f"`, and the symbols widgets are drawn
+/// with: `"auto"`, `"unicode"` or `"ascii"`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ThemeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbols: Option<String>,
+    #[serde(flatten)]
+    pub colors: HashMap<String, String>,
+}
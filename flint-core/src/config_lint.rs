@@ -0,0 +1,483 @@
+use std::fmt;
+use std::ops::Range;
+use toml_edit::{ImDocument, Item, Key, TableLike};
+
+use crate::plugin::ci::CiOptions;
+
+/// The tables flint reads, every other table is dead configuration
+const TABLES: [&str; 10] = [
+    "flint",
+    "common",
+    "rules",
+    "tests",
+    "config",
+    "ci",
+    "report",
+    "fmt",
+    "audit",
+    "workspace",
+];
+
+/// The tables with a section for every plugin of a kind, like `[rules.eslint]`. CI
+/// plugins have theirs in `[ci]`, next to the options of the pipeline.
+const PLUGIN_TABLES: [&str; 5] = ["rules", "tests", "fmt", "audit", "report"];
+
+/// Keys every plugin section can have, besides the options of the plugin
+pub const SECTION_KEYS: [&str; 2] = ["source", "memory_limit"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Configuration that may be missing, which doesn't fail `flint config lint`
+    Note,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Note => write!(f, "note"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Something in flint.toml that's never read, or shouldn't be
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    /// The line in flint.toml, from 1, for problems in the file
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// A section flint.toml can have: the section of an installed plugin, or of a report
+/// format flint writes itself
+#[derive(Debug, Clone)]
+pub struct Section {
+    /// The table of the section, like "rules"
+    pub table: String,
+    pub id: String,
+    /// The keys the section can have, with `.*` allowing every key in a table, like
+    /// `"rules.*"`. Sections of plugins that don't declare their options can have any.
+    pub options: Option<Vec<String>>,
+    /// Whether the section is of a plugin, which is expected to be configured
+    pub is_plugin: bool,
+}
+
+/// Finds the dead configuration in flint.toml: plugins configured but not installed,
+/// plugins installed but not configured, keys the plugins don't have, and keys other
+/// keys shadow. Duplicate keys make the file invalid, which is the only problem found
+/// then. Problems are ordered by their line, with the ones outside the file last.
+pub fn lint(contents: &str, sections: &[Section]) -> Vec<ConfigProblem> {
+    let document = match ImDocument::parse(contents) {
+        Ok(document) => document,
+        Err(err) => {
+            return vec![ConfigProblem {
+                line: err.span().map(|span| line(contents, span)),
+                severity: Severity::Error,
+                message: format!("flint.toml is invalid: {}", err.message()),
+            }]
+        }
+    };
+    let mut lint = Lint {
+        contents,
+        sections,
+        problems: Vec::new(),
+    };
+
+    let root = document.as_table();
+    for (table, _) in root.iter() {
+        if !TABLES.contains(&table) {
+            lint.problem(
+                lint.key_line(root, table),
+                Severity::Warning,
+                format!("[{}] isn't read by flint", table),
+            );
+        }
+    }
+
+    for table in PLUGIN_TABLES {
+        let Some(plugins) = root.get(table).and_then(Item::as_table_like) else {
+            continue;
+        };
+        for (id, section) in plugins.iter() {
+            lint.plugin_section(plugins, table, id, section, &[]);
+        }
+    }
+    lint.ci(root.get("ci").and_then(Item::as_table_like));
+
+    // Extra configs are only passed to lint plugins
+    if let Some(configs) = root.get("config").and_then(Item::as_table_like) {
+        let rules = root.get("rules").and_then(Item::as_table_like);
+        for (id, _) in configs.iter() {
+            if !rules.is_some_and(|rules| rules.contains_key(id)) {
+                lint.problem(
+                    lint.key_line(configs, id),
+                    Severity::Warning,
+                    format!(
+                        "[config.{}] is only read for the lint plugin in [rules.{}], which isn't configured",
+                        id, id
+                    ),
+                );
+            }
+        }
+    }
+
+    for section in sections.iter().filter(|section| section.is_plugin) {
+        let table = section.table.as_str();
+        if !PLUGIN_TABLES.contains(&table) {
+            continue;
+        }
+        let plugins = root.get(table).and_then(Item::as_table_like);
+        if !plugins.is_some_and(|plugins| plugins.contains_key(&section.id)) {
+            lint.problem(
+                None,
+                Severity::Note,
+                format!(
+                    "{} is installed, but not configured in [{}]",
+                    section.id, table
+                ),
+            );
+        }
+    }
+
+    let mut problems = lint.problems;
+    problems.sort_by_key(|problem| (problem.line.is_none(), problem.line));
+    problems
+}
+
+struct Lint<'a> {
+    contents: &'a str,
+    sections: &'a [Section],
+    problems: Vec<ConfigProblem>,
+}
+
+impl Lint<'_> {
+    fn problem(&mut self, line: Option<usize>, severity: Severity, message: String) {
+        self.problems.push(ConfigProblem {
+            line,
+            severity,
+            message,
+        });
+    }
+
+    /// The line of a key of the table, or of its value if the key has no position
+    fn key_line(&self, table: &dyn TableLike, key: &str) -> Option<usize> {
+        table
+            .key(key)
+            .and_then(Key::span)
+            .or_else(|| table.get(key).and_then(Item::span))
+            .map(|span| line(self.contents, span))
+    }
+
+    /// Checks that the section's plugin is installed and has its keys. CI plugins can
+    /// also have the options of the pipeline.
+    fn plugin_section(
+        &mut self,
+        plugins: &dyn TableLike,
+        table: &str,
+        id: &str,
+        section: &Item,
+        extra_options: &[String],
+    ) {
+        let installed = self
+            .sections
+            .iter()
+            .find(|installed| installed.table == table && installed.id == id);
+        let Some(installed) = installed else {
+            let line = self.key_line(plugins, id);
+            let other = self
+                .sections
+                .iter()
+                .find(|installed| installed.is_plugin && installed.id == id);
+            match other {
+                Some(other) => self.problem(
+                    line,
+                    Severity::Error,
+                    format!(
+                        "[{}.{}] is never read, as the section of {} is [{}.{}]",
+                        table, id, id, other.table, other.id
+                    ),
+                ),
+                None => self.problem(
+                    line,
+                    Severity::Warning,
+                    format!(
+                        "[{}.{}] configures {}, which isn't installed",
+                        table, id, id
+                    ),
+                ),
+            }
+            return;
+        };
+
+        let (Some(options), Some(section)) = (&installed.options, section.as_table_like()) else {
+            return;
+        };
+        let options = options
+            .iter()
+            .cloned()
+            .chain(extra_options.iter().cloned())
+            .chain(SECTION_KEYS.map(String::from))
+            .collect::<Vec<_>>();
+        self.unknown_keys(section, "", table, id, &options);
+    }
+
+    /// Reports the keys of the section that aren't options, descending into the tables
+    /// that options are in
+    fn unknown_keys(
+        &mut self,
+        section: &dyn TableLike,
+        prefix: &str,
+        table: &str,
+        id: &str,
+        options: &[String],
+    ) {
+        for (key, item) in section.iter() {
+            let path = match prefix.is_empty() {
+                true => key.to_string(),
+                false => format!("{}.{}", prefix, key),
+            };
+            if is_option(&path, options) {
+                continue;
+            }
+            let nested = format!("{}.", path);
+            match item.as_table_like() {
+                Some(nested_table) if options.iter().any(|option| option.starts_with(&nested)) => {
+                    self.unknown_keys(nested_table, &path, table, id, options)
+                }
+                _ => self.problem(
+                    self.key_line(section, key),
+                    Severity::Warning,
+                    format!("[{}.{}] {} isn't an option of {}", table, id, path, id),
+                ),
+            }
+        }
+    }
+
+    /// Checks `[ci]`: its provider, the sections of the CI plugins in it, and the
+    /// options of the pipeline, which the sections of the plugins shadow
+    fn ci(&mut self, ci: Option<&dyn TableLike>) {
+        let Some(ci) = ci else {
+            return;
+        };
+        let pipeline_options = match toml::Value::try_from(CiOptions::default()) {
+            Ok(toml::Value::Table(options)) => options.keys().cloned().collect(),
+            _ => Vec::new(),
+        };
+
+        let provider = ci.get("provider").and_then(Item::as_str);
+        if let Some(provider) = provider {
+            let is_installed = self
+                .sections
+                .iter()
+                .any(|section| section.table == "ci" && section.id == provider);
+            if !is_installed {
+                self.problem(
+                    self.key_line(ci, "provider"),
+                    Severity::Warning,
+                    format!(
+                        "[ci] provider is {}, which isn't an installed CI plugin",
+                        provider
+                    ),
+                );
+            }
+        }
+
+        let mut generating = Vec::new();
+        for (key, item) in ci.iter() {
+            if key == "provider" || pipeline_options.contains(&key.to_string()) {
+                continue;
+            }
+            if !item.is_table_like() {
+                self.problem(
+                    self.key_line(ci, key),
+                    Severity::Warning,
+                    format!("[ci] {} isn't an option of the pipeline", key),
+                );
+                continue;
+            }
+            if let Some(provider) = provider.filter(|provider| *provider != key) {
+                self.problem(
+                    self.key_line(ci, key),
+                    Severity::Warning,
+                    format!(
+                        "[ci.{}] is never read, as [ci] provider is {}",
+                        key, provider
+                    ),
+                );
+                continue;
+            }
+            self.plugin_section(ci, "ci", key, item, &pipeline_options);
+            generating.extend(item.as_table_like());
+        }
+
+        // An option of the pipeline is dead once every plugin generating it sets its own
+        for option in &pipeline_options {
+            let shadowed = !generating.is_empty()
+                && generating
+                    .iter()
+                    .all(|section| section.contains_key(option));
+            if ci.contains_key(option) && shadowed {
+                self.problem(
+                    self.key_line(ci, option),
+                    Severity::Warning,
+                    format!(
+                        "[ci] {} is shadowed by the {} of the CI plugin's section",
+                        option, option
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Whether the key, like `core.dialect`, is one of the options
+fn is_option(path: &str, options: &[String]) -> bool {
+    options.iter().any(|option| {
+        option == path
+            || option == "*"
+            || option.strip_suffix(".*").is_some_and(|table| {
+                path.strip_prefix(table)
+                    .is_some_and(|rest| rest.starts_with('.'))
+            })
+    })
+}
+
+/// The line of a position in the file, from 1
+fn line(contents: &str, span: Range<usize>) -> usize {
+    let start = span.start.min(contents.len());
+    contents[..start].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(table: &str, id: &str, options: Option<&[&str]>) -> Section {
+        Section {
+            table: table.to_string(),
+            id: id.to_string(),
+            options: options
+                .map(|options| options.iter().map(|option| option.to_string()).collect()),
+            is_plugin: true,
+        }
+    }
+
+    fn messages(problems: &[ConfigProblem]) -> Vec<(Option<usize>, Severity, &str)> {
+        problems
+            .iter()
+            .map(|problem| (problem.line, problem.severity, problem.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_plugins_configured_but_not_installed() {
+        let contents = "[rules.eslint]\n\n[rules.pylint]\n\n[tests.eslint]\n";
+        let sections = [section("rules", "eslint", None)];
+        assert_eq!(
+            messages(&lint(contents, &sections)),
+            [
+                (
+                    Some(3),
+                    Severity::Warning,
+                    "[rules.pylint] configures pylint, which isn't installed"
+                ),
+                (
+                    Some(5),
+                    Severity::Error,
+                    "[tests.eslint] is never read, as the section of eslint is [rules.eslint]"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn notes_plugins_installed_but_not_configured() {
+        let sections = [
+            section("rules", "eslint", None),
+            section("fmt", "prettier", None),
+        ];
+        assert_eq!(
+            messages(&lint("[rules.eslint]\n", &sections)),
+            [(
+                None,
+                Severity::Note,
+                "prettier is installed, but not configured in [fmt]"
+            )]
+        );
+    }
+
+    #[test]
+    fn finds_keys_that_arent_options() {
+        let contents = "[rules.sqlfluff]\ncore.dialect = \"postgres\"\ncore.dialekt = \"ansi\"\nrules.capitalisation.policy = \"upper\"\nsource = { git = \"https://example.com\" }\n";
+        let sections = [section(
+            "rules",
+            "sqlfluff",
+            Some(&["core.dialect", "rules.*"]),
+        )];
+        assert_eq!(
+            messages(&lint(contents, &sections)),
+            [(
+                Some(3),
+                Severity::Warning,
+                "[rules.sqlfluff] core.dialekt isn't an option of sqlfluff"
+            )]
+        );
+    }
+
+    #[test]
+    fn finds_shadowed_ci_options_and_sections() {
+        let contents = "[ci]\nprovider = \"github-actions\"\nbranches = [\"main\"]\n\n[ci.github-actions]\nbranches = [\"develop\"]\n\n[ci.gitlab-ci]\n";
+        let sections = [
+            section("ci", "github-actions", Some(&[])),
+            section("ci", "gitlab-ci", Some(&[])),
+        ];
+        assert_eq!(
+            messages(&lint(contents, &sections)),
+            [
+                (
+                    Some(3),
+                    Severity::Warning,
+                    "[ci] branches is shadowed by the branches of the CI plugin's section"
+                ),
+                (
+                    Some(8),
+                    Severity::Warning,
+                    "[ci.gitlab-ci] is never read, as [ci] provider is github-actions"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_duplicate_keys() {
+        let problems = lint("[rules.eslint]\nsemi = true\nsemi = false\n", &[]);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(3));
+        assert_eq!(problems[0].severity, Severity::Error);
+        assert!(
+            problems[0].message.contains("duplicate key"),
+            "{}",
+            problems[0].message
+        );
+    }
+
+    #[test]
+    fn finds_dead_tables() {
+        let contents = "[rule.eslint]\n\n[config.eslint]\nroot = \"src\"\n";
+        assert_eq!(
+            messages(&lint(contents, &[])),
+            [
+                (Some(1), Severity::Warning, "[rule] isn't read by flint"),
+                (
+                    Some(3),
+                    Severity::Warning,
+                    "[config.eslint] is only read for the lint plugin in [rules.eslint], which isn't configured"
+                ),
+            ]
+        );
+    }
+}
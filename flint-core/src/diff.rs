@@ -0,0 +1,323 @@
+use similar::{capture_diff_slices, Algorithm, DiffTag, TextDiff};
+use std::fmt;
+use std::ops::Range;
+
+/// Unchanged lines shown around the changes of a unified diff
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+/// A part of a line, emphasized if it's what changed in the line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub text: String,
+    pub emphasized: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffLine {
+    /// The number of the line in its text, starting at 1
+    pub number: usize,
+    pub kind: LineKind,
+    pub segments: Vec<Segment>,
+}
+
+impl DiffLine {
+    pub fn text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect()
+    }
+}
+
+/// A row of a side by side diff. Unchanged lines are on both sides, and a changed
+/// line is next to the line replacing it, if there is one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffRow {
+    pub old: Option<DiffLine>,
+    pub new: Option<DiffLine>,
+}
+
+impl DiffRow {
+    pub fn is_changed(&self) -> bool {
+        [&self.old, &self.new].iter().any(|line| {
+            line.as_ref()
+                .is_some_and(|line| line.kind != LineKind::Unchanged)
+        })
+    }
+}
+
+/// The lines of two texts, like a file and the config generated to replace it, laid
+/// out side by side. Lines that are replaced by similar ones have the tokens that
+/// changed emphasized, so a changed value stands out from the key it's set on.
+///
+/// It's displayed as a unified diff, for output without a UI.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diff {
+    pub rows: Vec<DiffRow>,
+}
+
+impl Diff {
+    pub fn new(old: &str, new: &str) -> Self {
+        let diff = TextDiff::from_lines(old, new);
+        let old_lines = diff.old_slices();
+        let new_lines = diff.new_slices();
+
+        let mut rows = Vec::new();
+        for op in diff.ops() {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            let len = old_range.len().max(new_range.len());
+            for offset in 0..len {
+                let old_index = (offset < old_range.len()).then_some(old_range.start + offset);
+                let new_index = (offset < new_range.len()).then_some(new_range.start + offset);
+                let old_text = old_index.map(|index| trim_line_ending(old_lines[index]));
+                let new_text = new_index.map(|index| trim_line_ending(new_lines[index]));
+
+                let (old_segments, new_segments) = match (tag, old_text, new_text) {
+                    (DiffTag::Replace, Some(old_text), Some(new_text)) => {
+                        inline_segments(old_text, new_text)
+                    }
+                    _ => (
+                        old_text.map(whole_line).unwrap_or_default(),
+                        new_text.map(whole_line).unwrap_or_default(),
+                    ),
+                };
+                let (old_kind, new_kind) = match tag {
+                    DiffTag::Equal => (LineKind::Unchanged, LineKind::Unchanged),
+                    _ => (LineKind::Removed, LineKind::Added),
+                };
+
+                rows.push(DiffRow {
+                    old: old_index.map(|index| DiffLine {
+                        number: index + 1,
+                        kind: old_kind,
+                        segments: old_segments,
+                    }),
+                    new: new_index.map(|index| DiffLine {
+                        number: index + 1,
+                        kind: new_kind,
+                        segments: new_segments,
+                    }),
+                });
+            }
+        }
+
+        Self { rows }
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.rows.iter().any(DiffRow::is_changed)
+    }
+
+    /// The number of added lines
+    pub fn added(&self) -> usize {
+        self.count(|row| &row.new, LineKind::Added)
+    }
+
+    /// The number of removed lines
+    pub fn removed(&self) -> usize {
+        self.count(|row| &row.old, LineKind::Removed)
+    }
+
+    fn count(&self, side: impl Fn(&DiffRow) -> &Option<DiffLine>, kind: LineKind) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| side(row).as_ref().is_some_and(|line| line.kind == kind))
+            .count()
+    }
+
+    /// The rows of the hunks of a unified diff: the changed rows, with the unchanged
+    /// rows around them. Hunks whose context overlaps are merged.
+    fn hunks(&self) -> Vec<Range<usize>> {
+        let mut hunks: Vec<Range<usize>> = Vec::new();
+        for (index, row) in self.rows.iter().enumerate() {
+            if !row.is_changed() {
+                continue;
+            }
+
+            let start = index.saturating_sub(CONTEXT_LINES);
+            let end = (index + 1 + CONTEXT_LINES).min(self.rows.len());
+            match hunks.last_mut() {
+                Some(hunk) if hunk.end >= start => hunk.end = end,
+                _ => hunks.push(start..end),
+            }
+        }
+        hunks
+    }
+}
+
+impl fmt::Display for Diff {
+    /// Writes the changes as the hunks of a unified diff, like `git diff` does
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for hunk in self.hunks() {
+            let rows = &self.rows[hunk];
+            let old_lines = rows.iter().filter_map(|row| row.old.as_ref());
+            let new_lines = rows.iter().filter_map(|row| row.new.as_ref());
+            writeln!(
+                f,
+                "@@ -{},{} +{},{} @@",
+                old_lines.clone().next().map_or(0, |line| line.number),
+                old_lines.count(),
+                new_lines.clone().next().map_or(0, |line| line.number),
+                new_lines.count(),
+            )?;
+
+            // The removed lines of a run of changes come before the added ones
+            let mut index = 0;
+            while index < rows.len() {
+                if !rows[index].is_changed() {
+                    if let Some(line) = &rows[index].old {
+                        writeln!(f, " {}", line.text())?;
+                    }
+                    index += 1;
+                    continue;
+                }
+
+                let end = rows[index..]
+                    .iter()
+                    .position(|row| !row.is_changed())
+                    .map_or(rows.len(), |offset| index + offset);
+                for line in rows[index..end].iter().filter_map(|row| row.old.as_ref()) {
+                    writeln!(f, "-{}", line.text())?;
+                }
+                for line in rows[index..end].iter().filter_map(|row| row.new.as_ref()) {
+                    writeln!(f, "+{}", line.text())?;
+                }
+                index = end;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn trim_line_ending(line: &str) -> &str {
+    line.strip_suffix('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .unwrap_or(line)
+}
+
+fn whole_line(text: &str) -> Vec<Segment> {
+    vec![Segment {
+        text: text.to_string(),
+        emphasized: false,
+    }]
+}
+
+/// Splits a line replaced by another one into the tokens both lines share and the
+/// ones that changed
+fn inline_segments(old: &str, new: &str) -> (Vec<Segment>, Vec<Segment>) {
+    let old_tokens = tokens(old);
+    let new_tokens = tokens(new);
+    let mut old_segments = Vec::new();
+    let mut new_segments = Vec::new();
+
+    for op in capture_diff_slices(Algorithm::Myers, &old_tokens, &new_tokens) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        let emphasized = tag != DiffTag::Equal;
+        push_segment(
+            &mut old_segments,
+            old_tokens[old_range].concat(),
+            emphasized,
+        );
+        push_segment(
+            &mut new_segments,
+            new_tokens[new_range].concat(),
+            emphasized,
+        );
+    }
+
+    (old_segments, new_segments)
+}
+
+/// Adds text to the segments, extending the last one if it's emphasized the same way
+fn push_segment(segments: &mut Vec<Segment>, text: String, emphasized: bool) {
+    if text.is_empty() {
+        return;
+    }
+    match segments.last_mut() {
+        Some(last) if last.emphasized == emphasized => last.text.push_str(&text),
+        _ => segments.push(Segment { text, emphasized }),
+    }
+}
+
+/// Splits a line into words, runs of whitespace and single punctuation characters,
+/// so a changed value or operator is emphasized without the rest of the line
+fn tokens(line: &str) -> Vec<&str> {
+    fn class(char: char) -> u8 {
+        match char {
+            char if char.is_alphanumeric() || char == '_' => 0,
+            char if char.is_whitespace() => 1,
+            _ => 2,
+        }
+    }
+
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, char)) = chars.next() {
+        let mut end = start + char.len_utf8();
+        if class(char) != 2 {
+            while let Some((index, next)) = chars.next_if(|(_, next)| class(*next) == class(char)) {
+                end = index + next.len_utf8();
+            }
+        }
+        tokens.push(&line[start..end]);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emphasized(line: &Option<DiffLine>) -> Vec<&str> {
+        line.iter()
+            .flat_map(|line| &line.segments)
+            .filter(|segment| segment.emphasized)
+            .map(|segment| segment.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn pairs_replaced_lines() {
+        let diff = Diff::new("a = 1\nb = 2\nc = 3\n", "a = 1\nb = 20\nc = 3\nd = 4\n");
+        assert_eq!(diff.rows.len(), 4);
+        assert_eq!((diff.added(), diff.removed()), (2, 1));
+
+        let replaced = &diff.rows[1];
+        assert_eq!(emphasized(&replaced.old), ["2"]);
+        assert_eq!(emphasized(&replaced.new), ["20"]);
+        assert!(diff.rows[3].old.is_none());
+        assert_eq!(
+            diff.rows[3].new.as_ref().map(DiffLine::text),
+            Some("d = 4".into())
+        );
+    }
+
+    #[test]
+    fn has_no_changes_for_the_same_text() {
+        let diff = Diff::new("a\r\nb\r\n", "a\r\nb\r\n");
+        assert!(!diff.has_changes());
+        assert_eq!(diff.to_string(), "");
+        assert_eq!(
+            diff.rows[0].old.as_ref().map(DiffLine::text),
+            Some("a".into())
+        );
+    }
+
+    #[test]
+    fn displays_unified_hunks() {
+        let old = (1..=10).map(|n| format!("{n}\n")).collect::<String>();
+        let new = old.replace("2\n", "two\n").replace("9\n", "");
+        let diff = Diff::new(&old, &new);
+        assert_eq!(
+            diff.to_string(),
+            "@@ -1,10 +1,9 @@\n 1\n-2\n+two\n 3\n 4\n 5\n 6\n 7\n 8\n-9\n 10\n"
+        );
+    }
+}
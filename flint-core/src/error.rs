@@ -0,0 +1,237 @@
+use std::error::Error as ErrorTrait;
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::t;
+
+/// The errors of flint. Config and plugin errors wrap the error that caused them,
+/// which [`FlintError::chain`] lists, and most kinds suggest a way to fix them.
+#[derive(Error, Debug)]
+pub enum FlintError {
+    #[error("Invalid config {}: {source}", path.display())]
+    Config {
+        path: PathBuf,
+        #[source]
+        source: Box<FlintError>,
+    },
+
+    #[error("Unable to load plugin {plugin}: {source}")]
+    PluginLoad {
+        plugin: String,
+        #[source]
+        source: Box<FlintError>,
+    },
+
+    #[error("Plugin {plugin} failed: {source}")]
+    PluginRuntime {
+        plugin: String,
+        #[source]
+        source: Box<FlintError>,
+    },
+
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("Deserialization error: {0}")]
+    Deserialization(#[from] toml::de::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] toml::ser::Error),
+
+    #[error("Config edit error: {0}")]
+    ConfigEdit(#[from] toml_edit::TomlError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Application error: {0}")]
+    Err(String),
+
+    #[error("User requested exit")]
+    Exit,
+
+    #[error("Lua error: {0}")]
+    LuaError(mlua::Error),
+
+    #[error("The plugin used more memory than its limit, which memory_limit in its section of flint.toml raises")]
+    MemoryLimit,
+}
+
+impl From<mlua::Error> for FlintError {
+    fn from(error: mlua::Error) -> Self {
+        match is_memory_error(&error) {
+            true => FlintError::MemoryLimit,
+            false => FlintError::LuaError(error),
+        }
+    }
+}
+
+/// Whether the script ran out of memory, which helpers calling back into Lua report
+/// as the cause of their error
+fn is_memory_error(error: &mlua::Error) -> bool {
+    match error {
+        mlua::Error::MemoryError(_) => true,
+        mlua::Error::CallbackError { cause, .. } => is_memory_error(cause),
+        _ => false,
+    }
+}
+
+impl FlintError {
+    /// Attributes an error to the plugin that was running. Errors that already name a
+    /// plugin, like ones loading its scripts, are kept.
+    pub fn in_plugin(self, plugin: &str) -> Self {
+        match self {
+            FlintError::PluginLoad { .. } | FlintError::PluginRuntime { .. } | FlintError::Exit => {
+                self
+            }
+            error => FlintError::PluginRuntime {
+                plugin: plugin.to_string(),
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// What went wrong, from the outermost error to the one that caused it, like
+    /// ["Plugin eslint failed", "Lua error: ..."]
+    pub fn chain(&self) -> Vec<String> {
+        let (context, source) = match self {
+            FlintError::Config { path, source } => {
+                (format!("Invalid config {}", path.display()), source)
+            }
+            FlintError::PluginLoad { plugin, source } => {
+                (format!("Unable to load plugin {}", plugin), source)
+            }
+            FlintError::PluginRuntime { plugin, source } => {
+                (format!("Plugin {} failed", plugin), source)
+            }
+            error => return vec![error.to_string()],
+        };
+        let mut chain = vec![context];
+        chain.extend(source.chain());
+        chain
+    }
+
+    /// How the error may be fixed, if there's a usual way
+    pub fn suggestion(&self) -> Option<String> {
+        let suggestion = match self {
+            FlintError::Config { path, .. } => t!(
+                "Fix {path}, flint config lint reports mistakes in it",
+                path = path.display()
+            ),
+            FlintError::PluginLoad { .. } => {
+                t!("Reinstall the plugins with flint update").to_string()
+            }
+            FlintError::PluginRuntime { plugin, source } => match source.as_ref() {
+                FlintError::LuaError(_) => t!(
+                    "Try out the scripts of {plugin} with flint repl --plugin {plugin}",
+                    plugin = plugin
+                ),
+                source => return source.suggestion(),
+            },
+            FlintError::Network(_) => {
+                t!("Check the connection, or run with --offline to skip downloads").to_string()
+            }
+            FlintError::Deserialization(_) => t!("Fix the syntax of the TOML file").to_string(),
+            FlintError::Io(error) => match error.kind() {
+                io::ErrorKind::NotFound => {
+                    t!("Check that the file exists, flint init creates flint.toml").to_string()
+                }
+                io::ErrorKind::PermissionDenied => {
+                    t!("Check that flint may read and write the files of the project").to_string()
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(suggestion)
+    }
+
+    /// The error chain and the suggestion as text, to print or to copy into an issue
+    pub fn details(&self) -> String {
+        let mut details = String::new();
+        for (depth, error) in self.chain().iter().enumerate() {
+            match depth {
+                0 => details += error,
+                _ => details += &format!("\n  {} {}", t!("caused by:"), error),
+            }
+        }
+        if let Some(suggestion) = self.suggestion() {
+            details += &format!("\n{} {}", t!("Suggestion:"), suggestion);
+        }
+        details
+    }
+}
+
+// Convert Box<dyn Error> to FlintError using a catch-all approach
+impl From<Box<dyn ErrorTrait>> for FlintError {
+    fn from(error: Box<dyn ErrorTrait>) -> Self {
+        // Try to downcast to FlintError first
+        let str_err = error.to_string();
+        if let Ok(app_error) = error.downcast::<FlintError>() {
+            *app_error
+        } else {
+            FlintError::Err(str_err)
+        }
+    }
+}
+
+// Create type alias for Result with FlintError as default error type
+pub type AppResult<T> = Result<T, FlintError>;
+
+// Macro to create FlintError::Err with format string
+#[macro_export]
+macro_rules! app_err {
+    ($($arg:tt)*) => {{
+        let error_msg = format!($($arg)*);
+        $crate::error!("{}", error_msg);
+        $crate::error::FlintError::Err(error_msg)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chains_the_causes_of_plugin_errors() {
+        let error = FlintError::Err("bad output".to_string()).in_plugin("eslint");
+        assert_eq!(
+            error.to_string(),
+            "Plugin eslint failed: Application error: bad output"
+        );
+        assert_eq!(
+            error.chain(),
+            vec!["Plugin eslint failed", "Application error: bad output"]
+        );
+
+        // Errors loading the plugin aren't attributed twice
+        let error = FlintError::PluginLoad {
+            plugin: "jest".to_string(),
+            source: Box::new(FlintError::Err("syntax".to_string())),
+        }
+        .in_plugin("jest");
+        assert_eq!(error.chain().len(), 2);
+    }
+
+    #[test]
+    fn suggests_fixes_of_the_cause() {
+        let error = FlintError::from(mlua::Error::runtime("attempt to call nil")).in_plugin("jest");
+        assert!(error
+            .suggestion()
+            .unwrap()
+            .contains("flint repl --plugin jest"));
+        let error = FlintError::Network("timed out".to_string()).in_plugin("jest");
+        assert!(error.suggestion().unwrap().contains("--offline"));
+        assert_eq!(FlintError::Exit.suggestion(), None);
+        assert_eq!(
+            FlintError::Config {
+                path: PathBuf::from("flint.toml"),
+                source: Box::new(FlintError::Err("missing".to_string())),
+            }
+            .details(),
+            "Invalid config flint.toml\n  caused by: Application error: missing\nSuggestion: \
+             Fix flint.toml, flint config lint reports mistakes in it"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::logs::{emit, push_log, LogKind};
+use crate::plugin::{
+    exec::eval::PluginEvalOutput, helpers::tmp::TempScope, tools::ToolProblem, Plugin, PluginKind,
+};
+
+/// The task a worker runs a plugin for
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Task {
+    Generate,
+    Test,
+}
+
+/// Events sent from worker threads to the UI. Workers don't touch any UI state,
+/// the App drains the channel once per frame and hands the events to its views.
+#[derive(Clone, Debug)]
+pub enum WorkerEvent {
+    /// A log line, added to the logs shown in every view
+    Log(LogKind, String),
+    /// A plugin started running
+    PluginStarted {
+        task: Task,
+        plugin: String,
+        kind: PluginKind,
+    },
+    /// A plugin wrote a file, like a generated config or a report
+    FileWritten {
+        task: Task,
+        plugin: String,
+        path: PathBuf,
+    },
+    /// A plugin reported a problem while running
+    Diagnostic {
+        task: Task,
+        plugin: String,
+        kind: LogKind,
+        message: String,
+    },
+    /// A tool the plugin runs is missing or has another version than it needs
+    ToolProblem {
+        task: Task,
+        plugin: String,
+        problem: ToolProblem,
+    },
+    /// The output of a lint or test plugin was evaluated into results
+    Evaluated {
+        task: Task,
+        plugin: String,
+        kind: PluginKind,
+        output: PluginEvalOutput,
+    },
+    /// A plugin finished running
+    Finished {
+        task: Task,
+        plugin: String,
+        success: bool,
+        /// How long the plugin ran, measured by the worker
+        duration: Duration,
+    },
+}
+
+static SENDER: OnceLock<Sender<WorkerEvent>> = OnceLock::new();
+
+/// Opens the channel that worker events are sent to. Until it's opened, events
+/// are applied on the thread that sends them, like when running non-interactively.
+pub fn open_channel() -> Receiver<WorkerEvent> {
+    let (sender, receiver) = mpsc::channel();
+    SENDER
+        .set(sender)
+        .expect("Worker event channel is already open");
+    receiver
+}
+
+pub fn send_event(event: WorkerEvent) {
+    match SENDER.get() {
+        Some(sender) => {
+            // The receiver is only dropped once the App exits
+            let _ = sender.send(event);
+        }
+        None => {
+            if let WorkerEvent::Log(kind, log) = event {
+                push_log(kind, log);
+            }
+        }
+    }
+}
+
+/// Sends the events of a plugin run by a worker. `PluginStarted` is sent when the
+/// worker is created and `Finished` when it's dropped, so early returns still finish it.
+pub struct Worker {
+    task: Task,
+    plugin: String,
+    has_errors: Cell<bool>,
+    started: Instant,
+    /// The temp paths the plugin creates are kept until it finished running
+    _temp: TempScope,
+}
+
+impl Worker {
+    pub fn new(task: Task, plugin: &Plugin) -> Self {
+        send_event(WorkerEvent::PluginStarted {
+            task,
+            plugin: plugin.details.id.clone(),
+            kind: plugin.kind.clone(),
+        });
+
+        Self {
+            task,
+            plugin: plugin.details.id.clone(),
+            has_errors: Cell::new(false),
+            started: Instant::now(),
+            _temp: TempScope::open(),
+        }
+    }
+
+    pub fn file_written(&self, path: PathBuf) {
+        send_event(WorkerEvent::FileWritten {
+            task: self.task,
+            plugin: self.plugin.clone(),
+            path,
+        });
+    }
+
+    pub fn evaluated(&self, kind: PluginKind, output: &PluginEvalOutput) {
+        send_event(WorkerEvent::Evaluated {
+            task: self.task,
+            plugin: self.plugin.clone(),
+            kind,
+            output: output.clone(),
+        });
+    }
+
+    /// Reports a problem with a tool of the plugin as a diagnostic, and to the views
+    /// listing the tools to install
+    pub fn tool_problem(&self, kind: LogKind, problem: ToolProblem) {
+        self.diagnostic(kind, problem.to_string());
+        send_event(WorkerEvent::ToolProblem {
+            task: self.task,
+            plugin: self.plugin.clone(),
+            problem,
+        });
+    }
+
+    /// Logs the message and reports it as a diagnostic of the plugin. The plugin
+    /// counts as failed if any error is reported.
+    pub fn diagnostic(&self, kind: LogKind, message: String) {
+        if let LogKind::Error = kind {
+            self.has_errors.set(true);
+        }
+
+        emit(kind, message.clone());
+        send_event(WorkerEvent::Diagnostic {
+            task: self.task,
+            plugin: self.plugin.clone(),
+            kind,
+            message,
+        });
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // A plugin that panicked failed, even if it reported no errors
+        send_event(WorkerEvent::Finished {
+            task: self.task,
+            plugin: self.plugin.clone(),
+            success: !self.has_errors.get() && !thread::panicking(),
+            duration: self.started.elapsed(),
+        });
+    }
+}
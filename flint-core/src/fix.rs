@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::diff::Diff;
+use crate::error::{AppResult, FlintError};
+use crate::get_flag;
+use crate::plugin::exec::eval::TestCaseOutput;
+
+/// The suffix of the files fixes are written to before they replace the originals
+const TEMP_SUFFIX: &str = ".flint-fix";
+
+/// An edit a plugin returns to fix a finding, replacing a range of a file. Lines and
+/// columns start at 1, columns count characters, and the end is exclusive.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FixEdit {
+    /// The file to edit, if it isn't the file of the finding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+    pub replacement: String,
+}
+
+/// The fixes of a file, with its contents once they're applied
+#[derive(Clone, Debug)]
+pub struct FileFix {
+    pub path: PathBuf,
+    /// The file as it was read, to check it didn't change before the fixes are applied
+    pub original: String,
+    pub fixed: String,
+    pub applied: usize,
+    /// Edits overlapping one applied before them, or outside the file, which a later
+    /// run can fix
+    pub skipped: usize,
+}
+
+impl FileFix {
+    /// The path relative to the project root, for showing it
+    pub fn name(&self) -> String {
+        let current_dir = get_flag!(current_dir);
+        let path = self.path.strip_prefix(current_dir).unwrap_or(&self.path);
+        path.to_string_lossy().into_owned()
+    }
+
+    pub fn diff(&self) -> Diff {
+        Diff::new(&self.original, &self.fixed)
+    }
+}
+
+/// The fixes of a run's findings, by file
+#[derive(Clone, Debug, Default)]
+pub struct FixPlan {
+    pub files: Vec<FileFix>,
+}
+
+impl FixPlan {
+    /// Plans the fixes of the findings, with the plugin that found them. The fixes of
+    /// a rule in `skip_rules`, like `eslint:semi`, or of a plugin in it are left out.
+    pub fn new<'a>(
+        findings: impl IntoIterator<Item = (&'a str, &'a TestCaseOutput)>,
+        skip_rules: &[String],
+    ) -> Self {
+        let current_dir = get_flag!(current_dir).clone();
+        let mut edits: BTreeMap<PathBuf, Vec<&FixEdit>> = BTreeMap::new();
+        for (plugin, finding) in findings {
+            if is_skipped(plugin, finding.rule_id.as_deref(), skip_rules) {
+                continue;
+            }
+            for edit in &finding.fixes {
+                let file = edit.file_name.as_ref().unwrap_or(&finding.file_name);
+                edits.entry(current_dir.join(file)).or_default().push(edit);
+            }
+        }
+
+        let files = edits
+            .into_iter()
+            .filter_map(|(path, edits)| {
+                let original = fs::read_to_string(&path).ok()?;
+                let (fixed, applied) = apply_edits(&original, edits.clone());
+                Some(FileFix {
+                    path,
+                    fixed,
+                    applied,
+                    skipped: edits.len() - applied,
+                    original,
+                })
+            })
+            .filter(|file| file.fixed != file.original)
+            .collect();
+        Self { files }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The number of edits that will be applied
+    pub fn fixes(&self) -> usize {
+        self.files.iter().map(|file| file.applied).sum()
+    }
+
+    /// Writes every fixed file. The files are written next to the originals first,
+    /// with their permissions, and the originals are only replaced once all of them
+    /// were written, so none are fixed if writing one fails. If replacing one fails,
+    /// the ones replaced before it stay fixed, and the error names them.
+    pub fn apply(&self) -> AppResult<()> {
+        for file in &self.files {
+            if fs::read_to_string(&file.path)? != file.original {
+                return Err(FlintError::Err(format!(
+                    "{} changed since it was linted, lint it again to fix it",
+                    file.name()
+                )));
+            }
+        }
+
+        let mut written: Vec<PathBuf> = Vec::new();
+        for file in &self.files {
+            let temp_path = temp_path(&file.path);
+            if let Err(err) = write_temp(file, &temp_path) {
+                for path in written.iter().chain([&temp_path]) {
+                    let _ = fs::remove_file(path);
+                }
+                return Err(err.into());
+            }
+            written.push(temp_path);
+        }
+
+        for (index, (file, temp_path)) in self.files.iter().zip(&written).enumerate() {
+            if let Err(err) = fs::rename(temp_path, &file.path) {
+                for path in &written[index..] {
+                    let _ = fs::remove_file(path);
+                }
+                let applied = self.files[..index]
+                    .iter()
+                    .map(FileFix::name)
+                    .collect::<Vec<_>>();
+                let applied = match applied.is_empty() {
+                    true => "no files were fixed".to_string(),
+                    false => format!("only {} were fixed", applied.join(", ")),
+                };
+                return Err(FlintError::Err(format!(
+                    "Couldn't replace {}: {}, {}",
+                    file.name(),
+                    err,
+                    applied
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(TEMP_SUFFIX);
+    PathBuf::from(temp_path)
+}
+
+/// Writes the fixed file to its temp path, with the permissions of the original so
+/// replacing it keeps them, like an executable script staying executable
+fn write_temp(file: &FileFix, temp_path: &Path) -> std::io::Result<()> {
+    fs::write(temp_path, &file.fixed)?;
+    fs::set_permissions(temp_path, fs::metadata(&file.path)?.permissions())
+}
+
+fn is_skipped(plugin: &str, rule: Option<&str>, skip_rules: &[String]) -> bool {
+    skip_rules
+        .iter()
+        .any(|skipped| match skipped.split_once(':') {
+            Some((skipped_plugin, skipped_rule)) => {
+                skipped_plugin == plugin && Some(skipped_rule) == rule
+            }
+            None => skipped == plugin,
+        })
+}
+
+/// Applies the edits to the contents, returning the fixed contents and how many
+/// edits were applied. Edits are applied from the start of the file, skipping any
+/// overlapping an edit before it.
+pub fn apply_edits(contents: &str, edits: Vec<&FixEdit>) -> (String, usize) {
+    let mut ranges = edits
+        .into_iter()
+        .filter_map(|edit| {
+            let start = offset(contents, edit.start_line, edit.start_column)?;
+            let end = offset(contents, edit.end_line, edit.end_column)?;
+            (start <= end).then_some((start, end, edit.replacement.as_str()))
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|(start, end, _)| (*start, *end));
+
+    let mut fixed = String::with_capacity(contents.len());
+    let mut position = 0;
+    let mut applied = 0;
+    for (start, end, replacement) in ranges {
+        if start < position {
+            continue;
+        }
+        fixed.push_str(&contents[position..start]);
+        fixed.push_str(replacement);
+        position = end;
+        applied += 1;
+    }
+    fixed.push_str(&contents[position..]);
+    (fixed, applied)
+}
+
+/// The byte offset of a line and column, if they're in the contents. The column
+/// after the last character of a line is its end.
+fn offset(contents: &str, line: u32, column: u32) -> Option<usize> {
+    let line_start = match line {
+        0 => return None,
+        1 => 0,
+        _ => contents
+            .match_indices('\n')
+            .nth(line as usize - 2)
+            .map(|(index, _)| index + 1)?,
+    };
+    let line_text = contents[line_start..]
+        .split('\n')
+        .next()
+        .unwrap_or_default();
+    let column = (column as usize).checked_sub(1)?;
+    match line_text.char_indices().nth(column) {
+        Some((index, _)) => Some(line_start + index),
+        None if column == line_text.chars().count() => Some(line_start + line_text.len()),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(start: (u32, u32), end: (u32, u32), replacement: &str) -> FixEdit {
+        FixEdit {
+            file_name: None,
+            start_line: start.0,
+            start_column: start.1,
+            end_line: end.0,
+            end_column: end.1,
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn applies_edits_in_order() {
+        let contents = "let a = 1\nlet b = 2\n";
+        let semicolon_a = edit((1, 10), (1, 10), ";");
+        let semicolon_b = edit((2, 10), (2, 10), ";");
+        let rename = edit((2, 5), (2, 6), "c");
+        let (fixed, applied) = apply_edits(contents, vec![&semicolon_b, &semicolon_a, &rename]);
+        assert_eq!(fixed, "let a = 1;\nlet c = 2;\n");
+        assert_eq!(applied, 3);
+    }
+
+    #[test]
+    fn skips_overlapping_edits() {
+        let contents = "const x = 1;\n";
+        let first = edit((1, 1), (1, 6), "let");
+        let overlapping = edit((1, 3), (1, 8), "y");
+        let outside = edit((5, 1), (5, 2), "z");
+        let (fixed, applied) = apply_edits(contents, vec![&overlapping, &first, &outside]);
+        assert_eq!(fixed, "let x = 1;\n");
+        assert_eq!(applied, 1);
+    }
+
+    #[test]
+    fn skips_rules_and_plugins() {
+        let skip_rules = ["eslint:semi".to_string(), "sqlfluff".to_string()];
+        assert!(is_skipped("eslint", Some("semi"), &skip_rules));
+        assert!(!is_skipped("eslint", Some("quotes"), &skip_rules));
+        assert!(is_skipped("sqlfluff", Some("LT01"), &skip_rules));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_permissions_of_fixed_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("flint-fix-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("script.sh");
+        fs::write(&path, "echo hi").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let plan = FixPlan {
+            files: vec![FileFix {
+                path: path.clone(),
+                original: "echo hi".to_string(),
+                fixed: "echo hi;".to_string(),
+                applied: 1,
+                skipped: 0,
+            }],
+        };
+        plan.apply().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "echo hi;");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
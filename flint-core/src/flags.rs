@@ -0,0 +1,67 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{LazyLock, RwLock},
+};
+
+pub struct Flags {
+    pub non_interactive: bool,
+    pub plugins_dir: PathBuf,
+    pub config_path: PathBuf,
+    pub current_dir: PathBuf,
+    pub no_install: bool,
+    /// The files changed in the branch, relative to the project root, when only
+    /// they should be checked
+    pub changed_files: Option<Vec<PathBuf>>,
+    /// The tests affected by the changed files, by the id of the test plugin running
+    /// them. Plugins without an entry run their whole suite.
+    pub affected_tests: HashMap<String, Vec<String>>,
+    /// Whether output is colored, from `--color` and `NO_COLOR`
+    pub color: bool,
+    /// Whether logs are printed to stderr, keeping stdout for the lint findings
+    pub logs_on_stderr: bool,
+    /// Whether flint runs without network access, only using the installed plugins.
+    /// It's also set once a download finds the network unreachable.
+    pub offline: bool,
+    /// Whether output is written for screen readers, as linear text without drawings,
+    /// from `--accessible` or `[flint] accessible`
+    pub accessible: bool,
+}
+
+// Create a static global instance with RwLock
+pub static GLOBAL_FLAGS: LazyLock<RwLock<Flags>> = LazyLock::new(|| {
+    RwLock::new(Flags {
+        non_interactive: false,
+        plugins_dir: crate::plugin::dir(),
+        config_path: std::env::current_dir().unwrap().join("flint.toml"),
+        current_dir: std::env::current_dir().unwrap(),
+        no_install: false,
+        changed_files: None,
+        affected_tests: HashMap::new(),
+        color: true,
+        logs_on_stderr: false,
+        offline: false,
+        accessible: false,
+    })
+});
+
+#[macro_export]
+macro_rules! get_flag {
+    ($name:ident) => {{
+        use $crate::flags::GLOBAL_FLAGS;
+
+        // Access the flag value through the RwLock read guard
+        &GLOBAL_FLAGS.read().unwrap().$name.clone()
+    }};
+}
+
+#[macro_export]
+macro_rules! set_flag {
+    ($name:ident, $value:expr) => {{
+        use $crate::flags::GLOBAL_FLAGS;
+
+        // Acquire a write lock to safely modify the flag
+        let mut flags = GLOBAL_FLAGS.write().unwrap();
+        flags.$name = $value;
+    }};
+}
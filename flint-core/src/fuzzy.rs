@@ -0,0 +1,80 @@
+/// An item matching a fuzzy query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// The index of the item in the items searched
+    pub index: usize,
+    /// How well the item matches, lower being better
+    pub score: usize,
+    /// The indices of the characters of the item that matched the query, to highlight them
+    pub positions: Vec<usize>,
+}
+
+/// Returns the items matching the query, best matches first. An item matches if it
+/// contains the characters of the query in order, ignoring case. Every item matches
+/// an empty query, in the order they're given.
+pub fn fuzzy_matches(query: &str, items: &[impl AsRef<str>]) -> Vec<FuzzyMatch> {
+    let mut matches = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let (score, positions) = fuzzy_match(query, item.as_ref())?;
+            Some(FuzzyMatch {
+                index,
+                score,
+                positions,
+            })
+        })
+        .collect::<Vec<_>>();
+    // Stable, so items that match as well keep their order
+    matches.sort_by_key(|fuzzy_match| fuzzy_match.score);
+    matches
+}
+
+/// Scores how well the query matches the item, returning the score and the matched
+/// characters. Characters skipped before and between the matched characters add to
+/// the score, so matches that are close together and near the start score best.
+pub fn fuzzy_match(query: &str, item: &str) -> Option<(usize, Vec<usize>)> {
+    let item = item.chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut positions = Vec::new();
+
+    for char in query.chars() {
+        let skipped = item[position..]
+            .iter()
+            .position(|item_char| eq_ignore_case(*item_char, char))?;
+        score += skipped;
+        positions.push(position + skipped);
+        position += skipped + 1;
+    }
+
+    Some((score, positions))
+}
+
+fn eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_characters_in_order() {
+        assert_eq!(
+            fuzzy_match("gnr", "Go to Generate"),
+            Some((8, vec![0, 8, 10]))
+        );
+        assert_eq!(fuzzy_match("rg", "Go to Generate"), None);
+        assert_eq!(fuzzy_match("", "Go to Generate"), Some((0, vec![])));
+    }
+
+    #[test]
+    fn sorts_best_matches_first() {
+        let items = ["Go to Logs", "Show debug logs", "Go to Plugins"];
+        let matches = fuzzy_matches("logs", &items);
+        let indices = matches.iter().map(|m| m.index).collect::<Vec<_>>();
+        assert_eq!(indices, [0, 1]);
+        assert_eq!(matches[0].positions, [6, 7, 8, 9]);
+    }
+}
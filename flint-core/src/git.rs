@@ -0,0 +1,87 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::app_err;
+use crate::error::AppResult;
+
+/// Runs git in a directory and returns its output, failing if git exits with an error
+fn git(dir: &Path, args: &[&str]) -> AppResult<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(app_err!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The branch changes are compared to if no base is given: the default branch of
+/// the remote, or a local main or master branch
+fn default_base(dir: &Path) -> String {
+    if let Ok(remote_head) = git(dir, &["rev-parse", "--abbrev-ref", "origin/HEAD"]) {
+        return remote_head.trim().to_string();
+    }
+
+    ["main", "master"]
+        .into_iter()
+        .find(|branch| git(dir, &["rev-parse", "--verify", "--quiet", branch]).is_ok())
+        .unwrap_or("HEAD")
+        .to_string()
+}
+
+/// The files in `dir` that were added or modified since the branch forked from
+/// `base`, including uncommitted and untracked files. Paths are relative to `dir`.
+pub fn changed_files(dir: &Path, base: Option<&str>) -> AppResult<Vec<PathBuf>> {
+    let base = base.map_or_else(|| default_base(dir), str::to_string);
+    let merge_base = git(dir, &["merge-base", &base, "HEAD"])?;
+
+    let changed = git(
+        dir,
+        &[
+            "diff",
+            "--name-only",
+            "--relative",
+            "--diff-filter=ACMR",
+            merge_base.trim(),
+        ],
+    )?;
+    let untracked = git(dir, &["ls-files", "--others", "--exclude-standard"])?;
+
+    let files = changed
+        .lines()
+        .chain(untracked.lines())
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect::<BTreeSet<_>>();
+    Ok(files.into_iter().collect())
+}
+
+/// The files in `dir` that are staged for the next commit, except deleted ones.
+/// Paths are relative to `dir`.
+pub fn staged_files(dir: &Path) -> AppResult<Vec<PathBuf>> {
+    let staged = git(
+        dir,
+        &[
+            "diff",
+            "--cached",
+            "--name-only",
+            "--relative",
+            "--diff-filter=ACMR",
+        ],
+    )?;
+    Ok(staged
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Whether a file has one of the extensions, which are given without a dot
+pub fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extensions.iter().any(|ext| ext == extension))
+}
@@ -0,0 +1,173 @@
+//! Translations of the messages flint shows. Messages are written in English in the
+//! code and looked up by their English text, which is shown if the locale has no
+//! translation for them:
+//!
+//! ```
+//! use flint_core::t;
+//!
+//! let title = t!("Commands");
+//! let running = t!("{count} running", count = 3);
+//! ```
+//!
+//! Catalogs are TOML files of English messages and their translations, like
+//! `"{count} running" = "{count} en ejecución"`. flint ships some, and teams add
+//! their own language, or override a shipped one, with a catalog named after it in the
+//! `locales` directory of flint's config directory, like `locales/pt.toml`.
+
+use directories::ProjectDirs;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::warn;
+
+/// The catalogs flint ships, by language
+const CATALOGS: [(&str, &str); 1] = [("es", include_str!("../locales/es.toml"))];
+
+/// The language messages are written in
+const SOURCE_LANGUAGE: &str = "en";
+
+struct Catalog {
+    language: String,
+    messages: HashMap<String, String>,
+}
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// Sets the locale messages are shown in: `[flint] locale` if it's set, or the locale
+/// of the system, from `LC_ALL`, `LC_MESSAGES` or `LANG`. It can only be set before
+/// the first message is translated, which uses the system's.
+pub fn set_locale(locale: Option<&str>) {
+    let language = locale
+        .and_then(language_of)
+        .or_else(system_language)
+        .unwrap_or_else(|| SOURCE_LANGUAGE.to_string());
+    let _ = CATALOG.set(load(language));
+}
+
+/// The language messages are shown in, like "es"
+pub fn language() -> &'static str {
+    &catalog().language
+}
+
+fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(|| load(system_language().unwrap_or_else(|| SOURCE_LANGUAGE.into())))
+}
+
+/// The translation of a message, or the message if there's none
+pub fn text(message: &'static str) -> &'static str {
+    catalog()
+        .messages
+        .get(message)
+        .map_or(message, String::as_str)
+}
+
+/// The translation of a message with its `{name}` placeholders replaced by the values
+pub fn format(message: &'static str, values: &[(&str, String)]) -> String {
+    values
+        .iter()
+        .fold(text(message).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// The language of a locale like "es_MX.UTF-8" or "pt-BR", or none for the locales
+/// of C programs, which aren't a language
+fn language_of(locale: &str) -> Option<String> {
+    let language = locale
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match language.as_str() {
+        "" | "c" | "posix" => None,
+        _ => Some(language),
+    }
+}
+
+fn system_language() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|locale| language_of(&locale))
+}
+
+/// The directory of the catalogs added by the user
+fn user_catalogs_dir() -> Option<PathBuf> {
+    ProjectDirs::from("com", "Flint", "flint").map(|dirs| dirs.config_dir().join("locales"))
+}
+
+/// The catalog of the language, with the user's translations taking precedence over
+/// the shipped ones. A language without any is shown in English.
+fn load(language: String) -> Catalog {
+    let mut messages = HashMap::new();
+    if let Some((_, catalog)) = CATALOGS.iter().find(|(name, _)| *name == language) {
+        messages.extend(parse(catalog).expect("shipped catalogs are valid"));
+    }
+
+    let user_catalog = user_catalogs_dir().map(|dir| dir.join(format!("{}.toml", language)));
+    if let Some(contents) = user_catalog.and_then(|path| fs::read_to_string(path).ok()) {
+        match parse(&contents) {
+            Ok(user_messages) => messages.extend(user_messages),
+            Err(err) => warn!("Ignoring the {} translations: {}", language, err),
+        }
+    }
+
+    Catalog { language, messages }
+}
+
+fn parse(catalog: &str) -> Result<HashMap<String, String>, toml::de::Error> {
+    toml::from_str(catalog)
+}
+
+/// Translates a message written in English, replacing its `{name}` placeholders with
+/// the values given for them
+#[macro_export]
+macro_rules! t {
+    ($message:literal) => {
+        $crate::i18n::text($message)
+    };
+    ($message:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::format($message, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(message: &str) -> Vec<&str> {
+        let mut placeholders = message
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect::<Vec<_>>();
+        placeholders.sort();
+        placeholders
+    }
+
+    #[test]
+    fn reads_languages_of_locales() {
+        assert_eq!(language_of("es_MX.UTF-8").as_deref(), Some("es"));
+        assert_eq!(language_of("pt-BR").as_deref(), Some("pt"));
+        assert_eq!(language_of("C.UTF-8"), None);
+        assert_eq!(language_of("POSIX"), None);
+    }
+
+    #[test]
+    fn translations_keep_the_placeholders() {
+        for (language, catalog) in CATALOGS {
+            for (message, translation) in parse(catalog).unwrap() {
+                assert_eq!(
+                    placeholders(&message),
+                    placeholders(&translation),
+                    "the {} translation of {:?}",
+                    language,
+                    message
+                );
+            }
+        }
+    }
+}
@@ -6,6 +6,8 @@ use std::{
 
 use ignore::Walk;
 
+use crate::error::AppResult;
+
 #[derive(Ord, PartialOrd, Eq, PartialEq, Debug)]
 pub enum Language {
     Supported(String),
@@ -31,25 +33,23 @@ pub fn get_language_map() -> &'static HashMap<String, String> {
     })
 }
 
-pub fn detect_languages<'a>(project_path: impl Into<&'a str>) -> BTreeSet<Language> {
+pub fn detect_languages<'a>(project_path: impl Into<&'a str>) -> AppResult<BTreeSet<Language>> {
     let mut languages = BTreeSet::new();
     let path = Path::new(project_path.into());
-    for result in Walk::new(path) {
-        if let Ok(entry) = result {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if let Some(ext) = extension.to_str() {
-                        languages.insert(ext.to_string());
-                    }
+    for entry in Walk::new(path).flatten() {
+        let path = entry.path();
+        if path.is_file() {
+            if let Some(extension) = path.extension() {
+                if let Some(ext) = extension.to_str() {
+                    languages.insert(ext.to_string());
                 }
             }
         }
     }
 
-    let supported_languages: BTreeSet<String> = crate::plugin::map().keys().cloned().collect();
+    let supported_languages: BTreeSet<String> = crate::plugin::map()?.keys().cloned().collect();
 
-    languages
+    Ok(languages
         .iter()
         .map(|lang| {
             let language_name = get_language_map().get(lang).unwrap_or(lang).to_string();
@@ -59,5 +59,5 @@ pub fn detect_languages<'a>(project_path: impl Into<&'a str>) -> BTreeSet<Langua
                 Language::Unsupported(language_name)
             }
         })
-        .collect()
+        .collect())
 }
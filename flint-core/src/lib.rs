@@ -0,0 +1,61 @@
+//! The plugins, config and diagnostics of flint, without its UI. The flint binary is
+//! a frontend over this crate, and other tools can drive flint the same way:
+//!
+//! ```no_run
+//! use flint_core::{config::Config, plugin, run_plugin, set_flag};
+//! use std::{path::PathBuf, sync::Arc};
+//!
+//! # fn main() -> flint_core::error::AppResult<()> {
+//! let project = PathBuf::from("my-project");
+//! set_flag!(current_dir, project.clone());
+//! set_flag!(non_interactive, true);
+//!
+//! let config = Arc::new(Config::load(&project.join("flint.toml"))?);
+//! for plugin in &plugin::list_from_config(&config)? {
+//!     let output = run_plugin(plugin, &config)?;
+//!     for finding in output.test_results.iter().filter(|result| !result.success) {
+//!         println!("{}: {:?}", finding.file_name, finding.error_message);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Plugins are found in `flags::Flags::plugins_dir` and run in `current_dir`, which
+//! are set with [`set_flag!`]. Logs are printed while `non_interactive` is set, and
+//! sent as [`events::WorkerEvent`]s once a frontend opens the events channel.
+
+pub mod audit;
+pub mod baseline;
+pub mod cancel;
+pub mod config;
+pub mod config_lint;
+pub mod diff;
+pub mod error;
+pub mod events;
+pub mod fix;
+pub mod flags;
+pub mod fuzzy;
+pub mod git;
+pub mod i18n;
+pub mod lang;
+pub mod logs;
+pub mod network;
+pub mod plugin;
+pub mod suppressions;
+pub mod timings;
+pub mod trace;
+pub mod workspace;
+
+pub use lang::{detect_languages, get_language_map};
+pub use plugin::exec::run::{run_formatter, run_plugin};
+
+/// Builds a `Command` running the program with the arguments
+#[macro_export]
+macro_rules! cmd {
+    ($program:expr, $($arg:expr),* $(,)?) => {{
+        let mut command = std::process::Command::new($program);
+        $(command.arg($arg);)*
+        command
+    }};
+}
@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{LockResult, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::events::{send_event, WorkerEvent};
+use crate::get_flag;
+
+#[derive(Copy, Clone, Debug, Default)]
+pub enum LogKind {
+    #[default]
+    Info,
+    Success,
+    Error,
+    Warn,
+    Debug,
+}
+
+pub type Logs = VecDeque<(LogKind, String)>;
+
+/// The file every log is appended to, relative to the directory flint runs in
+pub const LOG_FILE: &str = "logs.txt";
+
+/// How many logs are kept for the UI if `[flint] max_logs` isn't set
+pub const DEFAULT_MAX_LOGS: usize = 10_000;
+
+/// The most recent logs, shown in the UI. Every log is also in the log file, so the
+/// oldest ones are dropped once there are more than `MAX_LOGS`.
+pub static LOGS: RwLock<Logs> = RwLock::new(VecDeque::new());
+
+static MAX_LOGS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_LOGS);
+
+/// How many logs were dropped from `LOGS`, which are only in the log file
+static DROPPED_LOGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Colors the prefix of the logs printed when running non-interactively, set by the
+/// frontend as it knows the colors of its theme
+static PREFIX_STYLE: OnceLock<fn(LogKind, &str) -> String> = OnceLock::new();
+
+/// Whether debug logs are shown in the UI. They're still written to the log file while hidden.
+static SHOW_DEBUG_LOGS: AtomicBool = AtomicBool::new(true);
+
+pub fn show_debug_logs() -> bool {
+    SHOW_DEBUG_LOGS.load(Ordering::Relaxed)
+}
+
+/// Shows debug logs if they're hidden and hides them if they're shown
+pub fn toggle_debug_logs() {
+    SHOW_DEBUG_LOGS.fetch_xor(true, Ordering::Relaxed);
+}
+
+/// Sets how many logs are kept for the UI, dropping the oldest ones if there are more
+pub fn set_max_logs(max_logs: usize) {
+    MAX_LOGS.store(max_logs.max(1), Ordering::Relaxed);
+    drop_old_logs(&mut get_logs_mut().unwrap());
+}
+
+/// Sets how the prefix of printed logs is colored, like `[error]:` in red. Prefixes
+/// aren't colored until it's set, or if colors are disabled.
+pub fn set_prefix_style(style: fn(LogKind, &str) -> String) {
+    let _ = PREFIX_STYLE.set(style);
+}
+
+pub fn dropped_logs() -> usize {
+    DROPPED_LOGS.load(Ordering::Relaxed)
+}
+
+fn drop_old_logs(logs: &mut Logs) {
+    let max_logs = MAX_LOGS.load(Ordering::Relaxed);
+    if logs.len() > max_logs {
+        let dropped = logs.len() - max_logs;
+        logs.drain(..dropped);
+        DROPPED_LOGS.fetch_add(dropped, Ordering::Relaxed);
+    }
+}
+
+pub fn log_file() -> PathBuf {
+    std::env::current_dir()
+        .map(|dir| dir.join(LOG_FILE))
+        .unwrap_or_else(|_| PathBuf::from(LOG_FILE))
+}
+
+pub fn get_logs() -> LockResult<RwLockReadGuard<'static, Logs>> {
+    LOGS.read()
+}
+
+pub fn get_logs_mut() -> LockResult<RwLockWriteGuard<'static, Logs>> {
+    LOGS.write()
+}
+
+pub fn add_log(kind: LogKind, message: String) {
+    // Skip debug logs in release mode unless explicitly enabled
+    #[cfg(not(debug_assertions))]
+    {
+        if let LogKind::Debug = kind {
+            return;
+        }
+    }
+
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(LOG_FILE)
+        .unwrap();
+    let prefix = match kind {
+        LogKind::Info => "[info]:",
+        LogKind::Success => "[success]:",
+        LogKind::Error => "[error]:",
+        LogKind::Warn => "[warn]:",
+        LogKind::Debug => "[debug]:",
+    };
+
+    let is_non_interactive = get_flag!(non_interactive);
+
+    let log = format!("{} {}", prefix, message);
+    if *is_non_interactive {
+        // Only the prefix is colored, in the color of the kind in the theme
+        let color = *get_flag!(color);
+        let line = match PREFIX_STYLE.get().filter(|_| color) {
+            Some(style) => format!("{} {}", style(kind, prefix), message),
+            None => log.clone(),
+        };
+        if *get_flag!(logs_on_stderr) {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+    writeln!(file, "{}", log).unwrap();
+    send_event(WorkerEvent::Log(kind, log));
+}
+
+/// Logs the message as a `tracing` event, which the subscriber of [`crate::trace::init`]
+/// writes to the logs. Without a subscriber, it's written to them directly.
+pub fn emit(kind: LogKind, message: String) {
+    if !tracing::dispatcher::has_been_set() {
+        add_log(kind, message);
+        return;
+    }
+    match kind {
+        LogKind::Info => tracing::info!(kind = "info", "{}", message),
+        LogKind::Success => tracing::info!(kind = "success", "{}", message),
+        LogKind::Error => tracing::error!(kind = "error", "{}", message),
+        LogKind::Warn => tracing::warn!(kind = "warn", "{}", message),
+        LogKind::Debug => tracing::debug!(kind = "debug", "{}", message),
+    }
+}
+
+/// Adds a line to the logs shown in the UI. Only the thread that receives the worker
+/// events calls this, so the logs are never written to from several threads at once.
+pub fn push_log(kind: LogKind, log: String) {
+    let mut logs = get_logs_mut().unwrap();
+    logs.push_back((kind, log));
+    drop_old_logs(&mut logs);
+}
+
+#[macro_export]
+macro_rules! log {
+    ($kind:expr, $($arg:tt)*) => {{
+        let message = format!($($arg)*);
+        $crate::logs::emit($kind, message);
+    }};
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {{
+        $crate::log!($crate::logs::LogKind::Info, $($arg)*);
+    }};
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {{
+        $crate::log!($crate::logs::LogKind::Warn, $($arg)*);
+    }};
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => {{
+        $crate::log!($crate::logs::LogKind::Error, $($arg)*);
+    }};
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {{
+        $crate::log!($crate::logs::LogKind::Debug, $($arg)*);
+    }};
+}
+
+#[macro_export]
+macro_rules! success {
+    ($($arg:tt)*) => {{
+        $crate::log!($crate::logs::LogKind::Success, $($arg)*);
+    }};
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// The `[flint.network]` section, for networks only reachable through a proxy
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NetworkConfig {
+    /// The proxy downloads go through, like "http://proxy.corp:8080". It takes
+    /// precedence over HTTP_PROXY and HTTPS_PROXY.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Hosts connected to without the proxy, like NO_PROXY
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub no_proxy: Vec<String>,
+}
+
+/// The proxies of the network operations of flint, from `[flint.network]` or the
+/// environment. Tools are run with them in their environment.
+#[derive(Debug, Clone, Default)]
+pub struct Proxy {
+    http: Option<String>,
+    https: Option<String>,
+    no_proxy: Option<String>,
+}
+
+impl Proxy {
+    pub fn new(config: Option<&NetworkConfig>) -> Self {
+        let proxy = config.and_then(|config| config.proxy.clone());
+        let no_proxy = config
+            .filter(|config| !config.no_proxy.is_empty())
+            .map(|config| config.no_proxy.join(","));
+
+        Self {
+            http: proxy.clone().or_else(|| env_var("HTTP_PROXY")),
+            https: proxy.or_else(|| env_var("HTTPS_PROXY")),
+            no_proxy: no_proxy.or_else(|| env_var("NO_PROXY")),
+        }
+    }
+
+    /// The variables a command is run with to use the proxies. Both cases are set, as
+    /// tools only read one of them, like git only reading http_proxy in lowercase.
+    pub fn env(&self) -> Vec<(String, String)> {
+        [
+            ("HTTP_PROXY", &self.http),
+            ("HTTPS_PROXY", &self.https),
+            ("NO_PROXY", &self.no_proxy),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value.clone()?)))
+        .flat_map(|(name, value)| {
+            [
+                (name.to_string(), value.clone()),
+                (name.to_lowercase(), value),
+            ]
+        })
+        .collect()
+    }
+}
+
+/// A variable in either case, like `HTTP_PROXY` or `http_proxy`
+fn env_var(name: &str) -> Option<String> {
+    [name.to_string(), name.to_lowercase()]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
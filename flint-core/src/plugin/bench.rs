@@ -0,0 +1,185 @@
+use crate::{
+    config::Config,
+    error::AppResult,
+    plugin::{
+        exec::{eval::PluginEvalOutput, generate::pipeline, vm},
+        Plugin, PluginKind,
+    },
+    timings::Phase,
+};
+use mlua::{Function, IntoLuaMulti, LuaSerdeExt, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The timings and Lua memory of the runs of a function of a plugin
+#[derive(Debug, Clone)]
+pub struct PhaseBench {
+    pub phase: Phase,
+    /// How long every run took, in the order they ran
+    pub durations: Vec<Duration>,
+    /// The memory the VM used once every run returned, in bytes
+    pub memory: Vec<usize>,
+}
+
+impl PhaseBench {
+    fn new(phase: Phase) -> Self {
+        Self {
+            phase,
+            durations: Vec::new(),
+            memory: Vec::new(),
+        }
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.durations.len() {
+            0 => Duration::ZERO,
+            count => self.durations.iter().sum::<Duration>() / count as u32,
+        }
+    }
+
+    /// The duration the given percent of the runs took at most, like 95 for the p95
+    pub fn percentile(&self, percent: u32) -> Duration {
+        percentile(&self.durations, percent)
+    }
+
+    pub fn max(&self) -> Duration {
+        self.durations.iter().max().copied().unwrap_or_default()
+    }
+
+    pub fn mean_memory(&self) -> usize {
+        match self.memory.len() {
+            0 => 0,
+            count => self.memory.iter().sum::<usize>() / count,
+        }
+    }
+
+    pub fn max_memory(&self) -> usize {
+        self.memory.iter().max().copied().unwrap_or_default()
+    }
+}
+
+/// The nearest-rank percentile of the durations
+fn percentile(durations: &[Duration], percent: u32) -> Duration {
+    if durations.is_empty() {
+        return Duration::ZERO;
+    }
+    let mut sorted = durations.to_vec();
+    sorted.sort();
+    let rank = (sorted.len() * percent.min(100) as usize).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
+}
+
+/// The phases of a plugin of the kind that run Lua functions, as audit and report
+/// plugins don't generate anything and CI plugins don't run anything
+pub fn phases(kind: &PluginKind) -> &'static [Phase] {
+    match kind {
+        PluginKind::Lint | PluginKind::Test | PluginKind::Fmt => {
+            &[Phase::Validate, Phase::Generate, Phase::Run]
+        }
+        PluginKind::Ci => &[Phase::Validate, Phase::Generate],
+        PluginKind::Audit => &[Phase::Run],
+        PluginKind::Report => &[Phase::Report],
+    }
+}
+
+/// Calls the Validate, Generate and Run functions of the plugin the given number of
+/// times with its section of the config, timing every call. Nothing the functions
+/// return is written or run. Every phase is called once before it's timed, so the
+/// scripts are compiled and the VMs pooled like in a running flint.
+pub fn bench(plugin: &Plugin, toml: &Arc<Config>, iterations: usize) -> AppResult<Vec<PhaseBench>> {
+    let mut benches = phases(&plugin.kind)
+        .iter()
+        .map(|phase| PhaseBench::new(*phase))
+        .collect::<Vec<_>>();
+
+    for iteration in 0..=iterations {
+        for bench in &mut benches {
+            let (duration, memory) = call(plugin, toml, bench.phase)?;
+            if iteration > 0 {
+                bench.durations.push(duration);
+                bench.memory.push(memory);
+            }
+        }
+    }
+    Ok(benches)
+}
+
+/// Calls the function of the phase in a VM of the pool, returning how long it took
+/// and the memory the VM used once it returned
+fn call(plugin: &Plugin, toml: &Arc<Config>, phase: Phase) -> AppResult<(Duration, usize)> {
+    let scripts: &[&str] = match phase {
+        Phase::Validate | Phase::Generate => &["generate.lua", "validate.lua"],
+        _ => &["run.lua"],
+    };
+    let lua = vm::load(plugin, scripts)?;
+    let plugin_config = plugin.get_config_lua(&lua, toml)?;
+
+    let (function, args) = match phase {
+        Phase::Validate => ("Validate", plugin_config.into_lua_multi(&lua)?),
+        Phase::Generate if plugin.kind == PluginKind::Ci => {
+            let pipeline = lua.to_value(&pipeline(plugin, toml)?)?;
+            ("Generate", (plugin_config, pipeline).into_lua_multi(&lua)?)
+        }
+        Phase::Generate => ("Generate", plugin_config.into_lua_multi(&lua)?),
+        Phase::Report => {
+            // Reports are written from the output of a run, which is empty here
+            let output = PluginEvalOutput {
+                tests_passed: 0,
+                total_tests: 0,
+                passing_percentage: 0.0,
+                test_results: Vec::new(),
+            };
+            let state = lua.create_table()?;
+            state.set("config", plugin_config)?;
+            state.set("output", lua.to_value(&output)?)?;
+            ("Run", state.into_lua_multi(&lua)?)
+        }
+        _ => ("Run", plugin_config.into_lua_multi(&lua)?),
+    };
+    let function: Function = lua.globals().get(function)?;
+
+    let start = Instant::now();
+    function.call::<Value>(args)?;
+    let duration = start.elapsed();
+    Ok((duration, lua.used_memory()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values
+            .iter()
+            .map(|value| Duration::from_millis(*value))
+            .collect()
+    }
+
+    #[test]
+    fn percentiles_are_nearest_rank() {
+        let durations = millis(&[5, 1, 4, 2, 3, 10, 7, 6, 9, 8]);
+        assert_eq!(percentile(&durations, 50), Duration::from_millis(5));
+        assert_eq!(percentile(&durations, 90), Duration::from_millis(9));
+        assert_eq!(percentile(&durations, 95), Duration::from_millis(10));
+        assert_eq!(percentile(&durations, 100), Duration::from_millis(10));
+        assert_eq!(percentile(&durations, 0), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50), Duration::ZERO);
+    }
+
+    #[test]
+    fn summarizes_the_runs() {
+        let bench = PhaseBench {
+            phase: Phase::Run,
+            durations: millis(&[2, 4, 6]),
+            memory: vec![100, 300, 200],
+        };
+        assert_eq!(bench.mean(), Duration::from_millis(4));
+        assert_eq!(bench.max(), Duration::from_millis(6));
+        assert_eq!(bench.mean_memory(), 200);
+        assert_eq!(bench.max_memory(), 300);
+
+        let empty = PhaseBench::new(Phase::Run);
+        assert_eq!(empty.mean(), Duration::ZERO);
+        assert_eq!(empty.mean_memory(), 0);
+    }
+}
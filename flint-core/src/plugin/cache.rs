@@ -0,0 +1,190 @@
+use super::{exec::eval::PluginEvalOutput, inputs, inputs::InputsHasher, Plugin, PluginKind};
+use crate::error::AppResult;
+use crate::git::has_extension;
+use crate::{app_err, get_flag};
+use ignore::Walk;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+/// The file the outputs of plugin runs are cached in, relative to the project root
+pub const CACHE_FILE: &str = ".flint/cache.json";
+
+/// The scripts of a plugin its runs depend on
+const RUN_SCRIPTS: [&str; 2] = ["details.lua", "run.lua"];
+
+/// Whether the runs of a plugin are cached. Audit plugins check advisories published
+/// after their last run, so they always run.
+pub fn is_cached(plugin: &Plugin) -> bool {
+    matches!(plugin.kind, PluginKind::Lint | PluginKind::Test)
+}
+
+/// Whether the output of a run can be reused for the same inputs. Runs of test plugins
+/// are only reused if every test passed, as failing tests can be flaky, so they always
+/// run again.
+pub fn is_reusable(plugin: &Plugin, output: &PluginEvalOutput) -> bool {
+    plugin.kind != PluginKind::Test || output.test_results.iter().all(|result| result.success)
+}
+
+/// The outputs of the last runs of the lint and test plugins, with the files they
+/// checked, so plugins whose files haven't changed since aren't run again
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RunCache {
+    /// The hashes of the files, by their path relative to the project root
+    files: BTreeMap<String, FileHash>,
+    /// By plugin id
+    plugins: BTreeMap<String, CachedRun>,
+}
+
+/// The hash of a file, with the size and modification time it had when it was
+/// hashed, so it's only read again once they change
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct FileHash {
+    size: u64,
+    /// In nanoseconds since the Unix epoch
+    modified: u64,
+    hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CachedRun {
+    inputs: String,
+    output: PluginEvalOutput,
+}
+
+impl RunCache {
+    pub fn path() -> PathBuf {
+        get_flag!(current_dir).join(CACHE_FILE)
+    }
+
+    /// The cache, which is empty if there's none yet or it can't be read
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn write(&self) -> AppResult<()> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string(self)
+            .map_err(|err| app_err!("Failed to cache the plugin runs: {}", err))?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Hashes of everything the runs of the plugins depend on, by plugin id: their
+    /// scripts, the versions of their tools, flint.toml, the files they declare as
+    /// inputs and the project files with their extensions. The files are walked once
+    /// for every plugin, and only read if they changed since they were last hashed.
+    pub fn fingerprints(&mut self, plugins: &[Plugin]) -> BTreeMap<String, String> {
+        let root = get_flag!(current_dir).clone();
+        let extensions = plugins
+            .iter()
+            .flat_map(|plugin| plugin.details.extensions.iter().cloned())
+            .collect::<Vec<_>>();
+        let every_file = extensions.iter().any(|extension| extension == "*");
+
+        let mut files = BTreeMap::new();
+        for entry in Walk::new(&root).flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() || !(every_file || has_extension(path, &extensions)) {
+                continue;
+            }
+            let Ok(name) = path.strip_prefix(&root) else {
+                continue;
+            };
+            let name = name.to_string_lossy().into_owned();
+
+            let size = metadata.len();
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |modified| modified.as_nanos() as u64);
+            let hash = match self.files.get(&name) {
+                Some(known) if known.size == size && known.modified == modified => {
+                    known.hash.clone()
+                }
+                _ => match inputs::hash_file(path) {
+                    Ok(hash) => hash,
+                    Err(_) => continue,
+                },
+            };
+            files.insert(
+                name,
+                FileHash {
+                    size,
+                    modified,
+                    hash,
+                },
+            );
+        }
+        self.files = files;
+
+        let config = fs::read(get_flag!(config_path)).unwrap_or_default();
+        plugins
+            .iter()
+            .map(|plugin| {
+                let mut hasher = InputsHasher::default();
+                hasher.field(plugin.details.version.as_bytes());
+                for script in RUN_SCRIPTS {
+                    hasher.field(&fs::read(plugin.path.join(script)).unwrap_or_default());
+                }
+                hasher.field(&config);
+
+                // A newer linter can find more, so its runs aren't reused
+                for (tool, version) in plugin.tool_versions() {
+                    hasher.field(tool.as_bytes());
+                    hasher.field(version.unwrap_or_default().as_bytes());
+                }
+
+                // Like package.json, which the tests depend on without being checked
+                if let Ok(Some(inputs)) = inputs::get_inputs(plugin) {
+                    hasher.files(&inputs.files);
+                }
+
+                let extensions = &plugin.details.extensions;
+                let every_file = extensions.iter().any(|extension| extension == "*");
+                for (name, file) in &self.files {
+                    if every_file || has_extension(name.as_ref(), extensions) {
+                        hasher.field(name.as_bytes());
+                        hasher.field(file.hash.as_bytes());
+                    }
+                }
+                (plugin.details.id.clone(), hasher.finish())
+            })
+            .collect()
+    }
+
+    /// The output of the plugin's last run, if it ran with the same inputs
+    pub fn get(&self, plugin_id: &str, fingerprint: &str) -> Option<&PluginEvalOutput> {
+        self.plugins
+            .get(plugin_id)
+            .filter(|run| run.inputs == fingerprint)
+            .map(|run| &run.output)
+    }
+
+    /// Caches the output of a run, if it can be reused
+    pub fn record(&mut self, plugin: &Plugin, fingerprint: String, output: &PluginEvalOutput) {
+        if !is_reusable(plugin, output) {
+            self.plugins.remove(&plugin.details.id);
+            return;
+        }
+        self.plugins.insert(
+            plugin.details.id.clone(),
+            CachedRun {
+                inputs: fingerprint,
+                output: output.clone(),
+            },
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use super::deps::Dependency;
+use crate::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The file the cache of a pipeline is keyed by, so it's rebuilt when plugins change
+const CACHE_KEY_FILE: &str = "flint.toml";
+
+/// The directories flint installs plugins to on Linux and macOS runners
+const PLUGIN_DIRS: [&str; 2] = [
+    "~/.local/share/flint/plugins",
+    "~/Library/Application Support/com.Flint.flint/plugins",
+];
+
+fn default_branches() -> Vec<String> {
+    vec!["main".into()]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_runs_on() -> String {
+    "ubuntu-latest".into()
+}
+
+fn default_node_version() -> String {
+    "18".into()
+}
+
+fn default_python_version() -> String {
+    "3.12".into()
+}
+
+/// The `[ci]` section. The pipeline is generated by the CI plugin of the provider,
+/// which reads its own options from `[ci.<provider>]`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CiConfig {
+    /// The CI plugin generating the pipeline, like `gitlab-ci`. Every plugin with a
+    /// section generates it if no provider is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(flatten)]
+    pub options: Option<CiOptions>,
+    /// The sections of the CI plugins, by id
+    #[serde(flatten)]
+    pub plugins: HashMap<String, toml::Value>,
+}
+
+impl CiConfig {
+    /// The ids of the CI plugins that generate the pipeline
+    pub fn plugin_ids(&self) -> Vec<&String> {
+        match &self.provider {
+            Some(provider) => vec![provider],
+            None => self.plugins.keys().collect(),
+        }
+    }
+
+    /// The options of a CI plugin in `[ci.<id>]`
+    pub fn plugin_config(&self, id: &str) -> Option<&toml::Value> {
+        self.plugins.get(id)
+    }
+
+    /// The options of the pipeline generated by a CI plugin. Options in the section of
+    /// the plugin take precedence over the ones in `[ci]`.
+    pub fn pipeline_options(&self, id: &str) -> CiOptions {
+        let mut options = match toml::Value::try_from(self.options.clone().unwrap_or_default()) {
+            Ok(toml::Value::Table(options)) => options,
+            _ => toml::Table::new(),
+        };
+        if let Some(toml::Value::Table(plugin_options)) = self.plugins.get(id) {
+            options.extend(plugin_options.clone());
+        }
+
+        toml::Value::Table(options)
+            .try_into()
+            .unwrap_or_else(|err| {
+                warn!("Ignoring invalid options of the {} pipeline: {}", id, err);
+                CiOptions::default()
+            })
+    }
+}
+
+/// The options of the pipeline every CI provider generates
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CiOptions {
+    /// Branches the pipeline runs on when pushed to
+    #[serde(default = "default_branches")]
+    pub branches: Vec<String>,
+    /// Branches the pipeline runs on for pull requests into them
+    #[serde(default = "default_branches")]
+    pub pr_branches: Vec<String>,
+    /// Values the pipeline is run with, by name, like `os = ["ubuntu-latest"]`
+    #[serde(default)]
+    pub matrix: BTreeMap<String, Vec<String>>,
+    /// Whether plugins and dependencies are cached between runs
+    #[serde(default = "default_true")]
+    pub cache: bool,
+    /// The machine the pipeline runs on, unless the matrix has an `os`
+    #[serde(default = "default_runs_on")]
+    pub runs_on: String,
+    #[serde(default = "default_node_version")]
+    pub node_version: String,
+    #[serde(default = "default_python_version")]
+    pub python_version: String,
+}
+
+impl Default for CiOptions {
+    fn default() -> Self {
+        Self {
+            branches: default_branches(),
+            pr_branches: default_branches(),
+            matrix: BTreeMap::new(),
+            cache: true,
+            runs_on: default_runs_on(),
+            node_version: default_node_version(),
+            python_version: default_python_version(),
+        }
+    }
+}
+
+/// The provider-agnostic pipeline CI plugins generate their provider's config from,
+/// so every provider runs the same steps
+#[derive(Serialize, Debug, Clone)]
+pub struct Pipeline {
+    pub name: String,
+    pub branches: Vec<String>,
+    pub pr_branches: Vec<String>,
+    pub matrix: BTreeMap<String, Vec<String>>,
+    /// Every combination of the matrix values, for providers that list them
+    pub matrix_combinations: Vec<BTreeMap<String, String>>,
+    pub runs_on: String,
+    /// A Docker image with the toolchains, for providers that run jobs in containers
+    pub image: String,
+    pub toolchains: Vec<Toolchain>,
+    pub cache: Option<Cache>,
+    pub steps: Vec<Step>,
+    /// Files uploaded after the steps, even if one failed
+    pub artifacts: Vec<Artifact>,
+    /// The dependencies of the other plugins, by package manager
+    pub dependencies: HashMap<String, Vec<Dependency>>,
+}
+
+/// A language the runner has to set up before the steps, like `node`
+#[derive(Serialize, Debug, Clone)]
+pub struct Toolchain {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Cache {
+    /// The file whose hash keys the cache
+    pub key_file: String,
+    pub paths: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Step {
+    pub name: String,
+    /// The shell command the step runs
+    pub run: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Artifact {
+    pub name: String,
+    pub path: String,
+}
+
+impl Step {
+    fn new(name: &str, run: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            run: run.into(),
+        }
+    }
+}
+
+impl Pipeline {
+    /// The pipeline that installs the dependencies of the plugins and flint, checks
+    /// that the generated configs are up to date, that the files are formatted if there
+    /// are formatter plugins, and runs the tests
+    pub fn new(
+        options: CiOptions,
+        dependencies: HashMap<String, Vec<Dependency>>,
+        has_formatters: bool,
+    ) -> Self {
+        let npm = dependencies.get("npm").filter(|deps| !deps.is_empty());
+        let pip = dependencies.get("pip").filter(|deps| !deps.is_empty());
+
+        let mut toolchains = Vec::new();
+        let mut steps = Vec::new();
+        let mut cache_paths = PLUGIN_DIRS.map(String::from).to_vec();
+
+        if let Some(npm) = npm {
+            toolchains.push(Toolchain {
+                name: "node".into(),
+                version: options.node_version.clone(),
+            });
+            let packages = npm
+                .iter()
+                .map(|dep| format!("{}@{}", dep.name, dep.version))
+                .collect::<Vec<_>>();
+            steps.push(Step::new(
+                "Install Node.js dependencies",
+                format!(
+                    "npm install -g {} --no-fund --no-audit --silent",
+                    packages.join(" ")
+                ),
+            ));
+            cache_paths.push("~/.npm".into());
+        }
+
+        if let Some(pip) = pip {
+            toolchains.push(Toolchain {
+                name: "python".into(),
+                version: options.python_version.clone(),
+            });
+            let packages = pip
+                .iter()
+                .map(|dep| match dep.version.as_str() {
+                    "latest" => dep.name.clone(),
+                    version => format!("{}=={}", dep.name, version),
+                })
+                .collect::<Vec<_>>();
+            steps.push(Step::new(
+                "Install Python dependencies",
+                format!("pip install {}", packages.join(" ")),
+            ));
+            cache_paths.push("~/.cache/pip".into());
+        }
+
+        steps.extend([
+            Step::new(
+                "Install Flint",
+                "curl -fsSL -o flint https://github.com/skadewdl3/flint/releases/latest/download/flint && chmod +x ./flint",
+            ),
+            Step::new("Install Flint plugins", "./flint install"),
+            Step::new("Check generated configs", "./flint generate --check"),
+        ]);
+        if has_formatters {
+            steps.push(Step::new("Check formatting", "./flint fmt --check"));
+        }
+        steps.push(Step::new("Run tests", "./flint test"));
+
+        let image = image(&toolchains);
+        Self {
+            name: "Flint CI".into(),
+            branches: options.branches,
+            pr_branches: options.pr_branches,
+            matrix_combinations: combinations(&options.matrix),
+            matrix: options.matrix,
+            runs_on: options.runs_on,
+            image,
+            toolchains,
+            cache: options.cache.then(|| Cache {
+                key_file: CACHE_KEY_FILE.into(),
+                paths: cache_paths,
+            }),
+            steps,
+            artifacts: vec![
+                Artifact {
+                    name: "Reports".into(),
+                    path: "reports".into(),
+                },
+                Artifact {
+                    name: "Logs".into(),
+                    path: "logs.txt".into(),
+                },
+            ],
+            dependencies,
+        }
+    }
+}
+
+/// A Docker image with the toolchains, or a plain one if there are none
+fn image(toolchains: &[Toolchain]) -> String {
+    let version = |name: &str| {
+        toolchains
+            .iter()
+            .find(|toolchain| toolchain.name == name)
+            .map(|toolchain| toolchain.version.as_str())
+    };
+
+    match (version("node"), version("python")) {
+        (Some(node), Some(python)) => {
+            format!("nikolaik/python-nodejs:python{}-nodejs{}", python, node)
+        }
+        (Some(node), None) => format!("node:{}", node),
+        (None, Some(python)) => format!("python:{}", python),
+        (None, None) => "ubuntu:24.04".into(),
+    }
+}
+
+/// Every combination of the values of a matrix, in the order of its names
+fn combinations(matrix: &BTreeMap<String, Vec<String>>) -> Vec<BTreeMap<String, String>> {
+    if matrix.is_empty() {
+        return Vec::new();
+    }
+
+    let mut combinations = vec![BTreeMap::new()];
+    for (name, values) in matrix {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination| {
+                values.iter().map(move |value| {
+                    let mut combination = combination.clone();
+                    combination.insert(name.clone(), value.clone());
+                    combination
+                })
+            })
+            .collect();
+    }
+    combinations
+}
@@ -1,6 +1,6 @@
-use super::{helpers::add_helper_globals, Plugin};
-use crate::app::AppResult;
-use mlua::{Error, Function, Lua, LuaSerdeExt};
+use super::{exec::vm, Plugin};
+use crate::error::AppResult;
+use mlua::{Error, Function, LuaSerdeExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -11,13 +11,9 @@ pub struct Dependency {
 }
 
 pub fn get_dependencies(plugin: &Plugin) -> AppResult<HashMap<String, Vec<Dependency>>> {
-    let lua = Lua::new();
-    add_helper_globals(&lua)?;
+    let lua = vm::load(plugin, &["details.lua"])?;
 
     let deps_func: Result<Function, Error> = {
-        let contents = std::fs::read_to_string(plugin.path.join("details.lua"))?;
-        lua.load(&contents).exec()?;
-
         // Try to get the `Dependencies` function from lua globals
         match lua.globals().get("Dependencies") {
             Ok(func) => Ok(func),
@@ -29,8 +25,8 @@ pub fn get_dependencies(plugin: &Plugin) -> AppResult<HashMap<String, Vec<Depend
     };
 
     if let Ok(func) = deps_func {
-        let deps_value = func.call::<mlua::Value>(()).unwrap();
-        let deps: HashMap<String, Vec<Dependency>> = lua.from_value(deps_value).unwrap();
+        let deps_value = func.call::<mlua::Value>(())?;
+        let deps: HashMap<String, Vec<Dependency>> = lua.from_value(deps_value)?;
         Ok(deps)
     } else {
         // Return empty deps if no dependencies.lua exists
@@ -44,10 +40,7 @@ pub fn collect_dependencies(plugins: &Vec<Plugin>) -> AppResult<HashMap<String,
     for plugin in plugins {
         if let Ok(deps) = plugin.get_dependencies() {
             for (manager, deps_list) in deps {
-                all_deps
-                    .entry(manager)
-                    .or_insert_with(Vec::new)
-                    .extend(deps_list);
+                all_deps.entry(manager).or_default().extend(deps_list);
             }
         }
     }
@@ -108,10 +101,7 @@ fn should_replace_version(existing: &str, new: &str) -> bool {
     // Parse the versions, stripping any semver operators
     let parse_version = |v: &str| -> Option<semver::Version> {
         let version_str = v.trim_start_matches(|c| !char::is_digit(c, 10));
-        match semver::Version::parse(version_str) {
-            Ok(v) => Some(v),
-            Err(_e) => None,
-        }
+        semver::Version::parse(version_str).ok()
     };
 
     if let (Some(existing_ver), Some(new_ver)) = (parse_version(existing), parse_version(new)) {
@@ -121,10 +111,12 @@ fn should_replace_version(existing: &str, new: &str) -> bool {
 
     // Handle special cases for version ranges
     // If existing is a range and new is specific, prefer specific
-    if existing.contains('^') || existing.contains('~') || existing.contains('*') {
-        if !new.contains('^') && !new.contains('~') && !new.contains('*') {
-            return true;
-        }
+    if (existing.contains('^') || existing.contains('~') || existing.contains('*'))
+        && !new.contains('^')
+        && !new.contains('~')
+        && !new.contains('*')
+    {
+        return true;
     }
 
     // If both are ranges, prefer the one with the highest minimum
@@ -149,21 +141,13 @@ fn extract_minimum_version(version_range: &str) -> Option<semver::Version> {
     }
 
     // Handle caret ranges (^1.2.3)
-    if version_range.starts_with('^') {
-        let ver_str = &version_range[1..];
-        return match semver::Version::parse(ver_str) {
-            Ok(v) => Some(v),
-            Err(_e) => None,
-        };
+    if let Some(ver_str) = version_range.strip_prefix('^') {
+        return semver::Version::parse(ver_str).ok();
     }
 
     // Handle tilde ranges (~1.2.3)
-    if version_range.starts_with('~') {
-        let ver_str = &version_range[1..];
-        return match semver::Version::parse(ver_str) {
-            Ok(v) => Some(v),
-            Err(_e) => None,
-        };
+    if let Some(ver_str) = version_range.strip_prefix('~') {
+        return semver::Version::parse(ver_str).ok();
     }
 
     // Handle star ranges (1.2.*)
@@ -182,25 +166,16 @@ fn extract_minimum_version(version_range: &str) -> Option<semver::Version> {
                 1 => format!("{}.0", base_version),
                 _ => base_version.to_string(),
             };
-            return match semver::Version::parse(&version_str) {
-                Ok(v) => Some(v),
-                Err(_e) => None,
-            };
+            return semver::Version::parse(&version_str).ok();
         }
     }
 
     // Handle range with comparison operators
     if version_range.starts_with('>') {
         let ver_str = version_range.trim_start_matches(|c| !char::is_digit(c, 10));
-        return match semver::Version::parse(ver_str) {
-            Ok(v) => Some(v),
-            Err(_e) => None,
-        };
+        return semver::Version::parse(ver_str).ok();
     }
 
     // Default fallback - try to parse as is
-    match semver::Version::parse(version_range) {
-        Ok(v) => Some(v),
-        Err(_e) => None,
-    }
+    semver::Version::parse(version_range).ok()
 }
@@ -0,0 +1,437 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::error::{AppResult, FlintError};
+use crate::network::Proxy;
+use crate::{app_err, debug, error, get_flag, set_flag};
+use crate::{cmd, info};
+use crate::{success, warn};
+
+use super::lock::{LockedPlugin, Lockfile, PluginSource};
+use super::PluginKind;
+
+/// Downloads plugins of a kind from a repository at a git revision, like a tag or a
+/// commit, and returns the commit they were downloaded at. Every git command can
+/// download, as files of the clone are only fetched once they're checked out.
+pub fn clone_plugin_folders(
+    repo_url: &str,
+    plugin_kind: PluginKind,
+    plugin_ids: Vec<&String>,
+    revision: &str,
+    proxy: &Proxy,
+) -> AppResult<String> {
+    info!(
+        "Starting plugin clone process for {} plugins",
+        plugin_kind.to_string()
+    );
+
+    // Determine final destination path
+    let final_dest_path = get_flag!(plugins_dir);
+
+    info!("Downloading plugins to: {}", final_dest_path.display());
+
+    // Create a temporary directory for git operations
+    // TODO: Use ProjDirs crate here instead of std::env::temp_dir()
+    let temp_path = std::env::temp_dir().join("flint-plugins-temp");
+
+    // Create temporary directory
+    fs::create_dir_all(&temp_path)?;
+
+    // Create the kind subfolder
+    let kind_str = &plugin_kind.to_string();
+    let kind_path = final_dest_path.join(kind_str);
+
+    // Make sure the final plugin type directory exists
+    fs::create_dir_all(&kind_path)?;
+
+    // Check if git is installed and available
+    info!("Checking if git is installed");
+    let git_check = cmd!["git", "--help"].output().map_err(|e| {
+        app_err!(
+            "Git is not instaled on this system or not in PATH.\nError message: {}",
+            e
+        )
+    })?;
+
+    if !git_check.status.success() {
+        return Err(app_err!("Git is not available or not working properly"));
+    }
+
+    info!("Cloning repository metadata from {}", repo_url);
+
+    // Do git operations in the temporary path
+    // Define a helper macro for creating commands
+
+    // Nothing is checked out until the revision is resolved, which can be on any branch
+    let output = cmd![
+        "git",
+        "clone",
+        "--filter=blob:none",
+        "--sparse",
+        "--no-checkout",
+        repo_url,
+        &temp_path
+    ]
+    .envs(proxy.env())
+    .output()?;
+
+    let _stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let status = output.status;
+
+    if !status.success() {
+        info!(
+            "Cleaning up temporary directory after failed clone: {}",
+            temp_path.display()
+        );
+        _ = fs::remove_dir_all(&temp_path).map_err(|e| {
+            app_err!("Failed to remove temporary directory: {}", e);
+        });
+
+        // The installed plugins can still be used when the network is down
+        if is_network_error(&stderr) {
+            debug!("[git clone]: {}", stderr);
+            return Err(FlintError::Network(stderr.trim().to_string()));
+        }
+        return Err(app_err!(
+            "Failed to clone repository.\n Git Clone output: {}",
+            stderr
+        ));
+    }
+
+    if !status.success() {
+        // Clean up the temporary directory if clone fails
+        return Err(app_err!("Failed to clone repository"));
+    }
+
+    let output = cmd![
+        "git",
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        format!("{}^{{commit}}", revision)
+    ]
+    .current_dir(&temp_path)
+    .envs(proxy.env())
+    .output()?;
+    if !output.status.success() {
+        let _ = fs::remove_dir_all(&temp_path);
+        return Err(app_err!("{} wasn't found in {}", revision, repo_url));
+    }
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    info!("Resolved {} to commit {}", revision, commit);
+
+    let mut sparse_paths = Vec::new();
+    for id in &plugin_ids {
+        sparse_paths.push(format!("flint-plugins/{}/{}", kind_str, id));
+    }
+
+    info!(
+        "Metadata downloaded. Setting up sparse checkout for {} plugin paths",
+        sparse_paths.len()
+    );
+    let output = cmd!["git", "sparse-checkout", "set"]
+        .current_dir(&temp_path)
+        .envs(proxy.env())
+        .args(&sparse_paths)
+        .output()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let status = output.status;
+
+    if !stdout.is_empty() {
+        info!("[git sparse-checkout]: {}", stdout);
+    }
+
+    if !stderr.is_empty() {
+        error!("[git sparse-checkout]: {}", stderr);
+    }
+
+    if !status.success() {
+        // Clean up the temporary directory if sparse-checkout fails
+        info!(
+            "Cleaning up after failed sparse-checkout: {}",
+            temp_path.display()
+        );
+        let _ = fs::remove_dir_all(&temp_path);
+        return Err(app_err!("Failed to set sparse-checkout"));
+    }
+
+    let output = cmd!["git", "checkout", "--quiet", &commit]
+        .current_dir(&temp_path)
+        .envs(proxy.env())
+        .output()?;
+    if !output.status.success() {
+        let _ = fs::remove_dir_all(&temp_path);
+        return Err(app_err!(
+            "Failed to check out {}.\n Git Checkout output: {}",
+            commit,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    // Check if the plugins directory exists
+    let temp_plugins_dir = temp_path.join("flint-plugins").join(kind_str);
+    info!(
+        "Checking for plugins directory: {}",
+        temp_plugins_dir.display()
+    );
+
+    if !temp_plugins_dir.exists() {
+        error!(
+            "Plugins directory not found: {}",
+            temp_plugins_dir.display()
+        );
+        _ = fs::remove_dir_all(&temp_path).map_err(|e| {
+            app_err!(
+                "Failed to clean up temporary directory.\nError message: {}",
+                e
+            )
+        });
+        return Err(app_err!(
+            "Plugins directory not found: {}",
+            temp_plugins_dir.display()
+        ));
+    }
+
+    // Copy each requested plugin to the final destination
+    for id in &plugin_ids {
+        let src_plugin_path = temp_plugins_dir.join(id);
+        let dest_plugin_path = kind_path.join(id);
+
+        if src_plugin_path.exists() {
+            // Remove the destination plugin if it already exists
+            if dest_plugin_path.exists() {
+                fs::remove_dir_all(&dest_plugin_path)?;
+            }
+
+            // Copy the plugin directory recursively
+            copy_dir_all(&src_plugin_path, &dest_plugin_path)?;
+        } else {
+            warn!("Plugin not found: {}", src_plugin_path.display());
+        }
+    }
+
+    // Clean up the temporary directory
+    info!("Cleaning up temporary directory: {}", temp_path.display());
+    fs::remove_dir_all(&temp_path)?;
+
+    success!("Successfully downloaded {} plugins", kind_path.display());
+
+    Ok(commit)
+}
+
+// Helper function to recursively copy directories
+fn copy_dir_all(src: &Path, dst: &Path) -> Result<(), Box<dyn Error>> {
+    if !dst.exists() {
+        fs::create_dir_all(dst)?;
+    }
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let ty = entry.file_type()?;
+        let path = entry.path();
+
+        let dst_path = dst.join(entry.file_name());
+
+        if ty.is_dir() {
+            copy_dir_all(&path, &dst_path)?;
+        } else {
+            fs::copy(&path, &dst_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Downloads the plugins of the config at the commits pinned in flint.lock, and pins
+/// the ones that aren't yet. The plugins in `update` are downloaded at the latest
+/// commit of their source instead, which is all of them if it's empty.
+pub fn download_plugins_from_config(
+    toml: &Config,
+    update: Option<&[String]>,
+) -> Result<(), Box<dyn Error>> {
+    info!("Loading configuration from flint.toml");
+
+    if *get_flag!(offline) {
+        warn!("Running offline, so no plugins are downloaded and the installed ones are used");
+        return use_installed_plugins(toml);
+    }
+
+    let proxy = Proxy::new(toml.flint.network.as_ref());
+    let lock_path = Lockfile::path(get_flag!(config_path));
+    let lockfile = Lockfile::load(&lock_path)?;
+    let mut locked = Lockfile::default();
+
+    let plugins = configured_plugins(toml);
+    for id in update.unwrap_or_default() {
+        if !plugins.iter().any(|(_, ids)| ids.contains(&id)) {
+            warn!("{} isn't configured in flint.toml, so it isn't updated", id);
+        }
+    }
+
+    info!("Starting download of all configured plugins");
+    for (kind, ids) in plugins {
+        info!("Found {} {} plugins in configuration", ids.len(), kind);
+
+        // Plugins pinned to the same commit of a source are downloaded with one clone
+        let mut downloads: BTreeMap<(PluginSource, String), Vec<&String>> = BTreeMap::new();
+        for id in ids {
+            let source = PluginSource::of(toml, &kind, id)?;
+            let is_updated = update.is_some_and(|ids| ids.is_empty() || ids.contains(id));
+            let revision = match lockfile.commit(&kind, id, &source) {
+                Some(commit) if !is_updated => commit.to_string(),
+                _ => source.revision(),
+            };
+            downloads.entry((source, revision)).or_default().push(id);
+        }
+
+        for ((source, revision), ids) in downloads {
+            info!("Source repository: {}", source.git);
+            info!("Revision: {}", revision);
+            let commit = match clone_plugin_folders(
+                &source.git,
+                kind.clone(),
+                ids.clone(),
+                &revision,
+                &proxy,
+            ) {
+                Ok(commit) => commit,
+                // The plugins downloaded before are pinned at their new commits, and
+                // the others stay pinned as they were
+                Err(FlintError::Network(_)) => {
+                    warn!(
+                        "{} can't be reached, so flint continues offline with the installed plugins",
+                        source.git
+                    );
+                    set_flag!(offline, true);
+                    lockfile.updated(&locked).write(&lock_path)?;
+                    return use_installed_plugins(toml);
+                }
+                Err(err) => {
+                    lockfile.updated(&locked).write(&lock_path)?;
+                    return Err(err.into());
+                }
+            };
+            locked
+                .plugins
+                .extend(ids.into_iter().map(|id| LockedPlugin {
+                    id: id.clone(),
+                    kind: kind.to_string(),
+                    source: source.clone(),
+                    commit: commit.clone(),
+                }));
+            success!("Completed downloading {} plugins", kind.to_string());
+        }
+    }
+
+    locked
+        .plugins
+        .sort_by(|a, b| (&a.kind, &a.id).cmp(&(&b.kind, &b.id)));
+    locked.write(&lock_path)?;
+    success!(
+        "All plugins downloaded and pinned in {}",
+        lock_path.display()
+    );
+
+    Ok(())
+}
+
+/// The ids of the plugins configured in flint.toml, by kind
+fn configured_plugins(toml: &Config) -> [(PluginKind, Vec<&String>); 6] {
+    [
+        (PluginKind::Test, toml.tests.keys().collect()),
+        (PluginKind::Lint, toml.rules.keys().collect()),
+        (PluginKind::Fmt, toml.fmt.keys().collect()),
+        (PluginKind::Audit, toml.audit.keys().collect()),
+        (PluginKind::Ci, toml.ci.plugin_ids()),
+        (PluginKind::Report, toml.report_plugin_ids()),
+    ]
+}
+
+/// Checks that the configured plugins are installed, for when they can't be downloaded
+fn use_installed_plugins(toml: &Config) -> Result<(), Box<dyn Error>> {
+    let plugins_dir = get_flag!(plugins_dir).clone();
+    let missing = configured_plugins(toml)
+        .into_iter()
+        .flat_map(|(kind, ids)| {
+            let kind_path = plugins_dir.join(kind.to_string());
+            ids.into_iter()
+                .filter(move |id| !kind_path.join(id).exists())
+                .cloned()
+        })
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        return Err(app_err!(
+            "These plugins aren't installed and can't be downloaded offline: {}",
+            missing.join(", ")
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Whether git failed because the network or the host can't be reached, rather
+/// than because of the repository
+fn is_network_error(stderr: &str) -> bool {
+    const NETWORK_ERRORS: [&str; 7] = [
+        "could not resolve host",
+        "could not resolve proxy",
+        "failed to connect",
+        "connection timed out",
+        "connection refused",
+        "network is unreachable",
+        "temporary failure in name resolution",
+    ];
+    let stderr = stderr.to_lowercase();
+    NETWORK_ERRORS.iter().any(|error| stderr.contains(error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installs_without_the_report_formats() {
+        let plugins_dir =
+            std::env::temp_dir().join(format!("flint-plugins-{}", std::process::id()));
+        fs::create_dir_all(plugins_dir.join("report").join("json")).unwrap();
+        set_flag!(plugins_dir, plugins_dir.clone());
+        set_flag!(offline, true);
+
+        let toml: Config = toml::from_str(
+            r#"
+            common = {}
+            rules = {}
+            tests = {}
+            config = {}
+
+            [flint]
+            version = 1
+
+            [report.json]
+            output_path = "reports"
+
+            [report.html]
+            title = "flint report"
+
+            [report.markdown]
+            step_summary = true
+            "#,
+        )
+        .unwrap();
+
+        let ids = configured_plugins(&toml)
+            .into_iter()
+            .flat_map(|(_, ids)| ids)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["json"]);
+        assert!(download_plugins_from_config(&toml, None).is_ok());
+        fs::remove_dir_all(&plugins_dir).unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    error::{AppResult, FlintError},
+    fix::FixEdit,
+    plugin::Plugin,
+};
+use mlua::{Function, LuaSerdeExt};
+use serde::{Deserialize, Serialize};
+use std::process::Output;
+
+use super::vm;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestCaseOutput {
+    pub file_name: String,
+    pub line_no: Option<u32>, // Default values if not available
+    pub column_no: Option<u32>,
+    pub success: bool, // Converted from assertion.status == "passed"
+    pub error_message: Option<String>,
+    /// The rule a lint finding broke, like "no-unused-vars"
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    /// The severity of a lint finding: "error", "warning" or "note"
+    #[serde(default)]
+    pub severity: Option<String>,
+    /// Edits fixing a lint finding, which `flint fix` applies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fixes: Vec<FixEdit>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PluginEvalOutput {
+    pub tests_passed: u32,
+    pub total_tests: u32,
+    pub passing_percentage: f32,
+    pub test_results: Vec<TestCaseOutput>,
+}
+
+pub fn eval(plugin: &Plugin, output: Output) -> AppResult<PluginEvalOutput> {
+    let _span = tracing::info_span!("eval", plugin = %plugin.details.id).entered();
+    let lua = vm::load(plugin, &["run.lua"])?;
+    let eval: Function = lua.globals().get("Eval")?;
+
+    // Setting the output of a large run can exceed the memory limit of the plugin
+    let evaluation_state = lua.create_table()?;
+    evaluation_state.set("stdout", String::from_utf8_lossy(&output.stdout))?;
+    evaluation_state.set("stderr", String::from_utf8_lossy(&output.stderr))?;
+    evaluation_state.set("status", output.status.code())?;
+    evaluation_state.set("success", output.status.success())?;
+
+    let eval_output = eval.call::<mlua::Value>(evaluation_state)?;
+
+    let eval_output: PluginEvalOutput = lua.from_value(eval_output).map_err(|err| {
+        FlintError::Err(format!(
+            "Eval of {} didn't return the results of the run: {}",
+            plugin.details.id, err
+        ))
+    })?;
+
+    Ok(eval_output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{exec::vm::tests::limited_plugin, PluginKind};
+    use std::process::ExitStatus;
+
+    #[test]
+    fn fails_when_the_output_exceeds_the_memory_limit() {
+        let (plugin, _) = limited_plugin(
+            "limited-eval",
+            PluginKind::Lint,
+            "function Eval() return {} end",
+        );
+        let output = Output {
+            status: ExitStatus::default(),
+            stdout: vec![b'x'; 4 * 1024 * 1024],
+            stderr: Vec::new(),
+        };
+
+        let err = plugin.eval(output).unwrap_err();
+        assert!(matches!(
+            err,
+            FlintError::PluginRuntime { ref source, .. } if matches!(**source, FlintError::MemoryLimit)
+        ));
+        std::fs::remove_dir_all(&plugin.path).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    app_err,
+    config::Config,
+    error::{AppResult, FlintError},
+    plugin::{
+        ci::Pipeline,
+        deps::collect_dependencies,
+        helpers::emit::{take_emitted, FileContents},
+        Plugin, PluginKind,
+    },
+    timings::{self, Phase},
+};
+use mlua::{Function, LuaSerdeExt};
+use std::{collections::HashMap, sync::Arc};
+
+use super::vm;
+
+/// The files generated by the plugin, by their path relative to the project root.
+/// Files streamed with `emit_file` are included with the returned ones.
+pub fn generate(plugin: &Plugin, toml: &Arc<Config>) -> AppResult<HashMap<String, FileContents>> {
+    let lua = vm::load(plugin, &["generate.lua", "validate.lua"])?;
+    let plugin_config = plugin.get_config_lua(&lua, toml)?;
+    let generate: Function = lua.globals().get("Generate")?;
+    let validate: Function = lua.globals().get("Validate")?;
+
+    let timing = timings::time(Phase::Validate, &plugin.details.id);
+    let validate_success = validate.call::<mlua::Value>(&plugin_config)?;
+    drop(timing);
+
+    let validate_success: bool = lua.from_value(validate_success).map_err(|err| {
+        FlintError::Err(format!(
+            "Validate of {} didn't return a boolean: {}",
+            plugin.details.id, err
+        ))
+    })?;
+
+    if !validate_success {
+        return Err(app_err!("Plugin configuration validation failed"));
+    }
+
+    let timing = timings::time(Phase::Generate, &plugin.details.id);
+    let generate_results = if plugin.kind == PluginKind::Ci {
+        let pipeline = lua.to_value(&pipeline(plugin, toml)?)?;
+        generate.call::<mlua::Value>((plugin_config, pipeline))
+    } else {
+        generate.call::<mlua::Value>(plugin_config)
+    }?;
+    drop(timing);
+
+    let generate_results: HashMap<String, String> =
+        lua.from_value(generate_results).map_err(|err| {
+            FlintError::Err(format!(
+                "Generate of {} didn't return the contents of files by their path: {}",
+                plugin.details.id, err
+            ))
+        })?;
+
+    let mut files = generate_results
+        .into_iter()
+        .map(|(path, contents)| (path, FileContents::Text(contents)))
+        .collect::<HashMap<_, _>>();
+    files.extend(take_emitted(&lua)?);
+    Ok(files)
+}
+
+/// The pipeline a CI plugin generates its workflows from, with the steps of the other
+/// configured plugins
+pub fn pipeline(plugin: &Plugin, toml: &Arc<Config>) -> AppResult<Pipeline> {
+    let active_plugins = crate::plugin::list_from_config(toml)?;
+
+    // Filter out CI plugins from active_plugins to avoid circular dependencies
+    let active_plugins = active_plugins
+        .into_iter()
+        .filter(|p| p.kind != PluginKind::Ci)
+        .collect::<Vec<_>>();
+
+    let dependencies = collect_dependencies(&active_plugins)?;
+    let options = toml.ci.pipeline_options(&plugin.details.id);
+    let has_formatters = active_plugins
+        .iter()
+        .any(|plugin| plugin.kind == PluginKind::Fmt);
+    Ok(Pipeline::new(options, dependencies, has_formatters))
+}
@@ -1,4 +1,7 @@
 pub mod eval;
 pub mod generate;
+pub mod repl;
 pub mod report;
 pub mod run;
+pub mod traceback;
+pub mod vm;
@@ -0,0 +1,181 @@
+use crate::{
+    app_err,
+    config::Config,
+    error::AppResult,
+    plugin::{helpers::MODULES, Plugin},
+};
+use mlua::{Function, LuaSerdeExt, MultiValue, Table, Value};
+use std::sync::Arc;
+
+use super::vm::{self, PooledVm};
+
+/// The scripts of a plugin loaded into its console, if it has them
+const PLUGIN_SCRIPTS: [&str; 3] = ["generate.lua", "validate.lua", "run.lua"];
+
+/// What evaluating a line of the console did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Evaluated {
+    /// The values the line returned, formatted like Lua shows them
+    Values(Vec<String>),
+    /// The line doesn't end a statement, like `function f()`, so it's evaluated with
+    /// the next ones
+    Incomplete,
+}
+
+/// A Lua VM for trying out the helpers plugins use. The helper modules are globals,
+/// so `path.cwd()` works without requiring `path`, and `config` is the config.
+pub struct Console {
+    lua: PooledVm,
+    /// The lines of a statement that isn't complete yet
+    pending: String,
+}
+
+impl Console {
+    /// A console with the scripts of the plugin loaded and its section of the config
+    /// as `config`, or without a plugin, with the whole config as `config`
+    pub fn new(plugin: Option<&Plugin>, toml: Option<&Arc<Config>>) -> AppResult<Self> {
+        let lua = match plugin {
+            Some(plugin) => {
+                let scripts = PLUGIN_SCRIPTS
+                    .into_iter()
+                    .filter(|script| plugin.path.join(script).exists())
+                    .collect::<Vec<_>>();
+                vm::load(plugin, &scripts)?
+            }
+            None => vm::load_scripts(Vec::new())?,
+        };
+
+        let globals = lua.globals();
+        let loaded: Table = globals.get::<Table>("package")?.get("loaded")?;
+        for module in MODULES {
+            globals.set(module, loaded.get::<Value>(module)?)?;
+        }
+
+        let config = match (plugin, toml) {
+            (Some(plugin), Some(toml)) => {
+                if plugin.config(toml).is_none() {
+                    let [table, id] = plugin.config_keys();
+                    return Err(app_err!(
+                        "{} isn't configured, add a [{}.{}] section to flint.toml",
+                        id,
+                        table,
+                        id
+                    ));
+                }
+                Value::Table(plugin.get_config_lua(&lua, toml)?)
+            }
+            (None, Some(toml)) => lua.to_value(toml.as_ref())?,
+            (_, None) => Value::Nil,
+        };
+        globals.set("config", config)?;
+        drop(globals);
+
+        Ok(Self {
+            lua,
+            pending: String::new(),
+        })
+    }
+
+    /// Whether the last line didn't end a statement, so the next one continues it
+    pub fn is_incomplete(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Evaluates a line, with the lines before it if they didn't end a statement.
+    /// Expressions, like `1 + 1`, return their values.
+    pub fn eval(&mut self, line: &str) -> AppResult<Evaluated> {
+        let source = format!("{}{}\n", self.pending, line);
+        self.pending.clear();
+
+        let expression = self
+            .lua
+            .load(format!("return {}", source))
+            .set_name("=repl")
+            .eval::<MultiValue>();
+        let values = match expression {
+            Err(mlua::Error::SyntaxError { .. }) => {
+                match self
+                    .lua
+                    .load(&source)
+                    .set_name("=repl")
+                    .eval::<MultiValue>()
+                {
+                    Err(mlua::Error::SyntaxError {
+                        incomplete_input: true,
+                        ..
+                    }) => {
+                        self.pending = source;
+                        return Ok(Evaluated::Incomplete);
+                    }
+                    result => result?,
+                }
+            }
+            result => result?,
+        };
+
+        let values = values
+            .into_iter()
+            .map(|value| self.format(value))
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Evaluated::Values(values))
+    }
+
+    /// Formats a value like Lua shows it, with strings quoted and tables as JSON if
+    /// they can be
+    fn format(&self, value: Value) -> AppResult<String> {
+        match &value {
+            Value::String(string) => return Ok(format!("{:?}", string.to_string_lossy())),
+            Value::Table(_) => {
+                if let Ok(json) = serde_json::to_string_pretty(&value) {
+                    return Ok(json);
+                }
+            }
+            _ => (),
+        }
+        let tostring: Function = self.lua.globals().get("tostring")?;
+        Ok(tostring.call::<String>(value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(console: &mut Console, line: &str) -> Vec<String> {
+        match console.eval(line).unwrap() {
+            Evaluated::Values(values) => values,
+            Evaluated::Incomplete => panic!("{:?} is incomplete", line),
+        }
+    }
+
+    #[test]
+    fn evaluates_expressions_and_statements() {
+        let mut console = Console::new(None, None).unwrap();
+        assert_eq!(values(&mut console, "1 + 1"), vec!["2"]);
+        assert_eq!(values(&mut console, "x = 'a'"), Vec::<String>::new());
+        assert_eq!(values(&mut console, "x, #x"), vec!["\"a\"", "1"]);
+        assert_eq!(values(&mut console, "{ 1, 2 }"), vec!["[\n  1,\n  2\n]"]);
+    }
+
+    #[test]
+    fn continues_incomplete_statements() {
+        let mut console = Console::new(None, None).unwrap();
+        assert_eq!(console.eval("function f()").unwrap(), Evaluated::Incomplete);
+        assert!(console.is_incomplete());
+        assert_eq!(console.eval("return 3").unwrap(), Evaluated::Incomplete);
+        assert_eq!(values(&mut console, "end"), Vec::<String>::new());
+        assert!(!console.is_incomplete());
+        assert_eq!(values(&mut console, "f()"), vec!["3"]);
+    }
+
+    #[test]
+    fn has_the_helpers_as_globals() {
+        let mut console = Console::new(None, None).unwrap();
+        assert_eq!(
+            values(&mut console, "type(path.join), type(json), config"),
+            vec!["\"function\"", "\"table\"", "nil"]
+        );
+        assert!(console.eval("error('failed')").is_err());
+        assert!(!console.is_incomplete());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    app_err,
+    config::Config,
+    error::{AppResult, FlintError},
+    plugin::{
+        helpers::emit::{take_emitted, FileContents},
+        Plugin, PluginKind,
+    },
+    timings::{self, Phase},
+};
+use mlua::{Function, LuaSerdeExt};
+use std::{collections::HashMap, sync::Arc};
+
+use super::eval::PluginEvalOutput;
+use super::vm;
+
+pub fn report(
+    plugin: &Plugin,
+    toml: &Arc<Config>,
+    output: &PluginEvalOutput,
+) -> AppResult<HashMap<String, FileContents>> {
+    if plugin.kind != PluginKind::Report {
+        return Err(app_err!("{} is not a reporting plugin.", plugin.details.id));
+    }
+
+    let lua = vm::load(plugin, &["run.lua"])?;
+    let plugin_config = plugin.get_config_lua(&lua, toml)?;
+    let report: Function = lua.globals().get("Run")?;
+
+    // Converting the output of a large run can exceed the memory limit of the plugin
+    let report_state = lua.create_table()?;
+    report_state.set("config", plugin_config)?;
+    report_state.set("output", lua.to_value(&output)?)?;
+
+    let timing = timings::time(Phase::Report, &plugin.details.id);
+    let report_results = report.call::<mlua::Value>(report_state)?;
+    drop(timing);
+
+    let report_results: HashMap<String, String> =
+        lua.from_value(report_results).map_err(|err| {
+            FlintError::Err(format!(
+                "Run of {} didn't return the contents of reports by their path: {}",
+                plugin.details.id, err
+            ))
+        })?;
+
+    let mut files = report_results
+        .into_iter()
+        .map(|(path, contents)| (path, FileContents::Text(contents)))
+        .collect::<HashMap<_, _>>();
+    files.extend(take_emitted(&lua)?);
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{exec::eval::TestCaseOutput, exec::vm::tests::limited_plugin};
+
+    #[test]
+    fn fails_when_the_output_exceeds_the_memory_limit() {
+        let (plugin, toml) = limited_plugin(
+            "limited-report",
+            PluginKind::Report,
+            "function Run() return {} end",
+        );
+        let result = TestCaseOutput {
+            file_name: "src/index.js".into(),
+            line_no: Some(1),
+            column_no: Some(1),
+            success: false,
+            error_message: Some("x".repeat(100)),
+            rule_id: None,
+            severity: None,
+            fixes: Vec::new(),
+        };
+        let output = PluginEvalOutput {
+            tests_passed: 0,
+            total_tests: 20_000,
+            passing_percentage: 0.0,
+            test_results: vec![result; 20_000],
+        };
+
+        let err = plugin.report(&toml, &output).unwrap_err();
+        assert!(matches!(
+            err,
+            FlintError::PluginRuntime { ref source, .. } if matches!(**source, FlintError::MemoryLimit)
+        ));
+        std::fs::remove_dir_all(&plugin.path).unwrap();
+    }
+}
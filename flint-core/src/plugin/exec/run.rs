@@ -0,0 +1,80 @@
+use crate::{
+    app_err,
+    config::Config,
+    error::{AppResult, FlintError},
+    get_flag, info,
+    plugin::{helpers::tmp::TempScope, Plugin},
+    timings::{self, Phase},
+};
+use mlua::{Function, LuaSerdeExt};
+use std::{process::Command, sync::Arc};
+
+use super::{eval::PluginEvalOutput, vm};
+
+pub fn run(plugin: &Plugin, toml: &Arc<Config>) -> AppResult<Vec<String>> {
+    command(plugin, toml, "Run")
+}
+
+/// The command of a formatter plugin checking that the files are formatted, without
+/// changing them
+pub fn check(plugin: &Plugin, toml: &Arc<Config>) -> AppResult<Vec<String>> {
+    command(plugin, toml, "Check")
+}
+
+/// The command returned by a function of run.lua
+fn command(plugin: &Plugin, toml: &Arc<Config>, function: &str) -> AppResult<Vec<String>> {
+    let _span = tracing::info_span!("command", plugin = %plugin.details.id, function).entered();
+    let lua = vm::load(plugin, &["run.lua"])?;
+    let plugin_config = plugin.get_config_lua(&lua, toml)?;
+    let run: Function = lua.globals().get(function)?;
+
+    let run_success = run.call::<mlua::Value>(plugin_config)?;
+
+    let run_command: Vec<String> = lua.from_value(run_success).map_err(|err| {
+        FlintError::Err(format!(
+            "{} of {} didn't return a command: {}",
+            function, plugin.details.id, err
+        ))
+    })?;
+
+    Ok(run_command)
+}
+
+/// Runs a lint or test plugin: its command is run in the project and the output
+/// evaluated into findings. Nothing is asked before running the command and no
+/// reports are written, which frontends do themselves if they need to.
+pub fn run_plugin(plugin: &Plugin, toml: &Arc<Config>) -> AppResult<PluginEvalOutput> {
+    let _timing = timings::time(Phase::Run, &plugin.details.id);
+    let _temp = TempScope::open();
+    let command = plugin.run(toml)?;
+    execute(plugin, &command)
+}
+
+/// Runs a formatter plugin, formatting the files of the project or only checking that
+/// they're formatted. The files it reports as failed weren't formatted before it ran.
+pub fn run_formatter(
+    plugin: &Plugin,
+    toml: &Arc<Config>,
+    check: bool,
+) -> AppResult<PluginEvalOutput> {
+    let _timing = timings::time(Phase::Run, &plugin.details.id);
+    let _temp = TempScope::open();
+    let command = match check {
+        true => plugin.check(toml)?,
+        false => plugin.run(toml)?,
+    };
+    execute(plugin, &command)
+}
+
+fn execute(plugin: &Plugin, command: &[String]) -> AppResult<PluginEvalOutput> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(app_err!("{} has no command to run", plugin.details.id));
+    };
+
+    info!("Running command: {:#?}", command);
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(get_flag!(current_dir).as_path())
+        .output()?;
+    plugin.eval(output)
+}
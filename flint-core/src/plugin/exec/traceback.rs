@@ -0,0 +1,196 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// Where a Lua error of a plugin happened and the functions that were running, read
+/// from its message, like
+/// "validate.lua:2: attempt to index nil\nstack traceback:\n\tvalidate.lua:2: in function 'check'"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LuaTrace {
+    /// The message without the location of the error
+    pub message: String,
+    /// The innermost frame first, which is where the error happened
+    pub frames: Vec<TraceFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceFrame {
+    pub path: PathBuf,
+    pub line: usize,
+    /// The function that was running, if it has a name
+    pub function: Option<String>,
+}
+
+impl LuaTrace {
+    /// The trace of an error message, if it has a location in a script. Frames of
+    /// functions implemented in Rust, like the helpers, have none and are left out.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (head, traceback) = text.split_once("stack traceback:").unwrap_or((text, ""));
+
+        let mut frames = traceback
+            .lines()
+            .filter_map(|line| {
+                let (path, line, rest) = location(line)?;
+                let function = rest
+                    .trim()
+                    .split_once('\'')
+                    .and_then(|(_, name)| name.strip_suffix('\''))
+                    .map(str::to_string);
+                Some(TraceFrame {
+                    path,
+                    line,
+                    function,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // The error happened on the line of its message, which is also the innermost
+        // frame unless it's a helper that failed
+        let mut message = head.trim().to_string();
+        if let Some((path, line, _)) = head.lines().find_map(location) {
+            let prefix = format!("{}:{}:", path.display(), line);
+            message = message
+                .replacen(&format!("{} ", prefix), "", 1)
+                .replacen(&prefix, "", 1);
+            let is_innermost = frames
+                .first()
+                .is_some_and(|frame| frame.path == path && frame.line == line);
+            if !is_innermost {
+                frames.insert(
+                    0,
+                    TraceFrame {
+                        path,
+                        line,
+                        function: None,
+                    },
+                );
+            }
+        }
+
+        (!frames.is_empty()).then_some(Self { message, frames })
+    }
+
+    /// The frame the error happened in
+    pub fn location(&self) -> &TraceFrame {
+        &self.frames[0]
+    }
+}
+
+impl TraceFrame {
+    /// The lines of the script around the line of the frame, by their number. There
+    /// are none if the script can't be read.
+    pub fn source(&self, context: usize) -> Vec<(usize, String)> {
+        let Ok(contents) = fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        let first = self.line.saturating_sub(context).max(1);
+        contents
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.to_string()))
+            .skip(first - 1)
+            .take(self.line + context + 1 - first)
+            .collect()
+    }
+}
+
+/// The script and line of a location like "/plugins/eslint/run.lua:12: rest", with the
+/// rest of the text after it
+fn location(text: &str) -> Option<(PathBuf, usize, &str)> {
+    let mut searched = 0;
+    while let Some(found) = text[searched..].find(".lua:") {
+        let end = searched + found + ".lua".len();
+        searched = end;
+
+        let after = &text[end + 1..];
+        let digits = after
+            .find(|char: char| !char.is_ascii_digit())
+            .unwrap_or(after.len());
+        let Ok(line) = after[..digits].parse() else {
+            continue;
+        };
+        let Some(rest) = after[digits..].strip_prefix(':') else {
+            continue;
+        };
+
+        let start = text[..end]
+            .rfind(char::is_whitespace)
+            .map_or(0, |space| space + 1);
+        let path = text[start..end].trim_start_matches('@');
+        return Some((PathBuf::from(path), line, rest.trim_start()));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(path: &str, line: usize, function: Option<&str>) -> TraceFrame {
+        TraceFrame {
+            path: PathBuf::from(path),
+            line,
+            function: function.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_runtime_errors() {
+        let text = "Lua error: runtime error: /plugins/eslint/validate.lua:2: attempt to index nil with 'field'\n\
+            stack traceback:\n\
+            \t[C]: in ?\n\
+            \t/plugins/eslint/validate.lua:2: in function 'check'\n\
+            \t/plugins/eslint/validate.lua:6: in function 'Validate'";
+        let trace = LuaTrace::parse(text).unwrap();
+        assert_eq!(
+            trace.message,
+            "Lua error: runtime error: attempt to index nil with 'field'"
+        );
+        assert_eq!(
+            trace.frames,
+            vec![
+                frame("/plugins/eslint/validate.lua", 2, Some("check")),
+                frame("/plugins/eslint/validate.lua", 6, Some("Validate")),
+            ]
+        );
+    }
+
+    #[test]
+    fn starts_at_the_line_of_the_message() {
+        let text = "/plugins/jest/run.lua:12: bad config\nstack traceback:\n\t[C]: in function 'error'\n\t/plugins/jest/run.lua:30: in function 'Run'";
+        let trace = LuaTrace::parse(text).unwrap();
+        assert_eq!(trace.message, "bad config");
+        assert_eq!(trace.location(), &frame("/plugins/jest/run.lua", 12, None));
+        assert_eq!(trace.frames.len(), 2);
+    }
+
+    #[test]
+    fn ignores_messages_without_a_location() {
+        assert_eq!(LuaTrace::parse("eslint was not allowed to run"), None);
+        assert_eq!(LuaTrace::parse("config.lua:x: not a line"), None);
+    }
+
+    #[test]
+    fn reads_the_source_around_the_line() {
+        let dir = std::env::temp_dir().join(format!("flint-traceback-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.lua");
+        fs::write(&path, "one\ntwo\nthree\nfour\nfive\n").unwrap();
+
+        let frame = TraceFrame {
+            path,
+            line: 2,
+            function: None,
+        };
+        let lines = frame.source(1);
+        assert_eq!(
+            lines,
+            vec![
+                (1, "one".to_string()),
+                (2, "two".to_string()),
+                (3, "three".to_string())
+            ]
+        );
+        assert_eq!(frame.source(5).len(), 5);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,290 @@
+use mlua::{ChunkMode, Compiler, Lua, Table, Value, VmState};
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    cancel::is_cancelled,
+    config::Config,
+    error::{AppResult, FlintError},
+    plugin::{
+        helpers::{add_helper_globals, emit::take_emitted, tmp::TempScope},
+        Plugin,
+    },
+};
+
+/// The most VMs kept idle for a set of scripts. More are created while more plugins
+/// run at once, but dropped once they finish.
+const MAX_IDLE_VMS: usize = 4;
+
+/// The memory a plugin's VM may use, in megabytes, unless flint.toml sets another limit
+pub const DEFAULT_MEMORY_LIMIT_MB: u64 = 512;
+
+/// The registry key of the globals a VM had once its scripts were loaded
+const GLOBALS_SNAPSHOT: &str = "flint.globals";
+
+/// Compiled scripts by path, with when they were last modified
+type Bytecode = HashMap<PathBuf, (SystemTime, Arc<Vec<u8>>)>;
+
+/// Idle VMs by the scripts they loaded, with when the scripts were last modified
+type Pool = HashMap<Vec<PathBuf>, (SystemTime, Vec<Lua>)>;
+
+static BYTECODE: LazyLock<Mutex<Bytecode>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static POOL: LazyLock<Mutex<Pool>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static MEMORY_LIMITS: LazyLock<RwLock<MemoryLimits>> =
+    LazyLock::new(|| RwLock::new(MemoryLimits::default()));
+
+/// The memory limits in megabytes of `[flint] memory_limit` and of the `memory_limit`
+/// of the plugins' sections, where 0 is no limit
+#[derive(Default)]
+struct MemoryLimits {
+    default: Option<u64>,
+    plugins: HashMap<String, u64>,
+}
+
+/// A VM of the pool, which is returned to it once dropped
+pub struct PooledVm {
+    lua: Lua,
+    scripts: Vec<PathBuf>,
+    modified: SystemTime,
+    /// Removes the temp paths created outside of a plugin run once the VM is dropped
+    _temp: TempScope,
+}
+
+impl Deref for PooledVm {
+    type Target = Lua;
+
+    fn deref(&self) -> &Lua {
+        &self.lua
+    }
+}
+
+impl Drop for PooledVm {
+    fn drop(&mut self) {
+        // The next run gets the globals as they were after loading, so runs can't see
+        // each other's state. VMs that can't be reset aren't reused.
+        let _ = take_emitted(&self.lua);
+        if restore_globals(&self.lua).is_err() {
+            return;
+        }
+
+        let mut pool = POOL.lock().unwrap();
+        let (modified, idle) = pool
+            .entry(std::mem::take(&mut self.scripts))
+            .or_insert_with(|| (self.modified, Vec::new()));
+        if *modified == self.modified && idle.len() < MAX_IDLE_VMS {
+            idle.push(self.lua.clone());
+        }
+    }
+}
+
+/// A Lua VM with the helper globals and the given scripts of a plugin loaded. Its
+/// allocations are limited to the plugin's memory limit, so a runaway script fails
+/// with a memory error instead of using up the memory of flint.
+pub fn load(plugin: &Plugin, scripts: &[&str]) -> AppResult<PooledVm> {
+    let paths = scripts
+        .iter()
+        .map(|script| plugin.path.join(script))
+        .collect::<Vec<_>>();
+    let vm = load_scripts(paths).map_err(|source| FlintError::PluginLoad {
+        plugin: plugin.details.id.clone(),
+        source: Box::new(source),
+    })?;
+    vm.set_memory_limit(memory_limit(&plugin.details.id))?;
+    Ok(vm)
+}
+
+/// Sets the memory limits of the plugins from the config. Frontends set them again
+/// when they load the config for a run, as a daemon runs several.
+pub fn set_memory_limits(config: &Config) {
+    let sections = [
+        &config.rules,
+        &config.tests,
+        &config.fmt,
+        &config.audit,
+        &config.report,
+    ];
+    let mut plugins = HashMap::new();
+    for (id, section) in sections.into_iter().flatten() {
+        if let Some(limit) = section_memory_limit(section) {
+            plugins.insert(id.clone(), limit);
+        }
+    }
+    for id in config.ci.plugin_ids() {
+        if let Some(limit) = config.ci.plugin_config(id).and_then(section_memory_limit) {
+            plugins.insert(id.clone(), limit);
+        }
+    }
+
+    *MEMORY_LIMITS.write().unwrap() = MemoryLimits {
+        default: config.flint.memory_limit,
+        plugins,
+    };
+}
+
+fn section_memory_limit(section: &toml::Value) -> Option<u64> {
+    let limit = section.get("memory_limit")?.as_integer()?;
+    u64::try_from(limit).ok()
+}
+
+/// The memory limit of the plugin in bytes, where 0 is no limit
+fn memory_limit(plugin_id: &str) -> usize {
+    let limits = MEMORY_LIMITS.read().unwrap();
+    let megabytes = limits
+        .plugins
+        .get(plugin_id)
+        .copied()
+        .or(limits.default)
+        .unwrap_or(DEFAULT_MEMORY_LIMIT_MB);
+    usize::try_from(megabytes.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX)
+}
+
+/// A Lua VM with the helper globals and the given scripts loaded. VMs are reused
+/// until one of their scripts changes, and scripts are only compiled once.
+pub fn load_scripts(scripts: Vec<PathBuf>) -> AppResult<PooledVm> {
+    let modified = scripts
+        .iter()
+        .filter_map(|path| modified(path))
+        .max()
+        .unwrap_or(UNIX_EPOCH);
+
+    let idle = {
+        let mut pool = POOL.lock().unwrap();
+        match pool.get_mut(&scripts) {
+            Some((loaded, idle)) if *loaded == modified => idle.pop(),
+            Some(entry) => {
+                *entry = (modified, Vec::new());
+                None
+            }
+            None => None,
+        }
+    };
+
+    let lua = match idle {
+        Some(lua) => lua,
+        None => new_vm(&scripts)?,
+    };
+    Ok(PooledVm {
+        lua,
+        scripts,
+        modified,
+        _temp: TempScope::open(),
+    })
+}
+
+fn new_vm(scripts: &[PathBuf]) -> AppResult<Lua> {
+    let lua = Lua::new();
+    add_helper_globals(&lua)?;
+
+    // Scripts stop at the next interrupt once flint is quitting
+    lua.set_interrupt(|_| match is_cancelled() {
+        true => Err(mlua::Error::runtime("Cancelled, as flint is quitting")),
+        false => Ok(VmState::Continue),
+    });
+    for path in scripts {
+        lua.load(bytecode(path)?.as_slice())
+            .set_name(format!("@{}", path.display()))
+            .set_mode(ChunkMode::Binary)
+            .exec()?;
+    }
+
+    let snapshot = lua.create_table()?;
+    for pair in lua.globals().pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        snapshot.raw_set(key, value)?;
+    }
+    lua.set_named_registry_value(GLOBALS_SNAPSHOT, snapshot)?;
+    Ok(lua)
+}
+
+/// The compiled script, which is compiled again once it changes
+fn bytecode(path: &Path) -> AppResult<Arc<Vec<u8>>> {
+    let modified = modified(path).unwrap_or(UNIX_EPOCH);
+    if let Some((compiled, bytecode)) = BYTECODE.lock().unwrap().get(path) {
+        if *compiled == modified {
+            return Ok(bytecode.clone());
+        }
+    }
+
+    let source = fs::read_to_string(path)?;
+    let bytecode = Arc::new(Compiler::new().compile(source)?);
+    BYTECODE
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), (modified, bytecode.clone()));
+    Ok(bytecode)
+}
+
+/// Removes the globals set since the snapshot, and restores the ones that were changed
+fn restore_globals(lua: &Lua) -> mlua::Result<()> {
+    let snapshot: Table = lua.named_registry_value(GLOBALS_SNAPSHOT)?;
+    let globals = lua.globals();
+
+    let mut added = Vec::new();
+    for pair in globals.pairs::<Value, Value>() {
+        let (key, _) = pair?;
+        if snapshot.raw_get::<Value>(key.clone())?.is_nil() {
+            added.push(key);
+        }
+    }
+    for key in added {
+        globals.raw_set(key, Value::Nil)?;
+    }
+    for pair in snapshot.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        globals.raw_set(key, value)?;
+    }
+    Ok(())
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::plugin::{PluginDetails, PluginKind};
+
+    /// The config of the plugins of the tests, whose VMs may use 1 MB. It's the same
+    /// for every test, as the memory limits are global.
+    const LIMITED_CONFIG: &str = r#"
+        common = {}
+        tests = {}
+        config = {}
+
+        [flint]
+        version = 1
+
+        [rules.limited-eval]
+        memory_limit = 1
+
+        [report.limited-report]
+        memory_limit = 1
+    "#;
+
+    /// A plugin with the script as its run.lua, whose VM may use 1 MB, with its config
+    pub fn limited_plugin(id: &str, kind: PluginKind, script: &str) -> (Plugin, Arc<Config>) {
+        let path = std::env::temp_dir().join(format!("flint-{}-{}", id, std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("run.lua"), script).unwrap();
+
+        let config: Config = toml::from_str(LIMITED_CONFIG).unwrap();
+        set_memory_limits(&config);
+        let plugin = Plugin {
+            details: PluginDetails {
+                id: id.to_string(),
+                extensions: Vec::new(),
+                version: "0.0.1".into(),
+                author: "flint".into(),
+            },
+            path,
+            kind,
+        };
+        (plugin, Arc::new(config))
+    }
+}
@@ -0,0 +1,178 @@
+use super::exec::vm;
+use super::validate::validate_plugin_structure;
+use super::{Plugin, PluginDetails, PluginKind};
+use crate::config::Config;
+use crate::error::AppResult;
+use crate::timings::{self, Phase};
+use crate::{debug, error, get_flag};
+use directories::ProjectDirs;
+use mlua::{Function, LuaSerdeExt};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::PathBuf,
+    sync::{mpsc, OnceLock},
+};
+use threadpool::ThreadPool;
+
+/// The most plugins loaded at once while they're found
+const DISCOVERY_THREADS: usize = 16;
+
+pub static PLUGINS: OnceLock<BTreeSet<Plugin>> = OnceLock::new();
+pub static PLUGIN_MAP: OnceLock<HashMap<String, BTreeSet<Plugin>>> = OnceLock::new();
+
+pub fn map() -> AppResult<&'static HashMap<String, BTreeSet<Plugin>>> {
+    if let Some(map) = PLUGIN_MAP.get() {
+        return Ok(map);
+    }
+    let mut m = HashMap::new();
+    for plugin in list()? {
+        for extension in &plugin.details.extensions {
+            m.entry(extension.clone())
+                .or_insert_with(BTreeSet::new)
+                .insert(plugin.clone());
+        }
+    }
+    Ok(PLUGIN_MAP.get_or_init(|| m))
+}
+
+pub fn dir() -> PathBuf {
+    if cfg!(debug_assertions) {
+        PathBuf::from("./flint-plugins")
+    } else if let Some(proj_dirs) = ProjectDirs::from("com", "Flint", "flint") {
+        let plugins_path = proj_dirs.data_dir().to_path_buf().join("plugins");
+        // Installing reports the error if the directory still can't be created
+        if let Err(err) = std::fs::create_dir_all(&plugins_path) {
+            error!(
+                "Unable to create the plugins directory {}: {}",
+                plugins_path.display(),
+                err
+            );
+        }
+        plugins_path
+    } else {
+        panic!("Unable to determine project directories");
+    }
+}
+
+pub fn list<'a>() -> AppResult<&'a BTreeSet<Plugin>> {
+    if PLUGINS.get().is_some() {
+        return Ok(PLUGINS.get().unwrap());
+    }
+    let _timing = timings::time(Phase::Discovery, "all plugins");
+
+    let kinds = [
+        ("lint", PluginKind::Lint),
+        ("test", PluginKind::Test),
+        ("fmt", PluginKind::Fmt),
+        ("audit", PluginKind::Audit),
+        ("ci", PluginKind::Ci),
+        ("report", PluginKind::Report),
+    ];
+    let plugin_dirs = kinds.iter().flat_map(|(dir_name, kind)| {
+        let plugins_dir = get_flag!(plugins_dir).join(dir_name);
+        if !plugins_dir.exists() {
+            error!("{} directory does not exist", dir_name);
+            return vec![];
+        }
+
+        let entries = match std::fs::read_dir(&plugins_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!(
+                    "Failed to read {} directory. Error message: {}",
+                    dir_name, e
+                );
+                return vec![];
+            }
+        };
+
+        entries
+            .filter_map(|entry| match entry {
+                Ok(entry) => Some((kind.clone(), entry.path())),
+                Err(err) => {
+                    error!("Error reading directory entry: {}", err);
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    });
+
+    // Plugins are loaded by the workers as they're found, and collected as they finish
+    let thread_pool = ThreadPool::new(DISCOVERY_THREADS);
+    let (sender, receiver) = mpsc::channel();
+    for (kind, path) in plugin_dirs {
+        let sender = sender.clone();
+        thread_pool.execute(move || {
+            let _ = sender.send(load(kind, path));
+        });
+    }
+    drop(sender);
+
+    let plugins = receiver.into_iter().flatten().collect::<BTreeSet<Plugin>>();
+    let x = PLUGINS.get_or_init(|| plugins);
+    Ok(x)
+}
+
+/// Loads the plugin in a directory, logging why if it can't be. Every plugin gets
+/// its own VM, so the globals of one can't leak into another that doesn't define them.
+fn load(kind: PluginKind, path: PathBuf) -> Option<Plugin> {
+    let _timing = timings::time(Phase::Discovery, path.display().to_string());
+    let details = vm::load_scripts(vec![path.join("details.lua")]).and_then(|lua| {
+        let details: Function = lua.globals().get("Details")?;
+        let lua_val = details.call::<mlua::Value>(())?;
+        Ok(lua.from_value::<PluginDetails>(lua_val)?)
+    });
+    let details = match details {
+        Ok(details) => details,
+        Err(err) => {
+            error!("Error loading lua file {}: {}", path.display(), err);
+            return None;
+        }
+    };
+
+    let plugin = Plugin {
+        details,
+        path,
+        kind,
+    };
+    match validate_plugin_structure(&plugin) {
+        Ok(_) => Some(plugin),
+        Err(err) => {
+            error!(
+                "Plugin {} has invalid file structure.\nError message: {}",
+                plugin.details.id, err
+            );
+            None
+        }
+    }
+}
+
+pub fn list_from_config<'a>(config: &Config) -> AppResult<Vec<&'a Plugin>> {
+    let linter_ids = config.rules.keys().collect::<HashSet<&String>>();
+    let tester_ids = config.tests.keys().collect::<HashSet<&String>>();
+    let formatter_ids = config.fmt.keys().collect::<HashSet<&String>>();
+    let auditor_ids = config.audit.keys().collect::<HashSet<&String>>();
+    let ci_ids = config
+        .ci
+        .plugin_ids()
+        .into_iter()
+        .collect::<HashSet<&String>>();
+    let report_ids = config
+        .report_plugin_ids()
+        .into_iter()
+        .collect::<HashSet<&String>>();
+    let plugins = list()?;
+    debug!("Loaded plugins: {:?}", plugins);
+
+    Ok(plugins
+        .iter()
+        .filter(|plugin| {
+            linter_ids.contains(&plugin.details.id)
+                || tester_ids.contains(&plugin.details.id)
+                || formatter_ids.contains(&plugin.details.id)
+                || auditor_ids.contains(&plugin.details.id)
+                || ci_ids.contains(&plugin.details.id)
+                || report_ids.contains(&plugin.details.id)
+        })
+        .collect())
+}
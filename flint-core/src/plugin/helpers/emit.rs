@@ -0,0 +1,170 @@
+use mlua::{Lua, UserData, UserDataMethods};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::diff::Diff;
+use crate::timings::{self, Phase};
+use crate::{error::AppResult, get_flag};
+
+/// The directory files are streamed to until they're written, relative to the project
+/// root. Files are renamed out of it, so it's in the project to be on the same disk.
+pub const EMIT_DIR: &str = ".flint/emitted";
+
+static EMITTED_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// The contents of a file a plugin generated, either returned by the plugin or
+/// streamed with `emit_file`
+#[derive(Debug)]
+pub enum FileContents {
+    Text(String),
+    Streamed(StagedFile),
+}
+
+/// A file streamed by a plugin, which is removed if it's never written
+#[derive(Debug)]
+pub struct StagedFile(PathBuf);
+
+impl Drop for StagedFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+impl FileContents {
+    /// Writes the contents to the file, creating its directory
+    pub fn write_to(self, path: &Path) -> io::Result<()> {
+        let _timing = timings::time(Phase::Write, path.display().to_string());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match self {
+            FileContents::Text(text) => fs::write(path, text),
+            FileContents::Streamed(staged) => {
+                // Renaming fails across disks, like when the file is outside the project
+                fs::rename(&staged.0, path).or_else(|_| fs::copy(&staged.0, path).map(|_| ()))
+            }
+        }
+    }
+
+    /// What writing the contents would change in the file. Streamed files can be too
+    /// big to diff, so only returned text is, against a file that's text too.
+    pub fn diff_from(&self, path: &Path) -> Option<Diff> {
+        match self {
+            FileContents::Text(text) => Some(Diff::new(&fs::read_to_string(path).ok()?, text)),
+            FileContents::Streamed(_) => None,
+        }
+    }
+
+    /// Whether the file has the same contents, compared in chunks for streamed files
+    pub fn is_written_to(&self, path: &Path) -> io::Result<bool> {
+        match self {
+            FileContents::Text(text) => Ok(fs::read(path)? == text.as_bytes()),
+            FileContents::Streamed(staged) => {
+                let existing = File::open(path)?;
+                if existing.metadata()?.len() != fs::metadata(&staged.0)?.len() {
+                    return Ok(false);
+                }
+                same_contents(
+                    BufReader::new(existing),
+                    BufReader::new(File::open(&staged.0)?),
+                )
+            }
+        }
+    }
+}
+
+fn same_contents(mut a: impl Read, mut b: impl Read) -> io::Result<bool> {
+    let mut a_chunk = [0; 8192];
+    let mut b_chunk = [0; 8192];
+    loop {
+        let read = a.read(&mut a_chunk)?;
+        if read == 0 {
+            return Ok(b.read(&mut b_chunk)? == 0);
+        }
+        b.read_exact(&mut b_chunk[..read])?;
+        if a_chunk[..read] != b_chunk[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+type Writer = Arc<Mutex<Option<BufWriter<File>>>>;
+
+/// The files streamed in a VM since they were last taken
+#[derive(Default)]
+struct EmittedFiles(Vec<(String, StagedFile, Writer)>);
+
+/// The handle `emit_file` returns, writing to the file in chunks:
+///
+/// ```lua
+/// local file = emit_file("reports/results.json")
+/// for _, result in ipairs(results) do
+///     file:write(json.stringify(result) .. "\n")
+/// end
+/// file:close()
+/// ```
+struct EmitHandle(Writer);
+
+impl UserData for EmitHandle {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_method("write", |_, this, chunk: mlua::String| {
+            match this.0.lock().unwrap().as_mut() {
+                Some(writer) => writer.write_all(&chunk.as_bytes())?,
+                None => return Err(mlua::Error::runtime("The file is already closed")),
+            }
+            Ok(())
+        });
+
+        // Files that aren't closed are closed once the plugin returns
+        methods.add_method("close", |_, this, ()| {
+            if let Some(mut writer) = this.0.lock().unwrap().take() {
+                writer.flush()?;
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Adds `emit_file(path)`, which streams a file instead of returning its contents,
+/// so large files don't have to fit in memory. Paths are relative to the project root,
+/// like the paths of returned files.
+pub fn add_emit_file(lua: &Lua) -> AppResult<()> {
+    let emit_file = lua.create_function(|lua, path: String| {
+        let dir = get_flag!(current_dir).join(EMIT_DIR);
+        fs::create_dir_all(&dir)?;
+        let count = EMITTED_COUNT.fetch_add(1, Ordering::Relaxed);
+        let staged = dir.join(format!("{}-{}", std::process::id(), count));
+
+        let writer = Arc::new(Mutex::new(Some(BufWriter::new(File::create(&staged)?))));
+        if lua.app_data_ref::<EmittedFiles>().is_none() {
+            lua.set_app_data(EmittedFiles::default());
+        }
+        lua.app_data_mut::<EmittedFiles>()
+            .expect("Emitted files were just set")
+            .0
+            .push((path, StagedFile(staged), writer.clone()));
+        Ok(EmitHandle(writer))
+    })?;
+
+    lua.globals().set("emit_file", emit_file)?;
+    Ok(())
+}
+
+/// Takes the files streamed in the VM, closing the ones the plugin didn't
+pub fn take_emitted(lua: &Lua) -> AppResult<Vec<(String, FileContents)>> {
+    let Some(emitted) = lua.remove_app_data::<EmittedFiles>() else {
+        return Ok(Vec::new());
+    };
+
+    let mut files = Vec::new();
+    for (path, staged, writer) in emitted.0 {
+        if let Some(mut writer) = writer.lock().unwrap().take() {
+            writer.flush()?;
+        }
+        files.push((path, FileContents::Streamed(staged)));
+    }
+    Ok(files)
+}
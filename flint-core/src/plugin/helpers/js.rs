@@ -29,9 +29,9 @@ pub fn indent_js_object(input: &str) -> String {
                     indent_level += 1;
 
                     // Check if the next non-whitespace char is closing bracket
-                    let mut temp_chars = chars.clone();
+                    let temp_chars = chars.clone();
                     let mut next_meaningful = None;
-                    while let Some(next_c) = temp_chars.next() {
+                    for next_c in temp_chars {
                         if !next_c.is_whitespace() {
                             next_meaningful = Some(next_c);
                             break;
@@ -600,13 +600,13 @@ pub fn function_helpers(lua: &Lua) -> LuaResult<Table> {
 
     let call_fn = lua.create_function(|ctx, args: Variadic<Value>| {
         // We need at least one argument (the function to call)
-        if args.len() == 0 {
+        if args.is_empty() {
             return Err(mlua::Error::RuntimeError(
                 "js.fn.call requires at least a function argument".to_string(),
             ));
         }
 
-        let function = args.get(0).unwrap().clone();
+        let function = args.first().unwrap().clone();
         let obj = ctx.create_table()?;
 
         // Store the function and arguments for later use
@@ -1,7 +1,7 @@
 use mlua::{Lua, Table, Value};
 use serde_json::to_string_pretty;
 
-use crate::app::AppResult;
+use crate::error::AppResult;
 
 pub fn json_helpers(lua: &Lua) -> AppResult<Table> {
     let json = lua.create_table()?;
@@ -24,10 +24,7 @@ pub fn json_helpers(lua: &Lua) -> AppResult<Table> {
     Ok(json)
 }
 
-fn serde_value_to_lua_value<'lua>(
-    lua: &'lua Lua,
-    value: &serde_json::Value,
-) -> Result<Value, mlua::Error> {
+fn serde_value_to_lua_value(lua: &Lua, value: &serde_json::Value) -> Result<Value, mlua::Error> {
     match value {
         serde_json::Value::Null => Ok(Value::Nil),
         serde_json::Value::Bool(b) => Ok(Value::Boolean(*b)),
@@ -1,4 +1,4 @@
-use crate::{app::AppResult, debug, error, info, success, warn};
+use crate::{debug, error, error::AppResult, info, success, warn};
 use mlua::{Lua, Table, Value};
 use serde_json::to_string_pretty;
 
@@ -1,21 +1,35 @@
 use mlua::{Lua, Table};
 
+pub mod emit;
 pub mod js;
 pub mod json;
 pub mod log;
+pub mod parser;
 pub mod path;
+pub mod platform;
+pub mod project;
+pub mod tmp;
 pub mod toml;
 pub mod yaml;
 
-use crate::app::AppResult;
+use crate::error::AppResult;
+
+/// The modules of helpers plugins require, like `require("path")`
+pub const MODULES: [&str; 10] = [
+    "log", "json", "toml", "yaml", "parser", "path", "platform", "js", "project", "tmp",
+];
 
 pub fn add_helper_globals(lua: &Lua) -> AppResult<()> {
     let log = log::log_helpers(lua)?;
     let json = json::json_helpers(lua)?;
     let toml = toml::toml_helpers(lua)?;
     let yaml = yaml::yaml_helpers(lua)?;
+    let parser = parser::parser_helpers(lua)?;
     let path = path::path_helpers(lua)?;
+    let platform = platform::platform_helpers(lua)?;
     let js = js::js_helpers(lua)?;
+    let project = project::project_helpers(lua)?;
+    let tmp = tmp::tmp_helpers(lua)?;
 
     let package: Table = lua.globals().get("package")?;
     let loaded: Table = package.get("loaded")?;
@@ -25,8 +39,14 @@ pub fn add_helper_globals(lua: &Lua) -> AppResult<()> {
     loaded.set("json", json)?;
     loaded.set("toml", toml)?;
     loaded.set("yaml", yaml)?;
+    loaded.set("parser", parser)?;
     loaded.set("path", path)?;
+    loaded.set("platform", platform)?;
     loaded.set("js", js)?;
+    loaded.set("project", project)?;
+    loaded.set("tmp", tmp)?;
+
+    emit::add_emit_file(lua)?;
 
     // Custom module loader to allow our modules to work
     lua.load(
@@ -0,0 +1,401 @@
+use mlua::{Lua, LuaSerdeExt, SerializeOptions, Table, Value};
+use regex_automata::meta::Regex;
+use regex_automata::util::captures::Captures;
+use serde::Deserialize;
+
+use crate::error::AppResult;
+use crate::fix::FixEdit;
+use crate::plugin::exec::eval::TestCaseOutput;
+
+/// The names of the built-in parsers, for `parser.parse`
+pub const FORMATS: [&str; 3] = ["eslint-json", "gcc", "pytest"];
+
+/// Lines like `src/main.c:12:5: error: expected ';' [-Wsyntax]`, printed by gcc, clang
+/// and the many tools that copy them
+const GCC_PATTERN: &str = r"^(?P<file>[^:\s][^:]*):(?P<line>\d+):(?:(?P<col>\d+):)?\s*(?P<severity>fatal error|error|warning|note):\s*(?P<message>.*?)(?:\s+\[(?P<rule>[^\]]+)\])?$";
+/// The lines `pytest -v` prints for every test, like `tests/test_a.py::test_one PASSED`
+const PYTEST_VERBOSE_PATTERN: &str =
+    r"^(?P<file>[^\s:]+)::(?P<test>\S+)\s+(?P<status>PASSED|FAILED|ERROR|XFAIL|XPASS|SKIPPED)";
+/// The short summary lines `pytest -r` prints, like `FAILED tests/test_a.py::test_two - assert 1 == 2`
+const PYTEST_SUMMARY_PATTERN: &str =
+    r"^(?P<status>FAILED|ERROR) (?P<file>[^\s:]+)::(?P<test>\S+)(?: - (?P<message>.*))?$";
+
+pub fn parser_helpers(lua: &Lua) -> AppResult<Table> {
+    let parser = lua.create_table()?;
+
+    // Parses the output line by line with one or more patterns, which name the parts
+    // of a finding with the captures file, line, col, severity, rule and message
+    let parser_lines = lua.create_function(|lua, (output, patterns): (String, Value)| {
+        let patterns = match patterns {
+            Value::Table(table) => table
+                .sequence_values::<String>()
+                .collect::<Result<_, _>>()?,
+            value => vec![lua.unpack::<String>(value)?],
+        };
+        let regexes = compile(&patterns).map_err(mlua::Error::runtime)?;
+        to_lua(lua, &parse_lines(&output, &regexes))
+    })?;
+
+    // Parses the output of a tool with one of the built-in parsers
+    let parser_parse = lua.create_function(|lua, (format, output): (String, String)| {
+        let results = parse(&format, &output).map_err(mlua::Error::runtime)?;
+        to_lua(lua, &results)
+    })?;
+
+    parser.set("lines", parser_lines)?;
+    parser.set("parse", parser_parse)?;
+    parser.set("formats", FORMATS)?;
+
+    Ok(parser)
+}
+
+/// Converts the results to the tables `Eval` returns. Missing fields are left out,
+/// rather than set to null, so plugins can check them like any other table.
+fn to_lua(lua: &Lua, results: &[TestCaseOutput]) -> mlua::Result<Value> {
+    lua.to_value_with(
+        results,
+        SerializeOptions::new().serialize_none_to_null(false),
+    )
+}
+
+/// Compiles the patterns, which need to capture at least the file
+pub fn compile(patterns: &[impl AsRef<str>]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            let pattern = pattern.as_ref();
+            let regex = Regex::new(pattern)
+                .map_err(|err| format!("Invalid pattern {:?}: {}", pattern, err))?;
+            match regex.group_info().to_index(Default::default(), "file") {
+                Some(_) => Ok(regex),
+                None => Err(format!("Pattern {:?} doesn't capture a file", pattern)),
+            }
+        })
+        .collect()
+}
+
+/// Parses every line matching one of the patterns into a failed result. Lines
+/// matching no pattern, like summaries and blank lines, are skipped.
+pub fn parse_lines(output: &str, regexes: &[Regex]) -> Vec<TestCaseOutput> {
+    let mut results = Vec::new();
+
+    for line in output.lines() {
+        for regex in regexes {
+            let mut captures = regex.create_captures();
+            regex.captures(line, &mut captures);
+            if !captures.is_match() {
+                continue;
+            }
+
+            let capture = |name: &str| group(line, &captures, name);
+            let number = |name: &str| capture(name).and_then(|value| value.parse().ok());
+            results.push(TestCaseOutput {
+                file_name: capture("file").unwrap_or_default().to_string(),
+                line_no: number("line"),
+                column_no: number("col").or_else(|| number("column")),
+                success: false,
+                error_message: capture("message").map(str::to_string),
+                rule_id: capture("rule").map(str::to_string),
+                severity: capture("severity").map(severity),
+                fixes: Vec::new(),
+            });
+            break;
+        }
+    }
+
+    results
+}
+
+/// Parses the output with one of the built-in parsers in `FORMATS`
+pub fn parse(format: &str, output: &str) -> Result<Vec<TestCaseOutput>, String> {
+    match format {
+        "eslint-json" => parse_eslint_json(output),
+        "gcc" => Ok(parse_lines(output, &[built_in(GCC_PATTERN)])),
+        "pytest" => Ok(parse_pytest(output)),
+        _ => Err(format!(
+            "Unknown parser {:?}, expected one of {}",
+            format,
+            FORMATS.join(", ")
+        )),
+    }
+}
+
+fn built_in(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("the built-in patterns are valid")
+}
+
+/// The text of a named capture, if it took part in the match and isn't empty
+fn group<'a>(line: &'a str, captures: &Captures, name: &str) -> Option<&'a str> {
+    let span = captures.get_group_by_name(name)?;
+    Some(line[span.range()].trim()).filter(|text| !text.is_empty())
+}
+
+/// Normalizes the many ways tools spell a severity to "error", "warning" or "note"
+fn severity(severity: &str) -> String {
+    let severity = severity.to_lowercase();
+    match severity.as_str() {
+        "e" | "err" | "error" | "fatal" | "fatal error" | "critical" => "error".to_string(),
+        "w" | "warn" | "warning" => "warning".to_string(),
+        "n" | "i" | "note" | "info" | "hint" => "note".to_string(),
+        _ => severity,
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintFile {
+    file_path: String,
+    messages: Vec<EslintMessage>,
+    /// The linted file, included if ESLint found something in it
+    #[serde(default)]
+    source: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintMessage {
+    line: Option<u32>,
+    column: Option<u32>,
+    message: String,
+    rule_id: Option<String>,
+    severity: u8,
+    #[serde(default)]
+    fix: Option<EslintFix>,
+}
+
+/// A fix of a finding, replacing a range of the source given in UTF-16 code units
+#[derive(Deserialize)]
+struct EslintFix {
+    range: [usize; 2],
+    text: String,
+}
+
+/// Parses `eslint --format json`. Files ESLint found nothing in are passing results,
+/// so they count towards the files linted.
+fn parse_eslint_json(output: &str) -> Result<Vec<TestCaseOutput>, String> {
+    let files: Vec<EslintFile> = serde_json::from_str(output)
+        .map_err(|err| format!("Couldn't parse ESLint's output: {}", err))?;
+    let mut results = Vec::new();
+
+    for file in files {
+        if file.messages.is_empty() {
+            results.push(TestCaseOutput {
+                file_name: file.file_path,
+                line_no: None,
+                column_no: None,
+                success: true,
+                error_message: None,
+                rule_id: None,
+                severity: None,
+                fixes: Vec::new(),
+            });
+            continue;
+        }
+
+        for message in file.messages {
+            results.push(TestCaseOutput {
+                file_name: file.file_path.clone(),
+                line_no: message.line,
+                column_no: message.column,
+                success: false,
+                error_message: Some(message.message),
+                rule_id: message.rule_id,
+                severity: Some(match message.severity {
+                    2 => "error".to_string(),
+                    _ => "warning".to_string(),
+                }),
+                fixes: file
+                    .source
+                    .as_deref()
+                    .zip(message.fix)
+                    .and_then(|(source, fix)| eslint_fix(source, fix))
+                    .into_iter()
+                    .collect(),
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+fn eslint_fix(source: &str, fix: EslintFix) -> Option<FixEdit> {
+    let (start_line, start_column) = utf16_position(source, fix.range[0])?;
+    let (end_line, end_column) = utf16_position(source, fix.range[1])?;
+    Some(FixEdit {
+        file_name: None,
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+        replacement: fix.text,
+    })
+}
+
+/// The line and column of an offset in UTF-16 code units, as JavaScript counts them
+fn utf16_position(source: &str, offset: usize) -> Option<(u32, u32)> {
+    let (mut line, mut column, mut units) = (1, 1, 0);
+    for char in source.chars() {
+        if units >= offset {
+            break;
+        }
+        units += char.len_utf16();
+        match char {
+            '\n' => (line, column) = (line + 1, 1),
+            _ => column += 1,
+        }
+    }
+    (units == offset).then_some((line, column))
+}
+
+/// Parses the output of `pytest -v -rfE`: a result for every test from the verbose
+/// lines, with the failure messages from the short summary
+fn parse_pytest(output: &str) -> Vec<TestCaseOutput> {
+    let verbose = built_in(PYTEST_VERBOSE_PATTERN);
+    let summary = built_in(PYTEST_SUMMARY_PATTERN);
+    // The test each result is for, as a test can be in both the verbose lines and the summary
+    let mut tests: Vec<String> = Vec::new();
+    let mut results: Vec<TestCaseOutput> = Vec::new();
+
+    for line in output.lines() {
+        let (regex, is_summary) = if verbose.is_match(line) {
+            (&verbose, false)
+        } else if summary.is_match(line) {
+            (&summary, true)
+        } else {
+            continue;
+        };
+        let mut captures = regex.create_captures();
+        regex.captures(line, &mut captures);
+
+        let file = group(line, &captures, "file").unwrap_or_default();
+        let test = group(line, &captures, "test").unwrap_or_default();
+        let status = group(line, &captures, "status").unwrap_or_default();
+        let message = group(line, &captures, "message");
+        // Skipped and expected failures neither pass nor fail
+        if matches!(status, "SKIPPED" | "XFAIL") {
+            continue;
+        }
+
+        let id = format!("{}::{}", file, test);
+        let success = matches!(status, "PASSED" | "XPASS");
+        let error_message = match (success, message) {
+            (true, _) => None,
+            (false, Some(message)) => Some(format!("{}: {}", test, message)),
+            (false, None) => Some(format!("{} failed", test)),
+        };
+
+        match tests.iter().position(|known| *known == id) {
+            // The summary only adds the message to a test already seen
+            Some(index) if is_summary => {
+                if message.is_some() {
+                    results[index].error_message = error_message;
+                }
+            }
+            Some(_) => (),
+            None => {
+                tests.push(id);
+                results.push(TestCaseOutput {
+                    file_name: file.to_string(),
+                    line_no: None,
+                    column_no: None,
+                    success,
+                    error_message,
+                    rule_id: None,
+                    severity: (!success).then(|| "error".to_string()),
+                    fixes: Vec::new(),
+                });
+            }
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lines_with_named_captures() {
+        let regexes = compile(&[
+            r"^(?P<file>[^:]+):(?P<line>\d+):(?P<col>\d+): (?P<severity>\w) (?P<message>.+)$",
+        ])
+        .unwrap();
+        let results = parse_lines("src/a.py:3:1: W unused import\nAll done\n", &regexes);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_name, "src/a.py");
+        assert_eq!(
+            (results[0].line_no, results[0].column_no),
+            (Some(3), Some(1))
+        );
+        assert_eq!(results[0].severity.as_deref(), Some("warning"));
+        assert_eq!(results[0].error_message.as_deref(), Some("unused import"));
+
+        assert!(compile(&[r"^(?P<line>\d+)$"]).is_err());
+        assert!(compile(&[r"(?P<file>"]).is_err());
+    }
+
+    #[test]
+    fn parses_gcc_output() {
+        let output = "src/main.c:12:5: error: expected ';' before '}' token\n\
+                      src/util.c:4: warning: unused variable 'x' [-Wunused-variable]\n\
+                      1 error generated.";
+        let results = parse("gcc", output).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].column_no, Some(5));
+        assert_eq!(
+            results[0].error_message.as_deref(),
+            Some("expected ';' before '}' token")
+        );
+        assert_eq!(results[1].column_no, None);
+        assert_eq!(results[1].rule_id.as_deref(), Some("-Wunused-variable"));
+        assert_eq!(results[1].severity.as_deref(), Some("warning"));
+    }
+
+    #[test]
+    fn parses_eslint_json() {
+        let output = r#"[
+            {"filePath": "/p/a.js", "messages": []},
+            {"filePath": "/p/b.js", "messages": [
+                {"line": 1, "column": 7, "message": "'x' is unused", "ruleId": "no-unused-vars", "severity": 2}
+            ]}
+        ]"#;
+        let results = parse("eslint-json", output).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].success);
+        assert!(results[1].fixes.is_empty());
+        assert_eq!(results[1].rule_id.as_deref(), Some("no-unused-vars"));
+        assert_eq!(results[1].severity.as_deref(), Some("error"));
+        assert!(parse("eslint-json", "Oops").is_err());
+
+        let output = r#"[{"filePath": "/p/c.js", "source": "let é = 1\nlet b = 2\n", "messages": [
+            {"line": 2, "column": 10, "message": "Missing semicolon", "ruleId": "semi", "severity": 1,
+             "fix": {"range": [19, 19], "text": ";"}}
+        ]}]"#;
+        let results = parse("eslint-json", output).unwrap();
+        assert_eq!(
+            (
+                results[0].fixes[0].start_line,
+                results[0].fixes[0].start_column
+            ),
+            (2, 10)
+        );
+    }
+
+    #[test]
+    fn parses_pytest_output() {
+        let output = "tests/test_a.py::test_one PASSED                [ 33%]\n\
+                      tests/test_a.py::test_two FAILED                [ 66%]\n\
+                      tests/test_a.py::test_three SKIPPED             [100%]\n\
+                      =========== short test summary info ===========\n\
+                      FAILED tests/test_a.py::test_two - assert 1 == 2";
+        let results = parse("pytest", output).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].success);
+        assert_eq!(
+            results[1].error_message.as_deref(),
+            Some("test_two: assert 1 == 2")
+        );
+        assert!(parse("mypy", output).is_err());
+    }
+}
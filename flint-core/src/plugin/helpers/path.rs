@@ -0,0 +1,476 @@
+use directories::UserDirs;
+use mlua::{Lua, Table};
+use std::path::{is_separator, Component, Path, PathBuf, MAIN_SEPARATOR_STR};
+
+use crate::{error::AppResult, get_flag};
+
+pub fn path_helpers(lua: &Lua) -> AppResult<Table> {
+    let path = lua.create_table()?;
+
+    let cwd = lua.create_function(|lua, ()| {
+        let cwd = get_flag!(current_dir);
+        lua.create_string(cwd.to_string_lossy().as_ref())
+    })?;
+
+    // Like Node's path.resolve, paths are resolved from the right until one is absolute
+    let path_resolve = lua.create_function(|lua, paths: mlua::Variadic<String>| {
+        let home = UserDirs::new().map(|user_dirs| user_dirs.home_dir().to_path_buf());
+        let resolved = resolve(get_flag!(current_dir), home.as_deref(), &paths);
+        lua.create_string(resolved.to_string_lossy().as_ref())
+    })?;
+
+    // Like Node's path.join, which normalizes the joined path without resolving it
+    let path_join = lua.create_function(|lua, paths: mlua::Variadic<String>| {
+        if paths.is_empty() {
+            return lua.create_string("");
+        }
+        lua.create_string(join(&paths).to_string_lossy().as_ref())
+    })?;
+
+    let path_ls = lua.create_function(|lua, path: Option<String>| {
+        use std::fs;
+
+        // Get the directory to list
+        let dir_path = match path {
+            Some(p) => p,
+            None => {
+                // Default to current directory if no path provided
+                let cwd = get_flag!(current_dir);
+                cwd.to_string_lossy().to_string()
+            }
+        };
+
+        // Read directory contents
+        let entries = match fs::read_dir(&dir_path) {
+            Ok(entries) => entries,
+            Err(err) => {
+                return Err(mlua::Error::runtime(format!(
+                    "Failed to read directory: {}",
+                    err
+                )))
+            }
+        };
+
+        // Create a Lua table to hold the results
+        let results = lua.create_table()?;
+
+        // Populate the table with file/directory names
+        for (i, entry) in entries.enumerate() {
+            match entry {
+                Ok(entry) => {
+                    let file_name = entry.file_name();
+                    let name = file_name.to_string_lossy().to_string();
+                    results.raw_set(i + 1, name)?;
+                }
+                Err(err) => {
+                    return Err(mlua::Error::runtime(format!(
+                        "Error reading entry: {}",
+                        err
+                    )))
+                }
+            }
+        }
+
+        Ok(results)
+    })?;
+
+    let path_dirname = lua.create_function(|lua, path: String| {
+        let path = with_separators(&path);
+        let dirname = match Path::new(&path).parent() {
+            Some(parent) if parent.as_os_str().is_empty() => Path::new("."),
+            Some(parent) => parent,
+            // The root is its own directory
+            None => Path::new(&path),
+        };
+        lua.create_string(dirname.to_string_lossy().as_ref())
+    })?;
+
+    // The suffix is removed from the name if it's given, like `path.basename(file, ".ts")`
+    let path_basename = lua.create_function(|lua, (path, suffix): (String, Option<String>)| {
+        let path = with_separators(&path);
+        let name = basename(Path::new(&path));
+        let name = match suffix.as_deref() {
+            Some(suffix) if name != suffix => name.strip_suffix(suffix).unwrap_or(&name),
+            _ => &name,
+        };
+        lua.create_string(name)
+    })?;
+
+    // The extension with its dot like ".json", or "" for names like ".eslintrc"
+    let path_extname = lua.create_function(|lua, path: String| {
+        let path = with_separators(&path);
+        let extname = match Path::new(&path).extension() {
+            Some(extension) => format!(".{}", extension.to_string_lossy()),
+            None => String::new(),
+        };
+        lua.create_string(extname)
+    })?;
+
+    // Both paths are resolved first, so the relative path is from one to the other
+    // wherever they're relative to
+    let path_relative = lua.create_function(|lua, (from, to): (String, String)| {
+        let cwd = get_flag!(current_dir);
+        let home = UserDirs::new().map(|user_dirs| user_dirs.home_dir().to_path_buf());
+        let from = resolve(cwd, home.as_deref(), &[from]);
+        let to = resolve(cwd, home.as_deref(), &[to]);
+        lua.create_string(relative(&from, &to).to_string_lossy().as_ref())
+    })?;
+
+    // Paths starting at a root like `\foo` are absolute on Windows too, like in Node
+    let path_is_absolute =
+        lua.create_function(|_, path: String| Ok(Path::new(&with_separators(&path)).has_root()))?;
+
+    path.set("join", path_join)?;
+    path.set("resolve", path_resolve)?;
+    path.set("dirname", path_dirname)?;
+    path.set("basename", path_basename)?;
+    path.set("extname", path_extname)?;
+    path.set("relative", path_relative)?;
+    path.set("is_absolute", path_is_absolute)?;
+    path.set("ls", path_ls)?;
+    path.set("cwd", cwd)?;
+
+    Ok(path)
+}
+
+/// Joins the paths with the separator of the platform, normalizing the result. A
+/// trailing separator is kept, and a path that normalizes to nothing is ".".
+fn join(paths: &[String]) -> PathBuf {
+    let joined = paths
+        .iter()
+        .filter(|path| !path.is_empty())
+        .map(|path| with_separators(path))
+        .collect::<Vec<_>>()
+        .join(MAIN_SEPARATOR_STR);
+
+    let mut normalized = normalize(Path::new(&joined));
+    if normalized.as_os_str().is_empty() {
+        return PathBuf::from(".");
+    }
+    if joined.ends_with(is_separator) && normalized.file_name().is_some() {
+        normalized.as_mut_os_string().push(MAIN_SEPARATOR_STR);
+    }
+    normalized
+}
+
+/// Resolves the paths onto the current directory, expanding `~` to the home
+/// directory. On Windows, a path with a drive but no root like `C:foo` is relative
+/// to the resolved path if it's on that drive, and to the root of the drive if not.
+fn resolve(cwd: &Path, home: Option<&Path>, paths: &[String]) -> PathBuf {
+    let mut resolved = cwd.to_path_buf();
+    for path in paths.iter().filter(|path| !path.is_empty()) {
+        let path = with_separators(path);
+        let path = match (expand_home(&path), home) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => PathBuf::from(path),
+        };
+
+        resolved = match path.components().next() {
+            Some(Component::Prefix(prefix)) if !path.has_root() => {
+                let rest = path.strip_prefix(prefix.as_os_str()).unwrap_or(&path);
+                if is_same_prefix(&resolved, prefix.as_os_str().to_string_lossy().as_ref()) {
+                    resolved.join(rest)
+                } else {
+                    Path::new(prefix.as_os_str())
+                        .join(MAIN_SEPARATOR_STR)
+                        .join(rest)
+                }
+            }
+            // Absolute paths replace the resolved path, and on Windows a root without a
+            // drive like `\foo` keeps the drive of the resolved path
+            _ => resolved.join(path),
+        };
+    }
+    normalize(&resolved)
+}
+
+/// The last component of the path, which is "" for a root
+fn basename(path: &Path) -> String {
+    match path.components().next_back() {
+        Some(Component::Normal(name)) => name.to_string_lossy().to_string(),
+        Some(Component::ParentDir) => "..".to_string(),
+        Some(Component::CurDir) => ".".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// The path from one resolved path to another, which is "" for the same path. Paths
+/// on different drives have no relative path between them, so it's the path to.
+fn relative(from: &Path, to: &Path) -> PathBuf {
+    let from = from.components().collect::<Vec<_>>();
+    let to = to.components().collect::<Vec<_>>();
+    let common = from
+        .iter()
+        .zip(&to)
+        .take_while(|(from, to)| is_same_component(from, to))
+        .count();
+    if common == 0 {
+        return to.iter().collect();
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in common..from.len() {
+        relative.push(Component::ParentDir);
+    }
+    for component in &to[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+/// Windows compares names regardless of case
+fn is_same_component(a: &Component, b: &Component) -> bool {
+    if cfg!(windows) {
+        a.as_os_str()
+            .to_string_lossy()
+            .eq_ignore_ascii_case(&b.as_os_str().to_string_lossy())
+    } else {
+        a == b
+    }
+}
+
+/// Windows takes both `/` and `\` as separators, so paths are written with `\` only.
+/// Verbatim paths like `\\?\C:\foo` are left as they are, as `/` is a file name
+/// character in them.
+fn with_separators(path: &str) -> String {
+    if cfg!(windows) && !path.starts_with(r"\\?\") {
+        path.replace('/', MAIN_SEPARATOR_STR)
+    } else {
+        path.to_string()
+    }
+}
+
+/// The rest of a path starting with `~`, like `projects` in `~/projects`, which is
+/// relative to the home directory
+fn expand_home(path: &str) -> Option<&str> {
+    let rest = path.strip_prefix('~')?;
+    if rest.is_empty() || rest.starts_with(is_separator) {
+        Some(rest.trim_start_matches(is_separator))
+    } else {
+        None
+    }
+}
+
+/// Whether the path starts with the prefix, which drives are compared in regardless
+/// of case like Windows does
+fn is_same_prefix(path: &Path, prefix: &str) -> bool {
+    match path.components().next() {
+        Some(Component::Prefix(path_prefix)) => path_prefix
+            .as_os_str()
+            .to_string_lossy()
+            .eq_ignore_ascii_case(prefix),
+        _ => false,
+    }
+}
+
+/// Removes `.` and the directories `..` leaves without touching the filesystem, as
+/// the paths don't have to exist. A `..` can't go above the root, but is kept at the
+/// start of relative paths.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    let mut depth = 0;
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => normalized.push(component),
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => {
+                normalized.pop();
+                depth -= 1;
+            }
+            Component::ParentDir if normalized.has_root() => {}
+            Component::ParentDir => normalized.push(component),
+            Component::Normal(name) => {
+                normalized.push(name);
+                depth += 1;
+            }
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[cfg(unix)]
+    mod unix {
+        use super::*;
+
+        #[test]
+        fn joins_and_normalizes() {
+            assert_eq!(join(&paths(&["a", "b/c"])), Path::new("a/b/c"));
+            assert_eq!(join(&paths(&["/a/", "./b", "../c"])), Path::new("/a/c"));
+            assert_eq!(join(&paths(&["a", "..", ".."])), Path::new(".."));
+            assert_eq!(join(&paths(&["a", ".."])), Path::new("."));
+            assert_eq!(join(&paths(&["a", "", "b/"])).to_str(), Some("a/b/"));
+        }
+
+        #[test]
+        fn resolves_from_the_right() {
+            let cwd = Path::new("/work");
+            let home = Some(Path::new("/home/me"));
+            assert_eq!(resolve(cwd, home, &[]), Path::new("/work"));
+            assert_eq!(resolve(cwd, home, &paths(&["../x"])), Path::new("/x"));
+            assert_eq!(
+                resolve(cwd, home, &paths(&["a", "/b", "c"])),
+                Path::new("/b/c")
+            );
+            assert_eq!(
+                resolve(cwd, home, &paths(&["/", "..", "a"])),
+                Path::new("/a")
+            );
+        }
+
+        #[test]
+        fn finds_names() {
+            assert_eq!(basename(Path::new("/a/b.txt")), "b.txt");
+            assert_eq!(basename(Path::new("/a/b/")), "b");
+            assert_eq!(basename(Path::new("/a/..")), "..");
+            assert_eq!(basename(Path::new("/")), "");
+        }
+
+        #[test]
+        fn finds_relative_paths() {
+            let relative_to = |from, to| relative(Path::new(from), Path::new(to));
+            assert_eq!(relative_to("/a/b", "/a/c/d"), Path::new("../c/d"));
+            assert_eq!(relative_to("/a/b", "/a/b/c"), Path::new("c"));
+            assert_eq!(relative_to("/a/b/c", "/a"), Path::new("../.."));
+            assert_eq!(relative_to("/a", "/a"), Path::new(""));
+            assert_eq!(relative_to("/A", "/a"), Path::new("../a"));
+        }
+
+        #[test]
+        fn expands_home() {
+            let cwd = Path::new("/work");
+            let home = Some(Path::new("/home/me"));
+            assert_eq!(resolve(cwd, home, &paths(&["~"])), Path::new("/home/me"));
+            assert_eq!(
+                resolve(cwd, home, &paths(&["~/x"])),
+                Path::new("/home/me/x")
+            );
+            assert_eq!(resolve(cwd, home, &paths(&["~x"])), Path::new("/work/~x"));
+            assert_eq!(resolve(cwd, None, &paths(&["~/x"])), Path::new("/work/~/x"));
+        }
+    }
+
+    #[cfg(windows)]
+    mod windows {
+        use super::*;
+
+        #[test]
+        fn joins_mixed_separators() {
+            assert_eq!(join(&paths(&[r"C:\a", "b/c"])).to_str(), Some(r"C:\a\b\c"));
+            assert_eq!(join(&paths(&["C:/a/", "../b"])).to_str(), Some(r"C:\b"));
+            assert_eq!(join(&paths(&["a/", r"b\"])).to_str(), Some(r"a\b\"));
+            assert_eq!(join(&paths(&["C:", "a"])).to_str(), Some(r"C:\a"));
+        }
+
+        #[test]
+        fn joins_unc_paths() {
+            assert_eq!(
+                join(&paths(&[r"\\server\share", "a", "..", "b"])).to_str(),
+                Some(r"\\server\share\b")
+            );
+            assert_eq!(
+                join(&paths(&["//server/share/a", "b"])).to_str(),
+                Some(r"\\server\share\a\b")
+            );
+        }
+
+        #[test]
+        fn resolves_drives() {
+            let cwd = Path::new(r"C:\work");
+            assert_eq!(
+                resolve(cwd, None, &paths(&[r"D:\x", "y"])).to_str(),
+                Some(r"D:\x\y")
+            );
+            assert_eq!(
+                resolve(cwd, None, &paths(&["D:/x"])).to_str(),
+                Some(r"D:\x")
+            );
+            assert_eq!(
+                resolve(cwd, None, &paths(&[r"\root"])).to_str(),
+                Some(r"C:\root")
+            );
+            assert_eq!(
+                resolve(cwd, None, &paths(&["/root"])).to_str(),
+                Some(r"C:\root")
+            );
+            assert_eq!(
+                resolve(cwd, None, &paths(&[r"..\..\.."])).to_str(),
+                Some(r"C:\")
+            );
+        }
+
+        #[test]
+        fn resolves_drive_relative_paths() {
+            let cwd = Path::new(r"C:\work");
+            assert_eq!(
+                resolve(cwd, None, &paths(&["C:rel"])).to_str(),
+                Some(r"C:\work\rel")
+            );
+            assert_eq!(
+                resolve(cwd, None, &paths(&["c:rel"])).to_str(),
+                Some(r"C:\work\rel")
+            );
+            assert_eq!(
+                resolve(cwd, None, &paths(&["D:rel"])).to_str(),
+                Some(r"D:\rel")
+            );
+        }
+
+        #[test]
+        fn resolves_unc_paths() {
+            let cwd = Path::new(r"C:\work");
+            assert_eq!(
+                resolve(cwd, None, &paths(&[r"\\server\share\a", "b"])).to_str(),
+                Some(r"\\server\share\a\b")
+            );
+            assert_eq!(
+                resolve(cwd, None, &paths(&["//server/share/a", "..", ".."])).to_str(),
+                Some(r"\\server\share\")
+            );
+        }
+
+        #[test]
+        fn finds_names() {
+            assert_eq!(basename(Path::new(r"C:\a\b.txt")), "b.txt");
+            assert_eq!(basename(Path::new(r"C:\")), "");
+            assert_eq!(basename(Path::new(r"\\server\share\a")), "a");
+        }
+
+        #[test]
+        fn finds_relative_paths() {
+            let relative_to = |from, to| relative(Path::new(from), Path::new(to));
+            assert_eq!(relative_to(r"C:\a\b", r"C:\a\c").to_str(), Some(r"..\c"));
+            assert_eq!(relative_to(r"C:\A\b", r"c:\a\B\c").to_str(), Some("c"));
+            assert_eq!(relative_to(r"C:\a", r"D:\a").to_str(), Some(r"D:\a"));
+            assert_eq!(
+                relative_to(r"\\server\share\a", r"\\server\share\b").to_str(),
+                Some(r"..\b")
+            );
+        }
+
+        #[test]
+        fn expands_home_with_either_separator() {
+            let cwd = Path::new(r"C:\work");
+            let home = Some(Path::new(r"C:\Users\me"));
+            assert_eq!(
+                resolve(cwd, home, &paths(&["~"])).to_str(),
+                Some(r"C:\Users\me")
+            );
+            assert_eq!(
+                resolve(cwd, home, &paths(&[r"~\x"])).to_str(),
+                Some(r"C:\Users\me\x")
+            );
+            assert_eq!(
+                resolve(cwd, home, &paths(&["~/x"])).to_str(),
+                Some(r"C:\Users\me\x")
+            );
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use mlua::{Lua, Table};
+use std::env;
+use std::path::MAIN_SEPARATOR_STR;
+
+use crate::error::AppResult;
+
+/// Whether flint runs in CI. Most CI services set `CI`, like GitHub Actions, GitLab CI,
+/// CircleCI and Travis, and Azure Pipelines sets `TF_BUILD`.
+fn is_ci() -> bool {
+    let is_set = |name| env::var(name).is_ok_and(|value| !value.is_empty() && value != "false");
+    is_set("CI") || is_set("TF_BUILD")
+}
+
+/// The `platform` module, for configs that depend on where flint runs, like line
+/// endings or script extensions:
+///
+/// ```lua
+/// local platform = require("platform")
+/// local script = platform.os == "windows" and "lint.cmd" or "lint.sh"
+/// ```
+pub fn platform_helpers(lua: &Lua) -> AppResult<Table> {
+    let platform = lua.create_table()?;
+
+    // Like "linux", "macos" or "windows", and "x86_64" or "aarch64"
+    platform.set("os", env::consts::OS)?;
+    platform.set("arch", env::consts::ARCH)?;
+    platform.set("path_sep", MAIN_SEPARATOR_STR)?;
+    platform.set("is_ci", is_ci())?;
+
+    Ok(platform)
+}
@@ -0,0 +1,33 @@
+use mlua::{Lua, Table};
+use std::path::Path;
+
+use crate::git::has_extension;
+use crate::{error::AppResult, get_flag};
+
+pub fn project_helpers(lua: &Lua) -> AppResult<Table> {
+    let project = lua.create_table()?;
+
+    // The files changed in the branch when running with --changed, relative to the
+    // project root and optionally only the ones with the given extensions. Returns
+    // nil when every file should be checked.
+    let changed_files = lua.create_function(|lua, extensions: Option<Vec<String>>| {
+        let Some(files) = get_flag!(changed_files).clone() else {
+            return Ok(mlua::Value::Nil);
+        };
+
+        let files = files
+            .iter()
+            .filter(|file| {
+                extensions
+                    .as_ref()
+                    .is_none_or(|extensions| has_extension(Path::new(file), extensions))
+            })
+            .map(|file| file.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        Ok(mlua::Value::Table(lua.create_sequence_from(files)?))
+    })?;
+
+    project.set("changed_files", changed_files)?;
+
+    Ok(project)
+}
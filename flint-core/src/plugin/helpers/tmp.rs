@@ -0,0 +1,122 @@
+use mlua::{Lua, Table};
+use std::cell::RefCell;
+use std::io::Write;
+use std::marker::PhantomData;
+
+use crate::error::AppResult;
+
+/// The prefix of the names of temp paths, in the temp directory of the system
+const TMP_PREFIX: &str = "flint-";
+
+thread_local! {
+    /// The paths created in the scopes open on the thread, from the outermost scope
+    static SCOPES: RefCell<Vec<Vec<TempPath>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A temp file or directory, which is removed when it's dropped
+enum TempPath {
+    File(tempfile::TempPath),
+    Dir(tempfile::TempDir),
+}
+
+impl TempPath {
+    fn name(&self) -> String {
+        let path = match self {
+            TempPath::File(path) => path.as_ref(),
+            TempPath::Dir(dir) => dir.path(),
+        };
+        path.to_string_lossy().into_owned()
+    }
+}
+
+/// Removes the temp paths created while it's open once it's dropped. Workers open one
+/// for every plugin run, so the files outlive the script and can be passed to the
+/// command it returns. VMs open one too, so paths created outside of a run are
+/// removed with their VM.
+pub struct TempScope {
+    // Scopes belong to the thread that opened them
+    _thread: PhantomData<*const ()>,
+}
+
+impl TempScope {
+    pub fn open() -> Self {
+        SCOPES.with_borrow_mut(|scopes| scopes.push(Vec::new()));
+        Self {
+            _thread: PhantomData,
+        }
+    }
+}
+
+impl Drop for TempScope {
+    fn drop(&mut self) {
+        SCOPES.with_borrow_mut(|scopes| scopes.pop());
+    }
+}
+
+/// Creates a path in the temp directory, tracked by the outermost scope of the thread.
+/// Its name is random and it's created only if nothing is there yet, so other users
+/// can't guess it or have it point to one of their files.
+fn create(
+    suffix: &str,
+    create: impl FnOnce(tempfile::Builder) -> std::io::Result<TempPath>,
+) -> mlua::Result<String> {
+    if suffix.contains(std::path::is_separator) {
+        return Err(mlua::Error::runtime(format!(
+            "The suffix of a temp path can't contain separators: {}",
+            suffix
+        )));
+    }
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(TMP_PREFIX).suffix(suffix);
+    let temp_path = create(builder)?;
+
+    let name = temp_path.name();
+    SCOPES.with_borrow_mut(|scopes| match scopes.first_mut() {
+        Some(scope) => {
+            scope.push(temp_path);
+            Ok(())
+        }
+        None => Err(mlua::Error::runtime(
+            "Temp paths can only be created while a plugin runs",
+        )),
+    })?;
+    Ok(name)
+}
+
+/// The `tmp` module, creating temp files and directories that are removed once the
+/// plugin run ends, for tools that only read their input from disk:
+///
+/// ```lua
+/// local tmp = require("tmp")
+/// local config = tmp.file({ suffix = ".json", contents = json.stringify(options) })
+/// ```
+pub fn tmp_helpers(lua: &Lua) -> AppResult<Table> {
+    let tmp = lua.create_table()?;
+
+    // A file with the contents, or an empty one. Some tools need it to have an
+    // extension, which is added with the suffix.
+    let file = lua.create_function(|_, options: Option<Table>| {
+        let (suffix, contents) = match options {
+            Some(options) => (
+                options.get::<Option<String>>("suffix")?,
+                options.get::<Option<mlua::String>>("contents")?,
+            ),
+            None => (None, None),
+        };
+        create(suffix.as_deref().unwrap_or_default(), |builder| {
+            let mut file = builder.tempfile()?;
+            if let Some(contents) = contents {
+                file.write_all(&contents.as_bytes())?;
+            }
+            Ok(TempPath::File(file.into_temp_path()))
+        })
+    })?;
+
+    let dir =
+        lua.create_function(|_, ()| create("", |builder| builder.tempdir().map(TempPath::Dir)))?;
+
+    tmp.set("file", file)?;
+    tmp.set("dir", dir)?;
+
+    Ok(tmp)
+}
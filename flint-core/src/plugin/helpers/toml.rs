@@ -1,6 +1,6 @@
 use mlua::{Lua, Table, Value};
 
-use crate::app::AppResult;
+use crate::error::AppResult;
 
 pub fn toml_helpers(lua: &Lua) -> AppResult<Table> {
     let toml = lua.create_table()?;
@@ -24,16 +24,13 @@ pub fn toml_helpers(lua: &Lua) -> AppResult<Table> {
     Ok(toml)
 }
 
-fn toml_value_to_lua_value<'lua>(
-    lua: &'lua Lua,
-    value: &toml::Value,
-) -> Result<Value, mlua::Error> {
+fn toml_value_to_lua_value(lua: &Lua, value: &toml::Value) -> Result<Value, mlua::Error> {
     match value {
         toml::Value::String(s) => Ok(Value::String(lua.create_string(s)?)),
         toml::Value::Integer(i) => Ok(Value::Integer(*i as i32)),
         toml::Value::Float(f) => Ok(Value::Number(*f)),
         toml::Value::Boolean(b) => Ok(Value::Boolean(*b)),
-        toml::Value::Datetime(dt) => Ok(Value::String(lua.create_string(&dt.to_string())?)),
+        toml::Value::Datetime(dt) => Ok(Value::String(lua.create_string(dt.to_string())?)),
         toml::Value::Array(arr) => {
             let lua_table = lua.create_table()?;
             for (i, val) in arr.iter().enumerate() {
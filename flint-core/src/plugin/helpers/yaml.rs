@@ -1,6 +1,6 @@
 use mlua::{Lua, Table, Value};
 
-use crate::app::AppResult;
+use crate::error::AppResult;
 
 pub fn yaml_helpers(lua: &Lua) -> AppResult<Table> {
     let yaml = lua.create_table()?;
@@ -24,10 +24,7 @@ pub fn yaml_helpers(lua: &Lua) -> AppResult<Table> {
     Ok(yaml)
 }
 
-fn yaml_value_to_lua_value<'lua>(
-    lua: &'lua Lua,
-    value: &serde_yaml::Value,
-) -> Result<Value, mlua::Error> {
+fn yaml_value_to_lua_value(lua: &Lua, value: &serde_yaml::Value) -> Result<Value, mlua::Error> {
     match value {
         serde_yaml::Value::Null => Ok(Value::Nil),
         serde_yaml::Value::Bool(b) => Ok(Value::Boolean(*b)),
@@ -61,7 +58,7 @@ fn yaml_value_to_lua_value<'lua>(
                             lua_table.set(i, yaml_value_to_lua_value(lua, v)?)?;
                         } else if let Some(f) = n.as_f64() {
                             // Convert float keys to strings
-                            let key_str = lua.create_string(&f.to_string())?;
+                            let key_str = lua.create_string(f.to_string())?;
                             lua_table.set(key_str, yaml_value_to_lua_value(lua, v)?)?;
                         }
                     }
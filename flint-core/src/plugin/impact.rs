@@ -0,0 +1,147 @@
+use super::{exec::vm, Plugin};
+use crate::config::Config;
+use crate::error::{AppResult, FlintError};
+use globset::{GlobBuilder, GlobMatcher};
+use mlua::{Function, LuaSerdeExt};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Source files mapped to the tests they affect, declared by the `Impact` function of
+/// a test plugin. Files are globs relative to the project root, where `*` doesn't
+/// match `/` and `**` matches any number of directories. Tests are the targets the
+/// test runner is given, like files, directories or names of test suites.
+///
+/// ```lua
+/// function Impact()
+///     return {
+///         { files = { "src/api/**" }, tests = { "tests/api" } },
+///         { files = { "src/**/*.js", "package.json" }, tests = { "tests/unit" } },
+///     }
+/// end
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+pub struct Mapping {
+    pub files: Vec<String>,
+    pub tests: Vec<String>,
+}
+
+impl Mapping {
+    /// The globs of the files, matched like the paths git lists
+    fn matchers(&self) -> AppResult<Vec<GlobMatcher>> {
+        self.files
+            .iter()
+            .map(|glob| {
+                GlobBuilder::new(glob)
+                    .literal_separator(true)
+                    .build()
+                    .map(|glob| glob.compile_matcher())
+                    .map_err(|err| FlintError::Err(format!("Invalid glob {}: {}", glob, err)))
+            })
+            .collect()
+    }
+}
+
+/// The tests of a test plugin affected by the changed files, so `flint test --changed`
+/// only runs those. They're the tests of the mappings declared by its `Impact`
+/// function whose files changed, and the tests returned by the `Affected` function of
+/// its run.lua, which is given the changed files and its config for mappings that
+/// take more than globs:
+///
+/// ```lua
+/// function Affected(files, config)
+///     local tests = {}
+///     for _, file in ipairs(files) do
+///         table.insert(tests, (file:gsub("%.js$", ".test.js")))
+///     end
+///     return tests
+/// end
+/// ```
+///
+/// It's `None` when the whole suite should run, as the plugin maps no files to tests
+/// or `Affected` returned nil, like when a file every test depends on changed.
+pub fn affected_tests(
+    plugin: &Plugin,
+    toml: &Arc<Config>,
+    changed_files: &[PathBuf],
+) -> AppResult<Option<Vec<String>>> {
+    let mut tests = BTreeSet::new();
+    let mut mapped = false;
+
+    let details = vm::load(plugin, &["details.lua"])?;
+    if let Ok(impact) = details.globals().get::<Function>("Impact") {
+        let mappings: Vec<Mapping> = details.from_value(impact.call::<mlua::Value>(())?)?;
+        tests.extend(mapped_tests(&mappings, changed_files)?);
+        mapped = true;
+    }
+    drop(details);
+
+    if plugin.path.join("run.lua").exists() {
+        let lua = vm::load(plugin, &["run.lua"])?;
+        if let Ok(affected) = lua.globals().get::<Function>("Affected") {
+            let files = changed_files
+                .iter()
+                .map(|file| file.to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            let config = plugin.get_config_lua(&lua, toml)?;
+            let affected = affected.call::<mlua::Value>((files, config))?;
+            if affected.is_nil() {
+                return Ok(None);
+            }
+            tests.extend(lua.from_value::<Vec<String>>(affected)?);
+            mapped = true;
+        }
+    }
+
+    Ok(mapped.then(|| tests.into_iter().collect()))
+}
+
+/// The tests of the mappings matching any of the changed files
+fn mapped_tests(mappings: &[Mapping], changed_files: &[PathBuf]) -> AppResult<BTreeSet<String>> {
+    let mut tests = BTreeSet::new();
+    for mapping in mappings {
+        let matchers = mapping.matchers()?;
+        let changed = changed_files
+            .iter()
+            .any(|file| matchers.iter().any(|matcher| matcher.is_match(file)));
+        if changed {
+            tests.extend(mapping.tests.iter().cloned());
+        }
+    }
+    Ok(tests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(files: &[&str], tests: &[&str]) -> Mapping {
+        Mapping {
+            files: files.iter().map(|file| file.to_string()).collect(),
+            tests: tests.iter().map(|test| test.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn maps_changed_files_to_their_tests() {
+        let mappings = [
+            mapping(&["src/api/**"], &["tests/api"]),
+            mapping(&["src/*.js"], &["tests/unit"]),
+            mapping(&["docs/**"], &["tests/docs"]),
+        ];
+        let changed = [
+            PathBuf::from("src/api/users/list.js"),
+            PathBuf::from("README.md"),
+        ];
+
+        let tests = mapped_tests(&mappings, &changed).unwrap();
+        assert_eq!(tests.into_iter().collect::<Vec<_>>(), vec!["tests/api"]);
+    }
+
+    #[test]
+    fn rejects_invalid_globs() {
+        let mappings = [mapping(&["src/[a"], &["tests"])];
+        assert!(mapped_tests(&mappings, &[PathBuf::from("src/a")]).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use super::{exec::generate::pipeline, exec::vm, Plugin, PluginKind};
+use crate::config::Config;
+use crate::error::AppResult;
+use crate::{app_err, get_flag};
+use mlua::{Function, LuaSerdeExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The file the inputs of generated configs are recorded in, relative to the project root
+pub const GENERATE_FILE: &str = ".flint/generate.json";
+
+/// The scripts of a plugin its configs are generated with
+const GENERATE_SCRIPTS: [&str; 3] = ["details.lua", "generate.lua", "validate.lua"];
+
+/// The project files and config keys a plugin generates its configs from, declared by
+/// its `Inputs` function. Config keys are paths in the config the plugin gets, like
+/// "common.indent" or "max_line_length", or "*" for all of it. The runs of lint and
+/// test plugins depend on the files too, like the lockfile of the tools they run.
+///
+/// ```lua
+/// function Inputs()
+///     return { files = { "package.json" }, config = { "common", "max_line_length" } }
+/// end
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+pub struct Inputs {
+    /// Paths relative to the project root
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub config: Vec<String>,
+}
+
+/// A hash of everything the configs of a plugin are generated from: its scripts, the
+/// files and config keys it declares and, for CI plugins, the pipeline. It's `None`
+/// for plugins that don't declare their inputs.
+pub fn fingerprint(plugin: &Plugin, toml: &Arc<Config>) -> AppResult<Option<String>> {
+    let lua = vm::load(plugin, &["details.lua"])?;
+    let Some(inputs) = read_inputs(&lua)? else {
+        return Ok(None);
+    };
+
+    let mut hasher = InputsHasher::default();
+    hasher.field(plugin.details.version.as_bytes());
+    for script in GENERATE_SCRIPTS {
+        hasher.field(&fs::read(plugin.path.join(script))?);
+    }
+
+    hasher.files(&inputs.files);
+
+    let config: serde_json::Value =
+        lua.from_value(mlua::Value::Table(plugin.get_config_lua(&lua, toml)?))?;
+    for key in &inputs.config {
+        let value = match key.as_str() {
+            "*" => &config,
+            key => key
+                .split('.')
+                .try_fold(&config, |value, part| value.get(part))
+                .unwrap_or(&serde_json::Value::Null),
+        };
+        hasher.field(key.as_bytes());
+        hasher.field(value.to_string().as_bytes());
+    }
+
+    // CI workflows run the steps of the other plugins, so they change with them
+    if plugin.kind == PluginKind::Ci {
+        let pipeline = serde_json::to_value(pipeline(plugin, toml)?).unwrap_or_default();
+        hasher.field(pipeline.to_string().as_bytes());
+    }
+
+    Ok(Some(hasher.finish()))
+}
+
+/// The inputs the plugin declares, if it has an `Inputs` function
+pub fn get_inputs(plugin: &Plugin) -> AppResult<Option<Inputs>> {
+    let lua = vm::load(plugin, &["details.lua"])?;
+    read_inputs(&lua)
+}
+
+fn read_inputs(lua: &mlua::Lua) -> AppResult<Option<Inputs>> {
+    let Ok(inputs) = lua.globals().get::<Function>("Inputs") else {
+        return Ok(None);
+    };
+    Ok(Some(lua.from_value(inputs.call::<mlua::Value>(())?)?))
+}
+
+/// Hashes inputs with SHA-256, as the fingerprints of runs are shared by the remote
+/// cache. Every field is prefixed with its length, so moving bytes from one field to
+/// the next changes the hash.
+#[derive(Default)]
+pub(crate) struct InputsHasher(Sha256);
+
+impl InputsHasher {
+    pub fn field(&mut self, bytes: &[u8]) {
+        self.0.update((bytes.len() as u64).to_le_bytes());
+        self.0.update(bytes);
+    }
+
+    /// The files relative to the project root, with their contents. A missing file is
+    /// an input too, so creating it changes the hash.
+    pub fn files(&mut self, files: &[String]) {
+        let root = get_flag!(current_dir).clone();
+        for file in files {
+            self.field(file.as_bytes());
+            match fs::read(root.join(file)) {
+                Ok(contents) => {
+                    self.field(&[1]);
+                    self.field(&contents);
+                }
+                Err(_) => self.field(&[0]),
+            }
+        }
+    }
+
+    pub fn finish(self) -> String {
+        hex(&self.0.finalize())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// A hash of the contents of a file, read in chunks so large files aren't read
+/// into memory at once
+pub(crate) fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    loop {
+        let chunk = file.fill_buf()?;
+        if chunk.is_empty() {
+            return Ok(hex(&hasher.finalize()));
+        }
+        hasher.update(chunk);
+        let read = chunk.len();
+        file.consume(read);
+    }
+}
+
+/// What the configs of every plugin were last generated from, so plugins whose inputs
+/// haven't changed are skipped
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GenerateRecord {
+    /// By plugin id
+    plugins: BTreeMap<String, GeneratedConfigs>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct GeneratedConfigs {
+    inputs: String,
+    /// Hashes of the generated files, by their path relative to the project root
+    files: BTreeMap<String, String>,
+}
+
+impl GenerateRecord {
+    pub fn path() -> PathBuf {
+        get_flag!(current_dir).join(GENERATE_FILE)
+    }
+
+    /// The record, which is empty if there's none yet or it can't be read
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn write(&self) -> AppResult<()> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|err| app_err!("Failed to record the generated configs: {}", err))?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Whether the configs of the plugin were generated from the same inputs, and are
+    /// still as they were generated
+    pub fn is_up_to_date(&self, plugin_id: &str, fingerprint: &str) -> bool {
+        let Some(configs) = self.plugins.get(plugin_id) else {
+            return false;
+        };
+        let root = get_flag!(current_dir).clone();
+        configs.inputs == fingerprint
+            && configs.files.iter().all(|(file, hash)| {
+                hash_file(&root.join(file)).is_ok_and(|contents| contents == *hash)
+            })
+    }
+
+    /// Records the configs generated by a plugin, as they were written to the files.
+    /// Without a fingerprint, the plugin is forgotten, so it's generated again.
+    pub fn record(&mut self, plugin_id: &str, fingerprint: Option<String>, files: &[String]) {
+        match fingerprint {
+            Some(inputs) => {
+                let root = get_flag!(current_dir).clone();
+                let files = files
+                    .iter()
+                    .filter_map(|file| Some((file.clone(), hash_file(&root.join(file)).ok()?)))
+                    .collect();
+                self.plugins
+                    .insert(plugin_id.to_string(), GeneratedConfigs { inputs, files });
+            }
+            None => {
+                self.plugins.remove(plugin_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(fields: &[&str]) -> String {
+        let mut hasher = InputsHasher::default();
+        for field in fields {
+            hasher.field(field.as_bytes());
+        }
+        hasher.finish()
+    }
+
+    #[test]
+    fn separates_fields() {
+        assert_ne!(hash(&["ab", "c"]), hash(&["a", "bc"]));
+        assert_eq!(hash(&["ab", "c"]), hash(&["ab", "c"]));
+        assert_eq!(hash(&[]).len(), 64);
+    }
+}
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::PluginKind;
+use crate::app_err;
+use crate::config::Config;
+use crate::error::AppResult;
+
+/// The repository plugins are downloaded from when their section has no `source`
+pub const DEFAULT_REPOSITORY: &str = "https://github.com/skadewdl3/flint";
+
+/// The file the commits of the plugins are pinned in, next to flint.toml
+pub const LOCK_FILE: &str = "flint.lock";
+
+const LOCK_FILE_HEADER: &str = "\
+# Generated by flint install and flint update, which download the plugins at
+# these commits. Commit it, so everyone gets the same plugins.
+
+";
+
+/// Where a plugin is downloaded from, set with `source` in its section like
+/// `source = { git = "https://github.com/acme/plugins", tag = "v1.2" }`. Plugins are
+/// in `flint-plugins/<kind>/<id>` of the repository.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(deny_unknown_fields)]
+pub struct PluginSource {
+    pub git: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+}
+
+impl PluginSource {
+    /// The source of a plugin from its section, or the default repository at the
+    /// `plugins_branch` if it has none
+    pub fn of(config: &Config, kind: &PluginKind, id: &str) -> AppResult<Self> {
+        let section = match kind {
+            PluginKind::Lint => config.rules.get(id),
+            PluginKind::Test => config.tests.get(id),
+            PluginKind::Fmt => config.fmt.get(id),
+            PluginKind::Audit => config.audit.get(id),
+            PluginKind::Ci => config.ci.plugin_config(id),
+            PluginKind::Report => config.report_plugin_config(id),
+        };
+
+        let Some(source) = section.and_then(|section| section.get("source")) else {
+            return Ok(Self {
+                git: DEFAULT_REPOSITORY.into(),
+                tag: None,
+                branch: Some(config.flint.plugins_branch.clone()),
+                rev: None,
+            });
+        };
+
+        let source: Self = source
+            .clone()
+            .try_into()
+            .map_err(|err| app_err!("The source of the {} plugin is invalid: {}", id, err))?;
+        let refs = [&source.tag, &source.branch, &source.rev];
+        if refs.iter().filter(|reference| reference.is_some()).count() > 1 {
+            return Err(app_err!(
+                "The source of the {} plugin can only have one of tag, branch and rev",
+                id
+            ));
+        }
+        Ok(source)
+    }
+
+    /// The git revision the source points to in a clone of its repository. Sources
+    /// without a tag, branch or rev point to the default branch.
+    pub fn revision(&self) -> String {
+        match (&self.tag, &self.branch, &self.rev) {
+            (Some(tag), _, _) => format!("refs/tags/{}", tag),
+            (_, Some(branch), _) => format!("refs/remotes/origin/{}", branch),
+            (_, _, Some(rev)) => rev.clone(),
+            _ => "refs/remotes/origin/HEAD".into(),
+        }
+    }
+}
+
+/// A plugin pinned to the commit of its source it was downloaded at
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LockedPlugin {
+    pub id: String,
+    pub kind: String,
+    pub source: PluginSource,
+    pub commit: String,
+}
+
+/// The contents of flint.lock
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Lockfile {
+    pub version: u8,
+    #[serde(default, rename = "plugin")]
+    pub plugins: Vec<LockedPlugin>,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            plugins: Vec::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// The lockfile of the project of a flint.toml
+    pub fn path(config_path: &Path) -> PathBuf {
+        config_path.with_file_name(LOCK_FILE)
+    }
+
+    /// Loads the lockfile, which is empty if it doesn't exist yet
+    pub fn load(path: &Path) -> AppResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let lockfile = toml::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|err| app_err!("{} is invalid: {}", path.display(), err))?;
+        Ok(lockfile)
+    }
+
+    pub fn write(&self, path: &Path) -> AppResult<()> {
+        let contents = format!("{}{}", LOCK_FILE_HEADER, toml::to_string(self)?);
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// The lockfile with the plugins of `downloaded` pinned to the commits they were
+    /// downloaded at, and the others still pinned as they were
+    pub fn updated(&self, downloaded: &Lockfile) -> Self {
+        let mut plugins = self
+            .plugins
+            .iter()
+            .filter(|plugin| {
+                !downloaded
+                    .plugins
+                    .iter()
+                    .any(|new| new.id == plugin.id && new.kind == plugin.kind)
+            })
+            .chain(&downloaded.plugins)
+            .cloned()
+            .collect::<Vec<_>>();
+        plugins.sort_by(|a, b| (&a.kind, &a.id).cmp(&(&b.kind, &b.id)));
+        Self {
+            version: self.version,
+            plugins,
+        }
+    }
+
+    /// The commit a plugin is pinned to, if it was locked with the same source
+    pub fn commit(&self, kind: &PluginKind, id: &str, source: &PluginSource) -> Option<&str> {
+        self.plugins
+            .iter()
+            .find(|plugin| {
+                plugin.id == id && plugin.kind == kind.to_string() && plugin.source == *source
+            })
+            .map(|plugin| plugin.commit.as_str())
+    }
+}
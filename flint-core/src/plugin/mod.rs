@@ -0,0 +1,216 @@
+use crate::config::Config;
+use crate::config_lint::SECTION_KEYS;
+use crate::error::{AppResult, FlintError};
+use crate::get_flag;
+
+pub mod bench;
+pub mod cache;
+pub mod ci;
+pub mod find;
+pub mod helpers;
+use deps::Dependency;
+use eval::PluginEvalOutput;
+pub use find::*;
+use helpers::emit::FileContents;
+pub mod deps;
+pub mod download;
+pub mod exec;
+pub mod impact;
+pub mod inputs;
+pub mod lock;
+pub mod remote_cache;
+pub mod rules;
+pub mod tools;
+pub mod validate;
+
+use exec::*;
+
+use mlua::{Lua, LuaSerdeExt, Table};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf, process::Output, sync::Arc};
+
+#[derive(Serialize, Deserialize, Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct PluginDetails {
+    pub id: String,
+    pub extensions: Vec<String>,
+    pub version: String,
+    pub author: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct Plugin {
+    pub details: PluginDetails,
+    pub path: PathBuf,
+    pub kind: PluginKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub enum PluginKind {
+    Lint,
+    Test,
+    /// Formatters, which rewrite files rather than reporting findings
+    Fmt,
+    /// Security audits of the dependencies, whose findings are vulnerabilities
+    Audit,
+    Ci,
+    Report,
+}
+
+impl std::fmt::Display for PluginKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginKind::Lint => write!(f, "lint"),
+            PluginKind::Test => write!(f, "test"),
+            PluginKind::Fmt => write!(f, "fmt"),
+            PluginKind::Audit => write!(f, "audit"),
+            PluginKind::Ci => write!(f, "ci"),
+            PluginKind::Report => write!(f, "report"),
+        }
+    }
+}
+
+impl Plugin {
+    pub fn get_config_lua(&self, lua: &Lua, toml: &Arc<Config>) -> AppResult<Table> {
+        let common_config = lua.to_value(&toml.common)?;
+
+        // The CI provider selected in `[ci]` doesn't need a section of its own
+        let no_config = toml::Value::Table(toml::Table::new());
+        let plugin_config = match self.kind {
+            PluginKind::Ci => self.config(toml).or(Some(&no_config)),
+            _ => self.config(toml),
+        }
+        .ok_or_else(|| {
+            let [table, id] = self.config_keys();
+            FlintError::Err(format!("{} has no [{}.{}] section", id, table, id))
+        })?;
+
+        let mlua::Value::Table(plugin_config) = lua.to_value(plugin_config)? else {
+            return Err(FlintError::Err(format!(
+                "The config of {} isn't a table",
+                self.details.id
+            )));
+        };
+
+        // Where the plugin is downloaded from and how much memory it may use aren't
+        // options of the plugin
+        for key in SECTION_KEYS {
+            plugin_config.set(key, mlua::Value::Nil)?;
+        }
+        plugin_config.set("common", common_config)?;
+
+        // The tests affected by the changed files, which test plugins run instead of
+        // the whole suite when they're known
+        if self.kind == PluginKind::Test {
+            if let Some(tests) = get_flag!(affected_tests).get(&self.details.id) {
+                plugin_config.set("affected_tests", lua.create_sequence_from(tests.clone())?)?;
+            }
+        }
+
+        if self.kind == PluginKind::Lint {
+            if let Some(temp) = toml.config.get(&self.details.id) {
+                plugin_config.set("config", lua.to_value(temp)?)?;
+            }
+        }
+
+        Ok(plugin_config)
+    }
+
+    /// The section of the plugin in the config, like `[rules.eslint]`
+    pub fn config<'a>(&self, toml: &'a Config) -> Option<&'a toml::Value> {
+        match self.kind {
+            PluginKind::Lint => toml.rules.get(&self.details.id),
+            PluginKind::Test => toml.tests.get(&self.details.id),
+            PluginKind::Fmt => toml.fmt.get(&self.details.id),
+            PluginKind::Audit => toml.audit.get(&self.details.id),
+            PluginKind::Ci => toml.ci.plugin_config(&self.details.id),
+            PluginKind::Report => toml.report_plugin_config(&self.details.id),
+        }
+    }
+
+    /// The keys of the section of the plugin in the config, like `["rules", "eslint"]`
+    pub fn config_keys(&self) -> [&str; 2] {
+        let table = match self.kind {
+            PluginKind::Lint => "rules",
+            PluginKind::Test => "tests",
+            PluginKind::Fmt => "fmt",
+            PluginKind::Audit => "audit",
+            PluginKind::Ci => "ci",
+            PluginKind::Report => "report",
+        };
+        [table, &self.details.id]
+    }
+
+    pub fn get_dependencies(&self) -> AppResult<HashMap<String, Vec<Dependency>>> {
+        deps::get_dependencies(self)
+    }
+
+    pub fn get_tools(&self) -> AppResult<Vec<tools::Tool>> {
+        tools::get_tools(self)
+    }
+
+    pub fn get_rules(&self) -> AppResult<Vec<rules::Rule>> {
+        rules::get_rules(self)
+    }
+
+    pub fn get_options(&self) -> AppResult<Option<Vec<String>>> {
+        rules::get_options(self)
+    }
+
+    pub fn tool_problems(&self) -> Vec<tools::ToolProblem> {
+        tools::problems(self)
+    }
+
+    pub fn tool_versions(&self) -> Vec<(String, Option<String>)> {
+        tools::versions(self)
+    }
+
+    /// The tests affected by the changed files, or `None` if the whole suite runs
+    pub fn affected_tests(
+        &self,
+        toml: &Arc<Config>,
+        changed_files: &[PathBuf],
+    ) -> AppResult<Option<Vec<String>>> {
+        impact::affected_tests(self, toml, changed_files)
+            .map_err(|err| err.in_plugin(&self.details.id))
+    }
+
+    pub fn generate(&self, toml: &Arc<Config>) -> AppResult<HashMap<String, FileContents>> {
+        generate::generate(self, toml).map_err(|err| err.in_plugin(&self.details.id))
+    }
+
+    pub fn run(&self, toml: &Arc<Config>) -> AppResult<Vec<String>> {
+        run::run(self, toml).map_err(|err| err.in_plugin(&self.details.id))
+    }
+
+    pub fn check(&self, toml: &Arc<Config>) -> AppResult<Vec<String>> {
+        run::check(self, toml).map_err(|err| err.in_plugin(&self.details.id))
+    }
+
+    pub fn eval(&self, output: Output) -> AppResult<PluginEvalOutput> {
+        eval::eval(self, output).map_err(|err| err.in_plugin(&self.details.id))
+    }
+
+    pub fn report(
+        &self,
+        toml: &Arc<Config>,
+        output: &PluginEvalOutput,
+    ) -> AppResult<HashMap<String, FileContents>> {
+        report::report(self, toml, output).map_err(|err| err.in_plugin(&self.details.id))
+    }
+}
+
+pub fn list_from_config(config: &Arc<Config>) -> AppResult<Vec<Plugin>> {
+    let mut plugin_ids = Vec::new();
+    plugin_ids.extend(config.rules.keys());
+    plugin_ids.extend(config.tests.keys());
+    plugin_ids.extend(config.fmt.keys());
+    plugin_ids.extend(config.audit.keys());
+    plugin_ids.extend(config.report_plugin_ids());
+    plugin_ids.extend(config.ci.plugin_ids());
+
+    Ok(find::list()?
+        .iter()
+        .filter(|plugin| plugin_ids.contains(&&plugin.details.id))
+        .cloned()
+        .collect())
+}
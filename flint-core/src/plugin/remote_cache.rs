@@ -0,0 +1,292 @@
+use super::exec::eval::PluginEvalOutput;
+use crate::config::Config;
+use crate::error::{AppResult, FlintError};
+use crate::network::Proxy;
+use crate::{cmd, get_flag};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// How long a request to the store may take, so an unreachable store doesn't hold up runs
+const TIMEOUT_SECONDS: &str = "30";
+
+fn default_read_token_env() -> String {
+    "FLINT_CACHE_READ_TOKEN".to_string()
+}
+
+fn default_write_token_env() -> String {
+    "FLINT_CACHE_WRITE_TOKEN".to_string()
+}
+
+/// The `[flint.cache]` section, for a store the runs of lint and test plugins are
+/// shared in, so CI machines and teammates reuse each other's runs of the same files
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CacheConfig {
+    /// An HTTP store, which runs are read from with GET and written to with PUT, like
+    /// "https://cache.corp/flint", or an S3 bucket and prefix, like "s3://acme-ci/flint"
+    pub url: String,
+    /// The variable with the bearer token for reading from an HTTP store. Without it,
+    /// the store is read without authentication.
+    #[serde(default = "default_read_token_env")]
+    pub read_token_env: String,
+    /// The variable with the bearer token for writing to an HTTP store. Without it,
+    /// runs are only read from the store, like on the machines of teammates.
+    #[serde(default = "default_write_token_env")]
+    pub write_token_env: String,
+    /// The region of the S3 bucket, which defaults to AWS_REGION
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// The endpoint of an S3-compatible store, like MinIO, whose buckets are in its path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Store {
+    Http {
+        url: String,
+        read_token: Option<String>,
+        write_token: Option<String>,
+    },
+    /// Requests are signed with the credentials in the AWS variables, and read
+    /// anonymously without them
+    S3 {
+        url: String,
+        region: String,
+        credentials: Option<S3Credentials>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct S3Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+/// The store of `[flint.cache]`. Runs are stored by plugin and the fingerprint of
+/// their inputs, and requested with curl.
+#[derive(Debug, Clone)]
+pub struct RemoteCache {
+    store: Store,
+    proxy: Proxy,
+}
+
+impl RemoteCache {
+    /// The store configured in flint.toml, unless flint runs offline
+    pub fn new(config: &Config) -> Option<Self> {
+        let cache = config.flint.cache.as_ref()?;
+        if *get_flag!(offline) {
+            return None;
+        }
+        let url = cache.url.trim_end_matches('/');
+
+        let store = match url.strip_prefix("s3://") {
+            Some(path) => {
+                let region = cache
+                    .region
+                    .clone()
+                    .or_else(|| env("AWS_REGION"))
+                    .unwrap_or_else(|| "us-east-1".to_string());
+                let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+                let host = match &cache.endpoint {
+                    Some(endpoint) => format!("{}/{}", endpoint.trim_end_matches('/'), bucket),
+                    None => format!("https://{}.s3.{}.amazonaws.com", bucket, region),
+                };
+                let credentials = env("AWS_ACCESS_KEY_ID")
+                    .zip(env("AWS_SECRET_ACCESS_KEY"))
+                    .map(|(access_key_id, secret_access_key)| S3Credentials {
+                        access_key_id,
+                        secret_access_key,
+                        session_token: env("AWS_SESSION_TOKEN"),
+                    });
+                Store::S3 {
+                    url: match prefix.is_empty() {
+                        true => host,
+                        false => format!("{}/{}", host, prefix),
+                    },
+                    region,
+                    credentials,
+                }
+            }
+            None => Store::Http {
+                url: url.to_string(),
+                read_token: env(&cache.read_token_env),
+                write_token: env(&cache.write_token_env),
+            },
+        };
+
+        Some(Self {
+            store,
+            proxy: Proxy::new(config.flint.network.as_ref()),
+        })
+    }
+
+    /// Whether runs are written to the store, which needs a write token or credentials
+    pub fn is_writable(&self) -> bool {
+        match &self.store {
+            Store::Http { write_token, .. } => write_token.is_some(),
+            Store::S3 { credentials, .. } => credentials.is_some(),
+        }
+    }
+
+    fn url(&self, plugin_id: &str, fingerprint: &str) -> String {
+        let base = match &self.store {
+            Store::Http { url, .. } | Store::S3 { url, .. } => url,
+        };
+        format!("{}/{}/{}.json", base, plugin_id, fingerprint)
+    }
+
+    /// The output of a run of the plugin with the same inputs, if the store has one
+    pub fn get(&self, plugin_id: &str, fingerprint: &str) -> AppResult<Option<PluginEvalOutput>> {
+        let url = self.url(plugin_id, fingerprint);
+        let mut command = self.curl(&url);
+        let (status, body) = self.request(&mut command, false)?;
+        match status {
+            200 => Ok(Some(serde_json::from_slice(&body).map_err(|err| {
+                FlintError::Err(format!("The cached run at {} is invalid: {}", url, err))
+            })?)),
+            404 => Ok(None),
+            // S3 answers 403 for missing keys to users who can't list the bucket
+            403 if matches!(self.store, Store::S3 { .. }) => Ok(None),
+            status => Err(FlintError::Err(format!(
+                "Reading {} failed with HTTP {}",
+                url, status
+            ))),
+        }
+    }
+
+    /// Writes the output of a run of the plugin to the store
+    pub fn put(
+        &self,
+        plugin_id: &str,
+        fingerprint: &str,
+        output: &PluginEvalOutput,
+    ) -> AppResult<()> {
+        let url = self.url(plugin_id, fingerprint);
+        let json = serde_json::to_vec(output).map_err(|err| {
+            FlintError::Err(format!("Failed to cache the run of {}: {}", plugin_id, err))
+        })?;
+
+        // The body is uploaded from a file, as the credentials are passed on stdin. Its
+        // name is random, so other users can't have it point to one of their files.
+        let mut file = tempfile::Builder::new()
+            .prefix("flint-cache-")
+            .suffix(".json")
+            .tempfile()?;
+        file.write_all(&json)?;
+        let path = file.into_temp_path();
+
+        let mut command = self.curl(&url);
+        command
+            .args(["--request", "PUT", "--header"])
+            .arg("Content-Type: application/json")
+            .arg("--upload-file")
+            .arg(&path);
+        match self.request(&mut command, true)?.0 {
+            200..=299 => Ok(()),
+            status => Err(FlintError::Err(format!(
+                "Writing {} failed with HTTP {}",
+                url, status
+            ))),
+        }
+    }
+
+    fn curl(&self, url: &str) -> Command {
+        let mut command = cmd![
+            "curl",
+            "--silent",
+            "--show-error",
+            "--location",
+            "--max-time",
+            TIMEOUT_SECONDS,
+            "--config",
+            "-",
+            "--write-out",
+            "\n%{http_code}",
+        ];
+        if let Store::S3 {
+            region,
+            credentials: Some(_),
+            ..
+        } = &self.store
+        {
+            command
+                .arg("--aws-sigv4")
+                .arg(format!("aws:amz:{}:s3", region));
+        }
+        command.arg(url).envs(self.proxy.env());
+        command
+    }
+
+    /// Runs the request, passing the credentials to curl on stdin so they aren't in
+    /// its arguments, and returns the HTTP status and the body
+    fn request(&self, command: &mut Command, write: bool) -> AppResult<(u16, Vec<u8>)> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| {
+                FlintError::Err(format!("Unable to run curl for the remote cache: {}", err))
+            })?;
+
+        let mut config = String::new();
+        match &self.store {
+            Store::Http {
+                read_token,
+                write_token,
+                ..
+            } => {
+                let token = if write { write_token } else { read_token };
+                if let Some(token) = token {
+                    config += &format!("header = \"Authorization: Bearer {}\"\n", quote(token));
+                }
+            }
+            Store::S3 {
+                credentials: Some(credentials),
+                ..
+            } => {
+                config += &format!(
+                    "user = \"{}:{}\"\n",
+                    quote(&credentials.access_key_id),
+                    quote(&credentials.secret_access_key)
+                );
+                if let Some(token) = &credentials.session_token {
+                    config += &format!("header = \"x-amz-security-token: {}\"\n", quote(token));
+                }
+            }
+            Store::S3 { .. } => {}
+        }
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(config.as_bytes())?;
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(FlintError::Err(format!(
+                "The remote cache couldn't be reached: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        // The status is on the last line, after the body
+        let stdout = output.stdout;
+        let split = stdout.iter().rposition(|byte| *byte == b'\n').unwrap_or(0);
+        let status = String::from_utf8_lossy(&stdout[split..])
+            .trim()
+            .parse()
+            .unwrap_or_default();
+        Ok((status, stdout[..split].to_vec()))
+    }
+}
+
+/// A variable, if it's set and not empty
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Escapes a value for a quoted string in a curl config
+fn quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
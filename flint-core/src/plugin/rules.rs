@@ -0,0 +1,174 @@
+use super::{exec::vm, Plugin};
+use crate::config::Config;
+use crate::error::AppResult;
+use mlua::{Function, LuaSerdeExt};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The type of the value of a rule, which decides how it's edited
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleType {
+    Boolean,
+    String,
+    Integer,
+    Number,
+    /// One of the `values` of the rule
+    Enum,
+}
+
+/// An option of a plugin, declared by its `Rules` function so it can be browsed and
+/// edited without reading the plugin:
+///
+/// ```lua
+/// function Rules()
+///     return {
+///         { name = "verbose", type = "boolean", default = true },
+///         { name = "core.dialect", type = "enum", values = { "ansi", "postgres" } },
+///     }
+/// end
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Rule {
+    /// The key of the rule in the section of the plugin, with dots separating the
+    /// tables it's nested in
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: RuleType,
+    /// The value the plugin uses when the rule isn't set
+    #[serde(default)]
+    pub default: Option<toml::Value>,
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+impl Rule {
+    /// The keys leading to the rule in its section
+    pub fn keys(&self) -> Vec<&str> {
+        self.name.split('.').collect()
+    }
+
+    /// The value of the rule in the section of its plugin, if it's set
+    pub fn value<'a>(&self, plugin_config: Option<&'a toml::Value>) -> Option<&'a toml::Value> {
+        self.keys()
+            .iter()
+            .try_fold(plugin_config?, |value, key| value.get(*key))
+    }
+
+    /// The default of the rule. Lua has no integers, so whole numbers are read as
+    /// floats and turned back into integers.
+    pub fn default_value(&self) -> Option<toml::Value> {
+        match (&self.default, self.kind) {
+            (Some(toml::Value::Float(float)), RuleType::Integer) => {
+                Some(toml::Value::Integer(*float as i64))
+            }
+            (default, _) => default.clone(),
+        }
+    }
+
+    /// The value following the current one, for rules that are toggled rather than
+    /// typed: booleans are flipped and enums go to their next value
+    pub fn next_value(&self, current: Option<&toml::Value>) -> Option<toml::Value> {
+        let current = current.cloned().or_else(|| self.default_value());
+        match self.kind {
+            RuleType::Boolean => {
+                let enabled = current.and_then(|value| value.as_bool()).unwrap_or(false);
+                Some(toml::Value::Boolean(!enabled))
+            }
+            RuleType::Enum => {
+                let index = current
+                    .and_then(|value| {
+                        let value = value.as_str()?.to_string();
+                        self.values.iter().position(|known| *known == value)
+                    })
+                    .map_or(0, |index| (index + 1) % self.values.len().max(1));
+                self.values.get(index).cloned().map(toml::Value::String)
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads a typed value, or says why it isn't one of the rule
+    pub fn parse(&self, input: &str) -> Result<toml::Value, String> {
+        let input = input.trim();
+        match self.kind {
+            RuleType::String => Ok(toml::Value::String(input.to_string())),
+            RuleType::Integer => input
+                .parse()
+                .map(toml::Value::Integer)
+                .map_err(|_| format!("{} isn't an integer", input)),
+            RuleType::Number => match input.parse() {
+                Ok(integer) => Ok(toml::Value::Integer(integer)),
+                Err(_) => input
+                    .parse()
+                    .map(toml::Value::Float)
+                    .map_err(|_| format!("{} isn't a number", input)),
+            },
+            RuleType::Boolean => input
+                .parse()
+                .map(toml::Value::Boolean)
+                .map_err(|_| format!("{} isn't true or false", input)),
+            RuleType::Enum => match self.values.iter().any(|value| value == input) {
+                true => Ok(toml::Value::String(input.to_string())),
+                false => Err(format!("{} isn't one of {}", input, self.values.join(", "))),
+            },
+        }
+    }
+}
+
+pub fn get_rules(plugin: &Plugin) -> AppResult<Vec<Rule>> {
+    let lua = vm::load(plugin, &["details.lua"])?;
+
+    // Plugins that don't declare their rules can only be configured by hand
+    let Ok(rules) = lua.globals().get::<Function>("Rules") else {
+        return Ok(Vec::new());
+    };
+    let rules = rules.call::<mlua::Value>(())?;
+    Ok(lua.from_value(rules)?)
+}
+
+/// The keys the section of the plugin can have: its rules, and the keys it declares in
+/// its `Options` function that aren't rules, like the tables it passes on to its tool:
+///
+/// ```lua
+/// function Options()
+///     return { "include", "exclude", "rules.*" }
+/// end
+/// ```
+///
+/// None for plugins that declare neither, whose sections can have any key.
+pub fn get_options(plugin: &Plugin) -> AppResult<Option<Vec<String>>> {
+    let lua = vm::load(plugin, &["details.lua"])?;
+
+    let rules = lua.globals().get::<Function>("Rules").ok();
+    let options = lua.globals().get::<Function>("Options").ok();
+    if rules.is_none() && options.is_none() {
+        return Ok(None);
+    }
+
+    let mut names = Vec::new();
+    if let Some(rules) = rules {
+        let rules: Vec<Rule> = lua.from_value(rules.call::<mlua::Value>(())?)?;
+        names.extend(rules.into_iter().map(|rule| rule.name));
+    }
+    if let Some(options) = options {
+        let options: Vec<String> = lua.from_value(options.call::<mlua::Value>(())?)?;
+        names.extend(options);
+    }
+    Ok(Some(names))
+}
+
+/// Sets a rule in the section of its plugin in the config file, keeping the comments
+/// of the file
+pub fn set_rule(
+    config_path: &Path,
+    plugin: &Plugin,
+    rule: &Rule,
+    value: &toml::Value,
+) -> AppResult<()> {
+    let mut keys = plugin.config_keys().to_vec();
+    keys.extend(rule.keys());
+    Config::set_value(config_path, &keys, value)
+}
@@ -0,0 +1,249 @@
+use super::{exec::vm, Plugin};
+use crate::error::AppResult;
+use crate::get_flag;
+use mlua::{Function, LuaSerdeExt};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Package managers in the order they're preferred in, when a tool can be installed
+/// with several of them
+const PACKAGE_MANAGERS: [&str; 8] = ["npm", "pnpm", "yarn", "pip", "pipx", "uv", "cargo", "brew"];
+
+fn default_version_args() -> Vec<String> {
+    vec!["--version".into()]
+}
+
+/// An external tool a plugin runs, like a linter, declared by its `Tools` function:
+///
+/// ```lua
+/// function Tools()
+///     return {
+///         { name = "eslint", version = ">=8", install = { npm = "npm install --save-dev eslint" } },
+///     }
+/// end
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Tool {
+    pub name: String,
+    /// The executable of the tool, if it isn't named like it
+    #[serde(default)]
+    pub bin: Option<String>,
+    /// The versions the plugin works with, like ">=8, <10". Any version works if unset.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// The arguments the tool prints its version with
+    #[serde(default = "default_version_args")]
+    pub version_args: Vec<String>,
+    /// The command installing the tool, by package manager
+    #[serde(default)]
+    pub install: BTreeMap<String, String>,
+}
+
+/// Whether a tool can be run, as found by `Tool::check`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum ToolStatus {
+    Installed {
+        path: PathBuf,
+        version: Option<String>,
+    },
+    WrongVersion {
+        path: PathBuf,
+        version: String,
+    },
+    Missing,
+}
+
+impl ToolStatus {
+    pub fn is_installed(&self) -> bool {
+        matches!(self, ToolStatus::Installed { .. })
+    }
+}
+
+/// A tool a plugin can't run with, and how to fix it
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolProblem {
+    pub tool: String,
+    /// Whether the tool wasn't found at all, rather than found with another version
+    pub is_missing: bool,
+    /// What's wrong, like "eslint not found"
+    pub message: String,
+    /// The command installing the tool, like "npm install --save-dev eslint"
+    pub fix: Option<String>,
+}
+
+impl std::fmt::Display for ToolProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.fix {
+            Some(fix) => write!(f, "{}, install it with `{}`", self.message, fix),
+            None => write!(f, "{}, install it and run flint again", self.message),
+        }
+    }
+}
+
+impl Tool {
+    /// Finds the tool and checks its version
+    pub fn check(&self) -> ToolStatus {
+        let Some(path) = self.find() else {
+            return ToolStatus::Missing;
+        };
+
+        let version = Command::new(&path)
+            .args(&self.version_args)
+            .output()
+            .ok()
+            .and_then(|output| {
+                let text = String::from_utf8_lossy(&output.stdout).to_string()
+                    + &String::from_utf8_lossy(&output.stderr);
+                parse_version(&text)
+            });
+        let required = self
+            .version
+            .as_deref()
+            .filter(|version| !["latest", "*", ""].contains(version))
+            .and_then(|version| VersionReq::parse(version).ok());
+
+        match (version, required) {
+            (Some(version), Some(required)) if !required.matches(&version) => {
+                ToolStatus::WrongVersion {
+                    path,
+                    version: version.to_string(),
+                }
+            }
+            (version, _) => ToolStatus::Installed {
+                path,
+                version: version.map(|version| version.to_string()),
+            },
+        }
+    }
+
+    /// The executable of the tool, from the PATH or the node_modules of the project,
+    /// which is where npm installs tools of a project
+    pub fn find(&self) -> Option<PathBuf> {
+        let bin = self.bin.as_deref().unwrap_or(&self.name);
+        let node_modules = get_flag!(current_dir).join("node_modules").join(".bin");
+        find_executable(bin, [node_modules].into_iter())
+    }
+
+    /// What keeps the plugin from running the tool, if anything
+    pub fn problem(&self) -> Option<ToolProblem> {
+        let (is_missing, message) = match self.check() {
+            ToolStatus::Installed { .. } => return None,
+            ToolStatus::Missing => (true, format!("{} not found", self.name)),
+            ToolStatus::WrongVersion { version, .. } => (
+                false,
+                format!(
+                    "{} {} found, but {} is needed",
+                    self.name,
+                    version,
+                    self.version.as_deref().unwrap_or_default()
+                ),
+            ),
+        };
+
+        // The command of a package manager that isn't installed still shows what's needed
+        let fix = self
+            .install_command()
+            .map(|(_, command)| command)
+            .or_else(|| self.install.values().next().map(String::as_str));
+        Some(ToolProblem {
+            tool: self.name.clone(),
+            is_missing,
+            message,
+            fix: fix.map(String::from),
+        })
+    }
+
+    /// The command installing the tool with a package manager that's installed, as
+    /// the package manager and the command
+    pub fn install_command(&self) -> Option<(&str, &str)> {
+        let mut managers = self.install.keys().map(String::as_str).collect::<Vec<_>>();
+        managers.sort_by_key(|manager| {
+            PACKAGE_MANAGERS
+                .iter()
+                .position(|known| known == manager)
+                .unwrap_or(PACKAGE_MANAGERS.len())
+        });
+
+        managers.into_iter().find_map(|manager| {
+            let command = self.install.get(manager)?;
+            let program = command.split_whitespace().next()?;
+            find_executable(program, std::iter::empty())?;
+            Some((manager, command.as_str()))
+        })
+    }
+}
+
+pub fn get_tools(plugin: &Plugin) -> AppResult<Vec<Tool>> {
+    let lua = vm::load(plugin, &["details.lua"])?;
+
+    // Plugins that don't run external tools don't have a `Tools` function
+    let Ok(tools) = lua.globals().get::<Function>("Tools") else {
+        return Ok(Vec::new());
+    };
+    let tools = tools.call::<mlua::Value>(())?;
+    Ok(lua.from_value(tools)?)
+}
+
+/// What keeps a plugin from running its tools. Plugins whose tools can't be read
+/// have no problems, as their run reports why.
+pub fn problems(plugin: &Plugin) -> Vec<ToolProblem> {
+    get_tools(plugin)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Tool::problem)
+        .collect()
+}
+
+/// The versions of the tools of a plugin, as they're found. Tools that aren't found or
+/// don't print their version have none.
+pub fn versions(plugin: &Plugin) -> Vec<(String, Option<String>)> {
+    get_tools(plugin)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|tool| {
+            let version = match tool.check() {
+                ToolStatus::Installed { version, .. } => version,
+                ToolStatus::WrongVersion { version, .. } => Some(version),
+                ToolStatus::Missing => None,
+            };
+            (tool.name, version)
+        })
+        .collect()
+}
+
+/// Finds an executable in the given directories, then in the PATH
+fn find_executable(name: &str, dirs: impl Iterator<Item = PathBuf>) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let extensions: &[&str] = if cfg!(windows) {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+
+    dirs.chain(std::env::split_paths(&path))
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |extension| dir.join(format!("{}{}", name, extension)))
+        })
+        .find(|path| path.is_file())
+}
+
+/// The first version in the output of a tool, like 8.57.0 in "v8.57.0". Versions
+/// without a patch number, like "3.12", count as ".0".
+fn parse_version(text: &str) -> Option<Version> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '-'))
+        .map(|word| word.trim_start_matches('v'))
+        .filter(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .find_map(|word| {
+            let word = word.trim_end_matches('.');
+            Version::parse(word).ok().or_else(|| {
+                let parts = word.split('.').count();
+                (parts == 2).then(|| Version::parse(&format!("{}.0", word)).ok())?
+            })
+        })
+}
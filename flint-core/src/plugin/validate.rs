@@ -1,6 +1,6 @@
 use crate::{
-    app::AppResult,
     app_err,
+    error::AppResult,
     plugin::{Plugin, PluginKind},
 };
 
@@ -8,6 +8,8 @@ pub fn validate_plugin_structure(plugin: &Plugin) -> AppResult<()> {
     let required_files = match plugin.kind {
         PluginKind::Lint => vec!["details.lua", "generate.lua", "run.lua", "validate.lua"],
         PluginKind::Test => vec!["details.lua", "generate.lua", "run.lua", "validate.lua"],
+        PluginKind::Fmt => vec!["details.lua", "generate.lua", "run.lua", "validate.lua"],
+        PluginKind::Audit => vec!["details.lua", "run.lua", "validate.lua"], // Nothing to generate
         PluginKind::Ci => vec!["details.lua", "generate.lua", "validate.lua"], // No run.lua needed
         PluginKind::Report => vec!["details.lua", "run.lua", "validate.lua"], // No generate.lua needed
     };
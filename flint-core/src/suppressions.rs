@@ -0,0 +1,180 @@
+use ignore::Walk;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::get_flag;
+use crate::plugin::exec::eval::PluginEvalOutput;
+
+/// The comment suppressing findings on the line after it, like
+/// `// flint-disable-next-line eslint:no-unused-vars`
+pub const DISABLE_NEXT_LINE: &str = "flint-disable-next-line";
+
+/// What a suppression comment applies to: every rule of a plugin, or one of its rules
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuppressionTarget {
+    pub plugin: String,
+    pub rule: Option<String>,
+}
+
+impl fmt::Display for SuppressionTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.rule {
+            Some(rule) => write!(f, "{}:{}", self.plugin, rule),
+            None => write!(f, "{}", self.plugin),
+        }
+    }
+}
+
+/// A suppression comment in a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suppression {
+    /// The file, relative to the project root
+    pub file: PathBuf,
+    /// The line of the comment, starting at 1. It suppresses findings on the next line.
+    pub line: u32,
+    /// Without targets, every finding on the next line is suppressed
+    pub targets: Vec<SuppressionTarget>,
+}
+
+impl Suppression {
+    pub fn suppresses(&self, plugin: &str, rule: Option<&str>) -> bool {
+        self.targets.is_empty()
+            || self.targets.iter().any(|target| {
+                target.plugin == plugin
+                    && target
+                        .rule
+                        .as_deref()
+                        .is_none_or(|target_rule| Some(target_rule) == rule)
+            })
+    }
+}
+
+/// Parses the targets of a suppression comment in the line, if it has one. They're
+/// separated by spaces or commas, and anything after `--` explains the suppression.
+pub fn parse_line(line: &str) -> Option<Vec<SuppressionTarget>> {
+    let (_, rest) = line.split_once(DISABLE_NEXT_LINE)?;
+    // The marker has to be a word of its own, not part of a longer one
+    if rest.starts_with(|char: char| char.is_alphanumeric() || char == '-') {
+        return None;
+    }
+
+    let rest = rest.split(" --").next().unwrap_or_default();
+    let targets = rest
+        .split(|char: char| char.is_whitespace() || char == ',')
+        // Comment closers, like in `/* flint-disable-next-line eslint */`
+        .filter(|word| !word.is_empty() && !["*/", "-->", "#}", "%}"].contains(word))
+        .map(|word| match word.split_once(':') {
+            Some((plugin, rule)) => SuppressionTarget {
+                plugin: plugin.to_string(),
+                rule: Some(rule.to_string()),
+            },
+            None => SuppressionTarget {
+                plugin: word.to_string(),
+                rule: None,
+            },
+        })
+        .collect();
+    Some(targets)
+}
+
+/// The suppression comments in the contents of a file
+pub fn scan(file: &Path, contents: &str) -> Vec<Suppression> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            Some(Suppression {
+                file: file.to_path_buf(),
+                line: index as u32 + 1,
+                targets: parse_line(line)?,
+            })
+        })
+        .collect()
+}
+
+/// The suppression comments in every file of the project, skipping ignored files
+pub fn scan_project() -> Vec<Suppression> {
+    let current_dir = get_flag!(current_dir).clone();
+    let mut suppressions = Vec::new();
+
+    for entry in Walk::new(&current_dir).flatten() {
+        let path = entry.path();
+        // Binary files can't be read as text, and have no comments
+        let Ok(contents) = fs::read_to_string(path) else {
+            continue;
+        };
+        let file = path.strip_prefix(&current_dir).unwrap_or(path);
+        suppressions.extend(scan(file, &contents));
+    }
+    suppressions
+}
+
+/// Removes the findings of the plugin suppressed by a comment on the line before
+/// them from its output, returning the suppressions that were applied
+pub fn suppress(plugin: &str, output: &mut PluginEvalOutput) -> Vec<Suppression> {
+    let current_dir = get_flag!(current_dir).clone();
+    // Every file with findings is only read once
+    let mut files: HashMap<String, Vec<Suppression>> = HashMap::new();
+    let mut applied: Vec<Suppression> = Vec::new();
+
+    output.test_results.retain(|result| {
+        let Some(line) = result.line_no.filter(|_| !result.success) else {
+            return true;
+        };
+        let suppressions = files.entry(result.file_name.clone()).or_insert_with(|| {
+            let path = current_dir.join(&result.file_name);
+            let file = path.strip_prefix(&current_dir).unwrap_or(&path);
+            fs::read_to_string(&path)
+                .map(|contents| scan(file, &contents))
+                .unwrap_or_default()
+        });
+
+        let suppression = suppressions.iter().find(|suppression| {
+            suppression.line + 1 == line
+                && suppression.suppresses(plugin, result.rule_id.as_deref())
+        });
+        match suppression {
+            Some(suppression) => {
+                if !applied.contains(suppression) {
+                    applied.push(suppression.clone());
+                }
+                false
+            }
+            None => true,
+        }
+    });
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_targets() {
+        let targets =
+            parse_line("x = 1  # flint-disable-next-line ruff:E501, eslint -- legacy").unwrap();
+        assert_eq!(
+            targets.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["ruff:E501", "eslint"]
+        );
+        assert_eq!(parse_line("/* flint-disable-next-line */"), Some(vec![]));
+        assert_eq!(parse_line("// flint-disable-next-lines eslint"), None);
+        assert_eq!(parse_line("let x = 1;"), None);
+    }
+
+    #[test]
+    fn suppresses_matching_findings() {
+        let contents = "// flint-disable-next-line eslint:no-unused-vars\nlet x;\n";
+        let suppressions = scan(Path::new("a.js"), contents);
+        assert_eq!(suppressions.len(), 1);
+        assert_eq!(suppressions[0].line, 1);
+
+        let suppression = &suppressions[0];
+        assert!(suppression.suppresses("eslint", Some("no-unused-vars")));
+        assert!(!suppression.suppresses("eslint", Some("semi")));
+        assert!(!suppression.suppresses("oxlint", Some("no-unused-vars")));
+    }
+}
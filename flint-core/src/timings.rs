@@ -0,0 +1,230 @@
+use serde_json::json;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info_span, span::EnteredSpan};
+
+/// Set with `--timings`, so nothing is recorded otherwise
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// When the run started, which the spans of the trace are relative to
+static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+static SPANS: Mutex<Vec<Span>> = Mutex::new(Vec::new());
+
+static THREAD_COUNT: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// A small id for the thread, as the trace shows a row per thread
+    static THREAD_ID: Cell<u64> = Cell::new(THREAD_COUNT.fetch_add(1, Ordering::Relaxed));
+}
+
+/// What a span timed
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Phase {
+    Discovery,
+    Validate,
+    Generate,
+    Run,
+    Report,
+    Write,
+    Render,
+}
+
+impl Phase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::Discovery => "discovery",
+            Phase::Validate => "validate",
+            Phase::Generate => "generate",
+            Phase::Run => "run",
+            Phase::Report => "report",
+            Phase::Write => "write",
+            Phase::Render => "render",
+        }
+    }
+}
+
+/// The column the timings table is sorted by, set with `--timings-sort`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum TimingsSort {
+    /// The longest total time first
+    #[default]
+    Total,
+    /// The longest single span first
+    Max,
+    /// The most spans first
+    Count,
+    /// By phase, then name
+    Name,
+}
+
+struct Span {
+    phase: Phase,
+    name: String,
+    start: Duration,
+    duration: Duration,
+    thread: u64,
+}
+
+/// A `tracing` span of the phase until it's dropped, whose time is also recorded for
+/// the timings table if timings are enabled
+pub struct Timing {
+    _span: EnteredSpan,
+    recorded: Option<Recorded>,
+}
+
+struct Recorded {
+    phase: Phase,
+    name: String,
+    start: Instant,
+}
+
+impl Drop for Timing {
+    fn drop(&mut self) {
+        let Some(recorded) = &mut self.recorded else {
+            return;
+        };
+        let span = Span {
+            phase: recorded.phase,
+            name: std::mem::take(&mut recorded.name),
+            start: recorded.start.duration_since(*START),
+            duration: recorded.start.elapsed(),
+            thread: THREAD_ID.with(Cell::get),
+        };
+        SPANS.lock().unwrap().push(span);
+    }
+}
+
+pub fn enable() {
+    LazyLock::force(&START);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Enters a span of the phase until the returned guard is dropped, timing it if
+/// timings are enabled
+pub fn time(phase: Phase, name: impl Into<String>) -> Timing {
+    let name = name.into();
+    // Span names are static, so every phase has its own
+    let span = match phase {
+        Phase::Discovery => info_span!("discovery", name = %name),
+        Phase::Validate => info_span!("validate", name = %name),
+        Phase::Generate => info_span!("generate", name = %name),
+        Phase::Run => info_span!("run", name = %name),
+        Phase::Report => info_span!("report", name = %name),
+        Phase::Write => info_span!("write", name = %name),
+        Phase::Render => info_span!("render", name = %name),
+    };
+    Timing {
+        _span: span.entered(),
+        recorded: is_enabled().then(|| Recorded {
+            phase,
+            name,
+            start: Instant::now(),
+        }),
+    }
+}
+
+#[derive(Default)]
+struct Row {
+    count: u32,
+    total: Duration,
+    max: Duration,
+}
+
+/// Prints the time of every phase and name to stderr, so formatted output on stdout
+/// stays readable by other tools
+pub fn print_table(sort: TimingsSort) {
+    let spans = SPANS.lock().unwrap();
+    let mut rows: HashMap<(Phase, &str), Row> = HashMap::new();
+    for span in spans.iter() {
+        let row = rows.entry((span.phase, &span.name)).or_default();
+        row.count += 1;
+        row.total += span.duration;
+        row.max = row.max.max(span.duration);
+    }
+
+    let mut rows: Vec<_> = rows.into_iter().collect();
+    rows.sort_by(|(a_key, a), (b_key, b)| {
+        let order = match sort {
+            TimingsSort::Total => b.total.cmp(&a.total),
+            TimingsSort::Max => b.max.cmp(&a.max),
+            TimingsSort::Count => b.count.cmp(&a.count),
+            TimingsSort::Name => a_key.cmp(b_key),
+        };
+        order.then_with(|| a_key.cmp(b_key))
+    });
+
+    let name_width = rows
+        .iter()
+        .map(|((_, name), _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max("NAME".len());
+    eprintln!(
+        "{:<10} {:<name_width$} {:>6} {:>10} {:>10} {:>10}",
+        "PHASE", "NAME", "COUNT", "TOTAL", "MEAN", "MAX"
+    );
+    for ((phase, name), row) in &rows {
+        eprintln!(
+            "{:<10} {:<name_width$} {:>6} {:>10} {:>10} {:>10}",
+            phase.as_str(),
+            name,
+            row.count,
+            format_duration(row.total),
+            format_duration(row.total / row.count),
+            format_duration(row.max),
+        );
+    }
+    eprintln!("Total run time: {}", format_duration(START.elapsed()));
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Writes the spans as a Chrome trace, which chrome://tracing and Perfetto open
+pub fn write_chrome_trace(path: &Path) -> io::Result<()> {
+    let spans = SPANS.lock().unwrap();
+    let events: Vec<_> = spans
+        .iter()
+        .map(|span| {
+            json!({
+                "name": span.name,
+                "cat": span.phase.as_str(),
+                "ph": "X",
+                "ts": span.start.as_micros() as u64,
+                "dur": span.duration.as_micros() as u64,
+                "pid": std::process::id(),
+                "tid": span.thread,
+            })
+        })
+        .collect();
+
+    let trace = json!({ "traceEvents": events, "displayTimeUnit": "ms" });
+    fs::write(path, trace.to_string())
+}
+
+/// Prints the table and writes the trace, if timings are enabled
+pub fn finish(sort: TimingsSort, trace_path: Option<&Path>) {
+    if !is_enabled() {
+        return;
+    }
+    print_table(sort);
+    if let Some(path) = trace_path {
+        match write_chrome_trace(path) {
+            Ok(()) => eprintln!("Wrote the trace to {}", path.display()),
+            Err(err) => eprintln!("Couldn't write the trace to {}: {}", path.display(), err),
+        }
+    }
+}
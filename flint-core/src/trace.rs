@@ -0,0 +1,371 @@
+//! Instrumentation of flint with `tracing`. The log macros emit events and the phases
+//! timed by [`crate::timings`] are spans, which [`init`] sends to the logs, and to an
+//! OTLP collector if `OTEL_EXPORTER_OTLP_ENDPOINT` is set, like to debug flint in CI:
+//!
+//! ```sh
+//! OTEL_EXPORTER_OTLP_ENDPOINT=http://localhost:4318 flint lint
+//! ```
+//!
+//! Spans are exported once flint is done, with [`export`], as OTLP JSON posted to
+//! `/v1/traces` with curl. Headers like API keys are set with
+//! `OTEL_EXPORTER_OTLP_HEADERS`, like `x-api-key=secret,x-team=ci`.
+
+use serde_json::{json, Value};
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+use crate::cmd;
+use crate::error::{AppResult, FlintError};
+use crate::logs::{add_log, LogKind};
+
+/// How long exporting the spans may take, so an unreachable collector doesn't hold up flint
+const TIMEOUT_SECONDS: &str = "10";
+
+/// The spans that ended, which are exported once flint is done
+static FINISHED: Mutex<Vec<SpanData>> = Mutex::new(Vec::new());
+
+/// Sends the events and spans of flint to the logs, and to the OTLP collector if one is
+/// set. Frontends call it once, before anything is logged; without it the log macros
+/// write to the logs directly.
+pub fn init() {
+    let subscriber = Registry::default()
+        .with(LogLayer)
+        .with(endpoint().map(|_| OtlpLayer));
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+/// The OTLP collector spans are exported to, from `OTEL_EXPORTER_OTLP_ENDPOINT`
+fn endpoint() -> Option<String> {
+    std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .ok()
+        .filter(|endpoint| !endpoint.is_empty())
+}
+
+/// Whether spans are exported, so runs aren't sent to a daemon, whose spans aren't
+/// recorded here
+pub fn is_exporting() -> bool {
+    endpoint().is_some()
+}
+
+/// The kind of a log event, which the log macros set as its `kind` field, or else its
+/// level
+fn log_kind(level: &Level, kind: Option<&str>) -> LogKind {
+    match (kind, *level) {
+        (Some("success"), _) => LogKind::Success,
+        (_, Level::ERROR) => LogKind::Error,
+        (_, Level::WARN) => LogKind::Warn,
+        (_, Level::DEBUG | Level::TRACE) => LogKind::Debug,
+        _ => LogKind::Info,
+    }
+}
+
+/// The message and fields of an event or span, formatted
+#[derive(Default)]
+struct Fields {
+    message: String,
+    values: Vec<(String, String)>,
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            name => self.values.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            name => self.values.push((name.to_string(), format!("{:?}", value))),
+        }
+    }
+}
+
+impl Fields {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Writes the events to the logs, which the UI shows and the log file keeps
+struct LogLayer;
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let kind = log_kind(event.metadata().level(), fields.get("kind"));
+        add_log(kind, fields.message);
+    }
+}
+
+/// A span being recorded for the OTLP collector
+struct SpanData {
+    span_id: u64,
+    parent_id: Option<u64>,
+    name: String,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(String, String)>,
+    /// The logs of the span, by when they were logged
+    events: Vec<(SystemTime, String)>,
+}
+
+/// Records the spans, with the logs logged in them, for [`export`]
+struct OtlpLayer;
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        let parent_id = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<SpanData>()
+                .map(|data| data.span_id)
+        });
+
+        let now = SystemTime::now();
+        span.extensions_mut().insert(SpanData {
+            span_id: random_id(),
+            parent_id,
+            name: attrs.metadata().name().to_string(),
+            start: now,
+            end: now,
+            attributes: fields.values,
+            events: Vec::new(),
+        });
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let mut extensions = span.extensions_mut();
+        if let Some(data) = extensions.get_mut::<SpanData>() {
+            data.events.push((SystemTime::now(), fields.message));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(mut data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+        data.end = SystemTime::now();
+        FINISHED.lock().unwrap().push(data);
+    }
+}
+
+/// A random id for spans and traces, without a dependency for random numbers
+fn random_id() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(nanos(SystemTime::now()));
+    hasher.finish()
+}
+
+fn nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos())
+}
+
+/// The spans as an OTLP `ExportTraceServiceRequest`, all in one trace
+fn request(spans: &[SpanData], trace_id: &str) -> Value {
+    let spans = spans
+        .iter()
+        .map(|span| {
+            let attributes = span
+                .attributes
+                .iter()
+                .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+                .collect::<Vec<_>>();
+            let events = span
+                .events
+                .iter()
+                .map(|(time, message)| {
+                    json!({ "timeUnixNano": nanos(*time).to_string(), "name": message })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "traceId": trace_id,
+                "spanId": format!("{:016x}", span.span_id),
+                "parentSpanId": span.parent_id.map(|id| format!("{:016x}", id)).unwrap_or_default(),
+                "name": span.name,
+                // Internal
+                "kind": 1,
+                "startTimeUnixNano": nanos(span.start).to_string(),
+                "endTimeUnixNano": nanos(span.end).to_string(),
+                "attributes": attributes,
+                "events": events,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": "flint" } },
+                    { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+                ]
+            },
+            "scopeSpans": [{ "scope": { "name": "flint" }, "spans": spans }]
+        }]
+    })
+}
+
+/// Posts the spans that ended to the OTLP collector, if one is set. Frontends call it
+/// once they're done.
+pub fn export() -> AppResult<()> {
+    let Some(endpoint) = endpoint() else {
+        return Ok(());
+    };
+    let spans = std::mem::take(&mut *FINISHED.lock().unwrap());
+    if spans.is_empty() {
+        return Ok(());
+    }
+    let trace_id = format!("{:016x}{:016x}", random_id(), random_id());
+    let body = request(&spans, &trace_id).to_string();
+
+    // The body is posted from a file, as the headers are passed on stdin. Its name is
+    // random, so other users can't have it point to one of their files.
+    let mut file = tempfile::Builder::new()
+        .prefix("flint-spans-")
+        .suffix(".json")
+        .tempfile()?;
+    file.write_all(body.as_bytes())?;
+    let path = file.into_temp_path();
+
+    let url = format!("{}/v1/traces", endpoint.trim_end_matches('/'));
+    let mut command = cmd![
+        "curl",
+        "--silent",
+        "--show-error",
+        "--fail",
+        "--max-time",
+        TIMEOUT_SECONDS,
+        "--config",
+        "-",
+        "--header",
+        "Content-Type: application/json",
+        "--data-binary",
+    ];
+    command.arg(format!("@{}", path.display())).arg(&url);
+    post(command)
+        .map_err(|err| FlintError::Err(format!("Exporting spans to {} failed: {}", url, err)))
+}
+
+/// Runs curl with the headers of `OTEL_EXPORTER_OTLP_HEADERS`, which may hold API
+/// keys, in a config on stdin so they aren't in its arguments
+fn post(mut command: Command) -> Result<(), String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("unable to run curl: {}", err))?;
+
+    let headers = std::env::var("OTEL_EXPORTER_OTLP_HEADERS").unwrap_or_default();
+    let mut config = String::new();
+    for (name, value) in headers
+        .split(',')
+        .filter_map(|header| header.split_once('='))
+    {
+        let header = format!("{}: {}", name.trim(), value.trim());
+        config += &format!(
+            "header = \"{}\"\n",
+            header.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+    }
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.as_bytes())
+            .map_err(|err| err.to_string())?;
+    }
+
+    let output = child.wait_with_output().map_err(|err| err.to_string())?;
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn reads_the_kinds_of_logs() {
+        assert!(matches!(
+            log_kind(&Level::INFO, Some("success")),
+            LogKind::Success
+        ));
+        assert!(matches!(log_kind(&Level::INFO, None), LogKind::Info));
+        assert!(matches!(log_kind(&Level::WARN, None), LogKind::Warn));
+        assert!(matches!(log_kind(&Level::TRACE, None), LogKind::Debug));
+    }
+
+    #[test]
+    fn requests_have_the_spans_of_one_trace() {
+        let start = UNIX_EPOCH + Duration::from_secs(1);
+        let spans = [
+            SpanData {
+                span_id: 1,
+                parent_id: None,
+                name: "discovery".to_string(),
+                start,
+                end: start + Duration::from_millis(5),
+                attributes: vec![("name".to_string(), "all plugins".to_string())],
+                events: Vec::new(),
+            },
+            SpanData {
+                span_id: 2,
+                parent_id: Some(1),
+                name: "run".to_string(),
+                start,
+                end: start,
+                attributes: Vec::new(),
+                events: vec![(start, "Running command".to_string())],
+            },
+        ];
+        let request = request(&spans, "ab");
+        let spans = &request["resourceSpans"][0]["scopeSpans"][0]["spans"];
+
+        assert_eq!(spans[0]["traceId"], "ab");
+        assert_eq!(spans[0]["spanId"], "0000000000000001");
+        assert_eq!(spans[0]["parentSpanId"], "");
+        assert_eq!(spans[0]["endTimeUnixNano"], "1005000000");
+        assert_eq!(
+            spans[0]["attributes"][0]["value"]["stringValue"],
+            "all plugins"
+        );
+        assert_eq!(spans[1]["parentSpanId"], "0000000000000001");
+        assert_eq!(spans[1]["events"][0]["name"], "Running command");
+    }
+}
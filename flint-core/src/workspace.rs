@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::{AppResult, FlintError};
+
+/// The `[workspace]` section. The root of a monorepo lists its members, which list the
+/// members they depend on in their own flint.toml.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WorkspaceConfig {
+    /// The directories of the members, relative to the root. A directory ending in
+    /// `/*`, like `packages/*`, adds every directory in it with a flint.toml.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
+    /// The members run before this one, like `packages/shared`, named as the root
+    /// lists them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+/// A member's flint.toml, of which only the workspace section is read
+#[derive(Deserialize, Default)]
+struct MemberConfig {
+    #[serde(default)]
+    workspace: WorkspaceConfig,
+}
+
+/// A project in a workspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    /// The directory relative to the root, like `packages/api`
+    pub name: String,
+    pub path: PathBuf,
+    pub depends_on: Vec<String>,
+}
+
+/// The members of a monorepo, in the order the root lists them
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub members: Vec<Member>,
+}
+
+impl Workspace {
+    /// The workspace of the project at the root, if its config lists members. Fails
+    /// if a member depends on one that isn't in the workspace, or members depend on
+    /// each other in a cycle.
+    pub fn load(root: &Path, config: &Config) -> AppResult<Option<Self>> {
+        let Some(workspace) = config.workspace.as_ref() else {
+            return Ok(None);
+        };
+        if workspace.members.is_empty() {
+            return Ok(None);
+        }
+
+        let mut members: Vec<Member> = Vec::new();
+        for name in member_names(root, &workspace.members)? {
+            if members.iter().any(|member| member.name == name) {
+                continue;
+            }
+            let path = root.join(&name);
+            let config_path = path.join("flint.toml");
+            let config: MemberConfig = match fs::read_to_string(&config_path) {
+                Ok(contents) => toml::from_str(&contents)?,
+                Err(_) => {
+                    return Err(FlintError::Err(format!(
+                        "The workspace member {} has no flint.toml",
+                        name
+                    )))
+                }
+            };
+            let depends_on = config
+                .workspace
+                .depends_on
+                .iter()
+                .map(|dependency| normalize(dependency))
+                .collect();
+            members.push(Member {
+                name,
+                path,
+                depends_on,
+            });
+        }
+
+        let workspace = Self { members };
+        workspace.validate()?;
+        Ok(Some(workspace))
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        for member in &self.members {
+            for dependency in &member.depends_on {
+                if !self.members.iter().any(|other| other.name == *dependency) {
+                    return Err(FlintError::Err(format!(
+                        "{} depends on {}, which isn't a workspace member",
+                        member.name, dependency
+                    )));
+                }
+            }
+        }
+        self.order().map(|_| ())
+    }
+
+    /// The members in an order where each comes after the members it depends on,
+    /// keeping the order of the root otherwise. Fails naming the members of a cycle.
+    pub fn order(&self) -> AppResult<Vec<&Member>> {
+        let mut ordered: Vec<&Member> = Vec::with_capacity(self.members.len());
+        let mut done: HashSet<&str> = HashSet::new();
+        while ordered.len() < self.members.len() {
+            let next = self.members.iter().find(|member| {
+                !done.contains(member.name.as_str())
+                    && member
+                        .depends_on
+                        .iter()
+                        .all(|dependency| done.contains(dependency.as_str()))
+            });
+            match next {
+                Some(member) => {
+                    done.insert(&member.name);
+                    ordered.push(member);
+                }
+                None => {
+                    let cycle = self
+                        .members
+                        .iter()
+                        .filter(|member| !done.contains(member.name.as_str()))
+                        .map(|member| member.name.as_str())
+                        .collect::<Vec<_>>();
+                    return Err(FlintError::Err(format!(
+                        "The workspace members {} depend on each other in a cycle",
+                        cycle.join(", ")
+                    )));
+                }
+            }
+        }
+        Ok(ordered)
+    }
+}
+
+/// The member directories, with the ones ending in `/*` expanded
+fn member_names(root: &Path, members: &[String]) -> AppResult<Vec<String>> {
+    let mut names = Vec::new();
+    for member in members {
+        let member = normalize(member);
+        let Some(parent) = member.strip_suffix("/*").or((member == "*").then_some("")) else {
+            names.push(member);
+            continue;
+        };
+
+        let mut expanded = fs::read_dir(root.join(parent))?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().join("flint.toml").is_file())
+            .map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                match parent.is_empty() {
+                    true => name,
+                    false => format!("{}/{}", parent, name),
+                }
+            })
+            .collect::<Vec<_>>();
+        expanded.sort();
+        names.extend(expanded);
+    }
+    Ok(names)
+}
+
+/// Member names without a leading `./` or trailing slashes, so they can be compared
+fn normalize(name: &str) -> String {
+    let name = name.trim_start_matches("./").trim_end_matches('/');
+    name.to_string()
+}
+
+/// Which members can run, as the members they depend on finish. A member runs once
+/// every member it depends on succeeded, and never if any of them failed.
+pub struct Schedule<'a> {
+    pending: Vec<&'a Member>,
+    succeeded: HashSet<&'a str>,
+}
+
+impl<'a> Schedule<'a> {
+    pub fn new(workspace: &'a Workspace) -> Self {
+        Self {
+            pending: workspace.members.iter().collect(),
+            succeeded: HashSet::new(),
+        }
+    }
+
+    /// Takes the first pending member whose dependencies all succeeded, if any
+    pub fn take_ready(&mut self) -> Option<&'a Member> {
+        let index = self.pending.iter().position(|member| {
+            member
+                .depends_on
+                .iter()
+                .all(|dependency| self.succeeded.contains(dependency.as_str()))
+        })?;
+        Some(self.pending.remove(index))
+    }
+
+    pub fn finish(&mut self, member: &'a Member, success: bool) {
+        if success {
+            self.succeeded.insert(&member.name);
+        }
+    }
+
+    /// The members that didn't run, as a member they depend on failed or didn't run.
+    /// Read once no member is running and `take_ready` returns none.
+    pub fn skipped(&self) -> &[&'a Member] {
+        &self.pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(name: &str, depends_on: &[&str]) -> Member {
+        Member {
+            name: name.to_string(),
+            path: PathBuf::from(name),
+            depends_on: depends_on.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    fn names(members: &[&Member]) -> Vec<String> {
+        members.iter().map(|member| member.name.clone()).collect()
+    }
+
+    #[test]
+    fn orders_members_after_their_dependencies() {
+        let workspace = Workspace {
+            members: vec![
+                member("apps/web", &["packages/ui", "packages/shared"]),
+                member("packages/ui", &["packages/shared"]),
+                member("packages/shared", &[]),
+            ],
+        };
+        let order = workspace.order().unwrap();
+        assert_eq!(
+            names(&order),
+            ["packages/shared", "packages/ui", "apps/web"]
+        );
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let workspace = Workspace {
+            members: vec![member("a", &["b"]), member("b", &["a"]), member("c", &[])],
+        };
+        let err = workspace.order().unwrap_err().to_string();
+        assert!(err.contains("a, b depend on each other"), "{}", err);
+    }
+
+    #[test]
+    fn schedules_independent_members_together() {
+        let workspace = Workspace {
+            members: vec![
+                member("shared", &[]),
+                member("api", &["shared"]),
+                member("web", &["shared"]),
+                member("docs", &[]),
+            ],
+        };
+        let mut schedule = Schedule::new(&workspace);
+        let shared = schedule.take_ready().unwrap();
+        let docs = schedule.take_ready().unwrap();
+        assert_eq!(docs.name, "docs");
+        assert!(schedule.take_ready().is_none());
+
+        schedule.finish(shared, true);
+        assert_eq!(schedule.take_ready().unwrap().name, "api");
+        assert_eq!(schedule.take_ready().unwrap().name, "web");
+        assert!(schedule.take_ready().is_none());
+    }
+
+    #[test]
+    fn skips_dependents_of_failed_members() {
+        let workspace = Workspace {
+            members: vec![
+                member("shared", &[]),
+                member("api", &["shared"]),
+                member("cli", &["api"]),
+                member("docs", &[]),
+            ],
+        };
+        let mut schedule = Schedule::new(&workspace);
+        let shared = schedule.take_ready().unwrap();
+        let docs = schedule.take_ready().unwrap();
+        schedule.finish(shared, false);
+        schedule.finish(docs, true);
+        assert!(schedule.take_ready().is_none());
+        assert_eq!(names(schedule.skipped()), ["api", "cli"]);
+    }
+}
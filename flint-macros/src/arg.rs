@@ -1,6 +1,12 @@
+use crate::widget::{skip_doc_comments, Widget};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::format_ident;
 use syn::{
-    parse::{Parse, ParseStream},
-    Expr, Ident, Result, Token,
+    parenthesized,
+    parse::{discouraged::Speculative, Parse, ParseStream},
+    parse_quote_spanned,
+    punctuated::Punctuated,
+    token, Expr, ExprArray, ExprLit, Ident, Lit, Pat, Result, Token,
 };
 
 /// Represents the kind of argument that can be passed
@@ -11,6 +17,24 @@ pub enum ArgKind {
     Positional,
     /// A named argument with an identifier and value
     Named(Ident),
+    /// A key handler like `on_key(KeyCode::Enter): self.submit()`, where the value is
+    /// the code run when a key matching the pattern is pressed. Key handlers don't
+    /// affect rendering, and are collected by the `#[handlers]` attribute instead.
+    KeyHandler(Pat),
+}
+
+/// Represents the value of an argument, which is either a plain
+/// Rust expression or a nested widget expression.
+#[derive(Debug, Clone)]
+pub enum ArgValue {
+    /// Any valid Rust expression
+    Expr(Expr),
+    /// A widget expression (like `Block::bordered(title: "Logs")`),
+    /// which is code-generated inline
+    Widget(Box<Widget>),
+    /// A percentage (like `60%`), which is code-generated as a
+    /// `Constraint::Percentage`
+    Percentage(Expr),
 }
 
 /// Represents a single argument in a function or macro invocation,
@@ -19,16 +43,22 @@ pub enum ArgKind {
 #[derive(Debug, Clone)]
 pub struct Arg {
     /// The actual value/expression of the argument
-    pub value: Expr,
+    pub value: ArgValue,
     /// Whether this is a positional or named argument
     pub kind: ArgKind,
+    /// Condition for applying a named argument (like `style if selected: ..`).
+    /// The method call is only chained when the condition holds.
+    pub condition: Option<Expr>,
 }
 
 impl Parse for Arg {
     /// Parses a single argument from a token stream.
     ///
     /// This will parse either:
-    /// - A named argument in the form `name: value`
+    /// - A named argument in the form `name: value`, where the value may itself be a
+    ///   widget expression (like `block: Block::bordered(title: "Logs")`)
+    /// - A conditional named argument in the form `name if condition: value`
+    /// - A key handler in the form `on_key(pattern): handler`
     /// - A shorthand named argument which is just an identifier (treated as `ident: ident`)
     /// - A positional argument which is any other expression
     ///
@@ -36,23 +66,230 @@ impl Parse for Arg {
     /// - `Ok(Arg)` if parsing succeeds
     /// - `Err(Error)` if parsing fails
     fn parse(input: ParseStream) -> Result<Self> {
-        // First, check for named parameter pattern, if we find one then parse it
-        if input.peek(Ident) && input.peek2(Token![:]) {
+        skip_doc_comments(input)?;
+
+        // Catch `name = value`, which would otherwise be parsed as a positional
+        // assignment expression and fail with a confusing type error
+        if input.peek(Ident) && input.peek2(Token![=]) && !input.peek2(Token![==]) {
             let name = input.parse::<Ident>()?;
+            return Err(syn::Error::new_spanned(
+                &name,
+                format!("named arguments are written as `{}: value`", name),
+            ));
+        }
+
+        // Check for a key handler, which looks like a function call followed by a colon
+        if is_key_handler(input) {
+            input.parse::<Ident>()?;
+            let content;
+            parenthesized!(content in input);
+            let pattern = content.call(Pat::parse_multi_with_leading_vert)?;
             input.parse::<Token![:]>()?;
             let value = input.parse::<Expr>()?;
 
+            return Ok(Arg {
+                value: ArgValue::Expr(value),
+                kind: ArgKind::KeyHandler(pattern),
+                condition: None,
+            });
+        }
+
+        // First, check for named parameter pattern, if we find one then parse it.
+        // A path like `Direction::Vertical` is a positional argument, not a name.
+        if input.peek(Ident)
+            && !input.peek2(Token![::])
+            && (input.peek2(Token![:]) || input.peek2(Token![if]))
+        {
+            let name = input.parse::<Ident>()?;
+            let condition = if input.peek(Token![if]) {
+                input.parse::<Token![if]>()?;
+                Some(input.call(Expr::parse_without_eager_brace)?)
+            } else {
+                None
+            };
+            input.parse::<Token![:]>()?;
+            if input.is_empty() || input.peek(Token![,]) {
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    format!("missing value for named argument `{}`", name),
+                ));
+            }
+            let value = match name == "constraints" {
+                true => match parse_constraint_shorthands(input)? {
+                    Some(constraints) => ArgValue::Expr(constraints),
+                    None => parse_named_value(input)?,
+                },
+                false => parse_named_value(input)?,
+            };
+
             return Ok(Arg {
                 value,
                 kind: ArgKind::Named(name),
+                condition,
+            });
+        }
+
+        // Rich text (like `Text { "hello" bold }`) isn't a valid expression,
+        // so it's parsed as a widget
+        if input.peek(Ident)
+            && input.peek2(token::Brace)
+            && input.fork().parse::<Ident>()? == "Text"
+        {
+            let widget = input.parse::<Widget>()?;
+            return Ok(Arg {
+                value: ArgValue::Widget(Box::new(widget)),
+                kind: ArgKind::Positional,
+                condition: None,
             });
         }
 
         // If not named, try parsing as a positional parameter
         let value = input.parse::<Expr>()?;
         Ok(Arg {
-            value,
+            value: ArgValue::Expr(value),
             kind: ArgKind::Positional,
+            condition: None,
         })
     }
 }
+
+/// Parses the value of a named argument.
+///
+/// If the value parses as a complete Rust expression (followed by a comma or the end
+/// of the argument list), it is used as-is. An expression followed by `%` is parsed as
+/// a percentage. Otherwise, it is parsed as a nested widget expression, which allows
+/// writing `block: Block::bordered(title: "Logs")` instead of wrapping the value in a
+/// separate `widget!` call.
+fn parse_named_value(input: ParseStream) -> Result<ArgValue> {
+    let fork = input.fork();
+    if fork.parse::<Expr>().is_ok() && (fork.is_empty() || fork.peek(Token![,])) {
+        return Ok(ArgValue::Expr(input.parse::<Expr>()?));
+    }
+
+    // `60%` doesn't parse as an expression, since `%` is a binary operator, so the
+    // tokens up to a trailing `%` are parsed as the percentage value instead
+    if let Some(value) = parse_percentage(input)? {
+        return Ok(ArgValue::Percentage(value));
+    }
+
+    let widget = input.parse::<Widget>()?;
+    Ok(ArgValue::Widget(Box::new(widget)))
+}
+
+/// Checks whether the next tokens are a key handler, like `on_key(KeyCode::Enter): ..`.
+fn is_key_handler(input: ParseStream) -> bool {
+    let fork = input.fork();
+    let is_on_key = fork.parse::<Ident>().is_ok_and(|name| name == "on_key");
+    is_on_key
+        && fork.peek(token::Paren)
+        && fork
+            .parse::<proc_macro2::Group>()
+            .is_ok_and(|_| fork.peek(Token![:]) && !fork.peek(Token![::]))
+}
+
+/// Parses a value followed by a trailing `%` (like `60%` or `{width}%`).
+///
+/// # Returns
+/// - `Ok(Some(Expr))` with the percentage value if the argument ends with `%`
+/// - `Ok(None)` without consuming any tokens if it doesn't
+fn parse_percentage(input: ParseStream) -> Result<Option<Expr>> {
+    let fork = input.fork();
+    let mut tokens = TokenStream::new();
+    while !fork.is_empty() && !fork.peek(Token![,]) {
+        if fork.peek(Token![%]) {
+            let after = fork.fork();
+            after.parse::<Token![%]>()?;
+            if after.is_empty() || after.peek(Token![,]) {
+                break;
+            }
+        }
+        tokens.extend([fork.parse::<TokenTree>()?]);
+    }
+
+    if tokens.is_empty() || !fork.peek(Token![%]) {
+        return Ok(None);
+    }
+
+    let value = syn::parse2::<Expr>(tokens)?;
+    input.advance_to(&fork);
+    input.parse::<Token![%]>()?;
+    Ok(Some(value))
+}
+
+/// Parses a list of constraints written with shorthands, like
+/// `[30%, 3, min 10, max 20, fill, fill 2]`, into an array of the `Constraint`s they
+/// stand for, so it can be used like any `constraints` expression. Integers are
+/// lengths, and elements without a shorthand (like `Constraint::Ratio(1, 3)`) are
+/// kept as they are.
+///
+/// # Returns
+/// - `Ok(Some(Expr))` with the array of constraints if the value is such a list
+/// - `Ok(None)` without consuming any tokens if it isn't, like `[Constraint::Fill(1); 3]`
+/// - `Err(Error)` if it's a list that's neither an expression nor shorthands
+fn parse_constraint_shorthands(input: ParseStream) -> Result<Option<Expr>> {
+    if !input.peek(token::Bracket) {
+        return Ok(None);
+    }
+
+    let fork = input.fork();
+    let content;
+    let bracket_token = syn::bracketed!(content in fork);
+    let constraints =
+        Punctuated::<Expr, Token![,]>::parse_terminated_with(&content, parse_constraint_shorthand);
+    if !fork.is_empty() && !fork.peek(Token![,]) {
+        return Ok(None);
+    }
+    // Lists that aren't shorthands may still be expressions, and only otherwise is the
+    // error about the shorthand shown, like the missing comma in `[30%, min 10 3]`
+    let constraints = match constraints {
+        Ok(constraints) => constraints,
+        Err(_) if input.fork().parse::<Expr>().is_ok() => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    input.advance_to(&fork);
+    Ok(Some(Expr::Array(ExprArray {
+        attrs: Vec::new(),
+        bracket_token,
+        elems: constraints,
+    })))
+}
+
+/// Parses a single element of a constraint list, like `30%`, `3` or `min 10`.
+fn parse_constraint_shorthand(input: ParseStream) -> Result<Expr> {
+    let span = input.span();
+    if input.peek(Ident) && !input.peek2(Token![::]) && !input.peek2(token::Paren) {
+        let fork = input.fork();
+        let name = fork.parse::<Ident>()?;
+        let variant = match name.to_string().as_str() {
+            "fill" => Some(format_ident!("Fill", span = span)),
+            "min" => Some(format_ident!("Min", span = span)),
+            "max" => Some(format_ident!("Max", span = span)),
+            _ => None,
+        };
+        // A plain `fill` has a weight of 1, while `min` and `max` need a value
+        let value = match fork.is_empty() || fork.peek(Token![,]) {
+            true if name == "fill" => Some(parse_quote_spanned! {span=> 1 }),
+            true => None,
+            false => variant.as_ref().map(|_| fork.parse::<Expr>()).transpose()?,
+        };
+        if let (Some(variant), Some(value)) = (variant, value) {
+            input.advance_to(&fork);
+            return Ok(
+                parse_quote_spanned! {span=> ratatui::layout::Constraint::#variant(#value) },
+            );
+        }
+    }
+
+    if let Some(value) = parse_percentage(input)? {
+        return Ok(parse_quote_spanned! {span=> ratatui::layout::Constraint::Percentage(#value) });
+    }
+
+    match input.parse::<Expr>()? {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(length),
+            ..
+        }) => Ok(parse_quote_spanned! {span=> ratatui::layout::Constraint::Length(#length) }),
+        constraint => Ok(constraint),
+    }
+}
@@ -1,7 +1,10 @@
-use super::{util::get_render_function, WidgetHandlerOptions};
+use super::{
+    util::{get_child_render_code, get_widget_area},
+    WidgetHandlerOptions,
+};
 use crate::{
     codegen::{generate_widget_code, wrapper::get_conditional_wrapper},
-    widget::{Widget, WidgetRenderer},
+    widget::Widget,
     MacroInput,
 };
 use proc_macro2::TokenStream;
@@ -24,76 +27,59 @@ use syn::Expr;
 /// # Returns
 ///
 /// A `TokenStream` containing the generated code to conditionally render widgets:
-/// - For top-level widgets: Direct render function calls in an if-else block
-/// - For nested widgets: A `ConditionalWrapper` initialization
+/// - For ui!(): Render calls for the children in an if-else block
+/// - For widget!(): A `ConditionalWrapper` initialization
 pub fn handle_conditional_widget(
-    widget: &Widget,
+    _widget: &Widget,
     condition: &Expr,
-    if_child: &Box<Widget>,
+    if_child: &Widget,
     else_child: &Option<Box<Widget>>,
     options: &WidgetHandlerOptions,
 ) -> TokenStream {
     // Extract relevant options
     let WidgetHandlerOptions {
-        is_top_level,
         input,
         parent_id,
         child_index,
         ..
     } = options;
 
-    // Determine if we need to render by reference
-    let render_ref_code = match widget.render_ref {
-        true => quote! {&},
-        false => quote! {},
-    };
-
     // Create new options for child widgets (not top level)
     let new_options = WidgetHandlerOptions::new(false, *parent_id, *child_index, input);
-    // Generate code for the if branch widget
-    let if_child_widget = generate_widget_code(if_child, &new_options);
 
-    // Get the conditional wrapper type for nested widgets
-    let conditional_wrapper = get_conditional_wrapper();
-
-    // Handle UI rendering case
+    // In ui!(), the chosen branch is rendered directly into the conditional's area,
+    // so stateful and layout children are rendered the same way as anywhere else
     if let MacroInput::Ui { renderer, .. } = input {
-        let (render_fn, frame_render_fn) = get_render_function(widget);
-
-        // Generate code for else branch if it exists
-        let else_child_widget = match else_child {
-            Some(else_child) => {
-                let else_child_widget = generate_widget_code(else_child, &new_options);
-                else_child_widget
-            }
+        let area = get_widget_area(options);
+        let new_options = new_options.with_area(area.clone());
+        let if_render_code = get_child_render_code(
+            if_child,
+            &generate_widget_code(if_child, &new_options),
+            &area,
+            renderer,
+        );
+        let else_render_code = match else_child {
+            Some(else_child) => get_child_render_code(
+                else_child,
+                &generate_widget_code(else_child, &new_options),
+                &area,
+                renderer,
+            ),
             None => quote! {},
         };
 
-        // For top level widgets, generate direct conditional render calls
-        if *is_top_level {
-            return match renderer {
-                // Render to an area with buffer
-                WidgetRenderer::Area { area, buffer } => quote! {
-                    if #condition {
-                        #render_fn(#render_ref_code #if_child_widget, #area, #buffer);
-                    } else {
-                        #render_fn(#render_ref_code #else_child_widget, #area, #buffer);
-                    }
-                },
-
-                // Render to a frame
-                WidgetRenderer::Frame(frame) => quote! {
-                    if #condition {
-                        #frame .#frame_render_fn(#render_ref_code #if_child_widget, #frame.area());
-                    } else {
-                        #frame .#frame_render_fn(#render_ref_code #else_child_widget, #frame.area());
-                    }
-                },
-            };
-        }
+        return quote! {
+            if #condition {
+                #if_render_code
+            } else {
+                #else_render_code
+            }
+        };
     }
 
-    // For nested widgets, wrap in a ConditionalWrapper
+    // In widget!(), wrap the children in a ConditionalWrapper
+    let if_child_widget = generate_widget_code(if_child, &new_options);
+    let conditional_wrapper = get_conditional_wrapper();
     let conditional_code = match else_child {
         Some(else_child) => {
             let else_child_widget = generate_widget_code(else_child, &new_options);
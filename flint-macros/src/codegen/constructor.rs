@@ -1,4 +1,7 @@
-use super::{util::get_render_function, WidgetHandlerOptions};
+use super::{
+    util::{chain_method, get_arg_value, get_render_function, get_widget_area},
+    WidgetHandlerOptions,
+};
 use crate::{
     arg::ArgKind,
     widget::{Widget, WidgetRenderer},
@@ -23,11 +26,11 @@ use syn::Ident;
 /// # Arguments
 ///
 /// * `widget` - The widget definition containing arguments and configuration, including
-///             positional args, named args, and render settings
+///   positional args, named args, and render settings
 /// * `name` - The identifier for the widget type/name that will be constructed
 /// * `constructor` - The identifier for the specific constructor function to call
 /// * `options` - Additional options including whether this is a top-level widget
-///              and what kind of UI input is being used
+///   and what kind of UI input is being used
 ///
 /// # Returns
 ///
@@ -54,7 +57,7 @@ pub fn handle_constructor_widget(
     let positional_args: Vec<_> = args
         .iter()
         .filter_map(|arg| match &arg.kind {
-            ArgKind::Positional => Some(&arg.value),
+            ArgKind::Positional => Some(get_arg_value(&arg.value, options)),
             _ => None,
         })
         .collect();
@@ -66,11 +69,9 @@ pub fn handle_constructor_widget(
 
     // Add any named arguments as chained method calls
     for arg in args {
-        if let ArgKind::Named(name) = &arg.kind {
-            let value = &arg.value;
-            widget_code.extend(quote! {
-                .#name(#value)
-            });
+        if let ArgKind::Named(_) = arg.kind {
+            let value = get_arg_value(&arg.value, options);
+            widget_code = chain_method(widget_code, arg, value);
         }
     }
 
@@ -85,14 +86,15 @@ pub fn handle_constructor_widget(
     // For top-level widgets in UI context, generate rendering code
     if let MacroInput::Ui { renderer, .. } = input {
         if *is_top_level {
+            let widget_area = get_widget_area(options);
             return match renderer {
                 // TODO: if widget is stateful, pass in the state
-                WidgetRenderer::Area { area, buffer } => quote! {
-                    #render_fn(#render_ref_code #widget_code, #area, #buffer);
+                WidgetRenderer::Area { buffer, .. } => quote! {
+                    #render_fn(#render_ref_code #widget_code, #widget_area, #buffer);
                 },
 
                 WidgetRenderer::Frame(frame) => quote! {
-                    #frame .#frame_render_fn(#render_ref_code #widget_code, #frame.area());
+                    #frame .#frame_render_fn(#render_ref_code #widget_code, #widget_area);
                 },
             };
         }
@@ -0,0 +1,43 @@
+//! Debug drawing of Layout widgets.
+//!
+//! With the `debug-layout` feature enabled, every Layout draws the boundaries and
+//! indices of its chunks on top of its children, which makes it easy to see which
+//! chunk a widget ends up in.
+
+use crate::widget::WidgetRenderer;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Generates code that draws a border around every chunk of a layout, titled with
+/// the chunk's index.
+///
+/// # Arguments
+///
+/// * `chunks` - Expression evaluating to the chunks of the layout
+/// * `renderer` - How the borders are rendered. `None` renders into a `buf` variable,
+///   like in the render function of a widget.
+pub fn get_debug_layout_code(
+    chunks: &TokenStream,
+    renderer: Option<&WidgetRenderer>,
+) -> TokenStream {
+    let render_code = match renderer {
+        Some(WidgetRenderer::Frame(frame)) => quote! {
+            #frame .render_widget(block, *chunk);
+        },
+        Some(WidgetRenderer::Area { buffer, .. }) => quote! {
+            ratatui::widgets::Widget::render(block, *chunk, #buffer);
+        },
+        None => quote! {
+            ratatui::widgets::Widget::render(block, *chunk, buf);
+        },
+    };
+
+    quote! {
+        for (index, chunk) in #chunks.iter().enumerate() {
+            let block = ratatui::widgets::Block::bordered()
+                .border_style(ratatui::style::Style::new().fg(ratatui::style::Color::Magenta))
+                .title(index.to_string());
+            #render_code
+        }
+    }
+}
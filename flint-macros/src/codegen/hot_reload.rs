@@ -0,0 +1,79 @@
+//! Hot reloading of Layout widgets during development.
+//!
+//! With the `hot-reload` feature enabled, every Layout is passed through a small runtime
+//! interpreter, which applies overrides for its direction, constraints, margin, spacing
+//! and flex from the file given by the `FLINT_HOT_RELOAD` environment variable. Layout
+//! tweaks can then be made by editing that file while the app is running. Only the
+//! arguments of Layouts are reloaded, so changing styles still needs a recompile.
+
+use super::wrapper::get_hot_reload_wrapper;
+use crate::arg::{Arg, ArgKind, ArgValue};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::Ident;
+
+/// Names of the Layout arguments which can be hot reloaded
+const HOT_RELOAD_ARGS: [&str; 5] = ["direction", "constraints", "margin", "spacing", "flex"];
+
+/// Wraps the code constructing a layout, so overrides from the hot reload file are
+/// applied to it at runtime.
+///
+/// The layout is identified by the location of its name in the source code, and is
+/// described by the values of its hot reloadable arguments, written the way the
+/// runtime interpreter parses them (like `constraints: [Length(3), Fill(1)]`).
+///
+/// # Arguments
+///
+/// * `name` - The name of the layout widget
+/// * `args` - Arguments passed to the layout widget
+/// * `layout_expr` - The code constructing the layout
+pub fn get_hot_reload_code(name: &Ident, args: &[Arg], layout_expr: TokenStream) -> TokenStream {
+    let span = name.span().unwrap();
+    let id = format!("{}:{}:{}", span.file(), span.line(), span.column());
+
+    let description = args
+        .iter()
+        .filter_map(|arg| match (&arg.kind, &arg.value) {
+            (ArgKind::Named(name), ArgValue::Expr(value))
+                if HOT_RELOAD_ARGS.iter().any(|arg| name == arg) =>
+            {
+                Some(format!("{}: {}", name, describe_value(name, value)))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ; ");
+
+    let hot_reload_wrapper = get_hot_reload_wrapper();
+    quote! {
+        {
+            #hot_reload_wrapper
+            hot_reload_layout(#layout_expr, #id, #description)
+        }
+    }
+}
+
+/// Describes the value of a hot reloadable argument, without any paths and spacing,
+/// so `[Constraint::Length(3), Constraint::Fill(1)]` becomes `[Length(3), Fill(1)]`
+/// and `Direction::Vertical` becomes `vertical`.
+fn describe_value(name: &Ident, value: &syn::Expr) -> String {
+    let mut description = value.to_token_stream().to_string().replace(' ', "");
+    for prefix in ["ratatui::layout::", "Constraint::", "Direction::", "Flex::"] {
+        description = description.replace(prefix, "");
+    }
+    let description = description.replace(',', ", ");
+
+    if name != "direction" && name != "flex" {
+        return description;
+    }
+
+    // Variants are written in snake case, like the bare identifiers accepted by Layout
+    let mut snake_case = String::new();
+    for (index, char) in description.chars().enumerate() {
+        if char.is_uppercase() && index > 0 {
+            snake_case.push('_');
+        }
+        snake_case.push(char.to_ascii_lowercase());
+    }
+    snake_case
+}
@@ -1,8 +1,11 @@
 use super::{generate_widget_code, wrapper::get_iter_layout_wrapper, WidgetHandlerOptions};
 use crate::{
-    arg::ArgKind,
-    codegen::util::generate_unique_id,
-    widget::{Widget, WidgetKind, WidgetRenderer},
+    arg::{ArgKind, ArgValue},
+    codegen::util::{
+        chain_method, generate_unique_id, get_arg_value, get_child_render_code,
+        get_layout_arg_value, get_state_ref, get_widget_area,
+    },
+    widget::{Widget, WidgetKind},
     MacroInput,
 };
 use proc_macro2::Span;
@@ -19,6 +22,7 @@ use syn::{Expr, Pat};
 /// * `widget` - The widget definition containing layout arguments and configuration
 /// * `loop_var` - Pattern to bind each iterator item (e.g. the 'x' in 'for x in items')
 /// * `iter` - Expression that produces the iterator to loop over
+/// * `filter` - Condition of the `if` argument, which items must meet to be rendered
 /// * `child` - The widget template to render for each iterator item
 /// * `options` - Configuration including parent context and rendering mode
 ///
@@ -28,25 +32,40 @@ use syn::{Expr, Pat};
 /// - Set up the layout configuration
 /// - Split the available area into chunks
 /// - Render the child widget for each iterator item in the appropriate chunk
+///
+/// The `constraint` argument sizes the area of every item. It's evaluated once per item
+/// with the loop variable bound to a reference to the item, so the constraints can be
+/// computed at runtime from the items (like `constraint: Constraint::Length(item.height)`).
+/// The layout is split with one constraint per item.
+///
+/// If an `index` argument is given (like `index: i`), the position of every item is bound
+/// to that identifier, which is the same as iterating over `items.enumerate()` with a
+/// `(i, item)` pattern.
+///
+/// If an `if` argument is given (like `if: item.enabled`), only the items it holds for
+/// are rendered. Like the constraint, it's evaluated with the loop variable bound to a
+/// reference to the item. The items are filtered before the area is split, so skipped
+/// items don't take up a chunk, and the index counts the rendered items.
+///
+/// If a `key` argument is given, the child must be a Stateful widget whose state is a map
+/// (like a `HashMap`) of item states. Every item's state is then looked up by its key,
+/// so per-item state survives reordering of the iterated collection.
 pub fn handle_iter_layout_widget(
     widget: &Widget,
     loop_var: &Pat,
     iter: &Expr,
-    child: &Box<Widget>,
+    filter: Option<&Expr>,
+    child: &Widget,
     options: &WidgetHandlerOptions,
 ) -> TokenStream {
     let WidgetHandlerOptions {
-        is_top_level,
-        parent_id,
-        child_index,
-        input,
+        child_index, input, ..
     } = options;
 
     let args = &widget.args;
     let layout_index = generate_unique_id() as usize;
     let layout_ident =
         proc_macro2::Ident::new(&format!("layout_{}", layout_index), Span::call_site());
-    let parent_ident = proc_macro2::Ident::new(&format!("chunks_{}", parent_id), Span::call_site());
     let iterator_index_ident = proc_macro2::Ident::new(
         &format!("iterator_index_{}", layout_index),
         Span::call_site(),
@@ -55,29 +74,98 @@ pub fn handle_iter_layout_widget(
     let positional_args: Vec<_> = args
         .iter()
         .filter_map(|arg| match &arg.kind {
-            ArgKind::Positional => Some(&arg.value),
+            ArgKind::Positional => Some(get_arg_value(&arg.value, options)),
             _ => None,
         })
         .collect();
 
-    let mut layout_code = quote! {
-        let mut #layout_ident = ratatui::layout::Layout::default(#(#positional_args),*)
+    let mut layout_expr = quote! {
+        ratatui::layout::Layout::default(#(#positional_args),*)
     };
 
+    // The `constraint` argument sizes every item's area, the `key` argument
+    // identifies every item's state and the `index` argument names every item's
+    // position, so none of them is a method on Layout
+    let mut item_constraint = None;
+    let mut item_key = None;
+    let mut item_index = None;
+
     // Add named arguments as method calls
     for arg in args {
         if let ArgKind::Named(name) = &arg.kind {
-            let value = &arg.value;
-            layout_code.extend(quote! {
-                .#name(#value)
-            });
+            let value = get_layout_arg_value(name, &arg.value, options);
+            if name == "constraint" {
+                item_constraint = Some(value);
+                continue;
+            }
+            if name == "key" {
+                item_key = Some((name, value));
+                continue;
+            }
+            if name == "index" {
+                match &arg.value {
+                    ArgValue::Expr(Expr::Path(path)) if path.path.get_ident().is_some() => {
+                        item_index = path.path.get_ident().cloned();
+                    }
+                    _ => {
+                        return syn::Error::new_spanned(
+                            name,
+                            "`index` must be an identifier, like `index: i`",
+                        )
+                        .to_compile_error();
+                    }
+                }
+                continue;
+            }
+            layout_expr = chain_method(layout_expr, arg, value);
         }
     }
 
-    layout_code.extend(quote! { ; });
+    let mut layout_code = quote! {
+        let mut #layout_ident = #layout_expr;
+    };
+
+    // With a key, the state of a Stateful child is looked up by the item's key in the
+    // given map, so every item keeps its own state even if the items are reordered
+    let mut key_code = quote! {};
+    let mut keyed_child = None;
+    if let Some((name, key)) = item_key {
+        let WidgetKind::Stateful {
+            state,
+            child: inner,
+        } = &child.kind
+        else {
+            return syn::Error::new_spanned(
+                name,
+                "`key` can only be used when the child of For is a Stateful widget",
+            )
+            .to_compile_error();
+        };
 
-    let new_options = WidgetHandlerOptions::new(false, layout_index, *child_index, input);
-    let child_widget = generate_widget_code(child, &new_options);
+        let state = get_state_ref(state);
+        let key_ident =
+            proc_macro2::Ident::new(&format!("key_{}", layout_index), Span::call_site());
+        key_code = quote! { let #key_ident = #key; };
+        keyed_child = Some(Widget {
+            kind: WidgetKind::Stateful {
+                state: syn::parse_quote! { &mut *(#state).entry(#key_ident).or_default() },
+                child: inner.clone(),
+            },
+            args: child.args.clone(),
+            render_ref: child.render_ref,
+        });
+    }
+    let child = keyed_child.as_ref().unwrap_or(child);
+
+    // Skipped items are left out of the iterator, so they're never given a chunk. Parts
+    // of the loop variable may only be there for the filter, so they can go unused.
+    let allow_unused = filter.map(|_| quote! { #[allow(unused_variables)] });
+    let iter = match filter {
+        Some(filter) => quote! {
+            (#iter).filter(|#[allow(unused_variables)] #loop_var| #filter)
+        },
+        None => quote! { #iter },
+    };
 
     match input {
         MacroInput::Ui { renderer, .. } => {
@@ -85,59 +173,53 @@ pub fn handle_iter_layout_widget(
             let chunks_ident =
                 proc_macro2::Ident::new(&format!("chunks_{}", layout_index), Span::call_site());
 
-            // Split the area - for top level use frame.area(), for nested use the parent's chunk
-            let split_code = if *is_top_level {
-                match renderer {
-                    WidgetRenderer::Area { area, .. } => {
-                        quote! {
-                            let #chunks_ident = #layout_ident.split(#area);
-                        }
-                    }
+            // Every item is rendered into its own chunk
+            let item_area = quote! { #chunks_ident[#iterator_index_ident] };
+            let new_options = WidgetHandlerOptions::new(false, layout_index, *child_index, input)
+                .with_area(item_area.clone());
+            let child_widget = generate_widget_code(child, &new_options);
 
-                    WidgetRenderer::Frame(frame) => {
-                        quote! {
-                            let #chunks_ident = #layout_ident.split(#frame .area());
-                        }
-                    }
-                }
-            } else {
-                quote! {
-                    let #chunks_ident = #layout_ident.split(#parent_ident[#child_index]);
-                }
+            // Split the area given to this layout
+            let area = get_widget_area(options);
+            let split_code = quote! {
+                let #chunks_ident = #layout_ident.split(#area);
             };
 
-            let mut render_statements = quote! {};
-            match child.kind {
-                // Layout widgets don't return an actual widget, so we don't call frame.render_widget on them
-                // Instead, their children are rendered recursively
-                WidgetKind::Layout { .. } | WidgetKind::IterLayout { .. } => {
-                    render_statements.extend(quote! {
-                        #child_widget
+            // If a per-item constraint was given, collect the items first so the layout
+            // can be given one constraint per item before splitting the area
+            let iter_code = match &item_constraint {
+                Some(constraint) => {
+                    let items_ident = proc_macro2::Ident::new(
+                        &format!("items_{}", layout_index),
+                        Span::call_site(),
+                    );
+                    layout_code.extend(quote! {
+                        let #items_ident = #iter.collect::<Vec<_>>();
+                        #layout_ident = #layout_ident.constraints(
+                            #items_ident
+                                .iter()
+                                .map(|#[allow(unused_variables)] #loop_var| #constraint)
+                                .collect::<Vec<_>>()
+                        );
                     });
+                    quote! { #items_ident.into_iter() }
                 }
+                None => iter,
+            };
 
-                // For other widgets (Variable and Constructor), we call frame.render_widget on them
-                // since they actually retturn something that implements ratatui::Widget
-                _ => {
-                    render_statements.extend(match renderer {
-                        WidgetRenderer::Area { buffer, .. } => {
-                            quote! {
-                                for (#iterator_index_ident, #loop_var) in #iter.enumerate() {
-                                    #child_widget.render(#chunks_ident[#iterator_index_ident], #buffer);
-                                }
-                            }
-                        }
-
-                        WidgetRenderer::Frame(frame) => {
-                            quote! {
-                                for (#iterator_index_ident, #loop_var) in #iter.enumerate() {
-                                    #frame .render_widget(#child_widget, #chunks_ident[#iterator_index_ident]);
-                                }
-                            }
-                        }
-                    });
+            let index_code = item_index.map(|index| {
+                quote! { let #index = #iterator_index_ident; }
+            });
+
+            let render_code = get_child_render_code(child, &child_widget, &item_area, renderer);
+            let render_statements = quote! {
+                #allow_unused
+                for (#iterator_index_ident, #loop_var) in #iter_code.enumerate() {
+                    #index_code
+                    #key_code
+                    #render_code
                 }
-            }
+            };
 
             quote! {
                 {
@@ -150,24 +232,44 @@ pub fn handle_iter_layout_widget(
 
         MacroInput::Raw { .. } => {
             let wrapper_code = get_iter_layout_wrapper();
+            let new_options = WidgetHandlerOptions::new(false, layout_index, *child_index, input);
+            let child_widget = generate_widget_code(child, &new_options);
 
-            let render_statements = quote! {
+            // With an index, the items are enumerated so the closure gets their position
+            let (iter, item_pattern, constraint_pattern) = match item_index {
+                Some(index) => (
+                    quote! { (#iter).enumerate() },
+                    quote! { (#index, #loop_var) },
+                    quote! { (_, #loop_var) },
+                ),
+                None => (quote! { #iter }, quote! { #loop_var }, quote! { #loop_var }),
+            };
+
+            let mut render_statements = quote! {
                 IterLayoutWrapper::new(
                     #layout_ident,
                     #iter,
                     |item, area, buf| {
-                        let #loop_var = item;
+                        #allow_unused
+                        let #item_pattern = item;
+                        #key_code
                         let widget = #child_widget;
                         widget.render(*area, buf);
                     }
                 )
             };
 
-            return quote! {{
+            if let Some(constraint) = item_constraint {
+                render_statements.extend(quote! {
+                    .constraint(|#[allow(unused_variables)] #constraint_pattern| #constraint)
+                });
+            }
+
+            quote! {{
                 #layout_code
                 #wrapper_code
                 #render_statements
-            }};
+            }}
         }
     }
 }
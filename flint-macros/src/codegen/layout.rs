@@ -3,10 +3,12 @@ use crate::{
     arg::ArgKind,
     codegen::{
         generate_widget_code,
-        util::{generate_unique_id, get_render_function, get_stateful_render_function},
-        wrapper::get_layout_wrapper,
+        util::{
+            chain_method, generate_unique_id, get_arg_value, get_child_render_code,
+            get_layout_arg_value, get_widget_area,
+        },
     },
-    widget::{Widget, WidgetKind, WidgetRenderer},
+    widget::{Widget, WidgetKind},
     MacroInput,
 };
 use proc_macro2::TokenStream;
@@ -20,13 +22,23 @@ use syn::Ident;
 /// 1. Creating a new layout instance with provided arguments
 /// 2. Configuring the layout with any named parameters
 /// 3. Handling child widgets differently based on the input type (Raw vs UI)
-/// 4. For Raw input: Creating a vector of render functions for children
+/// 4. For Raw input: Generating a struct that holds the layout and its children, and
+///    splits the area and renders the children when it is rendered
 /// 5. For UI input: Splitting the available area into chunks and rendering children into those chunks
 ///
 /// The function supports different types of child widgets:
 /// - Layout widgets: Rendered recursively
 /// - Constructor widgets: Always rendered statelessly
 /// - Stateful widgets: Rendered with state management
+/// - Overlay widgets: Don't take up a chunk, and are drawn over the whole layout area
+///   after all other children
+/// - Widgets with an `area` argument: Don't take up a chunk, and are drawn into
+///   the given area after all other children
+/// - Let statements: Don't take up a chunk, and are emitted in place, so the children
+///   after them can use the values they define
+/// - Spread widgets (`{* expr }`): Take up one chunk for every item of the iterator.
+///   Since the number of items is only known at runtime, the chunks of all children
+///   are then tracked with a counter instead of fixed indices
 /// - Other widgets: Rendered normally into layout chunks
 ///
 /// # Arguments
@@ -42,178 +54,361 @@ use syn::Ident;
 pub fn handle_layout_widget(
     widget: &Widget,
     name: &Ident,
-    children: &Vec<Widget>,
+    children: &[Widget],
     options: &WidgetHandlerOptions,
 ) -> TokenStream {
-    let WidgetHandlerOptions {
-        is_top_level,
-        parent_id,
-        child_index,
-        input,
-    } = options;
+    let WidgetHandlerOptions { input, .. } = options;
 
     let args = &widget.args;
     let layout_index = generate_unique_id() as usize;
     let layout_ident = proc_macro2::Ident::new(&format!("layout_{}", layout_index), name.span());
-    let parent_ident = proc_macro2::Ident::new(&format!("chunks_{}", parent_id), name.span());
 
     // Extract positional arguments from widget configuration
     let positional_args: Vec<_> = args
         .iter()
         .filter_map(|arg| match &arg.kind {
-            ArgKind::Positional => Some(&arg.value),
+            ArgKind::Positional => Some(get_arg_value(&arg.value, options)),
             _ => None,
         })
         .collect();
 
     // Begin constructing layout initialization code
-    let mut layout_code = quote! {
-        let mut #layout_ident = #name::default(#(#positional_args),*)
+    let mut layout_expr = quote! {
+        #name::default(#(#positional_args),*)
     };
 
     // Configure layout with named arguments
     for arg in args {
         if let ArgKind::Named(name) = &arg.kind {
-            let value = &arg.value;
-            layout_code.extend(quote! {
-                .#name(#value)
-            });
+            let value = get_layout_arg_value(name, &arg.value, options);
+            layout_expr = chain_method(layout_expr, arg, value);
         }
     }
 
-    layout_code.extend(quote! { ; });
+    // In development, apply any overrides from the hot reload file at runtime
+    #[cfg(feature = "hot-reload")]
+    let layout_expr = super::hot_reload::get_hot_reload_code(name, args, layout_expr);
+
+    let layout_code = quote! {
+        let mut #layout_ident = #layout_expr;
+    };
+
+    let has_iter_variable = children
+        .iter()
+        .any(|child| matches!(child.kind, WidgetKind::IterVariable { .. }));
 
     match input {
-        // Raw mode: Create vector of render functions
+        // Raw mode: Generate a struct holding the layout and its child widgets, which
+        // splits the area and renders the children when it is rendered itself. The
+        // children are constructed right away, so the layout can be returned as a value.
         MacroInput::Raw { .. } => {
-            layout_code.extend(quote! {
-                let mut children: Vec<Box<dyn Fn(Rect, &mut Buffer)>> = Vec::new();
-            });
+            let mut type_params = vec![];
+            let mut fields = vec![];
+            let mut field_values = vec![];
+            let mut child_statements = vec![];
+            let mut render_statements = vec![];
+            let mut render_ref_statements = vec![];
+            let mut overlay_statements = vec![];
+            let mut overlay_ref_statements = vec![];
+            let mut chunk_index = 0usize;
 
-            layout_code.extend(get_layout_wrapper());
+            let chunks_ident = proc_macro2::Ident::new("chunks", name.span());
+            let counter_ident = proc_macro2::Ident::new("chunk_index", name.span());
+            let (counter_code, next_chunk, counter_end) =
+                get_chunk_counter_code(has_iter_variable, &counter_ident);
 
             for (idx, child) in children.iter().enumerate() {
+                // The children are constructed in order, with the let statements
+                // between them, before they're moved into the struct
+                if let WidgetKind::Statement { stmt } = &child.kind {
+                    child_statements.push(quote! { #stmt });
+                    continue;
+                }
+
+                let type_param = proc_macro2::Ident::new(&format!("W{}", idx), name.span());
+                let field = proc_macro2::Ident::new(&format!("widget_{}", idx), name.span());
+                let child_ident = proc_macro2::Ident::new(
+                    &format!("child_{}_{}", layout_index, idx),
+                    name.span(),
+                );
+
+                // Spread widgets are collected into a Vec, and every item is
+                // rendered into the next chunk
+                if let WidgetKind::IterVariable { expr } = &child.kind {
+                    fields.push(quote! { #field: Vec<#type_param> });
+                    child_statements.push(quote! {
+                        let #child_ident = (#expr).into_iter().collect::<Vec<_>>();
+                    });
+                    field_values.push(quote! { #field: #child_ident });
+                    type_params.push(type_param);
+                    render_statements.push(quote! {
+                        for widget in self.#field {
+                            ratatui::widgets::Widget::render(widget, chunks[#counter_ident], buf);
+                            #next_chunk
+                        }
+                    });
+                    render_ref_statements.push(quote! {
+                        for widget in &self.#field {
+                            ratatui::widgets::WidgetRef::render_ref(widget, chunks[#counter_ident], buf);
+                            #next_chunk
+                        }
+                    });
+                    continue;
+                }
+
                 let new_options = WidgetHandlerOptions::new(false, layout_index, idx, input);
                 let child_widget = generate_widget_code(child, &new_options);
+                fields.push(quote! { #field: #type_param });
+                child_statements.push(quote! { let #child_ident = #child_widget; });
+                field_values.push(quote! { #field: #child_ident });
+                type_params.push(type_param);
 
-                layout_code.extend(quote! {
-                    children.push(Box::new(|area, buf| {
-                        #child_widget.render(area, buf);
-                    }));
-                });
+                // Overlays don't take up a chunk, they're drawn over the whole layout.
+                // Widgets with an explicit area are drawn into that area instead. Both
+                // are drawn after all other children.
+                if let Some(area) = child.area_arg() {
+                    let area = get_arg_value(&area.value, options);
+                    let area_field = proc_macro2::Ident::new(&format!("area_{}", idx), name.span());
+                    fields.push(quote! { #area_field: ratatui::layout::Rect });
+                    field_values.push(quote! { #area_field: #area });
+                    overlay_statements.push(quote! {
+                        ratatui::widgets::Widget::render(self.#field, self.#area_field, buf);
+                    });
+                    overlay_ref_statements.push(quote! {
+                        ratatui::widgets::WidgetRef::render_ref(&self.#field, self.#area_field, buf);
+                    });
+                } else if let WidgetKind::Overlay { .. } = child.kind {
+                    overlay_statements.push(quote! {
+                        ratatui::widgets::Widget::render(self.#field, area, buf);
+                    });
+                    overlay_ref_statements.push(quote! {
+                        ratatui::widgets::WidgetRef::render_ref(&self.#field, area, buf);
+                    });
+                } else {
+                    let chunk_area = get_chunk_area(
+                        has_iter_variable,
+                        &chunks_ident,
+                        &counter_ident,
+                        chunk_index,
+                    );
+                    render_statements.push(quote! {
+                        ratatui::widgets::Widget::render(self.#field, #chunk_area, buf);
+                        #next_chunk
+                    });
+                    render_ref_statements.push(quote! {
+                        ratatui::widgets::WidgetRef::render_ref(&self.#field, #chunk_area, buf);
+                        #next_chunk
+                    });
+                    chunk_index += 1;
+                }
             }
 
-            layout_code.extend(quote! {
-                LayoutWrapper::new(#layout_ident, children)
-            });
+            // Draw the chunk boundaries after the children, when debugging layouts
+            #[cfg(not(feature = "debug-layout"))]
+            let debug_code = quote! {};
+            #[cfg(feature = "debug-layout")]
+            let debug_code = super::debug::get_debug_layout_code(&quote! { chunks }, None);
 
             quote! {
                 {
                     #layout_code
+                    #(#child_statements)*
+
+                    /// A layout and its child widgets, generated by the widget!() macro
+                    struct LayoutWidget<#(#type_params),*> {
+                        layout: ratatui::layout::Layout,
+                        #(#fields,)*
+                    }
+
+                    impl<#(#type_params: ratatui::widgets::Widget),*> ratatui::widgets::Widget
+                        for LayoutWidget<#(#type_params),*>
+                    {
+                        fn render(self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
+                            let chunks = self.layout.split(area);
+                            #counter_code
+                            #(#render_statements)*
+                            #counter_end
+                            #debug_code
+                            #(#overlay_statements)*
+                        }
+                    }
+
+                    impl<#(#type_params: ratatui::widgets::WidgetRef),*> ratatui::widgets::WidgetRef
+                        for LayoutWidget<#(#type_params),*>
+                    {
+                        fn render_ref(&self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
+                            let chunks = self.layout.split(area);
+                            #counter_code
+                            #(#render_ref_statements)*
+                            #counter_end
+                            #debug_code
+                            #(#overlay_ref_statements)*
+                        }
+                    }
+
+                    LayoutWidget {
+                        layout: #layout_ident,
+                        #(#field_values,)*
+                    }
                 }
             }
         }
 
         // UI mode: Split area into chunks and render children
         MacroInput::Ui { renderer, .. } => {
+            let area_ident =
+                proc_macro2::Ident::new(&format!("area_{}", layout_index), name.span());
             let chunks_ident =
                 proc_macro2::Ident::new(&format!("chunks_{}", layout_index), name.span());
 
             // Generate area splitting code based on layout level
-            let split_code = if *is_top_level {
-                match renderer {
-                    WidgetRenderer::Area { area, .. } => {
-                        quote! {
-                            let #chunks_ident = #layout_ident.split(#area);
-                        }
-                    }
-
-                    WidgetRenderer::Frame(frame) => {
-                        quote! {
-                            let #chunks_ident = #layout_ident.split(#frame .area());
-                        }
-                    }
-                }
-            } else {
-                quote! {
-                    let #chunks_ident = #layout_ident.split(#parent_ident[#child_index]);
-                }
+            let widget_area = get_widget_area(options);
+            let split_code = quote! {
+                let #area_ident = #widget_area;
+                let #chunks_ident = #layout_ident.split(#area_ident);
             };
 
             let mut render_statements = quote! {};
+            let mut overlay_statements = quote! {};
+            let mut chunk_index = 0;
+
+            // With spread widgets, the chunk of every child is tracked with a counter
+            let counter_ident =
+                proc_macro2::Ident::new(&format!("chunk_index_{}", layout_index), name.span());
+            let (counter_code, next_chunk, counter_end) =
+                get_chunk_counter_code(has_iter_variable, &counter_ident);
 
             // Process each child widget
-            for (idx, child) in children.iter().enumerate() {
-                let new_options = WidgetHandlerOptions::new(false, layout_index, idx, input);
-                let (render_fn, frame_render_fn) = get_render_function(child);
-                let (stateful_render_fn, stateful_frame_render_fn) =
-                    get_stateful_render_function(child);
-                let child_widget = generate_widget_code(child, &new_options);
-                let render_ref_code = match child.render_ref {
-                    true => quote! {&},
-                    false => quote! {},
-                };
-
-                match child.kind {
-                    // Layout widgets render recursively
-                    WidgetKind::Layout { .. } | WidgetKind::IterLayout { .. } => {
-                        render_statements.extend(quote! {
-                            #child_widget
-                        });
-                    }
+            for child in children {
+                // Let statements are emitted in place, so the following children
+                // can use the values they define
+                if let WidgetKind::Statement { stmt } = &child.kind {
+                    render_statements.extend(quote! { #stmt });
+                    continue;
+                }
 
-                    // Constructor widgets render statelessly
-                    WidgetKind::Constructor { .. } => {
-                        let (render_fn, frame_render_fn) = get_render_function(widget);
-                        render_statements.extend(match renderer {
-                            WidgetRenderer::Area { buffer, .. } =>  {
-                                quote! {
-                                    #render_fn(#render_ref_code #child_widget, #chunks_ident[#idx], #buffer);
-                                }
-                            }
-
-                            WidgetRenderer::Frame(frame) => quote! {
-                                #frame .#frame_render_fn(#render_ref_code #child_widget, #chunks_ident[#idx]);
-                            },
-                        });
-                    }
+                // Widgets with an explicit area don't take up a chunk either. Like
+                // overlays, they're drawn after the other children.
+                if let Some(area) = child.area_arg() {
+                    let area = get_arg_value(&area.value, options);
+                    let new_options = WidgetHandlerOptions::new(false, layout_index, 0, input);
+                    let child_widget = generate_widget_code(child, &new_options);
+                    overlay_statements.extend(get_child_render_code(
+                        child,
+                        &child_widget,
+                        &area,
+                        renderer,
+                    ));
+                    continue;
+                }
 
-                    // Stateful widgets include state in rendering
-                    WidgetKind::Stateful { ref state, .. } => {
-                        render_statements.extend(match renderer {
-                            WidgetRenderer::Area {  buffer, .. } => quote! {
-                                #stateful_render_fn(#render_ref_code #child_widget, #chunks_ident[#idx], #buffer, #state);
-                            },
-
-                            WidgetRenderer::Frame(frame) => quote! {
-                                #frame .#stateful_frame_render_fn(#render_ref_code #child_widget, #chunks_ident[#idx], #state);
-                            },
-                        });
-                    }
+                // Overlays don't take up a chunk. They're drawn over the whole
+                // layout area, after all other children have been rendered.
+                if let WidgetKind::Overlay { .. } = child.kind {
+                    let new_options = WidgetHandlerOptions::new(false, layout_index, 0, input)
+                        .with_area(quote! { #area_ident });
+                    overlay_statements.extend(generate_widget_code(child, &new_options));
+                    continue;
+                }
 
-                    // Standard widgets render normally
-                    _ => {
-                        render_statements.extend(match renderer {
-                            WidgetRenderer::Area {  buffer, .. } => quote! {
-                                #render_fn(#render_ref_code #child_widget, #chunks_ident[#idx], #buffer);
-                            },
-
-                            WidgetRenderer::Frame(frame) => quote! {
-                                #frame .#frame_render_fn(#render_ref_code #child_widget, #chunks_ident[#idx]);
-                            },
-                        });
-                    }
+                // Every item of a spread widget is rendered into the next chunk
+                if let WidgetKind::IterVariable { expr } = &child.kind {
+                    let item_ident =
+                        proc_macro2::Ident::new(&format!("item_{}", layout_index), name.span());
+                    let child_area = quote! { #chunks_ident[#counter_ident] };
+                    let render_code = get_child_render_code(
+                        child,
+                        &quote! { #item_ident },
+                        &child_area,
+                        renderer,
+                    );
+                    render_statements.extend(quote! {
+                        for #item_ident in #expr {
+                            #render_code
+                            #next_chunk
+                        }
+                    });
+                    continue;
                 }
+
+                let child_area = get_chunk_area(
+                    has_iter_variable,
+                    &chunks_ident,
+                    &counter_ident,
+                    chunk_index,
+                );
+                let mut new_options =
+                    WidgetHandlerOptions::new(false, layout_index, chunk_index, input);
+                if has_iter_variable {
+                    new_options = new_options.with_area(child_area.clone());
+                }
+                let child_widget = generate_widget_code(child, &new_options);
+                render_statements.extend(get_child_render_code(
+                    child,
+                    &child_widget,
+                    &child_area,
+                    renderer,
+                ));
+                render_statements.extend(next_chunk.clone());
+                chunk_index += 1;
             }
 
+            // Draw the chunk boundaries after the children, when debugging layouts
+            #[cfg(not(feature = "debug-layout"))]
+            let debug_code = quote! {};
+            #[cfg(feature = "debug-layout")]
+            let debug_code =
+                super::debug::get_debug_layout_code(&quote! { #chunks_ident }, Some(renderer));
+
             // Combine all generated code
             quote! {
                 {
                     #layout_code
                     #split_code
+                    #counter_code
                     #render_statements
+                    #counter_end
+                    #debug_code
+                    #overlay_statements
                 }
             }
         }
     }
 }
+
+/// Generates the code for the counter tracking the next free chunk of a layout with
+/// spread widgets, whose chunks can't be given fixed indices.
+///
+/// Returns the code declaring the counter, the code advancing it after a chunk has
+/// been used, and the code reading it after all children have been rendered, so the
+/// last increment isn't reported as an unused assignment. Without spread widgets,
+/// all three are empty.
+fn get_chunk_counter_code(
+    has_iter_variable: bool,
+    counter: &proc_macro2::Ident,
+) -> (TokenStream, TokenStream, TokenStream) {
+    if !has_iter_variable {
+        return (quote! {}, quote! {}, quote! {});
+    }
+
+    (
+        quote! { let mut #counter = 0usize; },
+        quote! { #counter += 1; },
+        quote! { let _ = #counter; },
+    )
+}
+
+/// Returns the chunk a child of a layout is rendered into. This is the chunk at the
+/// child's index, or the chunk at the counter if the layout contains spread widgets.
+fn get_chunk_area(
+    has_iter_variable: bool,
+    chunks: &proc_macro2::Ident,
+    counter: &proc_macro2::Ident,
+    chunk_index: usize,
+) -> TokenStream {
+    if has_iter_variable {
+        quote! { #chunks[#counter] }
+    } else {
+        quote! { #chunks[#chunk_index] }
+    }
+}
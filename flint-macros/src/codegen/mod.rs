@@ -2,14 +2,21 @@
 
 pub mod conditional;
 pub mod constructor;
+#[cfg(feature = "debug-layout")]
+pub mod debug;
+#[cfg(feature = "hot-reload")]
+pub mod hot_reload;
 pub mod iter_layout;
 pub mod layout;
+pub mod overlay;
 pub mod stateful;
+pub mod text;
 pub mod util;
 pub mod variable;
 pub mod wrapper;
 
 use crate::{
+    arg::ArgKind,
     widget::{Widget, WidgetKind},
     MacroInput,
 };
@@ -17,7 +24,11 @@ use conditional::handle_conditional_widget;
 use constructor::handle_constructor_widget;
 use iter_layout::handle_iter_layout_widget;
 use layout::handle_layout_widget;
+use overlay::handle_overlay_widget;
+use quote::quote;
 use stateful::handle_stateful_widget;
+use text::handle_text_widget;
+use util::get_arg_value;
 use variable::handle_variable_widget;
 
 /// Options for configuring widget code generation.
@@ -31,6 +42,9 @@ pub struct WidgetHandlerOptions<'a> {
     child_index: usize,
     /// Identifier for the renderer being used (frame or area/buffer)
     input: &'a MacroInput,
+    /// Explicit area to render this widget into, overriding the area
+    /// derived from the parent widget
+    area: Option<proc_macro2::TokenStream>,
 }
 
 impl<'a> WidgetHandlerOptions<'a> {
@@ -41,7 +55,7 @@ impl<'a> WidgetHandlerOptions<'a> {
     /// * `is_top_level` - Whether this widget is at the top level
     /// * `parent_id` - ID of the parent widget
     /// * `child_index` - Index among siblings
-    /// * `frame` - Frame identifier
+    /// * `input` - The macro input being processed
     pub fn new(
         is_top_level: bool,
         parent_id: usize,
@@ -53,8 +67,20 @@ impl<'a> WidgetHandlerOptions<'a> {
             parent_id,
             child_index,
             input,
+            area: None,
         }
     }
+
+    /// Sets an explicit area for the widget to be rendered into, instead of
+    /// the chunk of the parent widget given by `parent_id` and `child_index`.
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - Expression evaluating to the `Rect` to render into
+    pub fn with_area(mut self, area: proc_macro2::TokenStream) -> Self {
+        self.area = Some(area);
+        self
+    }
 }
 
 /// Generates code for rendering a widget based on its kind and options.
@@ -71,12 +97,24 @@ pub fn generate_widget_code(
     widget: &Widget,
     options: &WidgetHandlerOptions,
 ) -> proc_macro2::TokenStream {
+    // An `area` argument overrides the area given by the parent widget. It's not a
+    // method on the widget, so it's removed before generating the widget's code.
+    if let Some(area) = widget.area_arg() {
+        let area = get_arg_value(&area.value, options);
+        let mut widget = widget.clone();
+        widget
+            .args
+            .retain(|arg| !matches!(&arg.kind, ArgKind::Named(name) if name == "area"));
+        return generate_widget_code(&widget, &options.clone().with_area(area));
+    }
+
     match &widget.kind {
         WidgetKind::IterLayout {
             loop_var,
             iter,
+            filter,
             child,
-        } => handle_iter_layout_widget(widget, loop_var, iter, child, options),
+        } => handle_iter_layout_widget(widget, loop_var, iter, filter.as_ref(), child, options),
 
         WidgetKind::Conditional {
             condition,
@@ -90,6 +128,18 @@ pub fn generate_widget_code(
 
         WidgetKind::Variable { expr } => handle_variable_widget(widget, expr, options),
 
+        // Spread widgets are handled by their parent layout, since they take up a
+        // variable number of its chunks
+        WidgetKind::IterVariable { expr } => syn::Error::new_spanned(
+            expr,
+            "`{* .. }` can only be used directly inside a Layout widget",
+        )
+        .to_compile_error(),
+
+        // Statements are only parsed between the children of a layout, which emits
+        // them in place
+        WidgetKind::Statement { stmt } => quote! { #stmt },
+
         WidgetKind::Constructor { name, constructor } => {
             handle_constructor_widget(widget, name, constructor, options)
         }
@@ -97,5 +147,11 @@ pub fn generate_widget_code(
         WidgetKind::Layout { name, children } => {
             handle_layout_widget(widget, name, children, options)
         }
+
+        WidgetKind::Text { lines } => handle_text_widget(widget, lines, options),
+
+        WidgetKind::Overlay { child, bordered } => {
+            handle_overlay_widget(widget, child, *bordered, options)
+        }
     }
 }
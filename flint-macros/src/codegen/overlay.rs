@@ -0,0 +1,147 @@
+use super::{
+    generate_widget_code,
+    util::{
+        chain_method, generate_unique_id, get_arg_value, get_centered_area_code,
+        get_child_render_code, get_percentage_value, get_widget_area,
+    },
+    wrapper::get_overlay_wrapper,
+    WidgetHandlerOptions,
+};
+use crate::{
+    arg::ArgKind,
+    widget::{Widget, WidgetRenderer},
+    MacroInput,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+/// Handles the rendering of an overlay widget (Overlay or Popup), which draws its
+/// child on top of previously rendered widgets.
+///
+/// The child is rendered into a centered area, sized by the `width` and `height`
+/// named arguments as percentages of the available area (50% each by default).
+/// The centered area is cleared using ratatui's `Clear` widget before the child is
+/// rendered, so that widgets underneath don't show through.
+///
+/// Popups additionally draw a bordered block in the centered area and render the
+/// child inside it. Any named arguments other than `width` and `height` are chained
+/// as method calls on the block (like `title: "Confirm"`).
+///
+/// When used as a child of a Layout, the overlay doesn't take up a chunk. Instead, it
+/// is centered over the whole layout area and drawn after all other children.
+///
+/// # Arguments
+///
+/// * `widget` - The overlay widget containing the size and block arguments
+/// * `child` - The widget to render on top
+/// * `bordered` - Whether the child is drawn inside a bordered block
+/// * `options` - Configuration options for widget handling
+///
+/// # Returns
+///
+/// A TokenStream containing:
+/// - For ui!(): Statements that clear the overlay area and render the child into it
+/// - For widget!(): An `OverlayWrapper` initialization
+pub fn handle_overlay_widget(
+    widget: &Widget,
+    child: &Widget,
+    bordered: bool,
+    options: &WidgetHandlerOptions,
+) -> TokenStream {
+    let WidgetHandlerOptions { input, .. } = options;
+
+    // Extract the size of the overlay, defaulting to half of the available area.
+    // Every other named argument is a method on the popup's block.
+    let mut width = quote! { 50 };
+    let mut height = quote! { 50 };
+    let mut block_code = quote! { ratatui::widgets::Block::bordered() };
+    for arg in &widget.args {
+        if let ArgKind::Named(name) = &arg.kind {
+            if name == "width" {
+                width = get_percentage_value(&arg.value, options);
+            } else if name == "height" {
+                height = get_percentage_value(&arg.value, options);
+            } else {
+                let value = get_arg_value(&arg.value, options);
+                block_code = chain_method(block_code, arg, value);
+            }
+        }
+    }
+
+    let overlay_index = generate_unique_id() as usize;
+
+    match input {
+        MacroInput::Ui { renderer, .. } => {
+            let area_ident =
+                proc_macro2::Ident::new(&format!("area_{}", overlay_index), Span::call_site());
+            let base_area = get_widget_area(options);
+            let area_code = get_centered_area_code(&area_ident, &base_area, &width, &height);
+
+            // Clear whatever was rendered underneath the overlay
+            let clear_code = match renderer {
+                WidgetRenderer::Area { buffer, .. } => quote! {
+                    ratatui::widgets::Widget::render(ratatui::widgets::Clear, #area_ident, #buffer);
+                },
+                WidgetRenderer::Frame(frame) => quote! {
+                    #frame .render_widget(ratatui::widgets::Clear, #area_ident);
+                },
+            };
+
+            // For popups, draw the block and render the child inside of it
+            let (block_code, child_area) = if bordered {
+                let block_ident =
+                    proc_macro2::Ident::new(&format!("block_{}", overlay_index), Span::call_site());
+                let inner_ident =
+                    proc_macro2::Ident::new(&format!("inner_{}", overlay_index), Span::call_site());
+                let render_code = match renderer {
+                    WidgetRenderer::Area { buffer, .. } => quote! {
+                        ratatui::widgets::Widget::render(#block_ident, #area_ident, #buffer);
+                    },
+                    WidgetRenderer::Frame(frame) => quote! {
+                        #frame .render_widget(#block_ident, #area_ident);
+                    },
+                };
+                let block_code = quote! {
+                    let #block_ident = #block_code;
+                    let #inner_ident = #block_ident.inner(#area_ident);
+                    #render_code
+                };
+                (block_code, quote! { #inner_ident })
+            } else {
+                (quote! {}, quote! { #area_ident })
+            };
+
+            let new_options = WidgetHandlerOptions::new(false, overlay_index, 0, input)
+                .with_area(child_area.clone());
+            let child_widget = generate_widget_code(child, &new_options);
+            let render_code = get_child_render_code(child, &child_widget, &child_area, renderer);
+
+            quote! {
+                {
+                    #area_code
+                    #clear_code
+                    #block_code
+                    #render_code
+                }
+            }
+        }
+
+        MacroInput::Raw { .. } => {
+            let new_options = WidgetHandlerOptions::new(false, overlay_index, 0, input);
+            let child_widget = generate_widget_code(child, &new_options);
+            let overlay_wrapper = get_overlay_wrapper();
+
+            let block_code = match bordered {
+                true => quote! { .block(#block_code) },
+                false => quote! {},
+            };
+
+            quote! {
+                {
+                    #overlay_wrapper
+                    OverlayWrapper::new(#child_widget, #width, #height) #block_code
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,13 @@
 /// Required imports for handling stateful widgets
 use crate::{
+    arg::ArgKind,
     codegen::{
-        generate_widget_code, util::get_stateful_render_function, wrapper::get_stateful_wrapper,
+        generate_widget_code,
+        util::{
+            chain_method, get_arg_value, get_state_ref, get_stateful_render_function,
+            get_widget_area,
+        },
+        wrapper::get_stateful_wrapper,
     },
     widget::{Widget, WidgetRenderer},
     MacroInput,
@@ -30,7 +36,7 @@ use syn::Expr;
 pub fn handle_stateful_widget(
     widget: &Widget,
     state: &Expr,
-    child: &Box<Widget>,
+    child: &Widget,
     options: &WidgetHandlerOptions,
 ) -> TokenStream {
     let WidgetHandlerOptions {
@@ -47,38 +53,50 @@ pub fn handle_stateful_widget(
         false => quote! {},
     };
 
+    let state = get_state_ref(state);
+
     // Create new options for child widget code generation
     let new_options = WidgetHandlerOptions::new(false, *parent_id, *child_index, input);
-    let child_widget = generate_widget_code(child, &new_options);
+    let mut child_widget = generate_widget_code(child, &new_options);
+
+    // Forward any named arguments on the Stateful wrapper as chained
+    // method calls on the inner stateful widget
+    for arg in &widget.args {
+        if let ArgKind::Named(_) = arg.kind {
+            let value = get_arg_value(&arg.value, options);
+            child_widget = chain_method(child_widget, arg, value);
+        }
+    }
 
     if let MacroInput::Ui { renderer, .. } = input {
         let (stateful_render_fn, stateful_frame_render_fn) = get_stateful_render_function(widget);
         if *is_top_level {
-            return match renderer {
+            let widget_area = get_widget_area(options);
+            match renderer {
                 // Generate code for rendering in an Area
-                WidgetRenderer::Area { area, buffer } => quote! {
-                    #stateful_render_fn(#render_ref_code #child_widget, #area, #buffer, #state);
+                WidgetRenderer::Area { buffer, .. } => quote! {
+                    #stateful_render_fn(#render_ref_code #child_widget, #widget_area, #buffer, #state);
                 },
 
                 // Generate code for rendering in a Frame
                 WidgetRenderer::Frame(frame) => quote! {
-                    #frame .#stateful_frame_render_fn(#render_ref_code #child_widget, #frame.area(), #state);
+                    #frame .#stateful_frame_render_fn(#render_ref_code #child_widget, #widget_area, #state);
                 },
-            };
+            }
         } else {
             child_widget
         }
     } else {
         // Handle stateful wrapper generation
-        let stateful_wrapper = match child.render_ref {
-            true => {
-                panic!(
-                    "The widget!() macro doesn't support rendering StatefulWidgetRef widgets yet."
-                )
-            }
-
-            false => get_stateful_wrapper(),
-        };
+        // The widget!() macro has no wrapper for StatefulWidgetRef widgets yet
+        if child.render_ref {
+            return syn::Error::new_spanned(
+                &state,
+                "The widget!() macro doesn't support rendering StatefulWidgetRef widgets yet",
+            )
+            .to_compile_error();
+        }
+        let stateful_wrapper = get_stateful_wrapper();
 
         // Generate initialization code for the stateful wrapper
         let stateful_wrapper_init = match child.render_ref {
@@ -0,0 +1,88 @@
+use super::{
+    util::{get_render_function, get_widget_area},
+    WidgetHandlerOptions,
+};
+use crate::{
+    widget::{SpanStyle, TextSpan, Widget, WidgetRenderer},
+    MacroInput,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+/// Handles the code generation for text written with the rich text syntax.
+///
+/// Every span is generated as a `Span::styled` call, with a style built from its
+/// modifiers and colors. The spans of each line are collected into a `Line`, and
+/// the lines into a `Text`.
+///
+/// # Arguments
+///
+/// * `widget` - The text widget
+/// * `lines` - The lines of the text, each made up of styled spans
+/// * `options` - Configuration options for widget handling
+///
+/// # Returns
+///
+/// A TokenStream containing either:
+/// - For top-level widgets in ui!(): Code that renders the text
+/// - Otherwise: An expression evaluating to the `ratatui::text::Text`
+pub fn handle_text_widget(
+    widget: &Widget,
+    lines: &[Vec<TextSpan>],
+    options: &WidgetHandlerOptions,
+) -> TokenStream {
+    let WidgetHandlerOptions {
+        is_top_level,
+        input,
+        ..
+    } = options;
+
+    let lines = lines.iter().map(|spans| {
+        let spans = spans.iter().map(get_span_code);
+        quote! {
+            ratatui::text::Line::from(vec![#(#spans),*])
+        }
+    });
+    let text_code = quote! {
+        ratatui::text::Text::from(vec![#(#lines),*])
+    };
+
+    if let (true, MacroInput::Ui { renderer, .. }) = (is_top_level, input) {
+        let (render_fn, frame_render_fn) = get_render_function(widget);
+        let widget_area = get_widget_area(options);
+        let render_ref_code = match widget.render_ref {
+            true => quote! {&},
+            false => quote! {},
+        };
+
+        return match renderer {
+            WidgetRenderer::Area { buffer, .. } => quote! {
+                #render_fn(#render_ref_code #text_code, #widget_area, #buffer);
+            },
+            WidgetRenderer::Frame(frame) => quote! {
+                #frame .#frame_render_fn(#render_ref_code #text_code, #widget_area);
+            },
+        };
+    }
+
+    text_code
+}
+
+/// Generates the code for a single styled span.
+fn get_span_code(span: &TextSpan) -> TokenStream {
+    let content = &span.content;
+    let styles = span.styles.iter().map(|style| match style {
+        SpanStyle::Modifier(name) => {
+            let modifier =
+                proc_macro2::Ident::new(&name.to_string().to_uppercase(), Span::call_site());
+            quote! { .add_modifier(ratatui::style::Modifier::#modifier) }
+        }
+        SpanStyle::Fg(color) => quote! { .fg(#color) },
+        SpanStyle::Bg(color) => quote! { .bg(#color) },
+        SpanStyle::Style(style) => quote! { .patch(#style) },
+    });
+
+    quote! {
+        ratatui::text::Span::styled(#content, ratatui::style::Style::new() #(#styles)*)
+    }
+}
@@ -1,7 +1,12 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
 
-use crate::widget::Widget;
+use super::{generate_widget_code, wrapper::get_state_wrapper, WidgetHandlerOptions};
+use crate::{
+    arg::{Arg, ArgKind, ArgValue},
+    widget::{Widget, WidgetKind, WidgetRenderer},
+    MacroInput,
+};
 
 /// Generates a unique identifier using an atomic counter.
 ///
@@ -43,3 +48,252 @@ pub fn get_stateful_render_function(widget: &Widget) -> (TokenStream, TokenStrea
     };
     (render_fn, frame_render_fn)
 }
+
+/// Generates a mutable reference to the state of a stateful widget.
+///
+/// States written as `&mut state` are used as-is. Any other expression is borrowed
+/// through the `StateMut` helper, which borrows owned states and reborrows states
+/// that are already `&mut` references, so `Stateful(state)` works for both.
+pub fn get_state_ref(state: &syn::Expr) -> TokenStream {
+    match state {
+        syn::Expr::Reference(reference) if reference.mutability.is_some() => quote! { #state },
+        _ => {
+            let state_wrapper = get_state_wrapper();
+            quote! {
+                {
+                    #state_wrapper
+                    (#state).state_mut()
+                }
+            }
+        }
+    }
+}
+
+/// Generates the code for an argument's value.
+///
+/// Plain expressions are emitted as-is, while nested widget expressions are
+/// code-generated inline as non-top-level widgets, so they evaluate to the
+/// constructed widget value.
+pub fn get_arg_value(value: &ArgValue, options: &WidgetHandlerOptions) -> TokenStream {
+    match value {
+        ArgValue::Expr(expr) => quote! { #expr },
+        ArgValue::Widget(widget) => {
+            let new_options = WidgetHandlerOptions::new(
+                false,
+                options.parent_id,
+                options.child_index,
+                options.input,
+            );
+            generate_widget_code(widget, &new_options)
+        }
+        ArgValue::Percentage(expr) => quote! { ratatui::layout::Constraint::Percentage(#expr) },
+    }
+}
+
+/// Chains a named argument as a method call on the given code.
+///
+/// If the argument has a condition (like `style if selected: selected_style`), the
+/// method is only called when the condition holds, and the value is left unchanged
+/// otherwise.
+pub fn chain_method(code: TokenStream, arg: &Arg, value: TokenStream) -> TokenStream {
+    let ArgKind::Named(name) = &arg.kind else {
+        return code;
+    };
+
+    match &arg.condition {
+        Some(condition) => {
+            let widget_ident = proc_macro2::Ident::new(
+                &format!("widget_{}", generate_unique_id()),
+                Span::call_site(),
+            );
+            quote! {
+                {
+                    let #widget_ident = #code;
+                    if #condition {
+                        #widget_ident.#name(#value)
+                    } else {
+                        #widget_ident
+                    }
+                }
+            }
+        }
+        None => quote! { #code.#name(#value) },
+    }
+}
+
+/// Generates the code for a named argument of a Layout (or For) widget.
+///
+/// Besides any expression, `direction` and `flex` accept bare identifiers for their
+/// variants, so `direction: horizontal` and `flex: space_between` expand to
+/// `Direction::Horizontal` and `Flex::SpaceBetween`.
+pub fn get_layout_arg_value(
+    name: &syn::Ident,
+    value: &ArgValue,
+    options: &WidgetHandlerOptions,
+) -> TokenStream {
+    let variants: &[&str] = if name == "direction" {
+        &["horizontal", "vertical"]
+    } else if name == "flex" {
+        &[
+            "legacy",
+            "start",
+            "end",
+            "center",
+            "space_between",
+            "space_around",
+        ]
+    } else {
+        &[]
+    };
+
+    if let ArgValue::Expr(syn::Expr::Path(path)) = value {
+        if let Some(ident) = path.path.get_ident() {
+            let variant = ident.to_string();
+            if variants.contains(&variant.as_str()) {
+                let variant = proc_macro2::Ident::new(
+                    &variant
+                        .split('_')
+                        .map(|word| word[..1].to_uppercase() + &word[1..])
+                        .collect::<String>(),
+                    ident.span(),
+                );
+                return match name == "direction" {
+                    true => quote! { ratatui::layout::Direction::#variant },
+                    false => quote! { ratatui::layout::Flex::#variant },
+                };
+            }
+        }
+    }
+
+    get_arg_value(value, options)
+}
+
+/// Generates the code for an argument that is always a percentage, so both
+/// `60` and `60%` evaluate to the plain number.
+pub fn get_percentage_value(value: &ArgValue, options: &WidgetHandlerOptions) -> TokenStream {
+    match value {
+        ArgValue::Percentage(expr) => quote! { #expr },
+        _ => get_arg_value(value, options),
+    }
+}
+
+/// Generates statements that bind `area_ident` to a rect centered in `base_area`,
+/// sized as a percentage of it.
+pub fn get_centered_area_code(
+    area_ident: &proc_macro2::Ident,
+    base_area: &TokenStream,
+    width: &TokenStream,
+    height: &TokenStream,
+) -> TokenStream {
+    quote! {
+        let [#area_ident] = ratatui::layout::Layout::vertical([
+            ratatui::layout::Constraint::Percentage(#height)
+        ])
+        .flex(ratatui::layout::Flex::Center)
+        .areas(#base_area);
+        let [#area_ident] = ratatui::layout::Layout::horizontal([
+            ratatui::layout::Constraint::Percentage(#width)
+        ])
+        .flex(ratatui::layout::Flex::Center)
+        .areas(#area_ident);
+    }
+}
+
+/// Generates an expression for the area a widget should be rendered into.
+///
+/// An explicit area set through [`WidgetHandlerOptions::with_area`] takes precedence.
+/// Otherwise, top-level widgets use the whole area of the renderer, and nested
+/// widgets use their chunk of the parent layout.
+pub fn get_widget_area(options: &WidgetHandlerOptions) -> TokenStream {
+    if let Some(area) = &options.area {
+        return area.clone();
+    }
+
+    if let (true, MacroInput::Ui { renderer, .. }) = (options.is_top_level, options.input) {
+        return match renderer {
+            WidgetRenderer::Area { area, .. } => quote! { #area },
+            WidgetRenderer::Frame(frame) => quote! { #frame .area() },
+        };
+    }
+
+    let parent_ident =
+        proc_macro2::Ident::new(&format!("chunks_{}", options.parent_id), Span::call_site());
+    let child_index = options.child_index;
+    quote! { #parent_ident[#child_index] }
+}
+
+/// Generates the code that renders a nested widget into the given area in ui!() mode.
+///
+/// - Layout-like widgets (Layout, For, Overlay and Popup) and conditional widgets
+///   render their own children, so their generated code is emitted as-is
+/// - Constructor widgets are always rendered statelessly
+/// - Stateful widgets are rendered with their state
+/// - Other widgets are rendered normally
+///
+/// # Arguments
+///
+/// * `child` - The widget being rendered
+/// * `child_widget` - The generated code for the widget
+/// * `area` - Expression evaluating to the `Rect` to render into
+/// * `renderer` - The renderer of the ui!() macro
+pub fn get_child_render_code(
+    child: &Widget,
+    child_widget: &TokenStream,
+    area: &TokenStream,
+    renderer: &WidgetRenderer,
+) -> TokenStream {
+    let (render_fn, frame_render_fn) = get_render_function(child);
+    let (stateful_render_fn, stateful_frame_render_fn) = get_stateful_render_function(child);
+    let render_ref_code = match child.render_ref {
+        true => quote! {&},
+        false => quote! {},
+    };
+
+    match &child.kind {
+        // Layout and conditional widgets render recursively
+        WidgetKind::Layout { .. }
+        | WidgetKind::IterLayout { .. }
+        | WidgetKind::Overlay { .. }
+        | WidgetKind::Conditional { .. } => {
+            quote! {
+                #child_widget
+            }
+        }
+
+        // Constructor widgets render statelessly
+        WidgetKind::Constructor { .. } => match renderer {
+            WidgetRenderer::Area { buffer, .. } => quote! {
+                ratatui::widgets::Widget::render(#render_ref_code #child_widget, #area, #buffer);
+            },
+
+            WidgetRenderer::Frame(frame) => quote! {
+                #frame .render_widget(#render_ref_code #child_widget, #area);
+            },
+        },
+
+        // Stateful widgets include state in rendering
+        WidgetKind::Stateful { state, .. } => {
+            let state = get_state_ref(state);
+            match renderer {
+                WidgetRenderer::Area { buffer, .. } => quote! {
+                    #stateful_render_fn(#render_ref_code #child_widget, #area, #buffer, #state);
+                },
+
+                WidgetRenderer::Frame(frame) => quote! {
+                    #frame .#stateful_frame_render_fn(#render_ref_code #child_widget, #area, #state);
+                },
+            }
+        }
+
+        // Standard widgets render normally
+        _ => match renderer {
+            WidgetRenderer::Area { buffer, .. } => quote! {
+                #render_fn(#render_ref_code #child_widget, #area, #buffer);
+            },
+
+            WidgetRenderer::Frame(frame) => quote! {
+                #frame .#frame_render_fn(#render_ref_code #child_widget, #area);
+            },
+        },
+    }
+}
@@ -0,0 +1,134 @@
+/// Applies the overrides for a layout from the hot reload file, if there are any.
+///
+/// The hot reload file is given by the `FLINT_HOT_RELOAD` environment variable, and
+/// contains one line per layout, like:
+///
+/// `src/app/test.rs:42:9 ; direction: vertical ; constraints: [Length(3), Fill(1)]`
+///
+/// Layouts that aren't in the file yet are appended to it with their description from
+/// the source code, so they can be edited in place. Any edited values are applied to
+/// the layout every time it's rendered, so no recompilation is needed. The file is only
+/// read again once its modification time changes. Values that can't be parsed are
+/// ignored.
+///
+/// # Arguments
+///
+/// * `layout` - The layout as written in the source code
+/// * `id` - Location of the layout in the source code
+/// * `description` - Description of the layout's arguments from the source code
+///
+/// Types are written with their full paths, since this code is expanded in the same
+/// scope as the layout's own code.
+fn hot_reload_layout(
+    mut layout: ratatui::layout::Layout,
+    id: &str,
+    description: &str,
+) -> ratatui::layout::Layout {
+    /// The line of the layout in the file, with the modification time it was read at.
+    /// Every layout gets its own copy of this function, so it only holds its own line.
+    static LINE: std::sync::Mutex<Option<(std::time::SystemTime, Option<String>)>> =
+        std::sync::Mutex::new(None);
+
+    let Ok(path) = std::env::var("FLINT_HOT_RELOAD") else {
+        return layout;
+    };
+    let modified = std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+
+    let mut cached = LINE.lock().unwrap_or_else(|err| err.into_inner());
+    let line = match (&*cached, modified) {
+        (Some((read_at, line)), Some(modified)) if *read_at == modified => line.clone(),
+        _ => {
+            let contents = std::fs::read_to_string(&path).unwrap_or_default();
+            let line = contents
+                .lines()
+                .find(|line| line.split(" ; ").next() == Some(id))
+                .map(String::from);
+            *cached = modified.map(|modified| (modified, line.clone()));
+            line
+        }
+    };
+    drop(cached);
+
+    let Some(line) = line else {
+        use std::io::Write;
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+        {
+            let _ = writeln!(file, "{} ; {}", id, description);
+        }
+        return layout;
+    };
+
+    for entry in line.split(" ; ").skip(1) {
+        let Some((key, value)) = entry.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "direction" => match value {
+                "horizontal" => layout = layout.direction(ratatui::layout::Direction::Horizontal),
+                "vertical" => layout = layout.direction(ratatui::layout::Direction::Vertical),
+                _ => (),
+            },
+            "constraints" => {
+                let constraints = value
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split("),")
+                    .filter(|constraint| !constraint.trim().is_empty())
+                    .map(|constraint| parse_constraint(constraint.trim()))
+                    .collect::<Option<Vec<_>>>();
+                if let Some(constraints) = constraints {
+                    layout = layout.constraints(constraints);
+                }
+            }
+            "margin" => {
+                if let Ok(margin) = value.parse() {
+                    layout = layout.margin(margin);
+                }
+            }
+            "spacing" => {
+                if let Ok(spacing) = value.parse::<u16>() {
+                    layout = layout.spacing(spacing);
+                }
+            }
+            "flex" => {
+                let flex = match value {
+                    "legacy" => ratatui::layout::Flex::Legacy,
+                    "start" => ratatui::layout::Flex::Start,
+                    "end" => ratatui::layout::Flex::End,
+                    "center" => ratatui::layout::Flex::Center,
+                    "space_between" => ratatui::layout::Flex::SpaceBetween,
+                    "space_around" => ratatui::layout::Flex::SpaceAround,
+                    _ => continue,
+                };
+                layout = layout.flex(flex);
+            }
+            _ => (),
+        }
+    }
+
+    layout
+}
+
+/// Parses a constraint like `Length(3)` or `Ratio(1, 2)`. The closing parenthesis
+/// is optional, since constraint lists are split on `),`.
+fn parse_constraint(constraint: &str) -> Option<ratatui::layout::Constraint> {
+    let (name, args) = constraint.trim_end_matches(')').split_once('(')?;
+    let args = args
+        .split(',')
+        .map(|arg| arg.trim().parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    match (name.trim(), args.as_slice()) {
+        ("Length", [value]) => Some(ratatui::layout::Constraint::Length(*value as u16)),
+        ("Min", [value]) => Some(ratatui::layout::Constraint::Min(*value as u16)),
+        ("Max", [value]) => Some(ratatui::layout::Constraint::Max(*value as u16)),
+        ("Percentage", [value]) => Some(ratatui::layout::Constraint::Percentage(*value as u16)),
+        ("Fill", [value]) => Some(ratatui::layout::Constraint::Fill(*value as u16)),
+        ("Ratio", [numerator, denominator]) => Some(ratatui::layout::Constraint::Ratio(*numerator, *denominator)),
+        _ => None,
+    }
+}
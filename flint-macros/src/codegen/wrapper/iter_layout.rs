@@ -1,6 +1,6 @@
 use ratatui::{
     buffer::Buffer,
-    layout::{Layout, Rect},
+    layout::{Constraint, Layout, Rect},
     widgets::Widget,
 };
 
@@ -17,7 +17,9 @@ where
     /// The iterator containing items to render
     iterator: I,
     /// Function that defines how to render each item in its allocated space
-    render_fn: Box<dyn Fn(I::Item, &Rect, &mut Buffer) + 'a>,
+    render_fn: Box<dyn FnMut(I::Item, &Rect, &mut Buffer) + 'a>,
+    /// Optional function computing the constraint that sizes the area of every item
+    constraint: Option<Box<dyn Fn(&I::Item) -> Constraint + 'a>>,
 }
 
 impl<'a, I> IterLayoutWrapper<'a, I>
@@ -33,14 +35,28 @@ where
     /// * `render_fn` - Function that will be called to render each item
     pub fn new<F>(layout: Layout, iterator: I, render_fn: F) -> Self
     where
-        F: Fn(I::Item, &Rect, &mut Buffer) + 'a,
+        F: FnMut(I::Item, &Rect, &mut Buffer) + 'a,
     {
         Self {
             layout,
             iterator,
             render_fn: Box::new(render_fn),
+            constraint: None,
         }
     }
+
+    /// Sets the function computing the constraint used to size the area of every item
+    ///
+    /// # Arguments
+    ///
+    /// * `constraint` - Function returning the constraint applied to an item's area
+    pub fn constraint<F>(mut self, constraint: F) -> Self
+    where
+        F: Fn(&I::Item) -> Constraint + 'a,
+    {
+        self.constraint = Some(Box::new(constraint));
+        self
+    }
 }
 
 impl<'a, I> Widget for IterLayoutWrapper<'a, I>
@@ -48,9 +64,17 @@ where
     I: Iterator,
 {
     /// Renders the widget by splitting the area according to layout and rendering each item
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let chunks = self.layout.split(area);
-        for (chunk, item) in chunks.into_iter().zip(self.iterator) {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let items = self.iterator.collect::<Vec<_>>();
+        let layout = match self.constraint {
+            Some(constraint) => self
+                .layout
+                .constraints(items.iter().map(constraint).collect::<Vec<_>>()),
+            None => self.layout,
+        };
+
+        let chunks = layout.split(area);
+        for (chunk, item) in chunks.iter().zip(items) {
             (self.render_fn)(item, chunk, buf);
         }
     }
@@ -13,6 +13,19 @@ pub fn get_conditional_wrapper() -> TokenStream {
     }
 }
 
+/// Retrieves and parses the contents of `hot_reload.rs` into a TokenStream.
+///
+/// This wrapper function includes the hot reload interpreter code
+/// and returns it as a parsed and quoted TokenStream ready for macro expansion.
+#[cfg(feature = "hot-reload")]
+pub fn get_hot_reload_wrapper() -> TokenStream {
+    let contents = include_str!("./hot_reload.rs");
+    let contents = syn::parse_str::<TokenStream>(contents).unwrap();
+    quote! {
+        #contents
+    }
+}
+
 /// Retrieves and parses the contents of `iter_layout.rs` into a TokenStream.
 ///
 /// This wrapper function includes the iterator-based layout implementation code
@@ -25,12 +38,24 @@ pub fn get_iter_layout_wrapper() -> TokenStream {
     }
 }
 
-/// Retrieves and parses the contents of `layout.rs` into a TokenStream.
+/// Retrieves and parses the contents of `overlay.rs` into a TokenStream.
+///
+/// This wrapper function includes the overlay implementation code
+/// and returns it as a parsed and quoted TokenStream ready for macro expansion.
+pub fn get_overlay_wrapper() -> TokenStream {
+    let contents = include_str!("./overlay.rs");
+    let contents = syn::parse_str::<TokenStream>(contents).unwrap();
+    quote! {
+        #contents
+    }
+}
+
+/// Retrieves and parses the contents of `state.rs` into a TokenStream.
 ///
-/// This wrapper function includes the basic layout implementation code
+/// This wrapper function includes the state borrowing helper code
 /// and returns it as a parsed and quoted TokenStream ready for macro expansion.
-pub fn get_layout_wrapper() -> TokenStream {
-    let contents = include_str!("./layout.rs");
+pub fn get_state_wrapper() -> TokenStream {
+    let contents = include_str!("./state.rs");
     let contents = syn::parse_str::<TokenStream>(contents).unwrap();
     quote! {
         #contents
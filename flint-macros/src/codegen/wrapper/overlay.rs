@@ -0,0 +1,118 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    widgets::{Block, Clear, Widget, WidgetRef},
+};
+
+/// A widget wrapper that renders its widget on top of anything previously rendered.
+///
+/// The widget is rendered into a centered area, sized as a percentage of the
+/// available area. The centered area is cleared before rendering, so widgets
+/// rendered underneath don't show through. If a block is set, it's drawn in the
+/// centered area and the widget is rendered inside of it.
+pub struct OverlayWrapper<'a, W>
+where
+    W: Widget,
+{
+    /// The widget to render on top
+    widget: W,
+    /// Width of the overlay as a percentage of the available width
+    width: u16,
+    /// Height of the overlay as a percentage of the available height
+    height: u16,
+    /// Optional block drawn around the widget
+    block: Option<Block<'a>>,
+}
+
+impl<'a, W> OverlayWrapper<'a, W>
+where
+    W: Widget,
+{
+    /// Creates a new OverlayWrapper with the given widget and size.
+    ///
+    /// # Arguments
+    ///
+    /// * `widget` - The widget to render on top
+    /// * `width` - Width of the overlay as a percentage of the available width
+    /// * `height` - Height of the overlay as a percentage of the available height
+    pub fn new(widget: W, width: u16, height: u16) -> Self {
+        Self {
+            widget,
+            width,
+            height,
+            block: None,
+        }
+    }
+
+    /// Computes the centered area of the overlay within the given area.
+    fn overlay_area(&self, area: Rect) -> Rect {
+        let [area] = Layout::vertical([Constraint::Percentage(self.height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Percentage(self.width)])
+            .flex(Flex::Center)
+            .areas(area);
+        area
+    }
+
+    /// Sets the block drawn around the widget.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The block to draw in the centered area
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl<W> Widget for OverlayWrapper<'_, W>
+where
+    W: Widget,
+{
+    /// Clears the centered overlay area and renders the widget into it, inside
+    /// the block if one is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - The area in which the overlay is centered
+    /// * `buf` - The buffer to render to
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = self.overlay_area(area);
+        Clear.render(area, buf);
+        let area = match self.block {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+        self.widget.render(area, buf);
+    }
+}
+
+impl<W> WidgetRef for OverlayWrapper<'_, W>
+where
+    W: Widget + WidgetRef,
+{
+    /// Clears the centered overlay area and renders the widget into it by reference,
+    /// inside the block if one is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - The area in which the overlay is centered
+    /// * `buf` - The buffer to render to
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let area = self.overlay_area(area);
+        Clear.render(area, buf);
+        let area = match &self.block {
+            Some(block) => {
+                block.render_ref(area, buf);
+                block.inner(area)
+            }
+            None => area,
+        };
+        self.widget.render_ref(area, buf);
+    }
+}
@@ -0,0 +1,13 @@
+/// Borrows the state of a stateful widget mutably, whether it's an owned state
+/// or already a mutable reference to one.
+///
+/// Method calls borrow their receiver automatically, so calling `state_mut` on an
+/// owned state borrows it mutably, and calling it on a `&mut` reference reborrows it.
+trait StateMut {
+    /// Returns a mutable reference to the state
+    fn state_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<S: ?Sized> StateMut for S {}
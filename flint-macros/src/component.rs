@@ -0,0 +1,200 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    spanned::Spanned, Attribute, Expr, FnArg, Ident, ItemFn, Pat, PatType, Result, ReturnType, Type,
+};
+
+/// A parameter of a component function, which becomes a field of the component
+struct Prop {
+    /// The field and the setter method of the prop
+    name: Ident,
+    /// The pattern the prop is bound to in the body, like `mut count`
+    pat: Pat,
+    ty: Type,
+    /// The value of the prop when it isn't set, from `#[prop(default = ..)]`
+    default: Option<Expr>,
+}
+
+impl Prop {
+    fn parse(param: &PatType) -> Result<Self> {
+        let name = match param.pat.as_ref() {
+            Pat::Ident(pat) => pat.ident.clone(),
+            pat => {
+                return Err(syn::Error::new_spanned(
+                    pat,
+                    "props must be named, since they're set by their name",
+                ))
+            }
+        };
+
+        let mut default = None;
+        for attr in &param.attrs {
+            if !attr.path().is_ident("prop") {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "only #[prop(default = ..)] is supported on props",
+                ));
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    default = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown prop option, expected `default = ..`"))
+                }
+            })?;
+        }
+
+        Ok(Self {
+            name,
+            pat: strip_attrs(&param.pat),
+            ty: (*param.ty).clone(),
+            default,
+        })
+    }
+}
+
+fn strip_attrs(pat: &Pat) -> Pat {
+    let mut pat = pat.clone();
+    if let Pat::Ident(ref mut pat) = pat {
+        pat.attrs.clear();
+    }
+    pat
+}
+
+/// Returns the `#[doc]` attributes and the other attributes, in order
+fn split_docs(attrs: &[Attribute]) -> (Vec<&Attribute>, Vec<&Attribute>) {
+    attrs.iter().partition(|attr| attr.path().is_ident("doc"))
+}
+
+/// Generates a widget from a component function.
+///
+/// The function takes its props followed by the area and buffer it's rendered into,
+/// like `fn StatusBadge(status: Status, area: Rect, buf: &mut Buffer)`. It becomes a
+/// struct with a field and a setter method for each prop, a `Default` impl and a
+/// `Widget` impl running the body, so `StatusBadge(status: s)` in ui!() builds it
+/// with `StatusBadge::default().status(s)` like any other widget. Props are set to
+/// their `Default` unless they're given one with `#[prop(default = ..)]`.
+///
+/// # Arguments
+///
+/// * `item` - The function the attribute is applied to
+pub fn generate_component(item: ItemFn) -> TokenStream {
+    match component(item) {
+        Ok(tokens) => tokens,
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+fn component(item: ItemFn) -> Result<TokenStream> {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = item;
+
+    if let ReturnType::Type(_, ty) = &sig.output {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "components render into their area and buffer, so they can't return a value",
+        ));
+    }
+
+    let mut params = Vec::new();
+    for input in &sig.inputs {
+        match input {
+            FnArg::Typed(param) => params.push(param),
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "components are functions, not methods",
+                ))
+            }
+        }
+    }
+
+    // The area and buffer are the last two parameters, like in Widget::render
+    let is_buffer = |param: &&PatType| matches!(param.ty.as_ref(), Type::Reference(reference) if reference.mutability.is_some());
+    let (area, buf) = match params.as_slice() {
+        [.., area, buf] if is_buffer(buf) => (area, buf),
+        _ => {
+            return Err(syn::Error::new(
+                sig.paren_token.span.join(),
+                "components take their props followed by the `area: Rect, buf: &mut Buffer` \
+                they're rendered into",
+            ))
+        }
+    };
+    let props = params[..params.len() - 2]
+        .iter()
+        .map(|param| Prop::parse(param))
+        .collect::<Result<Vec<_>>>()?;
+
+    let name = &sig.ident;
+    let (impl_generics, ty_generics, where_clause) = sig.generics.split_for_impl();
+    let generics = &sig.generics;
+    let (docs, other_attrs) = split_docs(&attrs);
+
+    let fields = props.iter().map(|prop| {
+        let Prop { name, ty, .. } = prop;
+        quote! { #name: #ty }
+    });
+    let defaults = props.iter().map(|prop| {
+        let name = &prop.name;
+        match &prop.default {
+            Some(default) => quote! { #name: #default },
+            None => quote! { #name: ::core::default::Default::default() },
+        }
+    });
+    let setters = props.iter().map(|prop| {
+        let Prop { name, ty, .. } = prop;
+        let doc = format!("Sets the `{}` prop", name);
+        quote! {
+            #[doc = #doc]
+            #[must_use]
+            pub fn #name(mut self, #name: #ty) -> Self {
+                self.#name = #name;
+                self
+            }
+        }
+    });
+    // Props are plain names, so they're bound with the field shorthand, like `mut count`
+    let bindings = props.iter().map(|prop| &prop.pat);
+
+    let area_pat = strip_attrs(&area.pat);
+    let area_ty = &area.ty;
+    let buf_pat = strip_attrs(&buf.pat);
+    let buf_ty = &buf.ty;
+    let area_name = format_ident!("__area", span = area.span());
+    let buf_name = format_ident!("__buf", span = buf.span());
+
+    Ok(quote! {
+        #(#docs)*
+        #vis struct #name #generics #where_clause {
+            #(#fields,)*
+        }
+
+        impl #impl_generics ::core::default::Default for #name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#defaults,)*
+                }
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#setters)*
+        }
+
+        impl #impl_generics ratatui::widgets::Widget for #name #ty_generics #where_clause {
+            #(#other_attrs)*
+            fn render(self, #area_name: ratatui::layout::Rect, #buf_name: &mut ratatui::buffer::Buffer) {
+                let Self { #(#bindings,)* } = self;
+                let #area_pat: #area_ty = #area_name;
+                let #buf_pat: #buf_ty = #buf_name;
+                #block
+            }
+        }
+    })
+}
@@ -0,0 +1,142 @@
+use crate::{
+    arg::{ArgKind, ArgValue},
+    widget::{Widget, WidgetKind},
+    MacroInput,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{visit::Visit, Expr, ItemImpl, Pat, Result};
+
+/// Collects the key handlers of the ui!() and widget!() invocations in an impl block.
+#[derive(Default)]
+struct HandlerCollector {
+    /// The key patterns and the code run for them, in order
+    handlers: Vec<(Pat, Expr)>,
+    /// The first error found while parsing the macros or collecting handlers
+    error: Option<syn::Error>,
+}
+
+impl<'ast> Visit<'ast> for HandlerCollector {
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let is_ui_macro = mac
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "ui" || segment.ident == "widget");
+        if !is_ui_macro || self.error.is_some() {
+            return;
+        }
+
+        // Errors in the macro itself are reported by the macro, so they're ignored here
+        let Ok(input) = mac.parse_body::<MacroInput>() else {
+            return;
+        };
+        let widget = match &input {
+            MacroInput::Ui { widget, .. } => widget,
+            MacroInput::Raw { widget } => widget,
+        };
+        if let Err(error) = self.collect(widget, false) {
+            self.error = Some(error);
+        }
+    }
+}
+
+impl HandlerCollector {
+    /// Collects the key handlers of a widget and all of its children.
+    ///
+    /// Handlers inside of For widgets are rejected, since the loop variable wouldn't
+    /// be in scope in the generated handler method.
+    fn collect(&mut self, widget: &Widget, in_loop: bool) -> Result<()> {
+        for arg in &widget.args {
+            match (&arg.kind, &arg.value) {
+                (ArgKind::KeyHandler(pattern), _) if in_loop => {
+                    return Err(syn::Error::new_spanned(
+                        pattern,
+                        "key handlers can't be used inside a For widget",
+                    ));
+                }
+                (ArgKind::KeyHandler(pattern), ArgValue::Expr(handler)) => {
+                    self.handlers.push((pattern.clone(), handler.clone()));
+                }
+                (_, ArgValue::Widget(child)) => self.collect(child, in_loop)?,
+                _ => (),
+            }
+        }
+
+        match &widget.kind {
+            WidgetKind::Layout { children, .. } => {
+                for child in children {
+                    self.collect(child, in_loop)?;
+                }
+            }
+            WidgetKind::IterLayout { child, .. } => self.collect(child, true)?,
+            WidgetKind::Stateful { child, .. } | WidgetKind::Overlay { child, .. } => {
+                self.collect(child, in_loop)?
+            }
+            WidgetKind::Conditional {
+                if_child,
+                else_child,
+                ..
+            } => {
+                self.collect(if_child, in_loop)?;
+                if let Some(else_child) = else_child {
+                    self.collect(else_child, in_loop)?;
+                }
+            }
+            WidgetKind::Constructor { .. }
+            | WidgetKind::Variable { .. }
+            | WidgetKind::IterVariable { .. }
+            | WidgetKind::Statement { .. }
+            | WidgetKind::Text { .. } => (),
+        }
+
+        Ok(())
+    }
+}
+
+/// Generates the companion key handler method for an impl block.
+///
+/// All `on_key(pattern): handler` arguments in the ui!() and widget!() invocations of
+/// the impl block are collected into a `handle_ui_key` method on the implementing type,
+/// which matches a key event's code against the patterns in order and runs the first
+/// matching handler. The impl block itself is emitted unchanged.
+///
+/// # Arguments
+///
+/// * `item` - The impl block the attribute is applied to
+pub fn generate_handlers(item: ItemImpl) -> TokenStream {
+    let mut collector = HandlerCollector::default();
+    collector.visit_item_impl(&item);
+    if let Some(error) = collector.error {
+        let error = error.to_compile_error();
+        return quote! {
+            #item
+            #error
+        };
+    }
+
+    let (patterns, handlers): (Vec<_>, Vec<_>) = collector.handlers.into_iter().unzip();
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let self_ty = &item.self_ty;
+
+    quote! {
+        #item
+
+        impl #impl_generics #self_ty #where_clause {
+            /// Runs the key handler declared with `on_key` in the ui!() and widget!()
+            /// macros for the given key event, if there is one.
+            ///
+            /// Returns whether a handler matched the key.
+            #[allow(unreachable_patterns)]
+            pub fn handle_ui_key(&mut self, key: ratatui::crossterm::event::KeyEvent) -> bool {
+                match key.code {
+                    #(#patterns => {
+                        #handlers;
+                        true
+                    })*
+                    _ => false,
+                }
+            }
+        }
+    }
+}
@@ -5,6 +5,10 @@
 mod arg;
 /// Internal module for code generation
 mod codegen;
+/// Internal module for component widgets
+mod component;
+/// Internal module for collecting key handlers
+mod handlers;
 /// Internal module for widget definitions
 mod widget;
 
@@ -24,6 +28,7 @@ use widget::{Widget, WidgetRenderer};
 /// * `Ui` - Contains both a widget and its renderer
 /// * `Raw` - Contains just a widget without rendering information
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 enum MacroInput {
     Ui {
         widget: Widget,
@@ -49,7 +54,7 @@ impl Parse for MacroInput {
         if input.peek(token::Brace) {
             let content;
             braced!(content in input);
-            let widget = content.parse()?;
+            let widget = Widget::parse_single_child(&content)?;
             return Ok(MacroInput::Raw { widget });
         }
 
@@ -62,6 +67,9 @@ impl Parse for MacroInput {
             let area = content.parse::<Expr>()?;
             content.parse::<Token![,]>()?;
             let buffer = content.parse::<Expr>()?;
+            if content.peek(Token![,]) {
+                content.parse::<Token![,]>()?;
+            }
 
             WidgetRenderer::Area { area, buffer }
         } else {
@@ -75,12 +83,40 @@ impl Parse for MacroInput {
 
         let content;
         braced!(content in input);
-        let widget = content.parse()?;
+        let widget = Widget::parse_single_child(&content)?;
 
         Ok(MacroInput::Ui { widget, renderer })
     }
 }
 
+/// Input of the test_render!() macro: the size of the test terminal and the widget
+/// to render into it
+struct TestRenderInput {
+    width: Expr,
+    height: Expr,
+    widget: Widget,
+}
+
+/// Parses `width, height => { widget }`
+impl Parse for TestRenderInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let width = input.parse::<Expr>()?;
+        input.parse::<Token![,]>()?;
+        let height = input.parse::<Expr>()?;
+        input.parse::<Token![=>]>()?;
+
+        let content;
+        braced!(content in input);
+        let widget = Widget::parse_single_child(&content)?;
+
+        Ok(TestRenderInput {
+            width,
+            height,
+            widget,
+        })
+    }
+}
+
 /// Generates UI widget code with rendering options
 ///
 /// This macro processes input containing both widget definition and rendering information.
@@ -93,18 +129,25 @@ impl Parse for MacroInput {
 ///
 /// TokenStream containing the generated widget code
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics if a widget is passed directly without rendering options
+/// Emits a compile error if a widget is passed directly without rendering options
 #[proc_macro]
 pub fn ui(input: TokenStream) -> TokenStream {
+    let tokens = proc_macro2::TokenStream::from(input.clone());
     let macro_input = parse_macro_input!(input as MacroInput);
     if let MacroInput::Ui { ref widget, .. } = macro_input {
         let options = WidgetHandlerOptions::new(true, 0, 0, &macro_input);
         let output = codegen::generate_widget_code(widget, &options);
         output.into()
     } else {
-        panic!("Cannot pass a widget directly to the ui!() macro")
+        syn::Error::new_spanned(
+            tokens,
+            "Cannot pass a widget directly to the ui!() macro. \
+            Use ui!(frame => { .. }) or ui!((area, buf) => { .. }), or use widget!({ .. }) instead",
+        )
+        .to_compile_error()
+        .into()
     }
 }
 
@@ -120,17 +163,121 @@ pub fn ui(input: TokenStream) -> TokenStream {
 ///
 /// TokenStream containing the generated widget code
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics if rendering options are included in the input
+/// Emits a compile error if rendering options are included in the input
 #[proc_macro]
 pub fn widget(input: TokenStream) -> TokenStream {
+    let tokens = proc_macro2::TokenStream::from(input.clone());
     let macro_input = parse_macro_input!(input as MacroInput);
     if let MacroInput::Raw { ref widget, .. } = macro_input {
         let options = WidgetHandlerOptions::new(true, 0, 0, &macro_input);
         let output = codegen::generate_widget_code(widget, &options);
         output.into()
     } else {
-        panic!("Cannot pass rendering options directly to the widget!() macro")
+        syn::Error::new_spanned(
+            tokens,
+            "Cannot pass rendering options directly to the widget!() macro. \
+            Use widget!({ .. }), or use ui!() to render the widget",
+        )
+        .to_compile_error()
+        .into()
+    }
+}
+
+/// Generates a companion key handler method for the ui!() and widget!() invocations
+/// in an impl block
+///
+/// Widgets in the macros can declare key handlers like
+/// `on_key(KeyCode::Enter): self.submit()`. Applying this attribute to the impl block
+/// containing the macros (like `impl WidgetRef for MyWidget`) generates a
+/// `handle_ui_key(&mut self, key: KeyEvent) -> bool` method on the type, which runs the
+/// first handler whose pattern matches the key's code. The widget's event handling can
+/// delegate to it, so the view and its key bindings live together.
+///
+/// # Errors
+///
+/// Emits a compile error if the attribute isn't applied to an impl block, or if a key
+/// handler is declared inside a For widget
+#[proc_macro_attribute]
+pub fn handlers(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as syn::ItemImpl);
+    handlers::generate_handlers(item).into()
+}
+
+/// Turns a function rendering its props into a reusable widget
+///
+/// The function takes its props followed by the area and buffer it's rendered into.
+/// It becomes a struct of the same name with a setter method for each prop, so it's
+/// used in ui!() by name with the props as named arguments, like
+/// `StatusBadge(status: Status::Passed)`. Props left out are their `Default`, or
+/// the value given with `#[prop(default = ..)]`.
+///
+/// ```ignore
+/// #[component]
+/// fn StatusBadge(status: Status, #[prop(default = "status")] label: &'static str, area: Rect, buf: &mut Buffer) {
+///     ui!((area, buf) => {
+///         Paragraph::new(format!("{}: {}", label, status))
+///     });
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Emits a compile error if the function doesn't end with the area and buffer, if it
+/// returns a value, or if a prop isn't a plain name
+#[proc_macro_attribute]
+pub fn component(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as syn::ItemFn);
+    component::generate_component(item).into()
+}
+
+/// Renders a widget into a test terminal and returns the result as a string
+///
+/// The widget is written the same way as in ui!(), and is rendered into a ratatui
+/// `TestBackend` of the given size. The returned string has one line per row of the
+/// terminal, which makes it suitable for snapshot assertions.
+///
+/// ```ignore
+/// let output = test_render!(20, 3 => {
+///     Paragraph::new("Hello", block: Block::bordered())
+/// });
+/// ```
+#[proc_macro]
+pub fn test_render(input: TokenStream) -> TokenStream {
+    let TestRenderInput {
+        width,
+        height,
+        widget,
+    } = parse_macro_input!(input as TestRenderInput);
+
+    let frame = syn::parse_quote! { frame };
+    let macro_input = MacroInput::Ui {
+        widget,
+        renderer: WidgetRenderer::Frame(frame),
+    };
+    let MacroInput::Ui { ref widget, .. } = macro_input else {
+        unreachable!()
+    };
+    let options = WidgetHandlerOptions::new(true, 0, 0, &macro_input);
+    let render_code = codegen::generate_widget_code(widget, &options);
+
+    quote::quote! {
+        {
+            let backend = ratatui::backend::TestBackend::new(#width, #height);
+            let mut terminal = ratatui::Terminal::new(backend).unwrap();
+            terminal.draw(|frame| { #render_code }).unwrap();
+
+            let buffer = terminal.backend().buffer();
+            (0..buffer.area.height)
+                .map(|y| {
+                    (0..buffer.area.width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
     }
+    .into()
 }
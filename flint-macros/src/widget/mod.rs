@@ -1,10 +1,13 @@
-use crate::arg::Arg;
+mod text;
+
+use crate::arg::{Arg, ArgKind, ArgValue};
 use syn::{
     braced, parenthesized,
-    parse::{Parse, ParseStream},
+    parse::{discouraged::Speculative, Parse, ParseStream},
     punctuated::Punctuated,
-    token, Expr, ExprBlock, Ident, Pat, Result, Token,
+    token, Attribute, Expr, ExprBlock, Ident, Pat, Result, Stmt, Token,
 };
+pub use text::{SpanStyle, TextSpan};
 
 /// Represents the different kinds of widgets that can be parsed
 #[derive(Debug, Clone)]
@@ -28,12 +31,34 @@ pub enum WidgetKind {
         /// The expression representing the widget
         expr: ExprBlock,
     },
+    /// A collection of prebuilt widgets spread into a Layout, written as `{* expr }`
+    ///
+    /// The expression can be anything that implements `IntoIterator` with widgets as
+    /// items. Every item takes up the next chunk of the parent Layout, so children
+    /// after it are shifted by the number of items. Since the number of items is only
+    /// known at runtime, the Layout's constraints must provide a chunk for every item.
+    IterVariable {
+        /// The expression evaluating to the widgets
+        expr: Expr,
+    },
+    /// A `let` statement between the children of a Layout, like
+    /// `let style = Style::new().bold();`
+    ///
+    /// The statement is emitted as-is before the children following it, so they can
+    /// use the values it defines. It doesn't take up a chunk of the Layout.
+    Statement {
+        /// The let statement, including its semicolon
+        stmt: Stmt,
+    },
     /// A layout widget that iterates over a collection to create widgets
     IterLayout {
         /// The loop variable pattern
         loop_var: Pat,
         /// The iterator expression
         iter: Expr,
+        /// The condition items are rendered on, from an `if: condition` argument.
+        /// Items it's false for are skipped and don't take up a chunk.
+        filter: Option<Expr>,
         /// The child widget to be rendered for each iteration
         child: Box<Widget>,
     },
@@ -44,6 +69,19 @@ pub enum WidgetKind {
         /// The child widget that depends on the state
         child: Box<Widget>,
     },
+    /// A widget that renders its child on top of previously rendered widgets,
+    /// in a centered area sized as a percentage of the available area
+    Overlay {
+        /// The widget to render on top
+        child: Box<Widget>,
+        /// Whether the child is drawn inside a bordered block (used by Popup widgets)
+        bordered: bool,
+    },
+    /// Styled text written with the rich text syntax, like `Text { "hello" bold red }`
+    Text {
+        /// The lines of the text, each made up of styled spans
+        lines: Vec<Vec<TextSpan>>,
+    },
     /// A widget that renders conditionally based on a condition
     Conditional {
         /// The condition expression
@@ -102,18 +140,178 @@ pub struct Widget {
     pub render_ref: bool,
 }
 
+impl Widget {
+    /// Returns the `area` argument of this widget, if it has one.
+    ///
+    /// A widget with an `area` argument is rendered into that area instead of the
+    /// area given to it by its parent, so it doesn't take up a chunk of a Layout.
+    pub fn area_arg(&self) -> Option<&Arg> {
+        self.args
+            .iter()
+            .find(|arg| matches!(&arg.kind, ArgKind::Named(name) if name == "area"))
+    }
+
+    /// Parses the body of a widget that takes a single child widget, like the braces
+    /// of For, If and Stateful widgets or of the ui!() macro itself.
+    ///
+    /// A trailing comma after the child is allowed, so bodies can be written the same
+    /// way as the children of a Layout. Anything else after the child is an error.
+    pub fn parse_single_child(input: ParseStream) -> Result<Widget> {
+        let child = input.parse::<Widget>()?;
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+        skip_doc_comments(input)?;
+
+        if !input.is_empty() {
+            return Err(input.error(
+                "expected a single child widget. Wrap multiple widgets in a Layout widget",
+            ));
+        }
+
+        Ok(child)
+    }
+}
+
+/// Parses a list of comma separated items, like the arguments or child widgets of a
+/// widget. A trailing comma and doc comments after the last item are allowed.
+pub fn parse_comma_separated<T: Parse>(input: ParseStream) -> Result<Vec<T>> {
+    let mut items = vec![];
+    loop {
+        skip_doc_comments(input)?;
+        if input.is_empty() {
+            break;
+        }
+        items.push(input.parse::<T>()?);
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+
+    Ok(items)
+}
+
+/// Parses the arguments of a For widget after its iterator, which are the same as a
+/// Layout's besides `if: condition`, the filter of the items that are rendered.
+fn parse_for_args(input: ParseStream) -> Result<(Vec<Arg>, Option<Expr>)> {
+    let mut args = vec![];
+    let mut filter = None;
+    loop {
+        skip_doc_comments(input)?;
+        if input.is_empty() {
+            break;
+        }
+
+        if input.peek(Token![if]) && input.peek2(Token![:]) {
+            let keyword = input.parse::<Token![if]>()?;
+            input.parse::<Token![:]>()?;
+            if filter.is_some() {
+                return Err(syn::Error::new_spanned(
+                    keyword,
+                    "For widgets take a single `if` filter. Combine the conditions with `&&`",
+                ));
+            }
+            filter = Some(input.parse::<Expr>()?);
+        } else {
+            args.push(input.parse::<Arg>()?);
+        }
+
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+
+    Ok((args, filter))
+}
+
+/// Parses the children of a Layout widget, which are separated by commas.
+///
+/// `let` statements can be placed between the children. They're terminated by their
+/// semicolon, so they don't need a comma after them.
+fn parse_layout_children(input: ParseStream) -> Result<Vec<Widget>> {
+    let mut children = vec![];
+    loop {
+        skip_doc_comments(input)?;
+        if input.is_empty() {
+            break;
+        }
+
+        if input.peek(Token![let]) {
+            children.push(Widget {
+                kind: WidgetKind::Statement {
+                    stmt: input.parse()?,
+                },
+                args: vec![],
+                render_ref: false,
+            });
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            }
+            continue;
+        }
+
+        children.push(input.parse::<Widget>()?);
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+
+    Ok(children)
+}
+
+/// Skips doc comments (`///` and `/** */`), which the compiler turns into `#[doc]`
+/// attributes, so they can be used to explain parts of a macro body like regular
+/// comments. Any other attribute is an error.
+pub fn skip_doc_comments(input: ParseStream) -> Result<()> {
+    for attr in input.call(Attribute::parse_outer)? {
+        if !attr.path().is_ident("doc") {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "attributes aren't supported inside macro bodies",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 /// Parser implementation for Widget
 impl Parse for Widget {
     /// Parses a widget from a token stream
     fn parse(input: ParseStream) -> Result<Self> {
+        skip_doc_comments(input)?;
+
         // If we find an "&", this widget should be rendered as a reference
         let render_ref = if input.peek(Token![&]) {
-            _ = input.parse::<Token![&]>().unwrap();
+            input.parse::<Token![&]>()?;
             true
         } else {
             false
         };
 
+        // If we find a "{*", the widgets from the iterator inside the braces are
+        // spread into consecutive chunks of the parent layout
+        if input.peek(token::Brace) {
+            let fork = input.fork();
+            let content;
+            braced!(content in fork);
+            if content.peek(Token![*]) {
+                let content;
+                braced!(content in input);
+                content.parse::<Token![*]>()?;
+                let expr = content.parse::<Expr>()?;
+
+                return Ok(Widget {
+                    kind: WidgetKind::IterVariable { expr },
+                    args: vec![],
+                    render_ref,
+                });
+            }
+        }
+
         // If we find a "{", then try to parse for a variable widget
         if input.peek(token::Brace) {
             let expr: ExprBlock = input.parse()?;
@@ -125,8 +323,24 @@ impl Parse for Widget {
             });
         }
 
+        // Lowercase Rust keywords can't be used as widget names, so point the
+        // user to the corresponding wrapper widget instead
+        if input.peek(Token![if]) {
+            return Err(input.error("use `If (condition) { .. }` to render widgets conditionally"));
+        }
+        if input.peek(Token![let]) {
+            return Err(input
+                .error("let statements can only be used between the children of a Layout widget"));
+        }
+        if input.peek(Token![for]) {
+            return Err(
+                input.error("use `For (item in iterator) { .. }` to render a widget for each item")
+            );
+        }
+
         // Parse widget name
         let widget_name = input.parse::<Ident>()?;
+        check_wrapper_keyword(&widget_name)?;
 
         if widget_name == "For" {
             // Parse the condition (which evaluates to a boolean) given in
@@ -138,15 +352,12 @@ impl Parse for Widget {
             let iter = content.parse::<Expr>()?;
 
             // Parse named argument if it exists (separated by comma)
-            let mut args = Vec::new();
-            if content.peek(Token![,]) {
+            let (args, filter) = if content.peek(Token![,]) {
                 content.parse::<Token![,]>()?;
-                let temp = Punctuated::<Arg, Token![,]>::parse_terminated(&content)?;
-                args = temp.into_iter().collect();
-                if args.is_empty() {
-                    return Err(input.error("For widgets must have at least 1 argument"));
-                }
-            }
+                parse_for_args(&content)?
+            } else {
+                (vec![], None)
+            };
 
             // The content in braces is rendered if the condition is true
             // The braces can contain only one single widget. So if multiple child elements
@@ -154,7 +365,7 @@ impl Parse for Widget {
             let brace_content;
             braced!(brace_content in input);
 
-            let child = brace_content.parse::<Widget>()?;
+            let child = Widget::parse_single_child(&brace_content)?;
 
             // If this was a conditional widget, we're done, since we've
             // extracted the condition and children for both branches.
@@ -162,6 +373,7 @@ impl Parse for Widget {
                 kind: WidgetKind::IterLayout {
                     loop_var,
                     iter,
+                    filter,
                     child: Box::new(child),
                 },
                 render_ref: false,
@@ -176,12 +388,29 @@ impl Parse for Widget {
             syn::parenthesized!(content in input);
             let state = content.parse::<Expr>()?;
 
+            // Parse named arguments if they exist (separated by comma). These are
+            // forwarded as chained method calls to the inner stateful widget.
+            let mut args = Vec::new();
+            if content.peek(Token![,]) {
+                content.parse::<Token![,]>()?;
+                let temp = parse_comma_separated::<Arg>(&content)?;
+                for arg in temp {
+                    if let (ArgKind::Positional, ArgValue::Expr(value)) = (&arg.kind, &arg.value) {
+                        return Err(syn::Error::new_spanned(
+                            value,
+                            "Stateful widgets only accept named arguments after the state",
+                        ));
+                    }
+                    args.push(arg);
+                }
+            }
+
             // The content in braces is rendered if the condition is true
             // The braces can contain only one single widget. So if multiple child elements
             // are required, they must be nested in a Layout widget.
             let brace_content;
             braced!(brace_content in input);
-            let child = brace_content.parse::<Widget>()?;
+            let child = Widget::parse_single_child(&brace_content)?;
 
             // If this was a conditional widget, we're done, since we've
             // extracted the condition and children for both branches.
@@ -191,7 +420,7 @@ impl Parse for Widget {
                     child: Box::new(child),
                 },
                 render_ref: false,
-                args: vec![],
+                args,
             });
         }
 
@@ -199,13 +428,20 @@ impl Parse for Widget {
             let mut content;
             parenthesized!(content in input);
             let condition = content.parse::<Expr>()?;
+            if content.peek(Token![,]) {
+                content.parse::<Token![,]>()?;
+            }
 
             braced!(content in input);
-            let if_child = content.parse::<Widget>()?;
+            let if_child = Widget::parse_single_child(&content)?;
 
-            let else_child = if input.peek(Ident) && input.parse::<Ident>()? == "Else" {
+            // Doc comments between the If body and Else belong to the Else branch
+            let fork = input.fork();
+            skip_doc_comments(&fork)?;
+            let else_child = if fork.peek(Ident) && fork.parse::<Ident>()? == "Else" {
+                input.advance_to(&fork);
                 braced!(content in input);
-                Some(content.parse::<Widget>()?)
+                Some(Widget::parse_single_child(&content)?)
             } else {
                 None
             };
@@ -214,13 +450,81 @@ impl Parse for Widget {
                 kind: WidgetKind::Conditional {
                     condition,
                     if_child: Box::new(if_child),
-                    else_child: else_child.map(|child| Box::new(child)),
+                    else_child: else_child.map(Box::new),
                 },
                 args: vec![],
                 render_ref: false,
             });
         }
 
+        // Text widgets followed by braces use the rich text syntax, where spans are
+        // separated by commas and lines by semicolons, like
+        // `Text { "Name: " bold, name fg: yellow; "second line" }`.
+        // A path like Text::from(..) is still treated as a regular constructor widget.
+        if widget_name == "Text" && input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+
+            let mut lines = vec![vec![]];
+            while !content.is_empty() {
+                skip_doc_comments(&content)?;
+                if content.is_empty() {
+                    break;
+                }
+                lines.last_mut().unwrap().push(content.parse::<TextSpan>()?);
+                if content.peek(Token![;]) {
+                    content.parse::<Token![;]>()?;
+                    lines.push(vec![]);
+                } else if !content.is_empty() {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+
+            // Ignore the empty line after a trailing semicolon
+            if lines.len() > 1 && lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+
+            return Ok(Widget {
+                kind: WidgetKind::Text { lines },
+                args: vec![],
+                render_ref,
+            });
+        }
+
+        // Overlay and Popup widgets take only named arguments (width and height as
+        // percentages of the available area) and a single child widget in braces.
+        // Popups draw the child inside a bordered block, so any other named arguments
+        // are applied to the block. A path like Popup::new(..) is still treated as a
+        // regular constructor widget.
+        if (widget_name == "Overlay" || widget_name == "Popup") && !input.peek(Token![::]) {
+            let bordered = widget_name == "Popup";
+            let args = if input.peek(token::Paren) {
+                let content;
+                parenthesized!(content in input);
+                parse_comma_separated::<Arg>(&content)?
+                    .into_iter()
+                    .collect()
+            } else {
+                vec![]
+            };
+            let allowed: &[&str] = &["width", "height"];
+            check_named_args(&widget_name, &args, (!bordered).then_some(allowed))?;
+
+            let content;
+            braced!(content in input);
+            let child = Widget::parse_single_child(&content)?;
+
+            return Ok(Widget {
+                kind: WidgetKind::Overlay {
+                    child: Box::new(child),
+                    bordered,
+                },
+                args,
+                render_ref: false,
+            });
+        }
+
         // If the user provided a constructor function (like MyWidget::new)
         // use that function to create the widget, otherwise, use the
         // default constructor (MyWidget::default)
@@ -239,7 +543,7 @@ impl Parse for Widget {
             let content;
             syn::parenthesized!(content in input);
 
-            let args_punctuated = Punctuated::<Arg, Token![,]>::parse_terminated(&content)?;
+            let args_punctuated = parse_comma_separated::<Arg>(&content)?;
             args_punctuated.into_iter().collect()
         } else {
             vec![]
@@ -261,7 +565,17 @@ impl Parse for Widget {
         };
 
         // If this is a constructor widget, we're done since we don't need to parse child widgets
-        if let WidgetKind::Constructor { .. } = kind {
+        if let WidgetKind::Constructor { ref name, .. } = kind {
+            if input.peek(token::Brace) {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    format!(
+                        "`{}` can't have child widgets. Only Layout, For, If, Stateful, Overlay and Popup widgets take a body",
+                        name
+                    ),
+                ));
+            }
+
             return Ok(Widget {
                 kind,
                 args,
@@ -280,8 +594,24 @@ impl Parse for Widget {
             {
                 // Parse the child widgets. Every child widget must be separated by a comma.
                 // Child widgets can be any kind of widget, including other layout widgets.
-                let child_widgets = Punctuated::<Widget, Token![,]>::parse_terminated(&content)?;
-                children.extend(child_widgets);
+                children.extend(parse_layout_children(&content)?);
+
+                // The number of chunks taken by spread widgets is only known at runtime
+                let has_iter_variable = children
+                    .iter()
+                    .any(|child| matches!(child.kind, WidgetKind::IterVariable { .. }));
+
+                // Overlays are drawn over the whole layout and widgets with an explicit
+                // area are drawn into that area, so neither needs a constraint
+                let chunk_count = children
+                    .iter()
+                    .filter(|child| !matches!(child.kind, WidgetKind::Overlay { .. }))
+                    .filter(|child| !matches!(child.kind, WidgetKind::Statement { .. }))
+                    .filter(|child| child.area_arg().is_none())
+                    .count();
+                if !has_iter_variable {
+                    check_constraints_count(&args, chunk_count)?;
+                }
             } else {
                 return Err(input.error("Only Layout widgets can have child elements"));
             }
@@ -294,3 +624,111 @@ impl Parse for Widget {
         })
     }
 }
+
+/// Names of the built-in wrapper widgets, which are handled specially by the parser
+const WRAPPER_KEYWORDS: [&str; 7] = [
+    "Layout", "For", "If", "Else", "Stateful", "Overlay", "Popup",
+];
+
+/// Reports misspelt wrapper keywords (like `layout` or `STATEFUL`) and a stray `Else`,
+/// which would otherwise be treated as constructor widgets and fail with confusing
+/// type errors in the generated code.
+fn check_wrapper_keyword(name: &Ident) -> Result<()> {
+    if name == "Else" {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`Else` must directly follow the body of an `If` widget",
+        ));
+    }
+
+    let name_str = name.to_string();
+    for keyword in WRAPPER_KEYWORDS {
+        if name_str != keyword && name_str.eq_ignore_ascii_case(keyword) {
+            return Err(syn::Error::new_spanned(
+                name,
+                format!("unknown widget `{}`. Did you mean `{}`?", name_str, keyword),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that a wrapper widget was only given named arguments from the allowed list.
+/// If no list is given, any named argument is allowed.
+fn check_named_args(widget_name: &Ident, args: &[Arg], allowed: Option<&[&str]>) -> Result<()> {
+    for arg in args {
+        match (&arg.kind, &arg.value, allowed) {
+            (ArgKind::Named(name), _, Some(allowed))
+                if !allowed.iter().any(|allowed| name == allowed) =>
+            {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    format!(
+                        "unknown argument `{}` for {}. Expected one of: {}",
+                        name,
+                        widget_name,
+                        allowed.join(", ")
+                    ),
+                ));
+            }
+            (ArgKind::Positional, ArgValue::Expr(value), _) => {
+                return Err(syn::Error::new_spanned(
+                    value,
+                    format!("{} widgets only accept named arguments", widget_name),
+                ));
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that a literal `constraints` argument on a Layout widget has exactly one
+/// constraint per child widget.
+///
+/// Only array literals (`[..]`) and `vec![..]` invocations are checked, since the
+/// length of any other expression is only known at runtime. A mismatch is reported
+/// as a compile error pointing at the constraints argument.
+fn check_constraints_count(args: &[Arg], children_count: usize) -> Result<()> {
+    for arg in args {
+        let ArgKind::Named(ref name) = arg.kind else {
+            continue;
+        };
+        let ArgValue::Expr(ref value) = arg.value else {
+            continue;
+        };
+        if name != "constraints" {
+            continue;
+        }
+
+        let constraints_count = match value {
+            Expr::Array(array) => array.elems.len(),
+            Expr::Macro(mac) if mac.mac.path.is_ident("vec") => {
+                match mac
+                    .mac
+                    .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                {
+                    Ok(elems) => elems.len(),
+                    // vec![value; count] and other forms can't be checked
+                    Err(_) => continue,
+                }
+            }
+            _ => continue,
+        };
+
+        if constraints_count != children_count {
+            return Err(syn::Error::new_spanned(
+                value,
+                format!(
+                    "Layout has {} constraint(s) but {} child widget(s). \
+                    Every child widget needs exactly one constraint.",
+                    constraints_count, children_count
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use syn::{
+    parse::{Parse, ParseStream},
+    Expr, Ident, Result, Token,
+};
+
+/// Names of the style modifiers that can follow the content of a span
+const MODIFIERS: [&str; 9] = [
+    "bold",
+    "dim",
+    "italic",
+    "underlined",
+    "slow_blink",
+    "rapid_blink",
+    "reversed",
+    "hidden",
+    "crossed_out",
+];
+
+/// Names of the colors that can follow the content of a span, or be used as
+/// the value of `fg` and `bg`
+const COLORS: [&str; 17] = [
+    "reset",
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "gray",
+    "dark_gray",
+    "light_red",
+    "light_green",
+    "light_yellow",
+    "light_blue",
+    "light_magenta",
+    "light_cyan",
+    "white",
+];
+
+/// A single styling instruction applied to a span
+#[derive(Debug, Clone)]
+pub enum SpanStyle {
+    /// A text modifier like `bold` or `italic`
+    Modifier(Ident),
+    /// The foreground color, given as `fg: color` or a bare color name
+    Fg(Expr),
+    /// The background color, given as `bg: color`
+    Bg(Expr),
+    /// A complete style, given as `style: expr`, which is patched onto the span's style
+    Style(Expr),
+}
+
+/// A piece of styled text in a `Text { .. }` widget, like `"hello" bold red`
+#[derive(Debug, Clone)]
+pub struct TextSpan {
+    /// The content of the span
+    pub content: Expr,
+    /// The styles applied to the span, in order
+    pub styles: Vec<SpanStyle>,
+}
+
+impl Parse for TextSpan {
+    /// Parses a span, which is its content followed by any number of style
+    /// modifiers, bare color names and `fg:`, `bg:` or `style:` arguments.
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content = input.call(Expr::parse_without_eager_brace)?;
+
+        let mut styles = vec![];
+        while !input.is_empty() && !input.peek(Token![,]) && !input.peek(Token![;]) {
+            let name = input.parse::<Ident>()?;
+            let name_str = name.to_string();
+
+            if input.peek(Token![:]) {
+                input.parse::<Token![:]>()?;
+                let value = parse_color(input)?;
+                styles.push(match name_str.as_str() {
+                    "fg" => SpanStyle::Fg(value),
+                    "bg" => SpanStyle::Bg(value),
+                    "style" => SpanStyle::Style(value),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &name,
+                            format!(
+                                "unknown span argument `{}`. Expected one of: fg, bg, style",
+                                name
+                            ),
+                        ))
+                    }
+                });
+            } else if MODIFIERS.contains(&name_str.as_str()) {
+                styles.push(SpanStyle::Modifier(name));
+            } else if COLORS.contains(&name_str.as_str()) {
+                styles.push(SpanStyle::Fg(color_expr(&name)));
+            } else {
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    format!(
+                        "unknown span style `{}`. Expected a modifier (like `bold`), a color (like `red`) or `fg:`, `bg:` or `style:`",
+                        name
+                    ),
+                ));
+            }
+        }
+
+        Ok(TextSpan { content, styles })
+    }
+}
+
+/// Parses the value of a span argument, which is either a bare color name
+/// (like `yellow`) or any expression.
+fn parse_color(input: ParseStream) -> Result<Expr> {
+    if input.peek(Ident) && !input.peek2(Token![::]) && !input.peek2(syn::token::Paren) {
+        let fork = input.fork();
+        let name = fork.parse::<Ident>()?;
+        if COLORS.contains(&name.to_string().as_str()) {
+            input.parse::<Ident>()?;
+            return Ok(color_expr(&name));
+        }
+    }
+
+    input.call(Expr::parse_without_eager_brace)
+}
+
+/// Converts a color name like `light_red` into the expression `Color::LightRed`.
+fn color_expr(name: &Ident) -> Expr {
+    let variant = Ident::new(
+        &name
+            .to_string()
+            .split('_')
+            .map(|word| word[..1].to_uppercase() + &word[1..])
+            .collect::<String>(),
+        name.span(),
+    );
+    syn::parse_quote! { ratatui::style::Color::#variant }
+}
@@ -0,0 +1,7 @@
+//! Checks the diagnostics of the macros for common mistakes.
+
+#[test]
+fn compile_fail() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/*.rs");
+}
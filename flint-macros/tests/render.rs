@@ -0,0 +1,212 @@
+//! Snapshot tests for the output of the ui!() and widget!() macros.
+
+use flint_macros::{component, test_render, ui, widget};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Block, List, ListState, Paragraph},
+};
+
+#[test]
+fn constructor() {
+    let output = test_render!(12, 3 => {
+        Paragraph::new("Hello", block: Block::bordered(title: "Hi"))
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn layout() {
+    let output = test_render!(12, 4 => {
+        Layout(direction: Direction::Vertical, constraints: [Constraint::Length(1), Constraint::Fill(1)]) {
+            Paragraph::new("Title"),
+            Layout(direction: horizontal, constraints: [Constraint::Fill(1), Constraint::Fill(1)]) {
+                Paragraph::new("Left"),
+                Paragraph::new("Right"),
+            },
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn constraint_shorthands() {
+    let output = test_render!(12, 6 => {
+        Layout(direction: vertical, constraints: [1, min 2, 50%]) {
+            Paragraph::new("Title"),
+            Paragraph::new("Body"),
+            Layout(direction: horizontal, constraints: [fill, fill 2, Constraint::Length(2)]) {
+                Paragraph::new("ab"),
+                Paragraph::new("cdef"),
+                Paragraph::new("gh"),
+            },
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn iter_layout() {
+    let items = ["one", "two", "three"];
+    let output = test_render!(12, 3 => {
+        For (item in items.iter(), direction: vertical, constraint: Constraint::Length(1)) {
+            Paragraph::new(*item)
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn iter_variable() {
+    let rows = [Paragraph::new("one"), Paragraph::new("two")];
+    let output = test_render!(12, 4 => {
+        Layout(direction: vertical, constraints: [Constraint::Length(1); 4]) {
+            Paragraph::new("header"),
+            {* rows.iter()},
+            Paragraph::new("footer"),
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn let_statements() {
+    let count = 2;
+    let output = test_render!(12, 2 => {
+        Layout(direction: vertical, constraints: [Constraint::Length(1); 2]) {
+            let title = format!("{} items", count);
+            Paragraph::new(title),
+            let footer = "footer";
+            Paragraph::new(footer),
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn iter_layout_index() {
+    let items = ["one", "two"];
+    let output = test_render!(12, 2 => {
+        For (item in items.iter(), index: i, direction: vertical, constraint: Constraint::Length(1)) {
+            Paragraph::new(format!("{}. {}", i + 1, item))
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn iter_layout_item_constraint() {
+    let items = [("one", 2), ("two", 1)];
+    let output = test_render!(12, 3 => {
+        For ((name, height) in items.iter(), direction: vertical, constraint: Constraint::Length(*height)) {
+            Paragraph::new(format!("{} ({})", name, height))
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn iter_layout_filter() {
+    let items = [("one", true), ("two", false), ("three", true)];
+    let output = test_render!(12, 3 => {
+        For ((name, enabled) in items.iter(), if: *enabled, index: i, direction: vertical, constraint: Constraint::Length(1)) {
+            Paragraph::new(format!("{}. {}", i + 1, name))
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn conditional() {
+    let output = test_render!(12, 2 => {
+        Layout(direction: vertical, constraints: [Constraint::Length(1), Constraint::Length(1)]) {
+            If (true) { Paragraph::new("shown") } Else { Paragraph::new("hidden") },
+            If (false) { Paragraph::new("hidden") } Else { Paragraph::new("else") },
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn stateful() {
+    let mut state = ListState::default().with_offset(1);
+    let output = test_render!(12, 2 => {
+        Stateful(state) { List::new(["a", "b", "c"]) }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn overlay_and_popup() {
+    let output = test_render!(20, 8 => {
+        Layout(direction: vertical, constraints: [Constraint::Fill(1)]) {
+            Paragraph::new("background"),
+            Popup(width: 60%, height: 50%, title: "Confirm") { Paragraph::new("Sure?") },
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn explicit_area() {
+    let footer = ratatui::layout::Rect::new(0, 2, 12, 1);
+    let output = test_render!(12, 3 => {
+        Layout(direction: vertical, constraints: [Constraint::Length(1)]) {
+            Paragraph::new("main"),
+            Paragraph::new("footer", area: footer),
+        }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn rich_text() {
+    let name = "flint";
+    let output = test_render!(16, 2 => {
+        Text { "Name: " bold, name fg: yellow; "second line" }
+    });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn widget_layout() {
+    let layout = widget!({
+        Layout(direction: horizontal, constraints: [Constraint::Length(4), Constraint::Fill(1)]) {
+            Paragraph::new("ab"),
+            Paragraph::new("cd"),
+        }
+    });
+    let output = test_render!(10, 1 => { {layout} });
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn widget_iter_layout_filter() {
+    let items = [("one", true), ("two", false), ("three", true)];
+    let layout = widget!({
+        For ((name, enabled) in items.iter(), if: *enabled, index: i, direction: vertical, constraint: Constraint::Length(1)) {
+            Paragraph::new(format!("{}. {}", i + 1, name))
+        }
+    });
+    let output = test_render!(12, 3 => { {layout} });
+    insta::assert_snapshot!(output);
+}
+
+/// A label with a count, like "Tests: 3"
+#[component]
+fn Counter(label: &'static str, #[prop(default = 1)] count: usize, area: Rect, buf: &mut Buffer) {
+    ui!((area, buf) => {
+        Paragraph::new(format!("{}: {}", label, count))
+    });
+}
+
+#[test]
+fn component() {
+    let output = test_render!(12, 2 => {
+        Layout(direction: vertical, constraints: [Constraint::Length(1); 2]) {
+            Counter(label: "Tests", count: 3),
+            Counter(label: "Runs"),
+        }
+    });
+    insta::assert_snapshot!(output);
+}
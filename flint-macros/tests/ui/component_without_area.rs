@@ -0,0 +1,8 @@
+use flint_macros::component;
+
+#[component]
+fn Badge(label: String) {
+    let _ = label;
+}
+
+fn main() {}
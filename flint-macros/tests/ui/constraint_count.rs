@@ -0,0 +1,10 @@
+use flint_macros::widget;
+
+fn main() {
+    let _ = widget!({
+        Layout(constraints: [Constraint::Fill(1)]) {
+            Paragraph::new("a"),
+            Paragraph::new("b"),
+        }
+    });
+}
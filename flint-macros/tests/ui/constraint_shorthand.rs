@@ -0,0 +1,10 @@
+use flint_macros::widget;
+
+fn main() {
+    let _ = widget!({
+        Layout(constraints: [30%, min 10 3]) {
+            Paragraph::new("a"),
+            Paragraph::new("b"),
+        }
+    });
+}
@@ -0,0 +1,9 @@
+use flint_macros::widget;
+
+fn main() {
+    let _ = widget!({
+        Paragraph::new("a") {
+            Paragraph::new("b")
+        }
+    });
+}
@@ -0,0 +1,10 @@
+use flint_macros::widget;
+
+fn main() {
+    let items = [1, 2, 3];
+    let _ = widget!({
+        For (item in items.iter(), if: *item > 1, if: *item < 3) {
+            Paragraph::new(item.to_string())
+        }
+    });
+}
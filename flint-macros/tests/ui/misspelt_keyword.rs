@@ -0,0 +1,9 @@
+use flint_macros::widget;
+
+fn main() {
+    let _ = widget!({
+        layout() {
+            Paragraph::new("a"),
+        }
+    });
+}
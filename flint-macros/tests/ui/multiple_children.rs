@@ -0,0 +1,10 @@
+use flint_macros::widget;
+
+fn main() {
+    let _ = widget!({
+        Stateful(state) {
+            List::new(items),
+            Paragraph::new("a"),
+        }
+    });
+}
@@ -0,0 +1,7 @@
+use flint_macros::widget;
+
+fn main() {
+    let _ = widget!({
+        Paragraph::new("a", block = Block::bordered())
+    });
+}
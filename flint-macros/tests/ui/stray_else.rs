@@ -0,0 +1,7 @@
+use flint_macros::widget;
+
+fn main() {
+    let _ = widget!({
+        Else { Paragraph::new("a") }
+    });
+}
@@ -0,0 +1,5 @@
+use flint_macros::ui;
+
+fn main() {
+    ui!({ Paragraph::new("a") });
+}
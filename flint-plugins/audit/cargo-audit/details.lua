@@ -0,0 +1,20 @@
+function Details()
+    return {
+        id = "cargo-audit",
+        author = "Onkar Kapuskari",
+        version = "0.0.1",
+        extensions = {} -- Audits check the dependencies rather than files
+    }
+end
+
+function Tools()
+    return {
+        {
+            name = "cargo-audit",
+            version_args = { "audit", "--version" },
+            install = {
+                cargo = "cargo install cargo-audit --locked",
+            },
+        },
+    }
+end
@@ -0,0 +1,33 @@
+local json = require("json")
+
+function Run(config)
+    return { "cargo", "audit", "--json" }
+end
+
+function Eval(output)
+    -- cargo audit prints nothing to stdout if it couldn't run, like without a Cargo.lock
+    if output.stdout == nil or output.stdout == "" then
+        error("cargo audit failed: " .. output.stderr)
+    end
+
+    local audit = json.parse(output.stdout)
+    local results = {}
+    -- RustSec advisories have no severity, only a CVSS vector
+    for _, vulnerability in ipairs(audit.vulnerabilities.list) do
+        local advisory = vulnerability.advisory
+        local package = vulnerability.package
+        table.insert(results, {
+            file_name = "Cargo.lock",
+            success = false,
+            error_message = advisory.title .. " in " .. package.name .. " " .. package.version,
+            rule_id = advisory.id,
+        })
+    end
+
+    return {
+        tests_passed = 0,
+        total_tests = #results,
+        passing_percentage = 0,
+        test_results = results
+    }
+end
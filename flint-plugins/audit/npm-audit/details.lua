@@ -0,0 +1,20 @@
+function Details()
+    return {
+        id = "npm-audit",
+        author = "Onkar Kapuskari",
+        version = "0.0.1",
+        extensions = {} -- Audits check the dependencies rather than files
+    }
+end
+
+function Tools()
+    return {
+        { name = "npm" },
+    }
+end
+
+function Rules()
+    return {
+        { name = "omit_dev", type = "boolean", default = false, description = "Whether dev dependencies are left out of the audit" },
+    }
+end
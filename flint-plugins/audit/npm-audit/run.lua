@@ -0,0 +1,53 @@
+local json = require("json")
+
+function Run(config)
+    local args = { "npm", "audit", "--json" }
+    if config.omit_dev then
+        table.insert(args, "--omit=dev")
+    end
+    return args
+end
+
+-- The advisory id is the last part of its URL, like GHSA-29mw-wpgm-hmr9
+local function advisory_id(advisory)
+    if advisory.url then
+        local id = advisory.url:match("([^/]+)$")
+        if id then return id end
+    end
+    return tostring(advisory.source)
+end
+
+function Eval(output)
+    -- npm prints nothing to stdout if it couldn't run, like without a package-lock.json
+    if output.stdout == nil or output.stdout == "" then
+        error("npm audit failed: " .. output.stderr)
+    end
+
+    local audit = json.parse(output.stdout)
+    if audit.error then
+        error("npm audit failed: " .. tostring(audit.error.summary))
+    end
+
+    local results = {}
+    for name, vulnerability in pairs(audit.vulnerabilities or {}) do
+        -- Packages only vulnerable through a dependency list its name instead of an advisory
+        for _, advisory in ipairs(vulnerability.via) do
+            if type(advisory) == "table" then
+                table.insert(results, {
+                    file_name = "package-lock.json",
+                    success = false,
+                    error_message = advisory.title .. " in " .. name .. " " .. advisory.range,
+                    rule_id = advisory_id(advisory),
+                    severity = advisory.severity,
+                })
+            end
+        end
+    end
+
+    return {
+        tests_passed = 0,
+        total_tests = #results,
+        passing_percentage = 0,
+        test_results = results
+    }
+end
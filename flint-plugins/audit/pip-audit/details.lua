@@ -0,0 +1,29 @@
+function Details()
+    return {
+        id = "pip-audit",
+        author = "Onkar Kapuskari",
+        version = "0.0.1",
+        extensions = {} -- Audits check the dependencies rather than files
+    }
+end
+
+function Dependencies()
+    return {
+        pip = {
+            { name = "pip-audit", version = "latest" }
+        },
+    }
+end
+
+function Tools()
+    return {
+        {
+            name = "pip-audit",
+            install = {
+                pip = "pip install pip-audit",
+                pipx = "pipx install pip-audit",
+                uv = "uv tool install pip-audit",
+            },
+        },
+    }
+end
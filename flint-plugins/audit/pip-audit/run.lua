@@ -0,0 +1,37 @@
+local json = require("json")
+
+function Run(config)
+    return { "pip-audit", "--format", "json", "--requirement", "requirements.txt" }
+end
+
+function Eval(output)
+    -- pip-audit prints nothing to stdout if it couldn't run, like without requirements.txt
+    if output.stdout == nil or output.stdout == "" then
+        error("pip-audit failed: " .. output.stderr)
+    end
+
+    local audit = json.parse(output.stdout)
+    local results = {}
+    -- PyPI advisories have no severity
+    for _, dependency in ipairs(audit.dependencies) do
+        for _, vulnerability in ipairs(dependency.vulns or {}) do
+            local message = dependency.name .. " " .. dependency.version .. " is vulnerable"
+            if vulnerability.fix_versions and #vulnerability.fix_versions > 0 then
+                message = message .. ", fixed in " .. table.concat(vulnerability.fix_versions, ", ")
+            end
+            table.insert(results, {
+                file_name = "requirements.txt",
+                success = false,
+                error_message = message,
+                rule_id = vulnerability.id,
+            })
+        end
+    end
+
+    return {
+        tests_passed = 0,
+        total_tests = #results,
+        passing_percentage = 0,
+        test_results = results
+    }
+end
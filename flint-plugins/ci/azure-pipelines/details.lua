@@ -0,0 +1,14 @@
+function Details()
+    return {
+        id = "azure-pipelines",
+        version = "1.0.0",
+        author = "Your Name",
+        extensions = {} -- Output plugins don't need file extensions
+    }
+end
+
+function Inputs()
+    return {
+        config = { "*" },
+    }
+end
@@ -0,0 +1,100 @@
+local log = require("log")
+local yaml = require("yaml")
+
+local function get_toolchain_step(toolchain)
+    if toolchain.name == "node" then
+        return {
+            task = "NodeTool@0",
+            displayName = "Install Node.js",
+            inputs = { versionSpec = toolchain.version .. ".x" }
+        }
+    elseif toolchain.name == "python" then
+        return {
+            task = "UsePythonVersion@0",
+            displayName = "Install Python",
+            inputs = { versionSpec = toolchain.version }
+        }
+    end
+end
+
+-- Matrix entries are named after their values, which may only contain letters,
+-- digits and underscores
+local function matrix_entry_name(combination)
+    local values = {}
+    for _, value in pairs(combination) do
+        table.insert(values, value)
+    end
+    table.sort(values)
+    return (string.gsub(table.concat(values, "_"), "[^%w_]", "_"))
+end
+
+function Generate(config, pipeline)
+    log.debug(pipeline)
+
+    local azure = {
+        trigger = { branches = { include = pipeline.branches } },
+        pr = { branches = { include = pipeline.pr_branches } },
+        pool = { vmImage = pipeline.runs_on },
+        steps = {
+            { checkout = "self" }
+        }
+    }
+
+    -- Azure lists every combination of the matrix, and the values are variables
+    if #pipeline.matrix_combinations > 0 then
+        local matrix = {}
+        for _, combination in ipairs(pipeline.matrix_combinations) do
+            matrix[matrix_entry_name(combination)] = combination
+        end
+        azure.strategy = { matrix = matrix }
+        if pipeline.matrix.os then
+            azure.pool.vmImage = "$(os)"
+        end
+    end
+
+    -- Only the caches of the package managers are kept, which are moved to the workspace
+    if pipeline.cache then
+        azure.variables = {
+            npm_config_cache = "$(Pipeline.Workspace)/.npm",
+            PIP_CACHE_DIR = "$(Pipeline.Workspace)/.pip"
+        }
+        for _, manager in ipairs({ "npm", "pip" }) do
+            table.insert(azure.steps, {
+                task = "Cache@2",
+                displayName = "Cache " .. manager .. " packages",
+                inputs = {
+                    key = "flint | " .. manager .. " | \"$(Agent.OS)\" | " .. pipeline.cache.key_file,
+                    path = "$(Pipeline.Workspace)/." .. manager
+                }
+            })
+        end
+    end
+
+    for _, toolchain in ipairs(pipeline.toolchains) do
+        table.insert(azure.steps, get_toolchain_step(toolchain))
+    end
+
+    for _, step in ipairs(pipeline.steps) do
+        table.insert(azure.steps, {
+            script = step.run,
+            displayName = step.name
+        })
+    end
+
+    for _, artifact in ipairs(pipeline.artifacts) do
+        table.insert(azure.steps, {
+            task = "PublishPipelineArtifact@1",
+            displayName = "Upload " .. artifact.name,
+            condition = "always()",
+            inputs = {
+                targetPath = artifact.path,
+                -- Artifacts of the jobs of a matrix need different names
+                artifact = artifact.name .. " $(System.JobName)"
+            }
+        })
+    end
+
+    return {
+        ["azure-pipelines.yml"] = yaml.stringify(azure)
+    }
+end
@@ -0,0 +1,89 @@
+local log = require("log")
+local yaml = require("yaml")
+
+function Generate(config, pipeline)
+    log.debug(pipeline)
+
+    local job = {
+        docker = {
+            { image = config.image or pipeline.image }
+        },
+        steps = { "checkout" }
+    }
+
+    local cache_key = nil
+    if pipeline.cache then
+        cache_key = "flint-{{ checksum \"" .. pipeline.cache.key_file .. "\" }}"
+        table.insert(job.steps, {
+            restore_cache = {
+                keys = { cache_key, "flint-" }
+            }
+        })
+    end
+
+    -- The matrix values are parameters of the job, passed to the steps as variables
+    local has_matrix = next(pipeline.matrix) ~= nil
+    if has_matrix then
+        job.parameters = {}
+        job.environment = {}
+        for name, _ in pairs(pipeline.matrix) do
+            job.parameters[name] = { type = "string" }
+            job.environment[string.upper(name)] = "<< parameters." .. name .. " >>"
+        end
+    end
+
+    for _, step in ipairs(pipeline.steps) do
+        table.insert(job.steps, {
+            run = {
+                name = step.name,
+                command = step.run
+            }
+        })
+    end
+
+    if cache_key then
+        table.insert(job.steps, {
+            save_cache = {
+                key = cache_key,
+                paths = pipeline.cache.paths
+            }
+        })
+    end
+
+    for _, artifact in ipairs(pipeline.artifacts) do
+        table.insert(job.steps, {
+            store_artifacts = {
+                path = artifact.path,
+                destination = artifact.name
+            }
+        })
+    end
+
+    -- CircleCI runs on pushes to the branches, which includes the branches of pull requests
+    local workflow_job = {
+        filters = {
+            branches = { only = pipeline.branches }
+        }
+    }
+    if has_matrix then
+        workflow_job.matrix = { parameters = pipeline.matrix }
+    end
+
+    local circleci = {
+        version = 2.1,
+        jobs = {
+            flint_checks = job
+        },
+        workflows = {
+            flint = {
+                jobs = {
+                    { flint_checks = workflow_job }
+                }
+            }
+        }
+    }
+
+    return {
+        [".circleci/config.yml"] = yaml.stringify(circleci)
+    }
+end
@@ -6,3 +6,9 @@ function Details()
         extensions = {} -- Output plugins don't need file extensions
     }
 end
+
+function Inputs()
+    return {
+        config = { "*" },
+    }
+end
@@ -1,140 +1,107 @@
 local log = require("log")
 local yaml = require("yaml")
 
-local function get_dependency_install_steps(dependencies)
-    local steps = {}
-    -- TODO: Add support for system dependencies
-
-    -- Add support for node.js (npm) dependencies
-    if dependencies.npm and #dependencies.npm > 0 then
-        -- Add Node.js setup
-        table.insert(steps, {
+local function get_toolchain_step(toolchain)
+    if toolchain.name == "node" then
+        return {
             name = "Install Node.js",
-            uses = "actions/setup-node@v3",
+            uses = "actions/setup-node@v4",
             with = {
-                ["node-version"] = "18.x"
+                ["node-version"] = toolchain.version
             }
-        })
-
-        -- Install NPM packages
-        local npm_deps = {}
-        for _, dep in ipairs(dependencies.npm) do
-            table.insert(npm_deps, dep.name .. "@" .. dep.version)
-        end
-
-        table.insert(steps, {
-            name = "Install Node.js dependencies",
-            run = "npm install -g " .. table.concat(npm_deps, " ") .. " --no-fund --no-audit --silent"
-        })
-    end
-
-    -- Add support for Python (pip) dependencies
-    if dependencies.pip and #dependencies.pip > 0 then
-        -- Add Python setup
-        table.insert(steps, {
+        }
+    elseif toolchain.name == "python" then
+        return {
             name = "Install Python",
-            uses = "actions/setup-python@v4",
+            uses = "actions/setup-python@v5",
             with = {
-                ["python-version"] = "3.x"
+                ["python-version"] = toolchain.version
             }
-        })
-
-        -- Install pip packages
-        local pip_deps = {}
-        for _, dep in ipairs(dependencies.pip) do
-            table.insert(pip_deps, dep.name .. "==" .. dep.version)
-        end
-
-        table.insert(steps, {
-            name = "Install Python dependencies",
-            run = "pip install " .. table.concat(pip_deps, " ")
-        })
+        }
     end
+end
 
-    return steps
+local function get_cache_step(cache)
+    return {
+        name = "Cache Flint plugins and dependencies",
+        uses = "actions/cache@v4",
+        with = {
+            path = table.concat(cache.paths, "\n"),
+            key = "flint-${{ runner.os }}-${{ hashFiles('" .. cache.key_file .. "') }}",
+            ["restore-keys"] = "flint-${{ runner.os }}-"
+        }
+    }
 end
 
-function Generate(config, dependencies)
-    log.debug(dependencies)
+function Generate(config, pipeline)
+    log.debug(pipeline)
 
     local workflow = {}
-    workflow.name = "Flint CI"
+    workflow.name = pipeline.name
     workflow.on = {
-        pull_request = { branches = { "main" } },
-        push = { branches = { "main" } }
+        pull_request = { branches = pipeline.pr_branches },
+        push = { branches = pipeline.branches }
     }
 
-
     -- Create empty job table
     local job = {
         name = "Flint Checks",
-        ["runs-on"] = "ubuntu-latest",
+        ["runs-on"] = pipeline.runs_on,
         steps = {}
     }
 
-    -- Checkout code from current repo
+    -- Run the job for every combination of the matrix
+    local has_matrix = next(pipeline.matrix) ~= nil
+    if has_matrix then
+        job.strategy = {
+            ["fail-fast"] = false,
+            matrix = pipeline.matrix
+        }
+        if pipeline.matrix.os then
+            job["runs-on"] = "${{ matrix.os }}"
+        end
+    end
+
     -- Add checkout step
     table.insert(job.steps, {
         name = "Checkout code",
         uses = "actions/checkout@v4"
     })
 
-    local dependency_install_steps = get_dependency_install_steps(dependencies)
-
-    -- Add dependency install steps to the job
-    for _, step in ipairs(dependency_install_steps) do
-        table.insert(job.steps, step)
+    if pipeline.cache then
+        table.insert(job.steps, get_cache_step(pipeline.cache))
     end
 
-    -- install Flint from the latest release
-    table.insert(job.steps, {
-        name = "Install Flint",
-        uses = "robinraju/release-downloader@v1",
-        with = {
-            latest = true,
-            repository = "skadewdl3/flint",
-            ["out-file-path"] = ".",
-            fileName = "flint",
-            prerelease = true
-        }
-    })
+    for _, toolchain in ipairs(pipeline.toolchains) do
+        table.insert(job.steps, get_toolchain_step(toolchain))
+    end
 
-    -- Run Flint Checks
-    table.insert(job.steps, {
-        name = "Run Tests",
-        run =
-        [[
-        chmod +x ./flint
-        ./flint install
-        ./flint test --test
-        ]]
-    })
+    for _, step in ipairs(pipeline.steps) do
+        table.insert(job.steps, {
+            name = step.name,
+            run = step.run
+        })
+    end
 
-    table.insert(job.steps, {
-        name = "Upload Test Results",
-        uses = "actions/upload-artifact@v4",
-        -- TODO: Make it return all logs
-        -- TODO: Make it adapt to the outputs of reporting plugins
-        with = {
-            name = "Test Results",
-            path = "reports/report.json"
-        }
-    })
+    -- Artifacts of the jobs of a matrix need different names
+    local artifact_suffix = has_matrix and " (${{ strategy.job-index }})" or ""
 
-    table.insert(job.steps, {
-        name = "Upload Logs",
-        uses = "actions/upload-artifact@v4",
-        with = {
-            name = "Logs",
-            path = "logs.txt",
-        }
-    })
+    for _, artifact in ipairs(pipeline.artifacts) do
+        table.insert(job.steps, {
+            name = "Upload " .. artifact.name,
+            uses = "actions/upload-artifact@v4",
+            ["if"] = "always()",
+            with = {
+                name = artifact.name .. artifact_suffix,
+                path = artifact.path
+            }
+        })
+    end
 
     workflow.jobs = {
         flint_checks = job
     }
 
-
     return {
         [".github/workflows/flint_checks.yml"] = yaml.stringify(workflow)
     }
@@ -0,0 +1,95 @@
+local log = require("log")
+local yaml = require("yaml")
+
+-- GitLab only caches directories in the project, so the caches of the package
+-- managers are moved there
+local cache_dirs = {
+    npm_config_cache = ".npm",
+    PIP_CACHE_DIR = ".cache/pip"
+}
+
+local function branch_condition(branches)
+    local conditions = {}
+    for _, branch in ipairs(branches) do
+        table.insert(conditions, "$CI_COMMIT_BRANCH == \"" .. branch .. "\"")
+    end
+    return "(" .. table.concat(conditions, " || ") .. ")"
+end
+
+local function target_branch_condition(branches)
+    local conditions = {}
+    for _, branch in ipairs(branches) do
+        table.insert(conditions, "$CI_MERGE_REQUEST_TARGET_BRANCH_NAME == \"" .. branch .. "\"")
+    end
+    return "(" .. table.concat(conditions, " || ") .. ")"
+end
+
+function Generate(config, pipeline)
+    log.debug(pipeline)
+
+    local job = {
+        image = config.image or pipeline.image,
+        script = {}
+    }
+
+    -- Tags pick the runners of the job, like ones on a specific OS
+    if config.tags then
+        job.tags = config.tags
+    end
+
+    -- The matrix values are passed to the jobs as variables, like OS for os
+    if next(pipeline.matrix) ~= nil then
+        local variables = {}
+        for name, values in pairs(pipeline.matrix) do
+            variables[string.upper(name)] = values
+        end
+        job.parallel = { matrix = { variables } }
+    end
+
+    if pipeline.cache then
+        job.variables = {}
+        local paths = {}
+        for variable, dir in pairs(cache_dirs) do
+            job.variables[variable] = "$CI_PROJECT_DIR/" .. dir
+            table.insert(paths, dir)
+        end
+        table.sort(paths)
+
+        job.cache = {
+            key = { files = { pipeline.cache.key_file } },
+            paths = paths
+        }
+    end
+
+    for _, step in ipairs(pipeline.steps) do
+        table.insert(job.script, step.run)
+    end
+
+    local artifact_paths = {}
+    for _, artifact in ipairs(pipeline.artifacts) do
+        table.insert(artifact_paths, artifact.path)
+    end
+    job.artifacts = {
+        when = "always",
+        paths = artifact_paths
+    }
+
+    local gitlab_ci = {
+        workflow = {
+            rules = {
+                {
+                    ["if"] = "$CI_PIPELINE_SOURCE == \"merge_request_event\" && "
+                        .. target_branch_condition(pipeline.pr_branches)
+                },
+                {
+                    ["if"] = "$CI_PIPELINE_SOURCE == \"push\" && " .. branch_condition(pipeline.branches)
+                }
+            }
+        },
+        flint_checks = job
+    }
+
+    return {
+        [".gitlab-ci.yml"] = yaml.stringify(gitlab_ci)
+    }
+end
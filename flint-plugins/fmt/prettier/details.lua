@@ -0,0 +1,44 @@
+function Details()
+    local details = {
+        id = "prettier",
+        author = "Onkar Kapuskari",
+        version = "0.0.1",
+        extensions = { "js", "ts", "jsx", "tsx", "mjs", "cjs", "json", "css", "scss", "md", "yaml", "yml", "html" },
+    }
+    return details
+end
+
+function Dependencies()
+    return {
+        npm = {
+            { name = "prettier", version = "latest" }
+        },
+    }
+end
+
+function Tools()
+    return {
+        {
+            name = "prettier",
+            version = ">=3",
+            install = {
+                npm = "npm install --save-dev prettier",
+                pnpm = "pnpm add --save-dev prettier",
+                yarn = "yarn add --dev prettier",
+            },
+        },
+    }
+end
+
+function Inputs()
+    return {
+        config = { "*" },
+    }
+end
+
+function Rules()
+    return {
+        { name = "trailing_comma", type = "enum", values = { "all", "es5", "none" }, default = "all", description = "Where trailing commas are added in multi-line lists" },
+        { name = "prose_wrap", type = "enum", values = { "always", "never", "preserve" }, default = "preserve", description = "How Markdown text is wrapped" },
+    }
+end
@@ -0,0 +1,27 @@
+local log = require("log")
+local json = require("json")
+
+function Generate(config)
+    local common = config.common
+    log.info("Generating Prettier configuration")
+
+    local prettierConfig = {
+        trailingComma = config.trailing_comma,
+        proseWrap = config.prose_wrap,
+        printWidth = common.max_line_length,
+        semi = common.require_semicolons,
+    }
+
+    if common.quote_style then
+        prettierConfig.singleQuote = common.quote_style == "single"
+    end
+
+    if common.indent then
+        prettierConfig.useTabs = common.indent.indent_style == "tabs"
+        prettierConfig.tabWidth = common.indent.indent_size
+    end
+
+    return {
+        [".prettierrc.json"] = json.stringify(prettierConfig)
+    }
+end
@@ -0,0 +1,32 @@
+-- Prettier lists the files it changes with --list-different, when it writes them and
+-- when it only checks them
+function Run(config)
+    return { "npx", "prettier", "--write", "--list-different", "--ignore-unknown", "." }
+end
+
+function Check(config)
+    return { "npx", "prettier", "--list-different", "--ignore-unknown", "." }
+end
+
+function Eval(output)
+    -- Prettier exits with 2 when it couldn't run, like when a file doesn't parse
+    if output.status == 2 then
+        error("prettier failed: " .. output.stderr)
+    end
+
+    local results = {}
+    for line in output.stdout:gmatch("[^\r\n]+") do
+        table.insert(results, {
+            file_name = line,
+            success = false,
+            error_message = "Not formatted",
+        })
+    end
+
+    return {
+        tests_passed = 0,
+        total_tests = #results,
+        passing_percentage = 0,
+        test_results = results
+    }
+end
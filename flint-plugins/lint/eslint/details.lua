@@ -15,3 +15,23 @@ function Dependencies()
         },
     }
 end
+
+function Tools()
+    return {
+        {
+            name = "eslint",
+            version = ">=8",
+            install = {
+                npm = "npm install --save-dev eslint",
+                pnpm = "pnpm add --save-dev eslint",
+                yarn = "yarn add --dev eslint",
+            },
+        },
+    }
+end
+
+function Inputs()
+    return {
+        config = { "*" },
+    }
+end
@@ -1,6 +1,7 @@
 local log = require("log")
-local json = require("json")
+local parser = require("parser")
 local path = require("path")
+local project = require("project")
 
 function Run(config)
     local extra = config.config
@@ -8,22 +9,53 @@ function Run(config)
     -- local args = { "npx", "eslint", "--format", "json", "src" } -- ideal output
     local args = { "npx", "eslint", "--format", "json" }
 
+    -- Only lint the files changed in the branch when running with --changed. Files
+    -- ESLint is configured to ignore are skipped without a warning.
+    local changed_files = project.changed_files({ "js", "ts", "mjs", "cjs" })
+    if changed_files then
+        table.insert(args, "--no-warn-ignored")
+        for _, file in ipairs(changed_files) do
+            table.insert(args, file)
+        end
+    end
+
     return args
 end
 
 function Eval(output)
-    local json_output = json.parse(output.stdout)
-    log.debug(json_output)
-
-
-
     local results = {}
     local tests_passed = 0
     local total_tests = 0
+    -- The files with findings, which are counted once however many they have
+    local seen = {}
+
+    -- ESLint prints nothing to stdout if it couldn't run
+    if output.stdout == nil or output.stdout == "" then
+        log.debug(output.stderr)
+        return {
+            tests_passed = 0,
+            total_tests = 0,
+            passing_percentage = 0,
+            test_results = {}
+        }
+    end
+
+    -- Every linted file counts as a test, which passes if ESLint found nothing in it
+    for _, result in ipairs(parser.parse("eslint-json", output.stdout)) do
+        if result.success then
+            tests_passed = tests_passed + 1
+            total_tests = total_tests + 1
+        elseif not seen[result.file_name] then
+            seen[result.file_name] = true
+            total_tests = total_tests + 1
+        end
+        table.insert(results, result)
+    end
+
     local coverage = {
         tests_passed = tests_passed,
         total_tests = total_tests,
-        passing_percentage = 0,
+        passing_percentage = total_tests > 0 and (tests_passed / total_tests * 100) or 0,
         test_results = results
     }
 
@@ -23,3 +23,35 @@ function Dependencies()
         },
     }
 end
+
+function Tools()
+    return {
+        {
+            name = "sqlfluff",
+            install = {
+                pip = "pip install sqlfluff",
+                pipx = "pipx install sqlfluff",
+                uv = "uv tool install sqlfluff",
+            },
+        },
+    }
+end
+
+function Inputs()
+    return {
+        config = { "*" },
+    }
+end
+
+function Rules()
+    return {
+        { name = "core.dialect", type = "enum", values = { "ansi", "bigquery", "mysql", "postgres", "snowflake", "sqlite", "tsql" }, description = "The SQL dialect the files are parsed as" },
+        { name = "core.templater", type = "enum", values = { "jinja", "raw", "python", "placeholder" }, default = "jinja", description = "How templated SQL is rendered before it's linted" },
+        { name = "core.max_line_length", type = "integer", default = 80, description = "The longest line allowed" },
+    }
+end
+
+-- Every other section of sqlfluff's config is passed on to it
+function Options()
+    return { "core.*", "indentation.*", "layout.*", "rules.*", "templater.*" }
+end
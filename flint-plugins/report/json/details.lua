@@ -6,3 +6,9 @@ function Details()
         extensions = {} -- Output plugins don't need file extensions
     }
 end
+
+function Rules()
+    return {
+        { name = "output_path", type = "string", description = "The directory report.json is written to" },
+    }
+end
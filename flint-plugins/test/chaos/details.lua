@@ -15,3 +15,16 @@ function Dependencies()
         },
     }
 end
+
+function Tools()
+    return {
+        {
+            name = "chaostoolkit",
+            bin = "chaos",
+            install = {
+                pip = "pip install chaostoolkit",
+                pipx = "pipx install chaostoolkit",
+            },
+        },
+    }
+end
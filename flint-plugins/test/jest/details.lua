@@ -15,3 +15,36 @@ function Dependencies()
         },
     }
 end
+
+function Tools()
+    return {
+        {
+            name = "jest",
+            install = {
+                npm = "npm install --save-dev jest",
+                pnpm = "pnpm add --save-dev jest",
+                yarn = "yarn add --dev jest",
+            },
+        },
+    }
+end
+
+function Inputs()
+    return {
+        files = { "package.json", "package-lock.json", "pnpm-lock.yaml", "yarn.lock" },
+        config = { "*" },
+    }
+end
+
+function Rules()
+    return {
+        { name = "test_environment", type = "enum", values = { "node", "jsdom" }, default = "node", description = "The environment the tests run in" },
+        { name = "verbose", type = "boolean", default = true, description = "Whether every test is reported, rather than only the files" },
+        { name = "collect_coverage", type = "boolean", default = false, description = "Whether coverage is collected while the tests run" },
+        { name = "root_dir", type = "string", default = ".", description = "The directory the tests are found in" },
+    }
+end
+
+function Options()
+    return { "include", "exclude" }
+end
@@ -2,10 +2,38 @@ local log = require("log")
 local path = require("path")
 local json = require("json")
 
+-- The files changed with --changed that jest finds the related tests of. Changes to
+-- its config or the dependencies can affect any test, so they run the whole suite.
+function Affected(files, config)
+    local related = {}
+    for _, file in ipairs(files) do
+        local name = path.basename(file)
+        if name == "package.json" or name:match("^jest%.config%.") then
+            return nil
+        end
+        for _, extension in ipairs({ ".js", ".ts", ".mjs", ".cjs" }) do
+            if path.extname(file) == extension then
+                table.insert(related, file)
+            end
+        end
+    end
+    return related
+end
+
 function Run(config)
     local cwd = path.cwd()
     local files = path.ls(cwd)
-    return { "jest", "--json" }
+    local args = { "jest", "--json" }
+
+    -- Only the tests affected by the changed files, when they're known
+    if config.affected_tests then
+        table.insert(args, "--findRelatedTests")
+        for _, file in ipairs(config.affected_tests) do
+            table.insert(args, file)
+        end
+    end
+
+    return args
 end
 
 function Eval(output)
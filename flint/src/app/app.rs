@@ -1,30 +1,82 @@
-use super::generate::{GenerateWidget, GenerateWidgetArgs};
-use super::help::HelpWidget;
+use super::baseline::BaselineArgs;
+use super::config::{ConfigArgs, ConfigWidget};
+use super::daemon::DaemonArgs;
+use super::fix;
+use super::fmt::FmtArgs;
+use super::generate::{CiArgs, GenerateWidget, GenerateWidgetArgs};
+use super::hooks::HooksArgs;
 use super::init::{InitWidget, InitWidgetArgs};
-use super::install::{InstallArgs, InstallWidget};
-use super::test::{TestArgs, TestWidget};
+use super::install::{InstallArgs, InstallWidget, UpdateArgs};
+use super::logs::LogsViewWidget;
+use super::lsp::LspArgs;
+use super::plugins::{PluginArgs, PluginsWidget};
+use super::repl::ReplArgs;
+use super::rules::RulesWidget;
+use super::rules_doc::RulesDocArgs;
+use super::setup::SetupArgs;
+use super::test::{AuditArgs, FixArgs, LintArgs, TestArgs, TestWidget};
+use super::workspace::WorkspaceArgs;
 use super::AppWidget;
-use super::{AppError, AppResult};
-use crate::error;
-use crate::util::handle_key_events;
+use super::{AppResult, FlintError};
+use crate::report::{format::LintFormat, history::FailureThreshold, Report};
+use crate::util::keys::{get_action, load_keymap, Action};
+use crate::util::layout::{is_too_small, MIN_HEIGHT, MIN_WIDTH};
+use crate::util::terminal::{copy_to_clipboard, request_suspend, suspend, take_suspend_request};
+use crate::util::theme::{set_theme, theme, ColorChoice, Theme};
+use crate::widgets::error_screen::{ErrorScreen, ErrorScreenAction};
+use crate::widgets::modal::{self, Modal, ModalResult, Modals};
+use crate::widgets::status_bar::StatusBar;
+use crate::widgets::toasts::Toasts;
 use clap::{Parser, Subcommand};
-use crossterm::event;
-use crossterm::event::KeyCode;
-use flint_macros::{ui, widget};
-use ratatui::widgets::WidgetRef;
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, MouseButton, MouseEvent, MouseEventKind,
+};
+use crossterm::execute;
+use flint_core::cancel::cancel;
+use flint_core::config::Config;
+use flint_core::events::{open_channel, WorkerEvent};
+use flint_core::fix::FixPlan;
+use flint_core::logs::{push_log, set_max_logs, show_debug_logs, toggle_debug_logs, LogKind};
+use flint_core::timings::{self, Phase, TimingsSort};
+use flint_core::{error, get_flag, i18n, info, set_flag, t};
+use flint_macros::ui;
+use ratatui::widgets::{Paragraph, Tabs, WidgetRef};
 use ratatui::{prelude::*, DefaultTerminal};
+use std::cell::Cell;
 use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use threadpool::ThreadPool;
-use tui_popup::Popup;
+
+/// How long quitting waits for the cancelled plugins to stop
+const CANCEL_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct App {
     exit: bool,
-    active_widget: Box<dyn AppWidget>,
-    error: Option<String>,
+    views: Vec<View>,
+    active_view: usize,
+    thread_pool: ThreadPool,
+    /// The error of the last command that failed, until it's closed
+    error: Option<ErrorScreen>,
+    /// Where the tab bar was last rendered, to handle clicks on it
+    tabs_area: Cell<Rect>,
+    /// Whether the terminal was too small to show the views in the last frame. Until
+    /// it's resized, the views ignore input and only quitting is possible.
+    is_too_small: Cell<bool>,
+    status_bar: StatusBar,
+    toasts: Toasts,
+    /// What the plugins did in this session, written to the HTML report
+    report: Report,
+    modals: Modals,
+    modal_requests: mpsc::Receiver<Modal>,
+    /// Modals opened by the App, until they're answered
+    app_modals: Vec<(AppModal, mpsc::Receiver<ModalResult>)>,
+    /// When the running plugins were cancelled to quit, which happens once they stopped
+    cancelled_at: Option<Instant>,
     sender: mpsc::Sender<()>,
     receiver: mpsc::Receiver<()>,
+    worker_events: mpsc::Receiver<WorkerEvent>,
     args: AppArgs,
 }
 
@@ -40,10 +92,132 @@ pub struct AppArgs {
     #[clap(long, default_value_t = false, global = false)]
     pub no_install: bool,
 
+    /// Work without network access, using only the installed plugins
+    #[clap(long, global = true)]
+    pub offline: bool,
+
+    /// Write output for screen readers: plugin runs are announced in labeled lines
+    /// instead of drawn, and the UI is drawn without borders or symbols
+    #[clap(long, global = true)]
+    pub accessible: bool,
+
+    /// When to use colors
+    #[clap(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto, global = true)]
+    pub color: ColorChoice,
+
+    /// Time plugin discovery, every plugin, file writes and rendering, printing a table
+    /// once flint exits. Given a path, a Chrome trace is written to it too.
+    #[clap(long, value_name = "TRACE", num_args = 0..=1, global = true)]
+    pub timings: Option<Option<PathBuf>>,
+
+    /// The column the timings table is sorted by
+    #[clap(long, value_enum, value_name = "COLUMN", default_value_t = TimingsSort::Total, global = true)]
+    pub timings_sort: TimingsSort,
+
     #[command(subcommand)]
     pub command: Option<AppWidgetArgs>,
 }
 
+impl AppArgs {
+    /// The file lint findings are written to in SARIF format, if the command asks for it
+    pub fn sarif_path(&self) -> Option<&Path> {
+        match &self.command {
+            Some(AppWidgetArgs::Test(args)) => args.sarif.as_deref(),
+            Some(AppWidgetArgs::Lint(args)) => args.sarif.as_deref(),
+            Some(AppWidgetArgs::Audit(args)) => args.sarif.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The format lint findings are printed in, if the command asks for it
+    pub fn lint_format(&self) -> Option<LintFormat> {
+        match &self.command {
+            Some(AppWidgetArgs::Test(args)) => args.format,
+            Some(AppWidgetArgs::Lint(args)) => args.format,
+            _ => None,
+        }
+    }
+
+    /// The findings the run may have without failing, from the config and the command
+    pub fn failure_threshold(&self) -> FailureThreshold {
+        let max_warnings = match &self.command {
+            Some(AppWidgetArgs::Test(args)) => args.max_warnings,
+            Some(AppWidgetArgs::Lint(args)) => args.max_warnings,
+            _ => None,
+        };
+        FailureThreshold {
+            fail_on: load_config()
+                .and_then(|config| config.flint.fail_on)
+                .unwrap_or_default(),
+            max_warnings,
+        }
+    }
+
+    /// Whether the command prints the fixes of the findings instead of applying them
+    pub fn is_fix_dry_run(&self) -> bool {
+        matches!(&self.command, Some(AppWidgetArgs::Fix(args)) if args.fix_dry_run)
+    }
+
+    /// The file the Chrome trace is written to, if `--timings` was given one
+    pub fn trace_path(&self) -> Option<&Path> {
+        self.timings.as_ref().and_then(Option::as_deref)
+    }
+
+    /// Whether the command checks that generated configs are up to date, instead of
+    /// writing them
+    pub fn is_check(&self) -> bool {
+        match &self.command {
+            Some(AppWidgetArgs::Generate(args)) => args.check,
+            Some(AppWidgetArgs::Ci(args)) => args.check,
+            _ => false,
+        }
+    }
+}
+
+impl AppWidgetArgs {
+    /// The widget running the command without the UI, for commands that run plugins
+    pub fn into_non_interactive_widget(self) -> Option<Box<dyn AppWidget>> {
+        match self {
+            AppWidgetArgs::Install(args) => Some(Box::new(InstallWidget::new(args))),
+            AppWidgetArgs::Update(args) => Some(Box::new(InstallWidget::new(args.into()))),
+            AppWidgetArgs::Test(args) => Some(Box::new(TestWidget::new(args))),
+            AppWidgetArgs::Lint(args) => Some(Box::new(TestWidget::new(args.into()))),
+            AppWidgetArgs::Fix(args) => Some(Box::new(TestWidget::new(args.into()))),
+            AppWidgetArgs::Audit(args) => Some(Box::new(TestWidget::new(args.into()))),
+            AppWidgetArgs::Generate(args) => Some(Box::new(GenerateWidget::new(args))),
+            AppWidgetArgs::Ci(args) => Some(Box::new(GenerateWidget::new(args.into()))),
+            _ => None,
+        }
+    }
+
+    /// Runs the commands that print their output without the UI or the report of a
+    /// run, like `flint hooks`. It's `None` for the other commands.
+    pub fn run_standalone(&self) -> Option<AppResult<()>> {
+        // The language server talks to the editor on stdout, so logs are only written
+        // to the log file
+        if let AppWidgetArgs::Lsp(_) = self {
+            return Some(super::lsp::run());
+        }
+
+        let run: Box<dyn FnOnce() -> AppResult<()>> = match self.clone() {
+            AppWidgetArgs::Hooks(args) => Box::new(|| super::hooks::run(args)),
+            AppWidgetArgs::Setup(args) => Box::new(|| super::setup::run(args)),
+            AppWidgetArgs::Baseline(args) => Box::new(|| super::baseline::run(args)),
+            AppWidgetArgs::Suppressions => Box::new(super::suppressions::run),
+            AppWidgetArgs::Fmt(args) => Box::new(|| super::fmt::run(args)),
+            AppWidgetArgs::RulesDoc(args) => Box::new(|| super::rules_doc::run(args)),
+            AppWidgetArgs::Config(args) => Box::new(|| super::config::run(args)),
+            AppWidgetArgs::Plugin(args) => Box::new(|| super::plugins::run(args)),
+            AppWidgetArgs::Repl(args) => Box::new(|| super::repl::run(args)),
+            AppWidgetArgs::Workspace(args) => Box::new(|| super::workspace::run(args)),
+            AppWidgetArgs::Daemon(args) => Box::new(|| super::daemon::run(args)),
+            _ => return None,
+        };
+        set_flag!(non_interactive, true);
+        Some(run())
+    }
+}
+
 #[derive(Subcommand, Clone)]
 #[command(version, about, long_about = None, disable_help_subcommand = true, disable_help_flag = true)]
 pub enum AppWidgetArgs {
@@ -53,117 +227,645 @@ pub enum AppWidgetArgs {
     Generate(GenerateWidgetArgs),
     /// Tests a flint project
     Test(TestArgs),
+    /// Lints a flint project
+    Lint(LintArgs),
+    /// Lints a flint project and applies the fixes of the findings, after showing them
+    Fix(FixArgs),
+    /// Formats the project with the formatter plugins, or checks that it's formatted
+    Fmt(FmtArgs),
+    /// Checks the dependencies of the project for known vulnerabilities with the audit
+    /// plugins
+    Audit(AuditArgs),
+    /// Generates the CI workflows configured in the ci section
+    Ci(CiArgs),
     /// Installs the given list of plugins
     Install(InstallArgs),
+    /// Updates the plugins to the latest commit of their sources, pinned in flint.lock
+    Update(UpdateArgs),
+    /// Installs or removes a git hook running flint before committing
+    Hooks(HooksArgs),
+    /// Runs a language server publishing lint findings as diagnostics to editors
+    Lsp(LspArgs),
+    /// Runs a daemon keeping the plugins loaded, which later commands of the project
+    /// run in
+    Daemon(DaemonArgs),
+    /// Checks the tools the plugins run, like linters, and installs the missing ones
+    Setup(SetupArgs),
+    /// Records the current lint findings, so only new findings fail later runs
+    Baseline(BaselineArgs),
+    /// Lists the comments suppressing lint findings, like `flint-disable-next-line eslint`
+    Suppressions,
+    /// Writes the enabled plugins and the values of their rules to RULES.md
+    RulesDoc(RulesDocArgs),
+    /// Runs a command in every member of a monorepo, after the members they depend on
+    Workspace(WorkspaceArgs),
+    /// Checks flint.toml
+    Config(ConfigArgs),
+    /// Tools for plugin authors, like benchmarking a plugin
+    Plugin(PluginArgs),
+    /// Opens a Lua prompt with the helpers plugins use, to try them out
+    Repl(ReplArgs),
     Help,
 }
 
+/// A view of the App, which can be switched to with the tab bar.
+/// Views are set up when they're first shown, and keep their state while hidden.
+struct View {
+    title: &'static str,
+    widget: Box<dyn AppWidget>,
+    is_set_up: bool,
+}
+
+/// A modal opened by the App itself, which handles its answer
+enum AppModal {
+    Quit,
+    /// Shown while the running plugins stop, before quitting
+    Cancelling,
+    /// The command palette, with its commands in the order they're listed
+    CommandPalette(Vec<PaletteCommand>),
+    /// Asks whether to apply the fixes of the findings, once `flint fix` linted
+    ApplyFixes(FixPlan),
+}
+
+#[derive(Copy, Clone)]
+enum PaletteCommand {
+    SelectView(usize),
+    /// A command of the view at the index
+    ViewCommand(usize, &'static str),
+    ToggleDebugLogs,
+}
+
+impl View {
+    fn new(title: &'static str, widget: impl AppWidget + 'static) -> Self {
+        Self {
+            title,
+            widget: Box::new(widget),
+            is_set_up: false,
+        }
+    }
+}
+
 impl App {
     pub fn new(args: AppArgs) -> Self {
         let (sender, receiver) = mpsc::channel();
         Self {
             exit: false,
-            active_widget: Box::new(HelpWidget::default()),
+            views: Vec::new(),
+            active_view: 0,
+            thread_pool: ThreadPool::new(16),
             error: None,
+            tabs_area: Cell::new(Rect::default()),
+            is_too_small: Cell::new(false),
+            status_bar: StatusBar::default(),
+            toasts: Toasts::default(),
+            report: Report::default(),
+            modals: Modals::default(),
+            modal_requests: modal::open_channel(),
+            app_modals: Vec::new(),
+            cancelled_at: None,
             sender,
             receiver,
+            worker_events: open_channel(),
             args,
         }
     }
 
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
         let args = self.args.clone();
+        load_keymap();
+
+        let config = load_config();
+        load_theme(config.as_ref());
+        if let Some(max_logs) = config.as_ref().and_then(|config| config.flint.max_logs) {
+            set_max_logs(max_logs);
+        }
+        let plugins = config.as_ref().map_or(0, |config| {
+            config.rules.len()
+                + config.tests.len()
+                + config.ci.plugin_ids().len()
+                + config.report_plugin_ids().len()
+        });
+        self.status_bar = StatusBar::new(get_flag!(current_dir).clone(), plugins);
 
-        self.active_widget = match args.command.unwrap() {
-            AppWidgetArgs::Install(args) => Box::new(InstallWidget::new(args)),
-            AppWidgetArgs::Generate(args) => Box::new(GenerateWidget::new(args)),
-            AppWidgetArgs::Test(args) => Box::new(TestWidget::new(args)),
-            AppWidgetArgs::Init(args) => Box::new(InitWidget::new(args)),
-            _ => Box::new(HelpWidget::default()),
+        let mouse_capture = config.as_ref().is_none_or(|config| config.flint.mouse);
+        if mouse_capture {
+            execute!(io::stdout(), EnableMouseCapture)?;
+        }
+
+        // Init and Install run on their own, every other command opens the views of
+        // a flint project, starting with the view of the command
+        let (views, active_view) = match args.command {
+            Some(AppWidgetArgs::Init(args)) => {
+                (vec![View::new(t!("Init"), InitWidget::new(args))], 0)
+            }
+            Some(AppWidgetArgs::Install(args)) => {
+                (vec![View::new(t!("Install"), InstallWidget::new(args))], 0)
+            }
+            Some(AppWidgetArgs::Update(args)) => (
+                vec![View::new(t!("Update"), InstallWidget::new(args.into()))],
+                0,
+            ),
+            Some(AppWidgetArgs::Generate(args)) => (Self::project_views(Some(args), None), 0),
+            Some(AppWidgetArgs::Test(args)) => (Self::project_views(None, Some(args)), 1),
+            Some(AppWidgetArgs::Lint(args)) => (Self::project_views(None, Some(args.into())), 1),
+            Some(AppWidgetArgs::Fix(args)) => (Self::project_views(None, Some(args.into())), 1),
+            Some(AppWidgetArgs::Audit(args)) => (Self::project_views(None, Some(args.into())), 1),
+            Some(AppWidgetArgs::Ci(args)) => (Self::project_views(Some(args.into()), None), 0),
+            _ => (Self::project_views(None, None), 2),
         };
+        self.views = views;
+        self.select_view(active_view);
 
-        self.active_widget.set_exit_sender(self.sender.clone());
+        // Frames are only drawn when something changed: an input event, a worker
+        // event, a modal opened by a worker or a step of the spinner
+        let mut needs_redraw = true;
+        while !self.exit {
+            needs_redraw |= self.handle_worker_events();
 
-        let thread_pool = ThreadPool::new(16);
-        self.active_widget.set_thread_pool(&thread_pool);
+            // Workers that ask while flint is quitting are answered with `Cancelled`
+            let modal_requests: Vec<Modal> = self.modal_requests.try_iter().collect();
+            if self.cancelled_at.is_none() {
+                needs_redraw |= !modal_requests.is_empty();
+                self.modals.extend(modal_requests);
+            }
 
-        match self.active_widget.setup() {
-            Ok(_) => (),
-            Err(err) => {
-                self.error = Some(err.to_string());
-                self.exit = true;
+            if self.handle_modal_answers() {
+                break;
+            }
+            if let Some(cancelled_at) = self.cancelled_at {
+                if self.thread_pool.active_count() == 0 || cancelled_at.elapsed() > CANCEL_TIMEOUT {
+                    break;
+                }
+            }
+            if self.status_bar.tick() {
+                let tick = self.status_bar.ticks();
+                for view in &mut self.views {
+                    view.widget.handle_tick(tick);
+                }
+                needs_redraw = true;
+            }
+            needs_redraw |= self.toasts.tick();
+
+            if needs_redraw {
+                let _timing = timings::time(Phase::Render, "frame");
+                terminal.draw(|frame| self.draw(frame))?;
+                needs_redraw = false;
             }
-        }
-        while !self.exit {
-            terminal.draw(|frame| self.draw(frame))?;
 
-            if let Ok(_) = self.receiver.recv_timeout(Duration::from_millis(1)) {
+            if take_suspend_request() {
+                suspend(terminal, mouse_capture)?;
+                needs_redraw = true;
+            }
+
+            if self.receiver.recv_timeout(Duration::from_millis(1)).is_ok() {
                 // Break if a forceful exit is requested
                 break;
             }
 
             match self.handle_all_events() {
-                Ok(_) => (),
+                Ok(handled) => needs_redraw |= handled,
+                Err(FlintError::Exit) => self.exit = true,
                 Err(err) => {
-                    self.error = Some(err.to_string());
+                    self.error = Some(ErrorScreen::new(&err));
                     self.exit = true;
                 }
             }
         }
 
-        thread_pool.join();
+        if mouse_capture {
+            execute!(io::stdout(), DisableMouseCapture)?;
+        }
 
         Ok(())
     }
 
+    /// Creates the views available in a flint project. Commands without arguments
+    /// use their default arguments.
+    fn project_views(
+        generate_args: Option<GenerateWidgetArgs>,
+        test_args: Option<TestArgs>,
+    ) -> Vec<View> {
+        let generate_args = generate_args.unwrap_or_default();
+        let test_args = test_args.unwrap_or_default();
+
+        vec![
+            View::new(t!("Generate"), GenerateWidget::new(generate_args)),
+            View::new(t!("Test"), TestWidget::new(test_args)),
+            View::new(t!("Plugins"), PluginsWidget::default()),
+            View::new(t!("Rules"), RulesWidget::default()),
+            View::new(t!("Logs"), LogsViewWidget::default()),
+            View::new(t!("Config"), ConfigWidget::default()),
+        ]
+    }
+
+    /// Hands the events sent by workers to the status bar and the views. Returns
+    /// true if any event was received.
+    fn handle_worker_events(&mut self) -> bool {
+        let mut received = false;
+        let mut has_finished = false;
+        for event in self.worker_events.try_iter() {
+            received = true;
+            self.status_bar.handle_event(&event);
+            self.toasts.handle_event(&event);
+            self.report.handle_event(&event);
+            has_finished |= matches!(event, WorkerEvent::Finished { .. });
+            if let WorkerEvent::Log(kind, log) = event {
+                push_log(kind, log);
+                continue;
+            }
+
+            for view in &mut self.views {
+                view.widget.handle_worker_event(&event);
+            }
+        }
+
+        // The report is written once the last running plugin finished
+        if has_finished && self.report.is_finished() {
+            let trend = self.report.write();
+            self.status_bar.set_last_run(trend);
+            self.report.log_vulnerabilities();
+            if let Some(path) = self.args.sarif_path() {
+                self.report.write_sarif(path);
+            }
+            if let Some(AppWidgetArgs::Fix(_)) = &self.args.command {
+                self.offer_fixes();
+            }
+        }
+
+        received
+    }
+
+    /// Shows the view at the given index, setting it up if it's shown for the first time
+    fn select_view(&mut self, index: usize) {
+        let Some(view) = self.views.get_mut(index) else {
+            return;
+        };
+
+        self.active_view = index;
+        self.error = None;
+        self.status_bar.command = view.title.to_string();
+
+        if !view.is_set_up {
+            view.is_set_up = true;
+            view.widget.set_exit_sender(self.sender.clone());
+            view.widget.set_thread_pool(&self.thread_pool);
+            if let Err(err) = view.widget.setup() {
+                error!("Error occurred: {}", err);
+                self.error = Some(ErrorScreen::new(&err));
+            }
+        }
+    }
+
     fn draw(&self, frame: &mut Frame) {
         ui!(frame => {
             { self }
         });
     }
 
-    fn handle_all_events(&mut self) -> AppResult<()> {
+    /// Waits for the next input event and handles it. Returns false if no event
+    /// arrived in time.
+    fn handle_all_events(&mut self) -> AppResult<bool> {
         // Exit early if no events are available
         if let Ok(event_exists) = event::poll(Duration::from_millis(100)) {
             if !event_exists {
+                return Ok(false);
+            }
+        }
+
+        let event = event::read()?;
+        self.handle_event(event)?;
+        Ok(true)
+    }
+
+    fn handle_event(&mut self, event: Event) -> AppResult<()> {
+        // Open modals take every event until they're closed
+        if self.modals.handle_event(&event) {
+            return Ok(());
+        }
+
+        if self.is_too_small.get() {
+            return match event {
+                Event::Key(key_event) if get_action(&key_event) == Some(Action::Quit) => {
+                    self.quit()
+                }
+                _ => Ok(()),
+            };
+        }
+
+        // The error screen takes every key and click until it's closed
+        if let Some(error) = &self.error {
+            match error.handle_event(&event) {
+                Some(ErrorScreenAction::Close) => self.error = None,
+                Some(ErrorScreenAction::Copy) => match copy_to_clipboard(error.details()) {
+                    Ok(()) => self
+                        .toasts
+                        .push(LogKind::Success, t!("Copied the error details")),
+                    Err(err) => error!("Unable to copy the error details: {}", err),
+                },
+                None => (),
+            }
+            return Ok(());
+        }
+
+        if let Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            ..
+        }) = event
+        {
+            if let Some(index) = self.tab_at(column, row) {
+                self.select_view(index);
                 return Ok(());
             }
         }
 
-        let event = event::read().expect("Could not get event");
-        let status1 = handle_key_events(event.clone(), |_, key_code| match key_code {
-            KeyCode::Esc => return Err(AppError::Exit),
-            _ => Ok(()),
-        });
+        // Global keys are handled before the active widget, unless it claims the key
+        if let Event::Key(key_event) = event {
+            if !self.views[self.active_view].widget.claims_key(&key_event) {
+                let view_count = self.views.len();
+                match get_action(&key_event) {
+                    Some(Action::Quit) => return self.quit(),
+                    Some(Action::NextView) if view_count > 1 => {
+                        self.select_view((self.active_view + 1) % view_count);
+                        return Ok(());
+                    }
+                    Some(Action::PreviousView) if view_count > 1 => {
+                        self.select_view((self.active_view + view_count - 1) % view_count);
+                        return Ok(());
+                    }
+                    Some(Action::Suspend) => {
+                        request_suspend();
+                        return Ok(());
+                    }
+                    Some(Action::CommandPalette) => {
+                        self.open_command_palette();
+                        return Ok(());
+                    }
+                    Some(Action::SelectView(index)) if index < view_count => {
+                        self.select_view(index);
+                        return Ok(());
+                    }
+                    _ => (),
+                }
+            }
+        }
 
-        let status2 = self.active_widget.handle_events(event);
-        if matches!(status1, Err(AppError::Exit)) || matches!(status2, Err(AppError::Exit)) {
-            return Err(AppError::Exit);
+        self.views[self.active_view].widget.handle_events(event)
+    }
+
+    /// Exits, after asking for confirmation if plugins are still running. Running
+    /// plugins are cancelled first, so they don't write files once flint exited.
+    fn quit(&mut self) -> AppResult<()> {
+        if self.thread_pool.active_count() == 0 {
+            return Err(FlintError::Exit);
         }
+
+        self.open_modal(
+            AppModal::Quit,
+            Modal::confirm(
+                t!("Quit"),
+                t!("Plugins are still running. Cancel them and quit?"),
+            ),
+        );
         Ok(())
     }
-}
 
-impl WidgetRef for App {
-    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
-        ui!((area, buf) => {
-            {
-                &self.error.as_ref().map(|err| {
+    /// Cancels the running plugins, and quits once they stopped
+    fn cancel_running_plugins(&mut self) {
+        cancel();
+        // Workers waiting for the answer to a modal get `Cancelled` once it's dropped
+        self.modals = Modals::default();
+        self.open_modal(
+            AppModal::Cancelling,
+            Modal::waiting(t!("Quitting"), t!("Cancelling the running plugins…")),
+        );
+        self.cancelled_at = Some(Instant::now());
+    }
+
+    /// Lists every view, the commands of the views and the global commands
+    fn open_command_palette(&mut self) {
+        let mut labels = Vec::new();
+        let mut commands = Vec::new();
+
+        for (index, view) in self.views.iter().enumerate() {
+            labels.push(t!("Go to {view}", view = view.title));
+            commands.push(PaletteCommand::SelectView(index));
+
+            for command in view.widget.commands() {
+                labels.push(i18n::text(command).to_string());
+                commands.push(PaletteCommand::ViewCommand(index, command));
+            }
+        }
+
+        let toggle_label = if show_debug_logs() {
+            t!("Hide debug logs")
+        } else {
+            t!("Show debug logs")
+        };
+        labels.push(toggle_label.to_string());
+        commands.push(PaletteCommand::ToggleDebugLogs);
+
+        self.open_modal(
+            AppModal::CommandPalette(commands),
+            Modal::search(t!("Commands"), t!("Type to search"), labels),
+        );
+    }
+
+    /// Shows the fixes of the findings of the run, asking whether to apply them
+    fn offer_fixes(&mut self) {
+        let plan = fix::plan(&self.report);
+        if plan.is_empty() {
+            info!("None of the findings can be fixed");
+            return;
+        }
+        let modal = fix::confirm(&plan);
+        self.open_modal(AppModal::ApplyFixes(plan), modal);
+    }
+
+    fn open_modal(&mut self, app_modal: AppModal, modal: Modal) {
+        let (reply, answer) = mpsc::channel();
+        self.modals.push(modal.with_reply(reply));
+        self.app_modals.push((app_modal, answer));
+    }
+
+    /// Handles the answers to the modals opened by the App. Returns whether to exit.
+    fn handle_modal_answers(&mut self) -> bool {
+        let mut exit = false;
+
+        for (app_modal, answer) in std::mem::take(&mut self.app_modals) {
+            match answer.try_recv() {
+                Ok(answer) => match (app_modal, answer) {
+                    (AppModal::Quit, ModalResult::Confirmed(true)) => self.cancel_running_plugins(),
+                    (AppModal::Cancelling, ModalResult::Cancelled) => exit = true,
+                    (AppModal::CommandPalette(commands), ModalResult::Selected(index)) => {
+                        self.run_palette_command(commands[index]);
+                    }
+                    (AppModal::ApplyFixes(plan), ModalResult::Confirmed(true)) => fix::apply(&plan),
+                    _ => (),
+                },
+                Err(mpsc::TryRecvError::Empty) => self.app_modals.push((app_modal, answer)),
+                Err(mpsc::TryRecvError::Disconnected) => (),
+            }
+        }
+
+        exit
+    }
+
+    fn run_palette_command(&mut self, command: PaletteCommand) {
+        match command {
+            PaletteCommand::SelectView(index) => self.select_view(index),
+            PaletteCommand::ViewCommand(index, command) => {
+                // Views run their work when they're set up, so a command only
+                // runs it again if the view was already set up
+                let is_set_up = self.views[index].is_set_up;
+                self.select_view(index);
+                if !is_set_up {
+                    return;
+                }
+
+                if let Err(err) = self.views[index].widget.run_command(command) {
                     error!("Error occurred: {}", err);
-                    Some(widget!({ Popup::new(err.as_str(), title: format!("Error occurred")) }))
-                })
+                    self.error = Some(ErrorScreen::new(&err));
+                }
             }
+            PaletteCommand::ToggleDebugLogs => toggle_debug_logs(),
+        }
+    }
+
+    fn tab_titles(&self) -> Vec<String> {
+        self.views
+            .iter()
+            .enumerate()
+            .map(|(index, view)| {
+                // Screen readers can't tell the active tab by its color
+                match *get_flag!(accessible) && index == self.active_view {
+                    true => t!(
+                        "{number} {view}, selected",
+                        number = index + 1,
+                        view = view.title
+                    ),
+                    false => format!("{} {}", index + 1, view.title),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the index of the tab at the given position, if any. Each tab is
+    /// its title with a space of padding on both sides, separated by a divider.
+    fn tab_at(&self, column: u16, row: u16) -> Option<usize> {
+        let tabs_area = self.tabs_area.get();
+        if self.views.len() < 2 || !tabs_area.contains(Position::new(column, row)) {
+            return None;
+        }
+
+        let mut x = tabs_area.x;
+        for (index, title) in self.tab_titles().iter().enumerate() {
+            let width = Line::from(title.as_str()).width() as u16 + 2;
+            if (x..x + width).contains(&column) {
+                return Some(index);
+            }
+            x += width + 1;
+        }
+
+        None
+    }
+}
+
+/// Loads the config, if there is one. Commands like init run without a config.
+pub(super) fn load_config() -> Option<Config> {
+    let config_path = get_flag!(config_path);
+    if !config_path.exists() {
+        return None;
+    }
+
+    Config::load(config_path).ok()
+}
+
+/// Sets the theme in `[flint.theme]`, or the default theme if there's none. Its colors
+/// are left out with `NO_COLOR` or `--color=never`, and its symbols replaced with
+/// `--accessible`.
+pub(super) fn load_theme(config: Option<&Config>) {
+    let theme = config
+        .and_then(|config| config.flint.theme.as_ref())
+        .map_or_else(Theme::default, Theme::from_config);
+    let theme = match *get_flag!(accessible) {
+        true => theme.accessible(),
+        false => theme,
+    };
+    let color = *get_flag!(color);
+    set_theme(if color { theme } else { theme.without_colors() });
+}
+
+impl WidgetRef for App {
+    fn render_ref(&self, frame_area: Rect, buf: &mut Buffer) {
+        self.is_too_small.set(is_too_small(frame_area));
+        if self.is_too_small.get() {
+            render_too_small(frame_area, buf);
+            self.modals.render_ref(frame_area, buf);
+            return;
+        }
+
+        let [area, status_bar_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame_area);
+        ui!((status_bar_area, buf) => {
+            { &self.status_bar }
         });
 
-        self.active_widget.render_ref(area, buf);
+        // The tab bar is only shown if there's more than one view to switch between
+        let view_area = if self.views.len() > 1 {
+            let [tabs_area, view_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+            self.tabs_area.set(tabs_area);
+            let titles = self.tab_titles();
+
+            ui!((tabs_area, buf) => {
+                Tabs::new(
+                    titles,
+                    select: self.active_view,
+                    highlight_style: theme().accent(),
+                    divider: theme().symbols.divider
+                )
+            });
+            view_area
+        } else {
+            area
+        };
+
+        if let Some(view) = self.views.get(self.active_view) {
+            view.widget.render_ref(view_area, buf);
+        }
+        if let Some(error) = &self.error {
+            error.render(view_area, buf);
+        }
+        self.toasts.render(view_area, buf);
+
+        self.modals.render_ref(frame_area, buf);
     }
 }
 
-#[macro_export]
-macro_rules! cmd {
-    ($program:expr, $($arg:expr),* $(,)?) => {{
-        let mut command = std::process::Command::new($program);
-        $(command.arg($arg);)*
-        command
-    }};
+/// Shown instead of the views when the terminal is smaller than the views need
+fn render_too_small(area: Rect, buf: &mut Buffer) {
+    let theme = theme();
+    let lines = vec![
+        Line::from(t!("Terminal too small")).style(theme.accent()),
+        Line::from(t!(
+            "need {width}x{height}",
+            width = MIN_WIDTH,
+            height = MIN_HEIGHT
+        )),
+        Line::from(t!(
+            "current {width}x{height}",
+            width = area.width,
+            height = area.height
+        ))
+        .style(Style::new().fg(theme.error)),
+    ];
+
+    let [notice_area] = Layout::vertical([Constraint::Length(lines.len() as u16)])
+        .flex(layout::Flex::Center)
+        .areas(area);
+    ui!((notice_area, buf) => {
+        Paragraph::new(lines, alignment: Alignment::Center)
+    });
 }
@@ -0,0 +1,84 @@
+use clap::{Parser, Subcommand};
+use std::time::Duration;
+use threadpool::ThreadPool;
+
+use super::test::{LintArgs, TestArgs, TestWidget};
+use super::{AppResult, AppWidget};
+use crate::report::{lint_findings, Report};
+use crate::widgets::progress::PluginStatus;
+use flint_core::baseline::{Baseline, BaselineFinding, BASELINE_FILE};
+use flint_core::events::open_channel;
+use flint_core::{app_err, success, warn};
+
+#[derive(Parser, Clone)]
+pub struct BaselineArgs {
+    #[command(subcommand)]
+    pub command: BaselineCommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum BaselineCommand {
+    /// Lints the project and records every finding in the baseline, replacing it.
+    /// Later runs leave these findings out, so only new ones fail them.
+    Create,
+}
+
+/// Runs `flint baseline`
+pub fn run(args: BaselineArgs) -> AppResult<()> {
+    match args.command {
+        BaselineCommand::Create => create(),
+    }
+}
+
+fn create() -> AppResult<()> {
+    let report = lint()?;
+    // The baseline is still created, as the other plugins' findings are known
+    for plugin in report.plugins() {
+        if plugin.status == PluginStatus::Failed {
+            warn!(
+                "{} failed, so its findings aren't in the baseline",
+                plugin.plugin
+            );
+        }
+    }
+
+    let findings = lint_findings(&report)
+        .into_values()
+        .flatten()
+        .map(|(plugin, result)| BaselineFinding::new(plugin, result))
+        .collect::<Vec<_>>();
+    let count = findings.len();
+    Baseline::new(findings).save()?;
+    success!("Recorded {} findings in {}", count, BASELINE_FILE);
+    Ok(())
+}
+
+/// Runs the lint plugins with the current baseline ignored, collecting what they found
+fn lint() -> AppResult<Report> {
+    let mut args: TestArgs = LintArgs::default().into();
+    args.no_baseline = true;
+
+    let worker_events = open_channel();
+    let thread_pool = ThreadPool::new(16);
+    let mut widget = TestWidget::new(args);
+    widget.set_thread_pool(&thread_pool);
+    widget.setup()?;
+
+    let mut report = Report::default();
+    loop {
+        let is_done = thread_pool.active_count() == 0 && thread_pool.queued_count() == 0;
+        while let Ok(event) = worker_events.recv_timeout(Duration::from_millis(50)) {
+            report.handle_event(&event);
+        }
+        if is_done {
+            break;
+        }
+    }
+
+    if !report.is_finished() {
+        return Err(app_err!(
+            "No lint plugins ran, so there's nothing to record"
+        ));
+    }
+    Ok(report)
+}
@@ -0,0 +1,151 @@
+use super::{AppResult, AppWidget};
+use crate::report::ReportFormat;
+use crate::util::keys::{get_action, Action};
+use crate::util::theme::theme;
+use crate::util::{handle_key_events, handle_mouse_event};
+use clap::{Parser, Subcommand};
+use crossterm::event::{Event, MouseEventKind};
+use flint_core::config_lint::{self, Section, Severity};
+use flint_core::plugin;
+use flint_core::{app_err, error, get_flag, info, success, warn};
+use flint_macros::ui;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Paragraph, WidgetRef};
+
+/// Shows the contents of the config file
+#[derive(Debug, Default)]
+pub struct ConfigWidget {
+    contents: String,
+    scroll: u16,
+}
+
+impl AppWidget for ConfigWidget {
+    fn setup(&mut self) -> AppResult<()> {
+        self.contents = std::fs::read_to_string(get_flag!(config_path))?;
+        Ok(())
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["Reload config"]
+    }
+
+    /// Reads the config file again, which is the only command
+    fn run_command(&mut self, _command: &str) -> AppResult<()> {
+        self.setup()
+    }
+
+    fn handle_events(&mut self, event: Event) -> AppResult<()> {
+        let _ = handle_key_events(event.clone(), |key_event, _| {
+            match get_action(&key_event) {
+                Some(Action::ScrollUp) => self.scroll = self.scroll.saturating_sub(1),
+                Some(Action::ScrollDown) => self.scroll = self.scroll.saturating_add(1),
+                _ => (),
+            }
+            Ok(())
+        });
+
+        handle_mouse_event(event, |_, kind| {
+            match kind {
+                MouseEventKind::ScrollUp => self.scroll = self.scroll.saturating_sub(1),
+                MouseEventKind::ScrollDown => self.scroll = self.scroll.saturating_add(1),
+                _ => (),
+            }
+            Ok(())
+        })
+    }
+}
+
+impl WidgetRef for ConfigWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let title = get_flag!(config_path).display().to_string();
+        ui!((area, buf) => {
+            Paragraph::new(
+                self.contents.as_str(),
+                block: Block::bordered(
+                    title: title,
+                    border_style: theme().border,
+                    border_set: theme().symbols.border
+                ),
+                scroll: (self.scroll, 0)
+            )
+        });
+    }
+}
+
+#[derive(Parser, Clone)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ConfigCommand {
+    /// Checks flint.toml for configuration that's never read: plugins that aren't
+    /// installed, keys the plugins don't have and keys shadowed by other keys
+    Lint,
+}
+
+/// Runs `flint config`
+pub fn run(args: ConfigArgs) -> AppResult<()> {
+    match args.command {
+        ConfigCommand::Lint => lint(),
+    }
+}
+
+/// Lints flint.toml against the installed plugins, failing on errors and warnings.
+/// Notes, like installed plugins that aren't configured, don't fail it.
+fn lint() -> AppResult<()> {
+    let path = get_flag!(config_path);
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| app_err!("Unable to read {}: {}", path.display(), err))?;
+
+    let mut sections = Vec::new();
+    for plugin in plugin::find::list()? {
+        let options = plugin.get_options().unwrap_or_else(|err| {
+            warn!(
+                "Unable to read the options of {}: {}",
+                plugin.details.id, err
+            );
+            None
+        });
+        let [table, id] = plugin.config_keys();
+        sections.push(Section {
+            table: table.to_string(),
+            id: id.to_string(),
+            options,
+            is_plugin: true,
+        });
+    }
+    for format in ReportFormat::ALL {
+        sections.push(Section {
+            table: "report".to_string(),
+            id: format.name().to_string(),
+            options: Some(format.options()),
+            is_plugin: false,
+        });
+    }
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let problems = config_lint::lint(&contents, &sections);
+    for problem in &problems {
+        let location = match problem.line {
+            Some(line) => format!("{}:{}", file_name, line),
+            None => file_name.to_string(),
+        };
+        match problem.severity {
+            Severity::Error => error!("{}: {}", location, problem.message),
+            Severity::Warning => warn!("{}: {}", location, problem.message),
+            Severity::Note => info!("{}: {}", location, problem.message),
+        }
+    }
+
+    let failing = problems
+        .iter()
+        .filter(|problem| problem.severity > Severity::Note)
+        .count();
+    if failing > 0 {
+        return Err(app_err!("Found {} problems in {}", failing, file_name));
+    }
+    success!("{} has no dead configuration", file_name);
+    Ok(())
+}
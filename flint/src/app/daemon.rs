@@ -0,0 +1,447 @@
+use clap::{Parser, Subcommand};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use super::AppResult;
+use flint_core::get_flag;
+
+/// The socket the daemon listens on, relative to the project root
+pub const SOCKET_FILE: &str = ".flint/daemon.sock";
+
+#[derive(Parser, Clone)]
+pub struct DaemonArgs {
+    #[command(subcommand)]
+    pub command: Option<DaemonCommand>,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum DaemonCommand {
+    /// Shows whether the daemon of the project is running
+    Status,
+    /// Stops the daemon of the project
+    Stop,
+}
+
+pub fn socket_path() -> PathBuf {
+    get_flag!(current_dir).join(SOCKET_FILE)
+}
+
+/// Runs `flint daemon`, which serves requests until it's stopped
+#[cfg(unix)]
+pub fn run(args: DaemonArgs) -> AppResult<()> {
+    use flint_core::{info, success};
+
+    match args.command {
+        None => server::serve(),
+        Some(DaemonCommand::Status) => {
+            let status = client::call("status", Value::Null)?;
+            info!(
+                "The daemon (pid {}) has been running for {}s and finished {} runs",
+                status["pid"], status["uptime_secs"], status["runs"]
+            );
+            Ok(())
+        }
+        Some(DaemonCommand::Stop) => {
+            client::call("shutdown", Value::Null)?;
+            success!("Stopped the daemon");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn run(_args: DaemonArgs) -> AppResult<()> {
+    Err(flint_core::app_err!(
+        "The daemon listens on a Unix socket, which this platform doesn't have"
+    ))
+}
+
+/// Runs a command like `["test", "--lint"]` in the daemon of the project and prints
+/// its logs. Returns whether the run failed, or None if no daemon is running.
+#[cfg(unix)]
+pub fn forward(args: &[String]) -> Option<bool> {
+    let stream = client::connect()?;
+    // Paths on the command line are relative to where flint was run
+    let cwd = std::env::current_dir().ok()?;
+    match client::send(stream, "run", json!({ "args": args, "cwd": cwd })) {
+        Ok(result) => {
+            // Like without the daemon, logs go to stderr when findings are formatted
+            let output = result["output"].as_str();
+            for log in result["logs"].as_array().into_iter().flatten() {
+                let log = log.as_str().unwrap_or_default();
+                if output.is_some() {
+                    eprintln!("{}", log);
+                } else {
+                    println!("{}", log);
+                }
+            }
+            if let Some(output) = output {
+                print!("{}", output);
+            }
+            Some(result["failed"].as_bool().unwrap_or(false))
+        }
+        Err(err) => {
+            flint_core::warn!("Running without the daemon, as it failed: {}", err);
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn forward(_args: &[String]) -> Option<bool> {
+    None
+}
+
+/// Connects to the daemon and sends it requests. Messages are JSON-RPC 2.0, one per line.
+#[cfg(unix)]
+mod client {
+    use serde_json::{json, Value};
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    use super::socket_path;
+    use crate::app::AppResult;
+    use flint_core::app_err;
+
+    /// Connects to the daemon of the project, if it's running
+    pub fn connect() -> Option<UnixStream> {
+        UnixStream::connect(socket_path()).ok()
+    }
+
+    /// Sends a request to the daemon of the project, failing if it isn't running
+    pub fn call(method: &str, params: Value) -> AppResult<Value> {
+        let stream = connect().ok_or_else(|| app_err!("The daemon isn't running"))?;
+        send(stream, method, params)
+    }
+
+    /// Sends a request and waits for its response
+    pub fn send(mut stream: UnixStream, method: &str, params: Value) -> AppResult<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        writeln!(stream, "{}", request)?;
+
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line)?;
+        let response: Value = serde_json::from_str(&line)
+            .map_err(|err| app_err!("The daemon sent an invalid response: {}", err))?;
+
+        match response.get("error") {
+            Some(error) => Err(app_err!(
+                "{}",
+                error["message"].as_str().unwrap_or("The request failed")
+            )),
+            None => Ok(response["result"].clone()),
+        }
+    }
+}
+
+/// Listens on the socket of the project and runs the requests of its clients
+#[cfg(unix)]
+mod server {
+    use clap::Parser;
+    use serde_json::{json, Value};
+    use std::fs;
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::sync::{Arc, Mutex, PoisonError};
+    use std::thread;
+    use std::time::Instant;
+    use threadpool::ThreadPool;
+
+    use super::{client, socket_path};
+    use crate::app::{AppArgs, AppResult, AppWidgetArgs};
+    use crate::report::{lint_findings, Report};
+    use crate::util::flags::{set_command_flags, SavedFlags};
+    use flint_core::events::{open_channel, send_event, WorkerEvent};
+    use flint_core::logs::LogKind;
+    use flint_core::plugin::find;
+    use flint_core::{app_err, error, get_flag, info, set_flag, success};
+
+    const PARSE_ERROR: i64 = -32700;
+    const METHOD_NOT_FOUND: i64 = -32601;
+    const INVALID_PARAMS: i64 = -32602;
+
+    /// A JSON-RPC response, with the result of a request or an error
+    fn response(id: Value, result: Result<Value, (i64, String)>) -> Value {
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        }
+    }
+
+    struct Daemon {
+        started: Instant,
+        runs: AtomicUsize,
+        /// Counts the logs marking the end of the events of a run
+        markers: AtomicUsize,
+        /// Runs happen one at a time, as they share the global flags
+        run_lock: Mutex<()>,
+        /// Where the worker events of the current run are forwarded to
+        run_events: Mutex<Option<Sender<WorkerEvent>>>,
+        /// The last run of every plugin, which diagnostics are looked up in
+        report: Mutex<Report>,
+        /// The connections streaming the logs
+        log_streams: Mutex<Vec<Sender<String>>>,
+    }
+
+    pub fn serve() -> AppResult<()> {
+        if client::connect().is_some() {
+            return Err(app_err!("The daemon of this project is already running"));
+        }
+
+        // A socket is left behind by a daemon that was killed
+        let path = socket_path();
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+
+        // Plugins are found once, and the VM pool keeps their VMs between runs
+        let plugins = find::list()?;
+        info!("Found {} plugins", plugins.len());
+
+        let daemon = Arc::new(Daemon {
+            started: Instant::now(),
+            runs: AtomicUsize::new(0),
+            markers: AtomicUsize::new(0),
+            run_lock: Mutex::new(()),
+            run_events: Mutex::new(None),
+            report: Mutex::new(Report::default()),
+            log_streams: Mutex::new(Vec::new()),
+        });
+
+        let events = open_channel();
+        let dispatcher = Arc::clone(&daemon);
+        thread::spawn(move || dispatcher.dispatch(events));
+
+        success!("Listening on {}", path.display());
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let daemon = Arc::clone(&daemon);
+                    thread::spawn(move || daemon.handle_connection(stream));
+                }
+                Err(err) => error!("Failed to accept a connection: {}", err),
+            }
+        }
+        Ok(())
+    }
+
+    impl Daemon {
+        /// Hands the worker events to the current run and the report, and the logs
+        /// to the connections streaming them
+        fn dispatch(&self, events: Receiver<WorkerEvent>) {
+            for event in events {
+                match &event {
+                    WorkerEvent::Log(_, log) => {
+                        let message = json!({
+                            "jsonrpc": "2.0",
+                            "method": "log",
+                            "params": { "message": log },
+                        });
+                        self.log_streams
+                            .lock()
+                            .unwrap()
+                            .retain(|stream| stream.send(message.to_string()).is_ok());
+                    }
+                    _ => self.report.lock().unwrap().handle_event(&event),
+                }
+
+                if let Some(run_events) = self.run_events.lock().unwrap().as_ref() {
+                    let _ = run_events.send(event);
+                }
+            }
+        }
+
+        fn handle_connection(&self, stream: UnixStream) {
+            let Ok(reader) = stream.try_clone() else {
+                return;
+            };
+            let mut writer = stream;
+
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    return;
+                };
+                let request = match serde_json::from_str::<Value>(&line) {
+                    Ok(request) => request,
+                    Err(err) => {
+                        let error = Err((PARSE_ERROR, err.to_string()));
+                        let _ = writeln!(writer, "{}", response(Value::Null, error));
+                        continue;
+                    }
+                };
+
+                let id = request["id"].clone();
+                let params = &request["params"];
+                let result = match request["method"].as_str().unwrap_or_default() {
+                    "run" => self.run(params),
+                    "diagnostics" => Ok(self.diagnostics(params)),
+                    "status" => Ok(self.status()),
+                    "logs" => {
+                        let _ = writeln!(writer, "{}", response(id, Ok(Value::Null)));
+                        self.stream_logs(writer);
+                        return;
+                    }
+                    "shutdown" => {
+                        let _ = writeln!(writer, "{}", response(id, Ok(Value::Null)));
+                        let _ = fs::remove_file(socket_path());
+                        info!("Stopping the daemon");
+                        std::process::exit(0);
+                    }
+                    method => Err((METHOD_NOT_FOUND, format!("{} isn't supported", method))),
+                };
+
+                if writeln!(writer, "{}", response(id, result)).is_err() {
+                    return;
+                }
+            }
+        }
+
+        /// Runs a command given like on the command line, like `["lint", "--changed"]`,
+        /// with the global flags it gives, like `--config-path`, set for the run
+        fn run(&self, params: &Value) -> Result<Value, (i64, String)> {
+            let args = params["args"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|arg| arg.as_str())
+                .collect::<Vec<_>>();
+            let app_args = AppArgs::try_parse_from(["flint"].into_iter().chain(args))
+                .map_err(|err| (INVALID_PARAMS, err.to_string()))?;
+            let sarif_path = app_args.sarif_path().map(Path::to_path_buf);
+            let lint_format = app_args.lint_format();
+            let cwd = params["cwd"]
+                .as_str()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| get_flag!(current_dir).clone());
+
+            // Installed plugins are only found once the daemon is restarted
+            let widget = match app_args.command.clone() {
+                Some(AppWidgetArgs::Install(_) | AppWidgetArgs::Update(_)) => None,
+                command => command.and_then(AppWidgetArgs::into_non_interactive_widget),
+            };
+            let Some(mut widget) = widget else {
+                return Err((INVALID_PARAMS, "The daemon can't run this command".into()));
+            };
+
+            // A run that panicked doesn't keep the next ones from running
+            let _running = self.run_lock.lock().unwrap_or_else(PoisonError::into_inner);
+            // Set back once the run is done, even if it fails
+            let _flags = SavedFlags::save();
+            set_command_flags(&app_args, &cwd);
+            set_flag!(non_interactive, true);
+            set_flag!(logs_on_stderr, lint_format.is_some());
+            let failure_threshold = app_args.failure_threshold();
+
+            let (sender, receiver) = mpsc::channel();
+            *self.run_events.lock().unwrap() = Some(sender);
+
+            let thread_pool = ThreadPool::new(16);
+            widget.set_thread_pool(&thread_pool);
+            let setup = widget.setup();
+            thread_pool.join();
+
+            let mut report = Report::default();
+            let mut logs = Vec::new();
+            self.wait_for_events(&receiver, &mut report, &mut logs);
+
+            let mut has_failed = setup.is_err();
+            let mut output = None;
+            if report.is_finished() {
+                has_failed |= report.write().current.has_failed(failure_threshold);
+                report.log_vulnerabilities();
+                if let Some(path) = sarif_path {
+                    report.write_sarif(&path);
+                }
+                output = lint_format.map(|format| format.render(&report));
+                self.wait_for_events(&receiver, &mut report, &mut logs);
+            }
+            *self.run_events.lock().unwrap() = None;
+            self.runs.fetch_add(1, Ordering::Relaxed);
+
+            Ok(json!({ "failed": has_failed, "logs": logs, "output": output }))
+        }
+
+        /// Collects the events sent so far. A log is sent after them and waited for,
+        /// as events of finished workers can still be on their way.
+        fn wait_for_events(
+            &self,
+            receiver: &Receiver<WorkerEvent>,
+            report: &mut Report,
+            logs: &mut Vec<String>,
+        ) {
+            let marker = format!(
+                "Finished the events of the run ({})",
+                self.markers.fetch_add(1, Ordering::Relaxed)
+            );
+            send_event(WorkerEvent::Log(LogKind::Debug, marker.clone()));
+
+            for event in receiver.iter() {
+                match event {
+                    WorkerEvent::Log(_, log) if log == marker => return,
+                    WorkerEvent::Log(_, log) => logs.push(log),
+                    event => report.handle_event(&event),
+                }
+            }
+        }
+
+        /// The lint findings of the last runs, of a file if one is given
+        fn diagnostics(&self, params: &Value) -> Value {
+            let current_dir = get_flag!(current_dir).clone();
+            let file = params["file"].as_str().map(|file| current_dir.join(file));
+
+            let report = self.report.lock().unwrap();
+            let diagnostics = lint_findings(&report)
+                .into_iter()
+                .filter(|(file_name, _)| {
+                    file.as_ref()
+                        .is_none_or(|file| *file == current_dir.join(file_name))
+                })
+                .flat_map(|(_, findings)| findings)
+                .map(|(plugin, finding)| {
+                    json!({
+                        "plugin": plugin,
+                        "file": finding.file_name,
+                        "line": finding.line_no,
+                        "column": finding.column_no,
+                        "severity": finding.severity.as_deref().unwrap_or("error"),
+                        "rule": finding.rule_id,
+                        "message": finding.error_message,
+                    })
+                })
+                .collect::<Vec<_>>();
+            Value::Array(diagnostics)
+        }
+
+        fn status(&self) -> Value {
+            json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "pid": std::process::id(),
+                "project": get_flag!(current_dir),
+                "uptime_secs": self.started.elapsed().as_secs(),
+                "runs": self.runs.load(Ordering::Relaxed),
+            })
+        }
+
+        /// Sends every log to the connection as a notification, until it's closed
+        fn stream_logs(&self, mut writer: UnixStream) {
+            let (sender, receiver) = mpsc::channel();
+            self.log_streams.lock().unwrap().push(sender);
+            for message in receiver {
+                if writeln!(writer, "{}", message).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
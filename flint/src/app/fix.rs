@@ -0,0 +1,60 @@
+use super::app::load_config;
+use crate::report::{lint_findings, Report};
+use crate::widgets::diff::DiffWidget;
+use crate::widgets::modal::Modal;
+use flint_core::fix::FixPlan;
+use flint_core::{error, info, success};
+
+/// Plans the fixes of the lint findings in the report, leaving out the rules in
+/// `[flint.fix] skip_rules`
+pub fn plan(report: &Report) -> FixPlan {
+    let skip_rules = load_config()
+        .and_then(|config| config.flint.fix)
+        .map(|fix| fix.skip_rules)
+        .unwrap_or_default();
+    FixPlan::new(lint_findings(report).into_values().flatten(), &skip_rules)
+}
+
+/// What the fixes change, like "3 fixes in 2 files"
+fn summary(plan: &FixPlan) -> String {
+    let skipped: usize = plan.files.iter().map(|file| file.skipped).sum();
+    let mut summary = format!("{} fixes in {} files", plan.fixes(), plan.files.len());
+    if skipped > 0 {
+        summary += &format!(", {} overlapping fixes left for another run", skipped);
+    }
+    summary
+}
+
+/// Asks whether to apply the fixes, showing the changes they make to every file
+pub fn confirm(plan: &FixPlan) -> Modal {
+    let mut diff: Option<DiffWidget> = None;
+    for file in &plan.files {
+        match &mut diff {
+            Some(diff) => diff.push(file.name(), file.diff()),
+            None => diff = Some(DiffWidget::new(file.name(), file.diff())),
+        }
+    }
+
+    let modal = Modal::confirm("Apply fixes", format!("Apply {}?", summary(plan)));
+    match diff {
+        Some(diff) => modal.with_diff(diff),
+        None => modal,
+    }
+}
+
+pub fn apply(plan: &FixPlan) {
+    match plan.apply() {
+        Ok(()) => success!("Applied {}", summary(plan)),
+        Err(err) => error!("Didn't apply the fixes: {}", err),
+    }
+}
+
+/// Prints the changes the fixes would make as unified diffs, for `--fix-dry-run`
+pub fn print_dry_run(plan: &FixPlan) {
+    for file in &plan.files {
+        println!("--- a/{}", file.name());
+        println!("+++ b/{}", file.name());
+        print!("{}", file.diff());
+    }
+    info!("Would apply {}", summary(plan));
+}
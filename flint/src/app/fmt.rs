@@ -0,0 +1,84 @@
+use clap::Parser;
+use std::sync::Arc;
+
+use super::AppResult;
+use flint_core::config::Config;
+use flint_core::plugin::{self, PluginKind};
+use flint_core::{app_err, error, get_flag, info, run_formatter, success, warn};
+
+#[derive(Parser, Clone)]
+pub struct FmtArgs {
+    /// Check that the files are formatted instead of formatting them, failing if any
+    /// aren't, like in CI
+    #[clap(long)]
+    check: bool,
+}
+
+/// Runs `flint fmt`, formatting the project with the formatter plugins in `[fmt]`
+pub fn run(args: FmtArgs) -> AppResult<()> {
+    let config = Arc::new(Config::load(get_flag!(config_path))?);
+    let plugins = plugin::list_from_config(&config)?
+        .into_iter()
+        .filter(|plugin| plugin.kind == PluginKind::Fmt)
+        .collect::<Vec<_>>();
+    if plugins.is_empty() {
+        info!("No formatter plugins are configured in the fmt section of flint.toml");
+        return Ok(());
+    }
+
+    let mut failed = Vec::new();
+    let mut files = 0;
+    // One at a time, as formatters of overlapping files would race to rewrite them
+    for plugin in &plugins {
+        let id = &plugin.details.id;
+        let problems = plugin.tool_problems();
+        for problem in &problems {
+            match problem.is_missing {
+                true => error!("{}", problem),
+                false => warn!("{}", problem),
+            }
+        }
+        if problems.iter().any(|problem| problem.is_missing) {
+            failed.push(id.as_str());
+            continue;
+        }
+
+        let output = match run_formatter(plugin, &config, args.check) {
+            Ok(output) => output,
+            Err(err) => {
+                error!("{} failed: {}", id, err);
+                failed.push(id.as_str());
+                continue;
+            }
+        };
+
+        let changed = output
+            .test_results
+            .iter()
+            .filter(|result| !result.success)
+            .collect::<Vec<_>>();
+        for result in &changed {
+            match args.check {
+                true => warn!("{} isn't formatted", result.file_name),
+                false => info!("Formatted {}", result.file_name),
+            }
+        }
+        files += changed.len();
+    }
+
+    if !failed.is_empty() {
+        return Err(app_err!("Unable to run {}", failed.join(", ")));
+    }
+    match (args.check, files) {
+        (true, 0) => success!("Every file is formatted"),
+        (true, files) => {
+            return Err(app_err!(
+                "{} files aren't formatted, run flint fmt to format them",
+                files
+            ))
+        }
+        (false, 0) => success!("Every file was already formatted"),
+        (false, files) => success!("Formatted {} files", files),
+    }
+    Ok(())
+}
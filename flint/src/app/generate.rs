@@ -1,17 +1,38 @@
 use super::{AppResult, AppWidget};
-use crate::{error, info};
 use crate::{
-    get_flag,
-    plugin::{self, Plugin},
-    success,
-    util::toml::Config,
-    widgets::logs::LogsWidget,
+    util::layout::{pane_constraints, pane_direction},
+    widgets::{
+        diff::DiffWidget,
+        logs::LogsWidget,
+        modal::{self, Modal, ModalResult},
+        plugin_errors::PluginErrors,
+        progress::TaskProgress,
+        tool_problems::ToolProblems,
+    },
 };
 use clap::Parser;
+use crossterm::event::Event;
+use flint_core::{
+    cancel::is_cancelled,
+    config::Config,
+    events::{Task, Worker, WorkerEvent},
+    get_flag, info,
+    logs::LogKind,
+    plugin::{
+        self,
+        helpers::emit::FileContents,
+        inputs::{self, GenerateRecord},
+        Plugin,
+    },
+    success, warn,
+};
 use flint_macros::ui;
 use ratatui::prelude::*;
 use ratatui::widgets::WidgetRef;
-use std::{fs, sync::Arc};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
 use threadpool::ThreadPool;
 
 #[allow(unused)]
@@ -19,14 +40,63 @@ pub struct GenerateWidget {
     plugins: Vec<Plugin>,
     thread_pool: Option<ThreadPool>,
     logs_widget: LogsWidget,
+    progress: TaskProgress,
+    tool_problems: ToolProblems,
+    plugin_errors: PluginErrors,
     args: GenerateWidgetArgs,
 }
 
-#[derive(Parser, Clone)]
+#[derive(Parser, Clone, Default)]
 pub struct GenerateWidgetArgs {
     /// Show help for the generate command
     #[clap(short, long)]
     help: bool,
+
+    /// Show the progress in the terminal's scrollback instead of taking over the screen
+    #[clap(long)]
+    pub inline: bool,
+
+    /// Check that the generated configs are up to date instead of writing them
+    #[clap(long)]
+    pub check: bool,
+
+    /// Generate every config, even the ones whose inputs haven't changed since they
+    /// were last generated
+    #[clap(long)]
+    pub force: bool,
+
+    /// Only generate the configs of the CI plugins, like `flint ci`
+    #[clap(skip)]
+    pub ci: bool,
+}
+
+/// The arguments of `flint ci`, which generates the CI workflows configured in `[ci]`
+#[derive(Parser, Clone, Default)]
+pub struct CiArgs {
+    /// Show help for the ci command
+    #[clap(short, long)]
+    help: bool,
+
+    /// Check that the CI workflows are up to date instead of writing them
+    #[clap(long)]
+    pub check: bool,
+
+    /// Generate the workflows, even if their inputs haven't changed since they were
+    /// last generated
+    #[clap(long)]
+    pub force: bool,
+}
+
+impl From<CiArgs> for GenerateWidgetArgs {
+    fn from(args: CiArgs) -> Self {
+        Self {
+            help: args.help,
+            check: args.check,
+            force: args.force,
+            ci: true,
+            ..Self::default()
+        }
+    }
 }
 
 impl GenerateWidget {
@@ -34,50 +104,146 @@ impl GenerateWidget {
         Self {
             plugins: Vec::new(),
             thread_pool: None,
-            logs_widget: LogsWidget::default(),
+            logs_widget: LogsWidget,
+            progress: TaskProgress::new(Task::Generate),
+            tool_problems: ToolProblems::new(Task::Generate),
+            plugin_errors: PluginErrors::new(Task::Generate),
             args,
         }
     }
+
+    /// The plugins configs are generated for, once the widget is set up
+    pub fn plugins(&self) -> &[Plugin] {
+        &self.plugins
+    }
+
+    pub fn progress(&self) -> &TaskProgress {
+        &self.progress
+    }
 }
 
 impl AppWidget for GenerateWidget {
     fn setup(&mut self) -> AppResult<()> {
         let config_path = get_flag!(config_path);
-        let toml = Arc::new(Config::load(config_path).unwrap());
+        let toml = Arc::new(Config::load(config_path)?);
         let mut plugin_ids = Vec::new();
-        plugin_ids.extend(toml.rules.keys());
-        plugin_ids.extend(toml.tests.keys());
-        plugin_ids.extend(toml.ci.keys());
+        if !self.args.ci {
+            plugin_ids.extend(toml.rules.keys());
+            plugin_ids.extend(toml.tests.keys());
+            plugin_ids.extend(toml.fmt.keys());
+        }
+        plugin_ids.extend(toml.ci.plugin_ids());
 
         self.plugins = plugin::list()
             .unwrap()
-            .into_iter()
+            .iter()
             .filter(|plugin| plugin_ids.contains(&&plugin.details.id))
             .cloned()
             .collect();
 
+        let record = Arc::new(Mutex::new(GenerateRecord::load()));
         for plugin in &self.plugins {
             let plugin = plugin.clone();
             let toml_clone = toml.clone();
+            let check = self.args.check;
+            let force = self.args.force;
+            let record = record.clone();
             let pool = self.thread_pool.as_ref().unwrap();
 
             pool.execute(move || {
+                let worker = Worker::new(Task::Generate, &plugin);
+                // Configs are generated without the tools, but can't be used until they're installed
+                if !check {
+                    for problem in plugin.tool_problems() {
+                        worker.tool_problem(LogKind::Warn, problem);
+                    }
+                }
+
+                // Checks compare every config, so they never skip any
+                let fingerprint = match inputs::fingerprint(&plugin, &toml_clone) {
+                    Ok(fingerprint) => fingerprint,
+                    Err(err) => {
+                        warn!(
+                            "Couldn't read the inputs of {}, so its config is generated again: {}",
+                            plugin.details.id, err
+                        );
+                        None
+                    }
+                };
+                if let Some(fingerprint) = fingerprint.as_deref().filter(|_| !check && !force) {
+                    if record
+                        .lock()
+                        .unwrap()
+                        .is_up_to_date(&plugin.details.id, fingerprint)
+                    {
+                        info!(
+                            "{} config is up to date, as its inputs haven't changed",
+                            plugin.details.id
+                        );
+                        return;
+                    }
+                }
+
+                if is_cancelled() {
+                    return;
+                }
+
                 let result = plugin.generate(&toml_clone);
                 info!("Generating {} config", plugin.details.id);
                 match result {
                     Ok(res) => {
-                        // TODO: Ask user if we want to overwrite files
-                        let flint_path = get_flag!(current_dir);
+                        let flint_path = get_flag!(current_dir).clone();
+                        let mut generated = Vec::new();
+                        let mut skipped = false;
                         for (file_name, contents) in res {
-                            fs::create_dir_all(&flint_path).unwrap();
-                            std::fs::write(flint_path.join(file_name), contents).unwrap();
+                            let file_path = flint_path.join(&file_name);
+                            if check {
+                                check_file(&worker, &file_name, &file_path, &contents);
+                                continue;
+                            }
+
+                            // Files aren't written once flint is quitting
+                            if is_cancelled() {
+                                return;
+                            }
+                            if file_path.exists()
+                                && !confirm_overwrite(&file_name, &file_path, &contents)
+                            {
+                                info!("Skipped generating {}", file_name);
+                                skipped = true;
+                                continue;
+                            }
+
+                            // Configs like CI workflows are written to their own directories
+                            if let Err(err) = contents.write_to(&file_path) {
+                                worker.diagnostic(
+                                    LogKind::Error,
+                                    format!("Error while writing {}: {}", file_name, err),
+                                );
+                                return;
+                            }
+                            worker.file_written(file_path);
+                            generated.push(file_name);
+                        }
+                        if !check {
+                            success!("Generated {} config successfully", plugin.details.id);
+
+                            // A config that wasn't overwritten is asked about again next time
+                            let fingerprint = fingerprint.filter(|_| !skipped);
+                            let mut record = record.lock().unwrap();
+                            record.record(&plugin.details.id, fingerprint, &generated);
+                            if let Err(err) = record.write() {
+                                warn!("Couldn't record the generated configs: {}", err);
+                            }
                         }
-                        success!("Generated {} config successfully", plugin.details.id)
                     }
                     Err(err) => {
-                        error!(
-                            "Error while generating {} config: {}",
-                            plugin.details.id, err
+                        worker.diagnostic(
+                            LogKind::Error,
+                            format!(
+                                "Error while generating {} config: {}",
+                                plugin.details.id, err
+                            ),
                         );
                     }
                 }
@@ -90,12 +256,92 @@ impl AppWidget for GenerateWidget {
     fn set_thread_pool(&mut self, thread_pool: &ThreadPool) {
         self.thread_pool = Some(thread_pool.clone())
     }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["Run generate"]
+    }
+
+    /// Generates the configs again, which is the only command
+    fn run_command(&mut self, _command: &str) -> AppResult<()> {
+        self.setup()
+    }
+
+    fn handle_worker_event(&mut self, event: &WorkerEvent) {
+        self.progress.handle_event(event);
+        self.tool_problems.handle_event(event);
+        self.plugin_errors.handle_event(event);
+    }
+
+    fn handle_tick(&mut self, tick: usize) {
+        self.progress.handle_tick(tick);
+    }
+
+    fn handle_events(&mut self, event: Event) -> AppResult<()> {
+        self.progress.handle_input(&event);
+        Ok(())
+    }
+}
+
+/// Reports a file that doesn't match the config it's generated from as an error,
+/// which fails the plugin
+fn check_file(worker: &Worker, file_name: &str, file_path: &Path, contents: &FileContents) {
+    match contents.is_written_to(file_path) {
+        Ok(true) => success!("{} is up to date", file_name),
+        Ok(false) => {
+            worker.diagnostic(
+                LogKind::Error,
+                format!(
+                    "{} is out of date, run flint generate to update it",
+                    file_name
+                ),
+            );
+            // Shows how the file drifted from the config, like a formatter's check does
+            if let Some(diff) = contents.diff_from(file_path) {
+                info!(
+                    "Generating {} would change it:\n{}",
+                    file_name,
+                    diff.to_string().trim_end()
+                );
+            }
+        }
+        Err(_) => worker.diagnostic(
+            LogKind::Error,
+            format!("{} is missing, run flint generate to create it", file_name),
+        ),
+    }
+}
+
+/// Asks whether an existing file should be overwritten, showing what overwriting it
+/// changes. Files are overwritten without asking if there's no UI to ask in, or if
+/// overwriting them changes nothing.
+fn confirm_overwrite(file_name: &str, file_path: &Path, contents: &FileContents) -> bool {
+    let mut modal = Modal::confirm(
+        "Overwrite file",
+        format!("{} already exists. Overwrite it?", file_name),
+    );
+    if let Some(diff) = contents.diff_from(file_path) {
+        if !diff.has_changes() {
+            return true;
+        }
+        modal = modal.with_diff(DiffWidget::new(file_name, diff));
+    }
+
+    let answer = modal::ask(modal);
+    matches!(answer, None | Some(ModalResult::Confirmed(true)))
 }
 
 impl WidgetRef for GenerateWidget {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let area = self.tool_problems.render_above(area, buf);
+        let area = self.plugin_errors.render_below(area, buf);
         ui!((area, buf) => {
-            { self.logs_widget }
+            Layout(
+                direction: pane_direction(area),
+                constraints: pane_constraints(area, self.progress.width(), self.progress.height())
+            ) {
+                { &self.progress },
+                { self.logs_widget }
+            }
         });
     }
 }
@@ -2,15 +2,9 @@ use super::AppWidget;
 use ratatui::prelude::*;
 use ratatui::widgets::WidgetRef;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct HelpWidget {}
 
-impl Default for HelpWidget {
-    fn default() -> Self {
-        Self {}
-    }
-}
-
 impl AppWidget for HelpWidget {}
 
 impl WidgetRef for HelpWidget {
@@ -0,0 +1,189 @@
+use clap::{Parser, Subcommand};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::AppResult;
+use flint_core::{app_err, get_flag, info, success};
+
+/// The line that marks hooks installed by flint, so they're the only ones uninstalled
+const MARKER: &str = "# Installed by flint hooks install";
+
+/// The extension of hooks that were replaced with `--force`, which are restored on uninstall
+const BACKUP_EXTENSION: &str = "flint-backup";
+
+/// Config files of hook managers, which run the hooks instead of git
+const HOOK_MANAGERS: [(&str, &str); 6] = [
+    (".husky", "husky"),
+    (".pre-commit-config.yaml", "pre-commit"),
+    ("lefthook.yml", "lefthook"),
+    (".lefthook.yml", "lefthook"),
+    ("lefthook.yaml", "lefthook"),
+    (".overcommit.yml", "overcommit"),
+];
+
+#[derive(Parser, Clone)]
+pub struct HooksArgs {
+    #[command(subcommand)]
+    pub command: HooksCommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum HooksCommand {
+    /// Installs a git hook that checks the generated configs and lints the changes
+    Install(InstallHookArgs),
+    /// Removes the git hooks installed by flint
+    Uninstall,
+}
+
+#[derive(Parser, Clone)]
+pub struct InstallHookArgs {
+    /// Install a pre-push hook linting the branch, instead of a pre-commit hook
+    /// linting the staged files
+    #[clap(long)]
+    pre_push: bool,
+
+    /// Install even if a hook manager is used, replacing an existing hook. The
+    /// replaced hook is restored when uninstalling.
+    #[clap(long)]
+    force: bool,
+}
+
+/// Runs `flint hooks`
+pub fn run(args: HooksArgs) -> AppResult<()> {
+    let hooks_dir = hooks_dir()?;
+    match args.command {
+        HooksCommand::Install(args) => install(&hooks_dir, args),
+        HooksCommand::Uninstall => uninstall(&hooks_dir),
+    }
+}
+
+fn install(hooks_dir: &Path, args: InstallHookArgs) -> AppResult<()> {
+    if let Some(manager) = hook_manager() {
+        if !args.force {
+            return Err(app_err!(
+                "Hooks are managed by {}, add `{}` to its config or install with --force",
+                manager,
+                hook_command(args.pre_push)
+            ));
+        }
+    }
+
+    let name = if args.pre_push {
+        "pre-push"
+    } else {
+        "pre-commit"
+    };
+    let path = hooks_dir.join(name);
+    if path.exists() && !is_installed_by_flint(&path) {
+        if !args.force {
+            return Err(app_err!(
+                "{} already exists, install with --force to replace it",
+                path.display()
+            ));
+        }
+        fs::rename(&path, path.with_extension(BACKUP_EXTENSION))?;
+        info!("Moved the existing {} hook aside", name);
+    }
+
+    fs::create_dir_all(hooks_dir)?;
+    fs::write(&path, script(args.pre_push))?;
+    make_executable(&path)?;
+    success!("Installed the {} hook to {}", name, path.display());
+    Ok(())
+}
+
+fn uninstall(hooks_dir: &Path) -> AppResult<()> {
+    let mut uninstalled = false;
+    for name in ["pre-commit", "pre-push"] {
+        let path = hooks_dir.join(name);
+        if !is_installed_by_flint(&path) {
+            continue;
+        }
+
+        fs::remove_file(&path)?;
+        let backup = path.with_extension(BACKUP_EXTENSION);
+        if backup.exists() {
+            fs::rename(&backup, &path)?;
+            info!("Restored the {} hook flint replaced", name);
+        }
+        success!("Uninstalled the {} hook", name);
+        uninstalled = true;
+    }
+
+    if !uninstalled {
+        info!("No hooks installed by flint were found");
+    }
+    Ok(())
+}
+
+/// Checks the generated configs, then lints the staged files before a commit or the
+/// branch before a push
+fn hook_command(pre_push: bool) -> String {
+    let files = if pre_push { "--changed" } else { "--staged" };
+    format!("flint generate --check && flint lint {}", files)
+}
+
+fn script(pre_push: bool) -> String {
+    format!(
+        "#!/bin/sh\n{}. Remove it with flint hooks uninstall.\n\n{}\n",
+        MARKER,
+        hook_command(pre_push)
+    )
+}
+
+fn is_installed_by_flint(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|contents| contents.contains(MARKER))
+}
+
+/// The directory git runs hooks from, which may be moved by `core.hooksPath`
+fn hooks_dir() -> AppResult<PathBuf> {
+    let current_dir = get_flag!(current_dir);
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .current_dir(current_dir)
+        .output()?;
+    if !output.status.success() {
+        return Err(app_err!(
+            "{} isn't in a git repository",
+            current_dir.display()
+        ));
+    }
+
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(current_dir.join(path))
+}
+
+/// The hook manager used by the project, like husky, which would overwrite or ignore
+/// hooks installed by flint
+fn hook_manager() -> Option<String> {
+    let current_dir = get_flag!(current_dir);
+    if let Some((_, manager)) = HOOK_MANAGERS
+        .iter()
+        .find(|(file, _)| current_dir.join(file).exists())
+    {
+        return Some(manager.to_string());
+    }
+
+    let hooks_path = Command::new("git")
+        .args(["config", "--get", "core.hooksPath"])
+        .current_dir(current_dir)
+        .output()
+        .ok()?;
+    let hooks_path = String::from_utf8_lossy(&hooks_path.stdout)
+        .trim()
+        .to_string();
+    (!hooks_path.is_empty()).then(|| format!("core.hooksPath ({})", hooks_path))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> AppResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> AppResult<()> {
+    Ok(())
+}
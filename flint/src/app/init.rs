@@ -1,10 +1,14 @@
-use super::{AppError, AppResult, AppWidget};
-use crate::{
-    get_flag, info,
-    util::{handle_key_events, lang::Language, toml::Config},
+use super::{AppResult, AppWidget, FlintError};
+use crate::util::{
+    handle_key_events,
+    keys::{get_action, Action},
+    theme::theme,
 };
+use crate::widgets::file_picker::{FilePicker, PickKind};
+use crate::widgets::text_input::TextInput;
 use clap::Parser;
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use flint_core::{config::Config, get_flag, info, lang::Language};
 use flint_macros::{ui, widget as w};
 use ratatui::{
     layout::{Constraint, Direction, Layout},
@@ -12,15 +16,18 @@ use ratatui::{
     widgets::{Block, Paragraph, WidgetRef, Wrap},
 };
 use std::{collections::BTreeSet, path::PathBuf};
-use tui_textarea::TextArea;
 
 #[derive(Debug)]
 #[allow(unused)]
-pub struct InitWidget<'a> {
-    textarea: TextArea<'a>,
+pub struct InitWidget {
+    input: TextInput,
     langs: BTreeSet<Language>,
     created_config: bool,
     config_exists: bool,
+    /// The directory flint.toml is created in, the current one unless another is chosen
+    dir: PathBuf,
+    /// Chooses another directory, while it's open
+    picker: Option<FilePicker>,
     args: InitWidgetArgs,
 }
 
@@ -31,38 +38,86 @@ pub struct InitWidgetArgs {
     help: bool,
 }
 
-impl<'a> InitWidget<'a> {
+/// The narrowest the confirmation input gets before it's moved below its message
+const MIN_INPUT_WIDTH: u16 = 10;
+
+impl InitWidget {
     pub fn new(args: InitWidgetArgs) -> Self {
         Self {
-            textarea: TextArea::default(),
+            input: TextInput::new().with_validator(|answer| match answer {
+                "y" | "n" | "c" => Ok(()),
+                _ => Err("Answer y, n or c".to_string()),
+            }),
             langs: BTreeSet::new(),
             created_config: false,
             config_exists: false,
+            dir: PathBuf::new(),
+            picker: None,
             args,
         }
     }
+
+    /// Finds the languages in the directory flint.toml is created in, and whether
+    /// it already has one
+    fn detect(&mut self) -> AppResult<()> {
+        info!("Determined current directory: {}", self.dir.display());
+        self.langs = flint_core::detect_languages(&*self.dir.to_string_lossy())?;
+        self.config_exists = self.dir.join("flint.toml").exists();
+        Ok(())
+    }
 }
 
-impl<'a> WidgetRef for InitWidget<'a> {
+impl WidgetRef for InitWidget {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(picker) = &self.picker {
+            picker.render_ref(area, buf);
+            return;
+        }
+
+        let location = match self.dir == *get_flag!(current_dir) {
+            true => "this directory".to_string(),
+            false => self.dir.display().to_string(),
+        };
         let confirm_message = if self.config_exists {
-            "flint.toml already exists in this directory. Would you like to overwrite it? (y/n)"
+            format!(
+                "flint.toml already exists in {}. Would you like to overwrite it? (y/n, c to choose another directory)",
+                location
+            )
         } else {
-            "Would you like to continue with creating flint.toml? (y/n)"
+            format!(
+                "Would you like to continue with creating flint.toml in {}? (y/n, c to choose another directory)",
+                location
+            )
         };
 
-        let textarea = w!({
-            Layout(
-                direction: Direction::Horizontal,
-                constraints: [
-                    Constraint::Length(confirm_message.len() as u16),
+        // The message is put above the input when they don't fit on one line
+        let message_width = confirm_message.len() as u16;
+        let (direction, constraints) = if message_width + 1 + MIN_INPUT_WIDTH <= area.width {
+            (
+                Direction::Horizontal,
+                [
+                    Constraint::Length(message_width),
                     Constraint::Length(1),
-                    Constraint::Fill(1)
-                ]
-            ) {
-                Paragraph::new(confirm_message, style: Style::default().fg(Color::Yellow)),
+                    Constraint::Fill(1),
+                ],
+            )
+        } else {
+            (
+                Direction::Vertical,
+                [
+                    Constraint::Length(1),
+                    Constraint::Length(0),
+                    Constraint::Length(self.input.height()),
+                ],
+            )
+        };
+
+        let theme = theme();
+        let textarea = w!({
+            Layout(direction: direction, constraints: constraints) {
+                Paragraph::new(confirm_message, style: Style::default().fg(theme.accent)),
                 {" "},
-                {&self.textarea}
+                {&self.input}
             }
         });
 
@@ -72,7 +127,7 @@ impl<'a> WidgetRef for InitWidget<'a> {
             .map(|lang| match lang {
                 Language::Supported(name) => Line::from(name.clone()),
                 Language::Unsupported(name) => {
-                    Line::from(name.clone()).style(Style::default().fg(Color::Red).bold())
+                    Line::from(name.clone()).style(Style::default().fg(theme.error).bold())
                 }
             })
             .collect::<Vec<Line>>();
@@ -88,13 +143,13 @@ impl<'a> WidgetRef for InitWidget<'a> {
                 ) {
                     Paragraph::new(
                       languages,
-                      block: w!({ Block::bordered(title: "We found the following languages in this directory (".to_string() + &self.langs.iter().filter(|lang| matches!(lang, Language::Unsupported(_))).count().to_string() + " unsupported)") }),
+                      block: Block::bordered(title: format!("We found the following languages in {} (", location) + &self.langs.iter().filter(|lang| matches!(lang, Language::Unsupported(_))).count().to_string() + " unsupported)", border_style: theme.border, border_set: theme.symbols.border),
                       wrap: Wrap { trim: false }
                     ),
                     If (!self.created_config) {
                         {textarea}
                     } Else {
-                        Paragraph::new("Configuration created successfully. Press any key to exit.", style: w!({ Style(fg: Color::Green) }))
+                        Paragraph::new("Configuration created successfully. Press any key to exit.", style: Style(fg: theme.success))
                     }
                 },
             }
@@ -103,41 +158,61 @@ impl<'a> WidgetRef for InitWidget<'a> {
     }
 }
 
-impl<'a> AppWidget for InitWidget<'a> {
+impl AppWidget for InitWidget {
     fn setup(&mut self) -> AppResult<()> {
-        let cwd = get_flag!(current_dir);
-        info!("Determined current directory: {}", cwd.display());
-
-        self.langs = crate::util::detect_languages(cwd.to_str().unwrap());
-
-        let config_path = std::path::Path::new(&cwd).join("flint.toml");
-        if config_path.exists() {
-            self.config_exists = true;
-        }
+        self.dir = get_flag!(current_dir).clone();
+        self.detect()
+    }
 
-        Ok(())
+    fn claims_key(&self, key_event: &KeyEvent) -> bool {
+        // Every key goes to the picker while it's open, and characters are typed into
+        // the confirmation prompt while it's shown
+        self.picker.is_some()
+            || !self.created_config
+                && matches!(key_event.code, KeyCode::Char(_))
+                && !key_event.modifiers.contains(KeyModifiers::CONTROL)
     }
 
     fn handle_events(&mut self, event: Event) -> AppResult<()> {
-        handle_key_events(event, |key_event, key_code| {
+        if let Some(picker) = self.picker.as_mut() {
+            if let Event::Key(KeyEvent {
+                code: KeyCode::Esc, ..
+            }) = event
+            {
+                self.picker = None;
+            } else if let Some(dir) = picker.handle_event(&event) {
+                self.picker = None;
+                self.dir = dir;
+                self.detect()?;
+            }
+            return Ok(());
+        }
+
+        handle_key_events(event, |key_event, _| {
             if self.created_config {
-                return Err(AppError::Exit);
+                return Err(FlintError::Exit);
             }
-            match key_code {
-                KeyCode::Enter => {
-                    let input = self.textarea.lines().get(0).unwrap();
+            match get_action(&key_event) {
+                Some(Action::Confirm) => {
+                    let Some(answer) = self.input.submit() else {
+                        return Ok(());
+                    };
 
-                    match input.as_str() {
-                        "n" => return Err(AppError::Exit),
+                    match answer.as_str() {
+                        "n" => return Err(FlintError::Exit),
                         "y" => {
-                            Config::create_default(PathBuf::from("./flint.toml")).unwrap();
+                            Config::create_default(self.dir.join("flint.toml"))?;
                             self.created_config = true;
                         }
+                        "c" => {
+                            self.picker = Some(FilePicker::new(&self.dir, PickKind::Directory));
+                            self.input.clear();
+                        }
                         _ => (),
                     }
                 }
                 _ => {
-                    self.textarea.input(key_event);
+                    self.input.input(key_event);
                 }
             }
             Ok(())
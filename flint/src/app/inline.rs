@@ -0,0 +1,101 @@
+use crossterm::event::{self, Event};
+use flint_macros::ui;
+use ratatui::prelude::*;
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::DefaultTerminal;
+use std::io;
+use std::time::Duration;
+use threadpool::ThreadPool;
+
+use super::app::{load_config, load_theme};
+use super::generate::{GenerateWidget, GenerateWidgetArgs};
+use super::{AppResult, AppWidget};
+use crate::report::Report;
+use crate::util::keys::{get_action, load_keymap, Action};
+use crate::util::terminal::TerminalGuard;
+use crate::util::theme::theme;
+use flint_core::events::{open_channel, WorkerEvent};
+use flint_core::logs::{show_debug_logs, LogKind};
+use flint_core::timings::{self, Phase};
+
+/// Runs generate without taking over the screen, for `flint generate --inline`. Logs
+/// are printed to the scrollback above a compact progress display, which is left
+/// in the scrollback as a summary once every plugin finished. Existing files are
+/// overwritten without asking, as there's no room for a prompt.
+pub fn run_generate(args: GenerateWidgetArgs) -> AppResult<()> {
+    load_keymap();
+    load_theme(load_config().as_ref());
+
+    let worker_events = open_channel();
+    let thread_pool = ThreadPool::new(16);
+    let mut generate = GenerateWidget::new(args);
+    generate.set_thread_pool(&thread_pool);
+    generate.setup()?;
+
+    // A line for every plugin and the column titles, between the borders
+    let mut terminal = TerminalGuard::inline(generate.plugins().len() as u16 + 3);
+    let mut report = Report::default();
+    // Every loop takes about as long as a frame of the spinners
+    let mut tick = 0;
+    loop {
+        // Workers send all their events before they finish, so once the pool is
+        // idle the events drained below are the last ones
+        let is_done = thread_pool.active_count() == 0 && thread_pool.queued_count() == 0;
+
+        for event in worker_events.try_iter() {
+            report.handle_event(&event);
+            match event {
+                WorkerEvent::Log(kind, log) => print_log(&mut terminal, kind, log)?,
+                event => generate.handle_worker_event(&event),
+            }
+        }
+        tick += 1;
+        generate.handle_tick(tick);
+        let timing = timings::time(Phase::Render, "frame");
+        terminal.draw(|frame| frame.render_widget(generate.progress(), frame.area()))?;
+        drop(timing);
+
+        if is_done {
+            report.write();
+            // Prints the logs of writing the report, like how the run compares to the last one
+            for event in worker_events.try_iter() {
+                if let WorkerEvent::Log(kind, log) = event {
+                    print_log(&mut terminal, kind, log)?;
+                }
+            }
+            break;
+        }
+
+        // Quitting stops the plugins that are still running
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key_event) = event::read()? {
+                if get_action(&key_event) == Some(Action::Quit) {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints a log above the progress display, wrapped to the width of the terminal
+fn print_log(terminal: &mut DefaultTerminal, kind: LogKind, log: String) -> io::Result<()> {
+    if matches!(kind, LogKind::Debug) && !show_debug_logs() {
+        return Ok(());
+    }
+
+    let text = Text::from(log).style(theme().log(kind));
+    let width = terminal.size()?.width.max(1);
+    let height = text
+        .lines
+        .iter()
+        .map(|line| (line.width() as u16).div_ceil(width).max(1))
+        .sum();
+    terminal.insert_before(height, |buf| {
+        let area = buf.area;
+        ui!((area, buf) => {
+            Paragraph::new(text, wrap: Wrap { trim: false })
+        });
+    })
+}
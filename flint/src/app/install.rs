@@ -2,16 +2,18 @@ use std::cell::RefCell;
 use std::sync::mpsc::Sender;
 use std::time::Duration;
 
-use crate::plugin::download::download_plugins_from_config;
-use crate::util::toml::Config;
+use crate::util::keys::{get_action, Action};
 use crate::util::{handle_key_events, handle_mouse_event};
 use crate::widgets::logs::{LogsState, LogsWidget};
-use crate::{error, get_flag, success, warn};
 use clap::Parser;
-use crossterm::event::{KeyCode, MouseEventKind};
+use crossterm::event::MouseEventKind;
+use flint_core::cancel::is_cancelled;
+use flint_core::config::Config;
+use flint_core::plugin::download::download_plugins_from_config;
+use flint_core::{error, get_flag, success, warn};
 use threadpool::ThreadPool;
 
-use super::{AppResult, AppWidget};
+use super::{AppResult, AppWidget, FlintError};
 use flint_macros::ui;
 use ratatui::prelude::*;
 use ratatui::widgets::WidgetRef;
@@ -51,12 +53,42 @@ pub struct InstallArgs {
     /// Show logs
     #[clap(long)]
     logs: bool,
+
+    /// The plugins moved to the latest commit of their source, for `flint update`
+    #[clap(skip)]
+    update: Option<Vec<String>>,
+}
+
+/// The arguments of `flint update`, which installs the plugins like `flint install`
+/// after moving their pins in flint.lock to the latest commit of their sources
+#[derive(Parser, Debug, Clone)]
+pub struct UpdateArgs {
+    /// The plugins to update, which is all of them if none are given
+    plugins: Vec<String>,
+
+    /// Show help for the update command
+    #[clap(short, long)]
+    help: bool,
+}
+
+impl From<UpdateArgs> for InstallArgs {
+    fn from(args: UpdateArgs) -> Self {
+        Self {
+            plugins: Vec::new(),
+            lint: false,
+            test: false,
+            all: true,
+            help: args.help,
+            logs: false,
+            update: Some(args.plugins),
+        }
+    }
 }
 
 impl InstallWidget {
     pub fn new(args: InstallArgs) -> Self {
         Self {
-            logs: LogsWidget::default(),
+            logs: LogsWidget,
             pool: None,
             exit_sender: None,
             logs_state: RefCell::new(LogsState::default()),
@@ -72,13 +104,27 @@ impl AppWidget for InstallWidget {
             return Ok(());
         };
 
-        let toml = Config::load(get_flag!(config_path)).unwrap();
+        let toml = Config::load(get_flag!(config_path))?;
         let toml_clone = toml.clone();
+        let update = self.args.update.clone();
+
+        // Without the UI, the plugins are downloaded before flint exits, so scripts and
+        // CI jobs fail when they can't be, like offline without the installed plugins
+        if *get_flag!(non_interactive) {
+            download_plugins_from_config(&toml_clone, update.as_deref())
+                .map_err(FlintError::from)?;
+            log_downloaded();
+            return Ok(());
+        }
+
         let pool = self.pool.as_ref().unwrap();
         pool.execute(move || {
             std::thread::sleep(Duration::from_secs(10));
-            match download_plugins_from_config(&toml_clone) {
-                Ok(_) => success!("Plugins downloaded successfully"),
+            if is_cancelled() {
+                return;
+            }
+            match download_plugins_from_config(&toml_clone, update.as_deref()) {
+                Ok(_) => log_downloaded(),
                 Err(e) => error!("Error downloading plugins: {}", e),
             }
         });
@@ -94,19 +140,16 @@ impl AppWidget for InstallWidget {
     }
 
     fn handle_events(&mut self, event: crossterm::event::Event) -> AppResult<()> {
-        let _ = handle_key_events(event.clone(), |_, key_code| match key_code {
-            KeyCode::Up => {
-                self.logs_state.borrow_mut().scroll_up(1);
-                Ok(())
+        let _ = handle_key_events(event.clone(), |key_event, _| {
+            match get_action(&key_event) {
+                Some(Action::ScrollUp) => self.logs_state.borrow_mut().scroll_up(1),
+                Some(Action::ScrollDown) => self.logs_state.borrow_mut().scroll_down(1),
+                _ => (),
             }
-            KeyCode::Down => {
-                self.logs_state.borrow_mut().scroll_down(1);
-                Ok(())
-            }
-            _ => Ok(()),
+            Ok(())
         });
 
-        handle_mouse_event(event.clone(), |mouse_event| match mouse_event {
+        handle_mouse_event(event.clone(), |_, kind| match kind {
             MouseEventKind::ScrollUp => {
                 self.logs_state.borrow_mut().scroll_up(1);
                 Ok(())
@@ -135,3 +178,10 @@ impl WidgetRef for InstallWidget {
         });
     }
 }
+
+fn log_downloaded() {
+    match *get_flag!(offline) {
+        true => success!("Using the installed plugins"),
+        false => success!("Plugins downloaded successfully"),
+    }
+}
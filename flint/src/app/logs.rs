@@ -0,0 +1,49 @@
+use super::{AppResult, AppWidget};
+use crate::util::keys::{get_action, Action};
+use crate::util::{handle_key_events, handle_mouse_event};
+use crate::widgets::logs::{LogsState, LogsWidget};
+use crossterm::event::{Event, MouseEventKind};
+use flint_macros::ui;
+use ratatui::prelude::*;
+use ratatui::widgets::WidgetRef;
+use std::cell::RefCell;
+
+/// Shows the logs of every command run in this session
+#[derive(Debug, Default)]
+pub struct LogsViewWidget {
+    logs: LogsWidget,
+    logs_state: RefCell<LogsState>,
+}
+
+impl AppWidget for LogsViewWidget {
+    fn handle_events(&mut self, event: Event) -> AppResult<()> {
+        let _ = handle_key_events(event.clone(), |key_event, _| {
+            match get_action(&key_event) {
+                Some(Action::ScrollUp) => self.logs_state.borrow_mut().scroll_up(1),
+                Some(Action::ScrollDown) => self.logs_state.borrow_mut().scroll_down(1),
+                _ => (),
+            }
+            Ok(())
+        });
+
+        handle_mouse_event(event, |_, kind| {
+            match kind {
+                MouseEventKind::ScrollUp => self.logs_state.borrow_mut().scroll_up(1),
+                MouseEventKind::ScrollDown => self.logs_state.borrow_mut().scroll_down(1),
+                _ => (),
+            }
+            Ok(())
+        })
+    }
+}
+
+impl WidgetRef for LogsViewWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let mut logs_state = self.logs_state.borrow_mut();
+        ui!((area, buf) => {
+            Stateful(&mut logs_state) {
+                { self.logs }
+            }
+        });
+    }
+}
@@ -0,0 +1,398 @@
+use clap::Parser;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use super::AppResult;
+use flint_core::config::Config;
+use flint_core::git::has_extension;
+use flint_core::plugin::exec::{eval::TestCaseOutput, vm};
+use flint_core::plugin::{self, PluginKind};
+use flint_core::{app_err, error, get_flag, info, run_plugin, set_flag, suppressions};
+
+/// The error code of requests the server doesn't handle
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The arguments of `flint lsp`, which runs a language server on stdin and stdout
+#[derive(Parser, Clone, Default)]
+pub struct LspArgs {
+    /// Show help for the lsp command
+    #[clap(short, long)]
+    help: bool,
+}
+
+/// A minimal language server, which lints documents when they're opened or saved and
+/// publishes the findings as diagnostics. Documents are linted again when flint.toml
+/// changes.
+struct Server<W: Write> {
+    writer: W,
+    open_documents: BTreeSet<PathBuf>,
+    /// Whether the client sends changes of the files the server registers for
+    watches_files: bool,
+    next_request_id: u64,
+    is_shut_down: bool,
+}
+
+/// Runs `flint lsp` until the client exits
+pub fn run() -> AppResult<()> {
+    let mut reader = BufReader::new(io::stdin().lock());
+    let mut server = Server {
+        writer: io::stdout().lock(),
+        open_documents: BTreeSet::new(),
+        watches_files: false,
+        next_request_id: 0,
+        is_shut_down: false,
+    };
+
+    while let Some(message) = read_message(&mut reader)? {
+        if !server.handle(&message)? {
+            break;
+        }
+    }
+
+    // Clients expect an error if the server exits without being shut down first
+    if !server.is_shut_down {
+        return Err(app_err!(
+            "The language server exited without a shutdown request"
+        ));
+    }
+    Ok(())
+}
+
+impl<W: Write> Server<W> {
+    /// Handles a message from the client, returning false once the server should exit
+    fn handle(&mut self, message: &Value) -> AppResult<bool> {
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+        let document = params["textDocument"]["uri"].as_str().and_then(uri_to_path);
+
+        match message["method"].as_str() {
+            Some("initialize") => {
+                use_workspace(params);
+                self.watches_files = params["capabilities"]["workspace"]["didChangeWatchedFiles"]
+                    ["dynamicRegistration"]
+                    .as_bool()
+                    .unwrap_or(false);
+                self.respond(id, initialize_result())?;
+            }
+            Some("initialized") if self.watches_files => self.watch_config()?,
+            Some("textDocument/didOpen") => {
+                if let Some(path) = document {
+                    self.open_documents.insert(path.clone());
+                    self.lint(&[path])?;
+                }
+            }
+            Some("textDocument/didSave") => {
+                if let Some(path) = document {
+                    self.lint(&[path])?;
+                }
+            }
+            Some("textDocument/didClose") => {
+                if let Some(path) = document {
+                    self.open_documents.remove(&path);
+                    self.publish(&path, Vec::new())?;
+                }
+            }
+            Some("workspace/didChangeWatchedFiles") => {
+                info!("flint.toml changed, linting the open documents again");
+                let documents = self.open_documents.iter().cloned().collect::<Vec<_>>();
+                self.lint(&documents)?;
+            }
+            Some("shutdown") => {
+                self.is_shut_down = true;
+                self.respond(id, Value::Null)?;
+            }
+            Some("exit") => return Ok(false),
+            // Requests need an answer, other notifications and responses are ignored
+            Some(method) => {
+                if let Some(id) = id {
+                    self.send(json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {
+                            "code": METHOD_NOT_FOUND,
+                            "message": format!("{} isn't supported", method),
+                        },
+                    }))?;
+                }
+            }
+            None => (),
+        }
+        Ok(true)
+    }
+
+    /// Runs the lint plugins of the files and publishes their findings
+    fn lint(&mut self, files: &[PathBuf]) -> AppResult<()> {
+        for (path, diagnostics) in lint_files(files) {
+            self.publish(&path, diagnostics)?;
+        }
+        Ok(())
+    }
+
+    fn publish(&mut self, path: &Path, diagnostics: Vec<Value>) -> AppResult<()> {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": path_to_uri(path), "diagnostics": diagnostics },
+        }))
+    }
+
+    /// Asks the client to send changes of flint.toml, as they change which plugins run
+    fn watch_config(&mut self) -> AppResult<()> {
+        self.next_request_id += 1;
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": self.next_request_id,
+            "method": "client/registerCapability",
+            "params": {
+                "registrations": [{
+                    "id": "flint-config",
+                    "method": "workspace/didChangeWatchedFiles",
+                    "registerOptions": { "watchers": [{ "globPattern": "**/flint.toml" }] },
+                }],
+            },
+        }))
+    }
+
+    fn respond(&mut self, id: Option<Value>, result: Value) -> AppResult<()> {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+    }
+
+    fn send(&mut self, message: Value) -> AppResult<()> {
+        let content = message.to_string();
+        write!(
+            self.writer,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+fn initialize_result() -> Value {
+    json!({
+        "capabilities": {
+            // Documents are linted as they're saved on disk, so their changes aren't needed
+            "textDocumentSync": {
+                "openClose": true,
+                "change": 0,
+                "save": { "includeText": false },
+            },
+        },
+        "serverInfo": { "name": "flint", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+/// Uses the workspace opened in the editor as the project, unless flint was started
+/// in a project already
+fn use_workspace(params: &Value) {
+    if get_flag!(config_path).exists() {
+        return;
+    }
+
+    let Some(root) = params["rootUri"].as_str().and_then(uri_to_path) else {
+        return;
+    };
+    let config_path = root.join("flint.toml");
+    if config_path.exists() {
+        set_flag!(current_dir, root);
+        set_flag!(config_path, config_path);
+    }
+}
+
+/// Lints the files with the lint plugins of their extensions, returning the diagnostics
+/// of every file the plugins reported. The linted files are always included, so their
+/// diagnostics are cleared when they have no findings.
+fn lint_files(files: &[PathBuf]) -> BTreeMap<PathBuf, Vec<Value>> {
+    let current_dir = get_flag!(current_dir).clone();
+    let files = files
+        .iter()
+        .filter_map(|file| file.strip_prefix(&current_dir).ok())
+        .map(Path::to_path_buf)
+        .collect::<Vec<_>>();
+
+    let mut diagnostics = files
+        .iter()
+        .map(|file| (current_dir.join(file), Vec::new()))
+        .collect::<BTreeMap<_, _>>();
+    if files.is_empty() {
+        return diagnostics;
+    }
+
+    let config_path = get_flag!(config_path).clone();
+    let toml = match Config::load(&config_path) {
+        Ok(toml) => Arc::new(toml),
+        Err(err) => {
+            error!("Couldn't load flint.toml: {}", err);
+            return diagnostics;
+        }
+    };
+    vm::set_memory_limits(&toml);
+    let plugins = match plugin::list_from_config(&toml) {
+        Ok(plugins) => plugins,
+        Err(err) => {
+            error!("Couldn't find the plugins: {}", err);
+            return diagnostics;
+        }
+    };
+    let plugins = plugins
+        .into_iter()
+        .filter(|plugin| plugin.kind == PluginKind::Lint)
+        .filter(|plugin| {
+            files
+                .iter()
+                .any(|file| has_extension(file, &plugin.details.extensions))
+        })
+        .collect::<Vec<_>>();
+
+    // Plugins only lint the given files, like when running with --changed
+    set_flag!(changed_files, Some(files));
+    let outputs = thread::scope(|scope| {
+        let handles = plugins
+            .iter()
+            .map(|plugin| {
+                let toml = &toml;
+                scope.spawn(move || (plugin, run_plugin(plugin, toml)))
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .collect::<Vec<_>>()
+    });
+    set_flag!(changed_files, None);
+
+    for (plugin, output) in outputs {
+        let mut output = match output {
+            Ok(output) => output,
+            Err(err) => {
+                error!("{} failed: {}", plugin.details.id, err);
+                continue;
+            }
+        };
+        // Findings suppressed with comments aren't shown in the editor either
+        suppressions::suppress(&plugin.details.id, &mut output);
+
+        for finding in output.test_results.iter().filter(|result| !result.success) {
+            diagnostics
+                .entry(current_dir.join(&finding.file_name))
+                .or_default()
+                .push(diagnostic(&plugin.details.id, finding));
+        }
+    }
+    diagnostics
+}
+
+/// A lint finding as an LSP diagnostic, which starts and ends at the reported position
+fn diagnostic(plugin: &str, finding: &TestCaseOutput) -> Value {
+    // Plugins count lines and columns from 1, LSP counts them from 0
+    let position = json!({
+        "line": finding.line_no.unwrap_or(1).saturating_sub(1),
+        "character": finding.column_no.unwrap_or(1).saturating_sub(1),
+    });
+    let severity = match finding.severity.as_deref() {
+        Some("warning") => 2,
+        Some("note") | Some("info") => 3,
+        _ => 1,
+    };
+
+    let mut diagnostic = json!({
+        "range": { "start": position, "end": position },
+        "severity": severity,
+        "source": plugin,
+        "message": finding.error_message.as_deref().unwrap_or("Lint finding"),
+    });
+    if let Some(rule) = &finding.rule_id {
+        diagnostic["code"] = json!(rule);
+    }
+    diagnostic
+}
+
+/// Reads a message, which is JSON content after a Content-Length header. Returns None
+/// once the client closes stdin.
+fn read_message(reader: &mut impl BufRead) -> AppResult<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let Some(length) = length else {
+        return Err(app_err!(
+            "Received a message without a Content-Length header"
+        ));
+    };
+    let mut content = vec![0; length];
+    reader.read_exact(&mut content)?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|err| app_err!("Received a message that isn't JSON: {}", err))
+}
+
+/// The path of a file URI, decoding escaped characters like `%20`
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let escaped = encoded
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (encoded[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    // Windows paths are written like /C:/Users in URIs
+    let path = String::from_utf8(decoded).ok()?;
+    match path.as_bytes() {
+        [b'/', _, b':', ..] => Some(PathBuf::from(&path[1..])),
+        _ => Some(PathBuf::from(path)),
+    }
+}
+
+/// The file URI of a path, escaping characters that aren't allowed in URIs
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let encoded = path
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            b':' if !path.starts_with('/') => ":".to_string(),
+            byte => format!("%{:02X}", byte),
+        })
+        .collect::<String>();
+
+    if encoded.starts_with('/') {
+        format!("file://{}", encoded)
+    } else {
+        format!("file:///{}", encoded)
+    }
+}
@@ -1,18 +1,35 @@
 pub use app::*;
 
+#[allow(clippy::module_inception)]
 pub mod app;
+pub mod baseline;
+pub mod config;
+pub mod daemon;
+pub mod fix;
+pub mod fmt;
 pub mod generate;
 pub mod help;
+pub mod hooks;
 pub mod init;
+pub mod inline;
 pub mod install;
+pub mod logs;
+pub mod lsp;
+pub mod plugins;
+pub mod repl;
+pub mod rules;
+pub mod rules_doc;
+pub mod setup;
+pub mod suppressions;
 pub mod test;
+pub mod workspace;
 
-use crossterm::event::Event;
+pub use flint_core::error::{AppResult, FlintError};
+
+use crossterm::event::{Event, KeyEvent};
+use flint_core::events::WorkerEvent;
 use ratatui::widgets::WidgetRef;
-use std::error::Error as ErrorTrait;
-use std::io;
 use std::sync::mpsc::Sender;
-use thiserror::Error;
 use threadpool::ThreadPool;
 
 pub trait AppWidget: WidgetRef {
@@ -24,55 +41,29 @@ pub trait AppWidget: WidgetRef {
     }
     fn set_exit_sender(&mut self, _exit_sender: Sender<()>) {}
 
-    fn set_thread_pool(&mut self, _thread_pool: &ThreadPool) {}
-}
-
-#[derive(Error, Debug)]
-pub enum AppError {
-    #[error("Network error: {0}")]
-    Network(String),
-
-    #[error("Deserialization error: {0}")]
-    Deserialization(#[from] toml::de::Error),
-
-    #[error("Serialization error: {0}")]
-    Serialization(#[from] toml::ser::Error),
-
-    #[error("IO error: {0}")]
-    Io(#[from] io::Error),
-
-    #[error("Application error: {0}")]
-    Err(String),
+    /// Whether the widget handles this key itself, instead of the App handling it
+    /// as a global key (like quitting with `q`). Widgets with an open input field
+    /// should claim the keys typed into it.
+    fn claims_key(&self, _key_event: &KeyEvent) -> bool {
+        false
+    }
 
-    #[error("User requested exit")]
-    Exit,
+    fn set_thread_pool(&mut self, _thread_pool: &ThreadPool) {}
 
-    #[error("Lua error: {0}")]
-    LuaError(#[from] mlua::Error),
-}
+    /// Commands the widget adds to the command palette, run with `run_command`
+    fn commands(&self) -> &'static [&'static str] {
+        &[]
+    }
 
-// Convert Box<dyn Error> to AppError using a catch-all approach
-impl From<Box<dyn ErrorTrait>> for AppError {
-    fn from(error: Box<dyn ErrorTrait>) -> Self {
-        // Try to downcast to AppError first
-        let str_err = error.to_string();
-        if let Ok(app_error) = error.downcast::<AppError>() {
-            return *app_error;
-        } else {
-            return AppError::Err(str_err);
-        }
+    fn run_command(&mut self, _command: &str) -> AppResult<()> {
+        Ok(())
     }
-}
 
-// Create type alias for Result with AppError as default error type
-pub type AppResult<T> = Result<T, AppError>;
+    /// Called with every event sent by a worker, including events of workers
+    /// started by other views
+    fn handle_worker_event(&mut self, _event: &WorkerEvent) {}
 
-// Macro to create AppError::Err with format string
-#[macro_export]
-macro_rules! app_err {
-    ($($arg:tt)*) => {{
-        let error_msg = format!($($arg)*);
-        $crate::error!("{}", error_msg);
-        $crate::app::AppError::Err(error_msg)
-    }};
+    /// Called whenever the spinners advance, which they only do while plugins are
+    /// running. The tick counts up, so it picks the frame of a spinner to show.
+    fn handle_tick(&mut self, _tick: usize) {}
 }
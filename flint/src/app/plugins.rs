@@ -0,0 +1,143 @@
+use super::{AppResult, AppWidget};
+use crate::widgets::table::{Column, ResultRow, ResultsTable};
+use clap::{Parser, Subcommand};
+use crossterm::event::Event;
+use flint_core::config::Config;
+use flint_core::plugin::{self, bench, Plugin, PluginKind};
+use flint_core::{app_err, get_flag, info};
+use ratatui::prelude::*;
+use ratatui::widgets::WidgetRef;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Lists the installed plugins
+#[derive(Debug)]
+pub struct PluginsWidget {
+    plugins: Vec<Plugin>,
+    table: ResultsTable,
+}
+
+impl Default for PluginsWidget {
+    fn default() -> Self {
+        let columns = ["Kind", "Plugin", "Version", "Author", "Extensions"].map(Column::new);
+        Self {
+            plugins: Vec::new(),
+            table: ResultsTable::new("Plugins", columns.to_vec()),
+        }
+    }
+}
+
+impl AppWidget for PluginsWidget {
+    fn setup(&mut self) -> AppResult<()> {
+        self.plugins = plugin::list()?.iter().cloned().collect();
+
+        let rows = self.plugins.iter().map(|plugin| {
+            ResultRow::new([
+                plugin.kind.to_string(),
+                plugin.details.id.clone(),
+                format!("v{}", plugin.details.version),
+                plugin.details.author.clone(),
+                plugin.details.extensions.join(", "),
+            ])
+        });
+        self.table.set_rows(rows.collect());
+        self.table
+            .set_title(format!("Plugins ({})", self.plugins.len()));
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Event) -> AppResult<()> {
+        self.table.handle_event(&event);
+        Ok(())
+    }
+}
+
+impl WidgetRef for PluginsWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        self.table.render_ref(area, buf);
+    }
+}
+
+#[derive(Parser, Clone)]
+pub struct PluginArgs {
+    #[command(subcommand)]
+    pub command: PluginCommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum PluginCommand {
+    /// Times the Validate, Generate and Run functions of a plugin with the current
+    /// config, and the Lua memory they use, so plugin authors notice when they get
+    /// slower
+    Bench(BenchArgs),
+}
+
+#[derive(Parser, Clone)]
+pub struct BenchArgs {
+    /// The id of the plugin, like eslint
+    pub id: String,
+    /// How many times every function is called
+    #[arg(long, short = 'n', default_value_t = 100)]
+    pub iterations: usize,
+}
+
+/// Runs `flint plugin`
+pub fn run(args: PluginArgs) -> AppResult<()> {
+    match args.command {
+        PluginCommand::Bench(args) => run_bench(args),
+    }
+}
+
+fn run_bench(args: BenchArgs) -> AppResult<()> {
+    if args.iterations == 0 {
+        return Err(app_err!("The plugin has to be called at least once"));
+    }
+    let plugins = plugin::list()?;
+    let Some(plugin) = plugins.iter().find(|plugin| plugin.details.id == args.id) else {
+        return Err(app_err!("No plugin named {} is installed", args.id));
+    };
+    let toml = Arc::new(Config::load(get_flag!(config_path))?);
+    if plugin.config(&toml).is_none() && plugin.kind != PluginKind::Ci {
+        let [table, id] = plugin.config_keys();
+        return Err(app_err!(
+            "{} isn't configured, add a [{}.{}] section to flint.toml",
+            id,
+            table,
+            id
+        ));
+    }
+
+    info!(
+        "Calling the functions of {} {} times",
+        plugin.details.id, args.iterations
+    );
+    let benches = bench::bench(plugin, &toml, args.iterations)
+        .map_err(|err| app_err!("{} failed: {}", plugin.details.id, err))?;
+
+    println!(
+        "{:<10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>12} {:>12}",
+        "PHASE", "MEAN", "P50", "P90", "P99", "MAX", "MEAN MEMORY", "MAX MEMORY"
+    );
+    for bench in &benches {
+        println!(
+            "{:<10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>12} {:>12}",
+            bench.phase.as_str(),
+            format_duration(bench.mean()),
+            format_duration(bench.percentile(50)),
+            format_duration(bench.percentile(90)),
+            format_duration(bench.percentile(99)),
+            format_duration(bench.max()),
+            format_memory(bench.mean_memory()),
+            format_memory(bench.max_memory()),
+        );
+    }
+    Ok(())
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn format_memory(bytes: usize) -> String {
+    format!("{:.1}KiB", bytes as f64 / 1024.0)
+}
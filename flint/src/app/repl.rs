@@ -0,0 +1,63 @@
+use clap::Parser;
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+use super::app::load_config;
+use super::AppResult;
+use flint_core::plugin::exec::repl::{Console, Evaluated};
+use flint_core::plugin::helpers::MODULES;
+use flint_core::{app_err, error, info, plugin};
+
+#[derive(Parser, Clone, Default)]
+pub struct ReplArgs {
+    /// Loads the scripts of a plugin, with its section of flint.toml as `config`
+    #[arg(long)]
+    pub plugin: Option<String>,
+}
+
+/// Runs `flint repl`, a Lua prompt with the helpers plugins use, until stdin ends
+pub fn run(args: ReplArgs) -> AppResult<()> {
+    let toml = load_config().map(Arc::new);
+    let plugins = plugin::list()?;
+    let plugin = match &args.plugin {
+        Some(id) => match plugins.iter().find(|plugin| plugin.details.id == *id) {
+            Some(plugin) => Some(plugin),
+            None => return Err(app_err!("No plugin named {} is installed", id)),
+        },
+        None => None,
+    };
+    let mut console = Console::new(plugin, toml.as_ref())?;
+
+    let config = match plugin {
+        Some(plugin) => {
+            let [table, id] = plugin.config_keys();
+            format!("[{}.{}]", table, id)
+        }
+        None => "flint.toml".to_string(),
+    };
+    info!(
+        "Lua with the helpers {} as globals, and {} as config. Ctrl+D quits.",
+        MODULES.join(", "),
+        config
+    );
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("{} ", if console.is_incomplete() { ">>" } else { ">" });
+        io::stdout().flush()?;
+        let Some(line) = lines.next() else {
+            println!();
+            return Ok(());
+        };
+
+        match console.eval(&line?) {
+            Ok(Evaluated::Values(values)) => {
+                for value in values {
+                    println!("{}", value);
+                }
+            }
+            Ok(Evaluated::Incomplete) => (),
+            Err(err) => error!("{}", err),
+        }
+    }
+}
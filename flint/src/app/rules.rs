@@ -0,0 +1,344 @@
+use super::{AppResult, AppWidget};
+use crate::util::keys::{get_action, Action};
+use crate::util::theme::theme;
+use crate::util::{handle_key_events, handle_mouse_event};
+use crate::widgets::text_input::TextInput;
+use crossterm::event::{Event, KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use flint_core::config::Config;
+use flint_core::plugin::rules::{self, Rule, RuleType};
+use flint_core::plugin::{self, Plugin};
+use flint_core::{error, get_flag, warn};
+use flint_macros::ui;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, List, ListState, Paragraph, WidgetRef};
+use std::cell::{Cell, RefCell};
+use std::sync::Arc;
+
+/// A rule of an enabled plugin, with its value in the config
+#[derive(Debug)]
+struct RuleRow {
+    plugin: Plugin,
+    rule: Rule,
+    value: Option<toml::Value>,
+}
+
+impl RuleRow {
+    /// The key of the rule in the config, like `rules.eslint.semi`
+    fn key(&self) -> String {
+        let mut keys = self.plugin.config_keys().to_vec();
+        keys.extend(self.rule.keys());
+        keys.join(".")
+    }
+}
+
+/// Lists the rules declared by the enabled plugins, and sets them in the config.
+/// Booleans and enums are toggled with enter, other rules are typed in.
+#[derive(Debug, Default)]
+pub struct RulesWidget {
+    rows: Vec<RuleRow>,
+    list_state: RefCell<ListState>,
+    /// Where the list was last rendered, to select rules by clicking them
+    list_area: Cell<Rect>,
+    /// The value of the selected rule being typed in, if it's edited
+    input: Option<TextInput>,
+    /// What the last change did, or why it failed
+    message: Option<Result<String, String>>,
+}
+
+impl AppWidget for RulesWidget {
+    fn setup(&mut self) -> AppResult<()> {
+        let config = Arc::new(Config::load(get_flag!(config_path))?);
+
+        self.rows = Vec::new();
+        for plugin in plugin::list_from_config(&config)? {
+            let rules = plugin.get_rules().unwrap_or_else(|err| {
+                warn!("Unable to read the rules of {}: {}", plugin.details.id, err);
+                Vec::new()
+            });
+            for rule in rules {
+                let value = rule.value(plugin.config(&config)).cloned();
+                self.rows.push(RuleRow {
+                    plugin: plugin.clone(),
+                    rule,
+                    value,
+                });
+            }
+        }
+
+        let list_state = self.list_state.get_mut();
+        if list_state
+            .selected()
+            .is_none_or(|index| index >= self.rows.len())
+        {
+            list_state.select_first();
+        }
+        Ok(())
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["Reload rules"]
+    }
+
+    /// Reads the rules and the config again, which is the only command
+    fn run_command(&mut self, _command: &str) -> AppResult<()> {
+        self.input = None;
+        self.message = None;
+        self.setup()
+    }
+
+    fn claims_key(&self, _key_event: &KeyEvent) -> bool {
+        // Every key goes to the value while it's typed in
+        self.input.is_some()
+    }
+
+    fn handle_events(&mut self, event: Event) -> AppResult<()> {
+        if self.input.is_some() {
+            return handle_key_events(event, |key_event, key_code| {
+                match (key_code, get_action(&key_event)) {
+                    (KeyCode::Esc, _) => {
+                        self.input = None;
+                        self.message = None;
+                    }
+                    (_, Some(Action::Confirm)) => self.submit_input(),
+                    _ => {
+                        if let Some(input) = self.input.as_mut() {
+                            input.input(key_event);
+                        }
+                    }
+                }
+                Ok(())
+            });
+        }
+
+        let _ = handle_key_events(event.clone(), |key_event, _| {
+            match get_action(&key_event) {
+                Some(Action::ScrollUp) => self.list_state.get_mut().select_previous(),
+                Some(Action::ScrollDown) => self.list_state.get_mut().select_next(),
+                Some(Action::Confirm) => self.change_selected(),
+                _ => (),
+            }
+            Ok(())
+        });
+
+        handle_mouse_event(event, |mouse_event, kind| {
+            match kind {
+                MouseEventKind::ScrollUp => self.list_state.get_mut().select_previous(),
+                MouseEventKind::ScrollDown => self.list_state.get_mut().select_next(),
+                MouseEventKind::Down(MouseButton::Left) => {
+                    if let Some(index) = self.rule_at(mouse_event.column, mouse_event.row) {
+                        self.list_state.get_mut().select(Some(index));
+                    }
+                }
+                _ => (),
+            }
+            Ok(())
+        })
+    }
+}
+
+impl RulesWidget {
+    fn selected(&self) -> Option<&RuleRow> {
+        self.rows.get(self.list_state.borrow().selected()?)
+    }
+
+    /// Returns the index of the rule shown at the given position, if any
+    fn rule_at(&self, column: u16, row: u16) -> Option<usize> {
+        let inner = self.list_area.get().inner(Margin::new(1, 1));
+        if !inner.contains(Position::new(column, row)) {
+            return None;
+        }
+
+        let index = self.list_state.borrow().offset() + (row - inner.y) as usize;
+        (index < self.rows.len()).then_some(index)
+    }
+
+    /// Toggles the selected rule, or starts typing its value in
+    fn change_selected(&mut self) {
+        let Some(row) = self.selected() else {
+            return;
+        };
+
+        if let Some(value) = row.rule.next_value(row.value.as_ref()) {
+            self.set_selected(value);
+            return;
+        }
+
+        let text = row
+            .value
+            .clone()
+            .or_else(|| row.rule.default_value())
+            .map(|value| input_text(&value))
+            .unwrap_or_default();
+        let rule = row.rule.clone();
+        self.input = Some(
+            TextInput::new()
+                .with_value(text)
+                .with_validator(move |input| rule.parse(input).map(|_| ())),
+        );
+        self.message = None;
+    }
+
+    /// Sets the selected rule to the typed value, if it's one of the rule
+    fn submit_input(&mut self) {
+        let Some(text) = self.input.as_mut().and_then(TextInput::submit) else {
+            return;
+        };
+        let Some(Ok(value)) = self.selected().map(|row| row.rule.parse(&text)) else {
+            return;
+        };
+
+        self.input = None;
+        self.set_selected(value);
+    }
+
+    /// Writes the value of the selected rule to the config, and reads the values of
+    /// the rules back from it
+    fn set_selected(&mut self, value: toml::Value) {
+        let Some(row) = self.selected() else {
+            return;
+        };
+
+        let key = row.key();
+        let result = rules::set_rule(get_flag!(config_path), &row.plugin, &row.rule, &value)
+            .and_then(|_| self.load_values());
+        self.message = Some(match result {
+            Ok(()) => Ok(format!("Set {} to {}", key, value)),
+            Err(err) => {
+                error!("Unable to set {}: {}", key, err);
+                Err(format!("Unable to set {}: {}", key, err))
+            }
+        });
+    }
+
+    fn load_values(&mut self) -> AppResult<()> {
+        let config = Config::load(get_flag!(config_path))?;
+        for row in &mut self.rows {
+            row.value = row.rule.value(row.plugin.config(&config)).cloned();
+        }
+        Ok(())
+    }
+
+    /// The description of the selected rule, or why the typed value was rejected
+    fn description(&self) -> Line<'_> {
+        let theme = theme();
+        if let Some(err) = self.input.as_ref().and_then(TextInput::error) {
+            return Line::styled(err, Style::default().fg(theme.error));
+        }
+
+        let Some(row) = self.selected() else {
+            return Line::default();
+        };
+        let mut description = row.rule.description.clone().unwrap_or_default();
+        if row.rule.kind == RuleType::Enum {
+            let options = format!("Options: {}", row.rule.values.join(", "));
+            description = match description.is_empty() {
+                true => options,
+                false => format!("{}. {}", description.trim_end_matches('.'), options),
+            };
+        }
+        Line::from(description)
+    }
+
+    /// What the last change did, or which keys change the selected rule
+    fn status(&self) -> Line<'_> {
+        let theme = theme();
+        match &self.message {
+            Some(Ok(message)) => Line::styled(message.as_str(), Style::default().fg(theme.success)),
+            Some(Err(err)) => Line::styled(err.as_str(), Style::default().fg(theme.error)),
+            None => {
+                let hint = match self.selected().map(|row| row.rule.kind) {
+                    Some(RuleType::Boolean | RuleType::Enum) => "Press enter to change the rule",
+                    Some(_) => "Press enter to edit the rule",
+                    None => "",
+                };
+                Line::styled(hint, Style::default().fg(theme.debug))
+            }
+        }
+    }
+}
+
+/// The text a value is edited as, which is the value itself for strings
+fn input_text(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+impl WidgetRef for RulesWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let theme = theme();
+        let plugin_width = self
+            .rows
+            .iter()
+            .map(|row| row.plugin.details.id.len())
+            .max()
+            .unwrap_or_default();
+        let rule_width = self
+            .rows
+            .iter()
+            .map(|row| row.rule.name.len())
+            .max()
+            .unwrap_or_default();
+
+        let items = self.rows.iter().map(|row| {
+            let name = format!(
+                "{:<plugin_width$} {:<rule_width$} ",
+                row.plugin.details.id, row.rule.name
+            );
+            let value = match (&row.value, row.rule.default_value()) {
+                (Some(value), _) => Span::raw(value.to_string()),
+                (None, Some(default)) => Span::styled(
+                    format!("{} (default)", default),
+                    Style::default().fg(theme.debug),
+                ),
+                (None, None) => Span::styled("not set", Style::default().fg(theme.debug)),
+            };
+            Line::from(vec![Span::raw(name), value])
+        });
+
+        let [list_area, description_area, status_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(area);
+        self.list_area.set(list_area);
+
+        let mut list_state = self.list_state.borrow_mut();
+        ui!((list_area, buf) => {
+            Stateful(&mut list_state) {
+                List::new(
+                    items,
+                    block: Block::bordered(
+                        title: format!("Rules ({})", self.rows.len()),
+                        border_style: theme.border,
+                        border_set: theme.symbols.border
+                    ),
+                    highlight_style: theme.selection()
+                )
+            }
+        });
+        drop(list_state);
+
+        Paragraph::new(self.description()).render(description_area, buf);
+        match &self.input {
+            Some(input) => {
+                let prompt = match self.selected() {
+                    Some(row) => format!("{} = ", row.rule.name),
+                    None => String::new(),
+                };
+                let [prompt_area, input_area] = Layout::horizontal([
+                    Constraint::Length(prompt.len() as u16),
+                    Constraint::Fill(1),
+                ])
+                .areas(status_area);
+                Paragraph::new(prompt)
+                    .style(Style::default().fg(theme.accent))
+                    .render(prompt_area, buf);
+                input.render_ref(input_area, buf);
+            }
+            None => Paragraph::new(self.status()).render(status_area, buf),
+        }
+    }
+}
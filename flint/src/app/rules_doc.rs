@@ -0,0 +1,232 @@
+use clap::Parser;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use super::AppResult;
+use crate::report::markdown::cell;
+use flint_core::config::{Config, FailOn};
+use flint_core::diff::Diff;
+use flint_core::plugin::rules::{Rule, RuleType};
+use flint_core::plugin::{self, Plugin};
+use flint_core::{app_err, get_flag, info, success, warn};
+
+/// The file the document is written to by default, relative to the project root
+pub const RULES_DOC_FILE: &str = "RULES.md";
+
+#[derive(Parser, Clone)]
+pub struct RulesDocArgs {
+    /// The file to write the document to, relative to the project root
+    #[clap(short, long, default_value = RULES_DOC_FILE)]
+    output: PathBuf,
+
+    /// Check that the document is up to date with flint.toml instead of writing it
+    #[clap(long)]
+    check: bool,
+}
+
+/// Runs `flint rules-doc`, writing the enabled plugins and the values of their rules to
+/// a Markdown document, so the project's policy can be read without flint.toml
+pub fn run(args: RulesDocArgs) -> AppResult<()> {
+    let config = Arc::new(Config::load(get_flag!(config_path))?);
+    let markdown = render(&config)?;
+    let path = get_flag!(current_dir).join(&args.output);
+    let file_name = args.output.display();
+
+    if !args.check {
+        fs::write(&path, markdown)?;
+        success!("Wrote the rules of the enabled plugins to {}", file_name);
+        return Ok(());
+    }
+
+    match fs::read_to_string(&path) {
+        Ok(contents) if contents == markdown => {
+            success!("{} is up to date", file_name);
+            Ok(())
+        }
+        Ok(contents) => {
+            info!(
+                "Generating {} would change it:\n{}",
+                file_name,
+                Diff::new(&contents, &markdown).to_string().trim_end()
+            );
+            Err(app_err!(
+                "{} is out of date, run flint rules-doc to update it",
+                file_name
+            ))
+        }
+        Err(_) => Err(app_err!(
+            "{} is missing, run flint rules-doc to create it",
+            file_name
+        )),
+    }
+}
+
+/// Renders the plugins enabled in the config, with the values their rules resolve to
+pub fn render(config: &Arc<Config>) -> AppResult<String> {
+    let mut plugins = plugin::list_from_config(config)?;
+    plugins.sort_by(|a, b| (&a.kind, &a.details.id).cmp(&(&b.kind, &b.details.id)));
+
+    let mut markdown = String::new();
+    let _ = writeln!(markdown, "# Rules\n");
+    let _ = writeln!(
+        markdown,
+        "<!-- Generated from flint.toml by flint rules-doc, don't edit it by hand -->\n"
+    );
+    render_policy(&mut markdown, config);
+
+    if plugins.is_empty() {
+        let _ = writeln!(markdown, "No plugins are enabled.");
+        return Ok(markdown);
+    }
+
+    let _ = writeln!(markdown, "| Plugin | Kind | Version | Files |");
+    let _ = writeln!(markdown, "|---|---|---|---|");
+    for plugin in &plugins {
+        let _ = writeln!(
+            markdown,
+            "| [{}](#{}) | {} | {} | {} |",
+            plugin.details.id,
+            anchor(&plugin.details.id),
+            plugin.kind,
+            cell(&plugin.details.version),
+            cell(&plugin.details.extensions.join(", ")),
+        );
+    }
+    let _ = writeln!(markdown);
+
+    for plugin in &plugins {
+        render_plugin(&mut markdown, config, plugin);
+    }
+    Ok(markdown)
+}
+
+/// The settings deciding which findings fail runs and which are fixed
+fn render_policy(markdown: &mut String, config: &Config) {
+    let fail_on = match config.flint.fail_on.unwrap_or_default() {
+        FailOn::Error => "Runs fail on lint errors.",
+        FailOn::Warning => "Runs fail on lint errors and warnings.",
+        FailOn::Never => "Lint findings never fail runs.",
+    };
+    let _ = writeln!(markdown, "{}", fail_on);
+
+    let skip_rules = config
+        .flint
+        .fix
+        .as_ref()
+        .map(|fix| fix.skip_rules.as_slice())
+        .unwrap_or_default();
+    if !skip_rules.is_empty() {
+        let skipped = skip_rules
+            .iter()
+            .map(|rule| format!("`{}`", rule))
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            markdown,
+            "`flint fix` doesn't apply the fixes of {}.",
+            skipped.join(", ")
+        );
+    }
+    let _ = writeln!(markdown);
+}
+
+fn render_plugin(markdown: &mut String, config: &Config, plugin: &Plugin) {
+    let section = plugin.config_keys().join(".");
+    let plugin_config = plugin.config(config);
+    let _ = writeln!(markdown, "## {}\n", plugin.details.id);
+    let _ = writeln!(markdown, "Configured in `[{}]`.\n", section);
+
+    let rules = plugin.get_rules().unwrap_or_else(|err| {
+        warn!("Unable to read the rules of {}: {}", plugin.details.id, err);
+        Vec::new()
+    });
+    if rules.is_empty() {
+        let _ = writeln!(markdown, "The plugin declares no rules.\n");
+    } else {
+        let _ = writeln!(markdown, "| Rule | Value | Type | Description |");
+        let _ = writeln!(markdown, "|---|---|---|---|");
+        for rule in &rules {
+            let _ = writeln!(
+                markdown,
+                "| `{}` | {} | {} | {} |",
+                rule.name,
+                value(rule, plugin_config),
+                rule_type(rule),
+                cell(rule.description.as_deref().unwrap_or_default()),
+            );
+        }
+        let _ = writeln!(markdown);
+    }
+
+    // Options the plugin reads without declaring them as rules
+    let mut options = BTreeMap::new();
+    if let Some(plugin_config) = plugin_config {
+        flatten(plugin_config, String::new(), &mut options);
+    }
+    options.retain(|key, _| !rules.iter().any(|rule| rule.name == *key));
+    if !options.is_empty() {
+        let _ = writeln!(markdown, "Other options:\n");
+        let _ = writeln!(markdown, "| Option | Value |");
+        let _ = writeln!(markdown, "|---|---|");
+        for (key, value) in options {
+            let _ = writeln!(markdown, "| `{}` | `{}` |", key, cell(&value.to_string()));
+        }
+        let _ = writeln!(markdown);
+    }
+}
+
+/// The value of the rule in the config, or its default if it isn't set
+fn value(rule: &Rule, plugin_config: Option<&toml::Value>) -> String {
+    match (rule.value(plugin_config), rule.default_value()) {
+        (Some(value), _) => format!("`{}`", cell(&value.to_string())),
+        (None, Some(default)) => format!("`{}` (default)", cell(&default.to_string())),
+        (None, None) => "Not set".to_string(),
+    }
+}
+
+fn rule_type(rule: &Rule) -> String {
+    match rule.kind {
+        RuleType::Boolean => "boolean".to_string(),
+        RuleType::String => "string".to_string(),
+        RuleType::Integer => "integer".to_string(),
+        RuleType::Number => "number".to_string(),
+        RuleType::Enum => {
+            let values = rule
+                .values
+                .iter()
+                .map(|value| format!("`{}`", cell(value)))
+                .collect::<Vec<_>>();
+            format!("one of {}", values.join(", "))
+        }
+    }
+}
+
+/// The values in a section by their dotted keys, like rules name them
+fn flatten(value: &toml::Value, key: String, options: &mut BTreeMap<String, toml::Value>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (name, value) in table {
+                let key = match key.is_empty() {
+                    true => name.clone(),
+                    false => format!("{}.{}", key, name),
+                };
+                flatten(value, key, options);
+            }
+        }
+        _ => {
+            options.insert(key, value.clone());
+        }
+    }
+}
+
+/// The anchor GitHub gives a heading, to link to the section of a plugin
+fn anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter(|char| char.is_alphanumeric() || *char == '-' || *char == '_' || *char == ' ')
+        .map(|char| if char == ' ' { '-' } else { char })
+        .collect()
+}
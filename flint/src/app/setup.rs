@@ -0,0 +1,225 @@
+use clap::Parser;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::AppResult;
+use flint_core::config::Config;
+use flint_core::plugin::{
+    self,
+    tools::{Tool, ToolStatus},
+};
+use flint_core::{app_err, get_flag, info, success, warn};
+
+/// The file the results of `flint setup` are recorded in, relative to the project root
+pub const TOOLS_FILE: &str = ".flint/tools.json";
+
+#[derive(Parser, Clone)]
+pub struct SetupArgs {
+    /// Run the install commands of missing tools without asking
+    #[clap(short, long)]
+    yes: bool,
+
+    /// Only check the tools, without offering to install the missing ones
+    #[clap(long, conflicts_with = "yes")]
+    check: bool,
+}
+
+/// The results of `flint setup`
+#[derive(Serialize)]
+struct SetupRecord<'a> {
+    /// When the tools were checked, in seconds since the Unix epoch
+    timestamp: u64,
+    tools: Vec<ToolRecord<'a>>,
+}
+
+#[derive(Serialize)]
+struct ToolRecord<'a> {
+    name: &'a str,
+    /// The plugins running the tool, by id
+    plugins: Vec<&'a str>,
+    required_version: Option<&'a str>,
+    #[serde(flatten)]
+    status: ToolStatus,
+    /// The command run to install the tool, if one was
+    install_command: Option<&'a str>,
+    install_succeeded: Option<bool>,
+}
+
+/// Runs `flint setup`
+pub fn run(args: SetupArgs) -> AppResult<()> {
+    let config = Arc::new(Config::load(get_flag!(config_path))?);
+    let plugins = plugin::list_from_config(&config)?;
+
+    // Tools run by several plugins are only checked once
+    let mut tools: Vec<(Tool, Vec<&str>)> = Vec::new();
+    for plugin in &plugins {
+        for tool in plugin.get_tools()? {
+            match tools.iter_mut().find(|(known, _)| known.name == tool.name) {
+                Some((_, ids)) => ids.push(&plugin.details.id),
+                None => tools.push((tool, vec![&plugin.details.id])),
+            }
+        }
+    }
+
+    let mut records = Vec::new();
+    for (tool, plugin_ids) in &tools {
+        let mut status = tool.check();
+        log_status(tool, plugin_ids, &status);
+
+        let mut install_command = None;
+        let mut install_succeeded = None;
+        if !status.is_installed() && !args.check {
+            if let Some(command) = install_command_to_run(tool, args.yes) {
+                install_command = Some(command);
+                install_succeeded = Some(install(command));
+                status = tool.check();
+                log_status(tool, plugin_ids, &status);
+            }
+        }
+
+        records.push(ToolRecord {
+            name: &tool.name,
+            plugins: plugin_ids.clone(),
+            required_version: tool.version.as_deref(),
+            status,
+            install_command,
+            install_succeeded,
+        });
+    }
+
+    let record = SetupRecord {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default(),
+        tools: records,
+    };
+    let path = get_flag!(current_dir).join(TOOLS_FILE);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(&record)
+        .map_err(|err| app_err!("Failed to record the tools: {}", err))?;
+    fs::write(&path, json)?;
+
+    let missing = record
+        .tools
+        .iter()
+        .filter(|tool| !tool.status.is_installed())
+        .count();
+    match (record.tools.len(), missing) {
+        (0, _) => info!("The configured plugins don't run any tools"),
+        (total, 0) => success!("All {} tools of the plugins are installed", total),
+        (total, missing) => {
+            return Err(app_err!(
+                "{} of the {} tools of the plugins can't be run yet",
+                missing,
+                total
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn log_status(tool: &Tool, plugin_ids: &[&str], status: &ToolStatus) {
+    match status {
+        ToolStatus::Installed {
+            version: Some(version),
+            ..
+        } => success!("{} {} is installed", tool.name, version),
+        ToolStatus::Installed { version: None, .. } => success!("{} is installed", tool.name),
+        ToolStatus::WrongVersion { version, .. } => warn!(
+            "{} {} is installed, but {} needs {}",
+            tool.name,
+            version,
+            plugin_ids.join(", "),
+            tool.version.as_deref().unwrap_or_default()
+        ),
+        ToolStatus::Missing => warn!(
+            "{} isn't installed, which {} runs",
+            tool.name,
+            plugin_ids.join(", ")
+        ),
+    }
+}
+
+/// The command installing a tool, if there's one the user agrees to run
+fn install_command_to_run(tool: &Tool, yes: bool) -> Option<&str> {
+    let Some((manager, command)) = tool.install_command() else {
+        if tool.install.is_empty() {
+            warn!(
+                "{} has no install command, so install it yourself",
+                tool.name
+            );
+        } else {
+            let managers = tool.install.keys().cloned().collect::<Vec<_>>();
+            warn!(
+                "{} can be installed with {}, but none of them were found",
+                tool.name,
+                managers.join(" or ")
+            );
+        }
+        return None;
+    };
+
+    if *get_flag!(offline) {
+        warn!(
+            "Running offline, so {} isn't installed with {}",
+            tool.name, manager
+        );
+        return None;
+    }
+    if !yes && !confirm(&format!("Install {} with `{}`?", tool.name, command)) {
+        info!(
+            "Skipped installing {}, which flint setup --yes installs without asking",
+            tool.name
+        );
+        return None;
+    }
+    Some(command)
+}
+
+/// Runs an install command, showing its output
+fn install(command: &str) -> bool {
+    info!("Running {}", command);
+    let mut parts = command.split_whitespace();
+    let Some(program) = parts.next() else {
+        return false;
+    };
+
+    match Command::new(program)
+        .args(parts)
+        .current_dir(get_flag!(current_dir))
+        .status()
+    {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            warn!("{} failed with {}", command, status);
+            false
+        }
+        Err(err) => {
+            warn!("{} couldn't be run: {}", command, err);
+            false
+        }
+    }
+}
+
+/// Asks a yes or no question on the terminal. Without one, the answer is no, so
+/// scripts use `--yes` instead.
+fn confirm(question: &str) -> bool {
+    if !io::stdin().is_terminal() {
+        return false;
+    }
+
+    print!("{} [y/N] ", question);
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
@@ -0,0 +1,35 @@
+use super::AppResult;
+use flint_core::info;
+use flint_core::suppressions::{scan_project, DISABLE_NEXT_LINE};
+
+/// Runs `flint suppressions`, listing the suppression comments of the project
+pub fn run() -> AppResult<()> {
+    let suppressions = scan_project();
+    if suppressions.is_empty() {
+        info!(
+            "No findings are suppressed with {} comments",
+            DISABLE_NEXT_LINE
+        );
+        return Ok(());
+    }
+
+    for suppression in &suppressions {
+        let targets = match suppression.targets.is_empty() {
+            true => "every plugin".to_string(),
+            false => suppression
+                .targets
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        info!(
+            "{}:{} suppresses {}",
+            suppression.file.display(),
+            suppression.line + 1,
+            targets
+        );
+    }
+    info!("Suppression comments: {}", suppressions.len());
+    Ok(())
+}
@@ -1,27 +1,73 @@
 use clap::Parser;
-use crossterm::event::{KeyCode, MouseEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, MouseEventKind};
 use flint_macros::ui;
 use ratatui::prelude::*;
-use ratatui::widgets::WidgetRef;
-use std::{cell::RefCell, fs, sync::Arc};
+use ratatui::widgets::{Block, WidgetRef};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
 use threadpool::ThreadPool;
 
 use crate::{
-    debug, error, get_flag, info,
-    plugin::{self, Plugin, PluginKind},
-    success,
-    util::{handle_key_events, handle_mouse_event, toml::Config},
+    report::format::LintFormat,
+    util::{
+        handle_key_events, handle_mouse_event,
+        keys::{get_action, Action},
+        layout::{pane_constraints, pane_direction},
+        theme::theme,
+    },
+    widgets::{
+        fuzzy_list::FuzzyList,
+        logs::{LogsState, LogsWidget},
+        modal::{self, Modal, ModalResult},
+        plugin_errors::PluginErrors,
+        progress::{PluginStatus, TaskProgress},
+        tool_problems::ToolProblems,
+    },
+};
+use flint_core::{
+    baseline::Baseline,
+    cancel::is_cancelled,
+    config::Config,
+    events::{Task, Worker, WorkerEvent},
+    get_flag,
+    git::{self, has_extension},
+    i18n, info,
+    logs::LogKind,
+    plugin::{
+        self,
+        cache::{self, is_reusable, RunCache},
+        exec::{eval::PluginEvalOutput, vm},
+        remote_cache::RemoteCache,
+        Plugin, PluginKind,
+    },
+    set_flag, success, suppressions, t,
+    timings::{self, Phase},
     warn,
-    widgets::logs::{LogsState, LogsWidget},
 };
 
 use super::{AppResult, AppWidget};
 
+const RERUN_FAILED_TESTS: &str = "Re-run failed tests";
+const RUN_ONE_PLUGIN: &str = "Run one plugin";
+
 #[derive(Debug)]
 pub struct TestWidget {
     logs: LogsWidget,
     thread_pool: Option<ThreadPool>,
     logs_state: RefCell<LogsState>,
+    progress: TaskProgress,
+    tool_problems: ToolProblems,
+    plugin_errors: PluginErrors,
+    /// Chooses the plugin to run, while it's open
+    picker: Option<FuzzyList>,
     args: TestArgs,
 }
 
@@ -39,42 +85,314 @@ pub struct TestArgs {
 
     #[clap(short, long)]
     test: bool,
+
+    /// Only run the audit plugins, checking the dependencies for known vulnerabilities
+    #[clap(long)]
+    audit: bool,
+
+    /// Write the lint findings to this file in SARIF format, like for GitHub Code Scanning
+    #[clap(long)]
+    pub sarif: Option<PathBuf>,
+
+    /// Print the lint findings in this format once every plugin finished, like
+    /// eslint's formatters. Logs are printed to stderr instead.
+    #[clap(long, value_enum, value_name = "FORMAT")]
+    pub format: Option<LintFormat>,
+
+    /// Only check the files changed since the branch forked from BASE_REF, which
+    /// defaults to the default branch
+    #[clap(long, value_name = "BASE_REF", num_args = 0..=1, require_equals = true)]
+    pub changed: Option<Option<String>>,
+
+    /// Only check the files staged for commit, like in a pre-commit hook
+    #[clap(long, conflicts_with = "changed")]
+    pub staged: bool,
+
+    /// Run the whole suite of the test plugins with --changed or --staged, rather than
+    /// only the tests affected by the changed files
+    #[clap(long)]
+    pub full_suite: bool,
+
+    /// Fail if the lint plugins find more than this many warnings
+    #[clap(long, value_name = "N")]
+    pub max_warnings: Option<usize>,
+
+    /// Report the findings in the baseline too, which are left out by default
+    #[clap(long)]
+    pub no_baseline: bool,
+
+    /// Run every plugin, even the ones whose files haven't changed since their last run
+    #[clap(long)]
+    pub force: bool,
+}
+
+impl Default for TestArgs {
+    /// The same arguments as running `flint test` without any flags
+    fn default() -> Self {
+        Self {
+            help: false,
+            all: true,
+            lint: false,
+            test: false,
+            audit: false,
+            sarif: None,
+            format: None,
+            changed: None,
+            staged: false,
+            full_suite: false,
+            max_warnings: None,
+            no_baseline: false,
+            force: false,
+        }
+    }
+}
+
+/// The arguments of `flint lint`, which runs the lint plugins like `flint test --lint`
+#[derive(Parser, Debug, Clone, Default)]
+pub struct LintArgs {
+    /// Show help for the lint command
+    #[clap(short, long)]
+    help: bool,
+
+    /// Write the lint findings to this file in SARIF format, like for GitHub Code Scanning
+    #[clap(long)]
+    pub sarif: Option<PathBuf>,
+
+    /// Print the lint findings in this format once every plugin finished, like
+    /// eslint's formatters. Logs are printed to stderr instead.
+    #[clap(long, value_enum, value_name = "FORMAT")]
+    pub format: Option<LintFormat>,
+
+    /// Only lint the files changed since the branch forked from BASE_REF, which
+    /// defaults to the default branch
+    #[clap(long, value_name = "BASE_REF", num_args = 0..=1, require_equals = true)]
+    pub changed: Option<Option<String>>,
+
+    /// Only lint the files staged for commit, like in a pre-commit hook
+    #[clap(long, conflicts_with = "changed")]
+    pub staged: bool,
+
+    /// Fail if more than this many warnings are found
+    #[clap(long, value_name = "N")]
+    pub max_warnings: Option<usize>,
+
+    /// Report the findings in the baseline too, which are left out by default
+    #[clap(long)]
+    pub no_baseline: bool,
+
+    /// Lint with every plugin, even the ones whose files haven't changed since their
+    /// last run
+    #[clap(long)]
+    pub force: bool,
+}
+
+impl From<LintArgs> for TestArgs {
+    fn from(args: LintArgs) -> Self {
+        Self {
+            help: args.help,
+            lint: true,
+            sarif: args.sarif,
+            format: args.format,
+            changed: args.changed,
+            staged: args.staged,
+            max_warnings: args.max_warnings,
+            no_baseline: args.no_baseline,
+            force: args.force,
+            ..Self::default()
+        }
+    }
+}
+
+/// The arguments of `flint audit`, which runs the audit plugins like `flint test --audit`
+#[derive(Parser, Debug, Clone, Default)]
+pub struct AuditArgs {
+    /// Show help for the audit command
+    #[clap(short, long)]
+    help: bool,
+
+    /// Write the vulnerabilities to this file in SARIF format, like for GitHub Code Scanning
+    #[clap(long)]
+    pub sarif: Option<PathBuf>,
+}
+
+impl From<AuditArgs> for TestArgs {
+    fn from(args: AuditArgs) -> Self {
+        Self {
+            help: args.help,
+            audit: true,
+            sarif: args.sarif,
+            ..Self::default()
+        }
+    }
+}
+
+/// The arguments of `flint fix`, which lints the project and applies the fixes the
+/// lint plugins return
+#[derive(Parser, Debug, Clone, Default)]
+pub struct FixArgs {
+    /// Show help for the fix command
+    #[clap(short, long)]
+    help: bool,
+
+    /// Print the changes the fixes would make, without applying them
+    #[clap(long)]
+    pub fix_dry_run: bool,
+
+    /// Only fix the files changed since the branch forked from BASE_REF, which
+    /// defaults to the default branch
+    #[clap(long, value_name = "BASE_REF", num_args = 0..=1, require_equals = true)]
+    pub changed: Option<Option<String>>,
+
+    /// Only fix the files staged for commit
+    #[clap(long, conflicts_with = "changed")]
+    pub staged: bool,
+}
+
+impl From<FixArgs> for TestArgs {
+    fn from(args: FixArgs) -> Self {
+        Self {
+            help: args.help,
+            lint: true,
+            changed: args.changed,
+            staged: args.staged,
+            ..Self::default()
+        }
+    }
 }
 
 impl TestWidget {
     pub fn new(args: TestArgs) -> Self {
         Self {
             thread_pool: None,
-            logs: LogsWidget::default(),
+            logs: LogsWidget,
             logs_state: RefCell::new(LogsState::default()),
+            progress: TaskProgress::new(Task::Test),
+            tool_problems: ToolProblems::new(Task::Test),
+            plugin_errors: PluginErrors::new(Task::Test),
+            picker: None,
             args,
         }
     }
-}
 
-impl AppWidget for TestWidget {
-    fn setup(&mut self) -> AppResult<()> {
-        let toml = Arc::new(Config::load(get_flag!(config_path)).unwrap());
-        let plugins = plugin::list_from_config(&toml);
+    /// Whether the args select the plugin to be run. Report, CI and formatter plugins
+    /// are never run by testing.
+    fn selects(&self, plugin: &Plugin) -> bool {
+        if matches!(
+            plugin.kind,
+            PluginKind::Report | PluginKind::Ci | PluginKind::Fmt
+        ) {
+            false
+        } else if !self.args.lint && !self.args.test && !self.args.audit && self.args.all {
+            true
+        } else if self.args.lint {
+            plugin.kind == PluginKind::Lint
+        } else if self.args.test {
+            plugin.kind == PluginKind::Test
+        } else if self.args.audit {
+            plugin.kind == PluginKind::Audit
+        } else {
+            false
+        }
+    }
+
+    /// Lists the plugins that can be run, to choose one of them by searching
+    fn open_picker(&mut self) -> AppResult<()> {
+        let config = Arc::new(Config::load(get_flag!(config_path))?);
+        let plugins = plugin::list_from_config(&config)?
+            .into_iter()
+            .filter(|plugin| self.selects(plugin))
+            .map(|plugin| plugin.details.id)
+            .collect();
+        self.picker = Some(FuzzyList::new(plugins).searchable());
+        Ok(())
+    }
+
+    /// Runs the plugins selected by the args for which `filter` returns true
+    fn run(&self, filter: impl Fn(&Plugin) -> bool) -> AppResult<()> {
+        let current_dir = get_flag!(current_dir).clone();
+        let changed_files = match &self.args.changed {
+            Some(base) => Some(git::changed_files(&current_dir, base.as_deref())),
+            None if self.args.staged => Some(git::staged_files(&current_dir)),
+            None => None,
+        };
+        // Always set, as a daemon runs several commands
+        let changed_files = changed_files.and_then(|files| {
+            files
+                .inspect_err(|err| {
+                    warn!(
+                        "Checking every file, as the changed files are unknown: {}",
+                        err
+                    )
+                })
+                .ok()
+        });
+        set_flag!(changed_files, changed_files);
 
-        let run_plugins: Vec<Plugin> = plugins
+        let toml = Arc::new(Config::load(get_flag!(config_path))?);
+        vm::set_memory_limits(&toml);
+        let plugins = plugin::list_from_config(&toml)?;
+
+        let mut run_plugins: Vec<Plugin> = plugins
             .clone()
             .iter()
-            .filter(|plugin| plugin.kind != PluginKind::Report && plugin.kind != PluginKind::Ci)
+            .filter(|plugin| self.selects(plugin) && filter(plugin))
             .filter(|plugin| {
-                if !self.args.lint && !self.args.test && self.args.all {
-                    true
-                } else if self.args.lint {
-                    plugin.kind == PluginKind::Lint
-                } else if self.args.test {
-                    plugin.kind == PluginKind::Test
-                } else {
-                    false
+                // Lint plugins have nothing to check if none of their files changed
+                let has_changes = plugin.kind != PluginKind::Lint
+                    || get_flag!(changed_files).as_ref().is_none_or(|files| {
+                        files
+                            .iter()
+                            .any(|file| has_extension(file, &plugin.details.extensions))
+                    });
+                if !has_changes {
+                    info!("Skipping {}, none of its files changed", plugin.details.id);
                 }
+                has_changes
             })
             .cloned()
             .collect();
 
+        // Test plugins that map the changed files to their tests only run the affected
+        // ones. Always set, as a daemon runs several commands.
+        let mut affected_tests = HashMap::new();
+        let changed_files = get_flag!(changed_files).clone();
+        if let Some(files) = changed_files.filter(|_| !self.args.full_suite) {
+            for plugin in run_plugins
+                .iter()
+                .filter(|plugin| plugin.kind == PluginKind::Test)
+            {
+                match plugin.affected_tests(&toml, &files) {
+                    Ok(Some(tests)) => {
+                        affected_tests.insert(plugin.details.id.clone(), tests);
+                    }
+                    Ok(None) => (),
+                    Err(err) => warn!(
+                        "Running every test of {}, as the affected tests are unknown: {}",
+                        plugin.details.id, err
+                    ),
+                }
+            }
+        }
+        run_plugins.retain(|plugin| match affected_tests.get(&plugin.details.id) {
+            Some(tests) if tests.is_empty() => {
+                info!(
+                    "Skipping {}, none of its tests are affected by the changes",
+                    plugin.details.id
+                );
+                false
+            }
+            Some(tests) => {
+                info!(
+                    "Running the tests of {} affected by the changes: {}",
+                    plugin.details.id,
+                    tests.join(", ")
+                );
+                true
+            }
+            None => true,
+        });
+        set_flag!(affected_tests, affected_tests);
+
         let report_plugins: Arc<Vec<Plugin>> = Arc::new(
             plugins
                 .iter()
@@ -83,84 +401,159 @@ impl AppWidget for TestWidget {
                 .collect(),
         );
 
+        // Findings the project already had are left out, so only new ones fail the run
+        let baseline = match self.args.no_baseline {
+            true => None,
+            false => Baseline::load().map(Arc::new),
+        };
+
+        // Runs of only the changed files find less than a cached run of every file
+        let cache = Arc::new(Mutex::new(RunCache::load()));
+        let fingerprints = match self.args.force || get_flag!(changed_files).is_some() {
+            true => BTreeMap::new(),
+            false => {
+                let cached_plugins = run_plugins
+                    .iter()
+                    .filter(|plugin| cache::is_cached(plugin))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                cache.lock().unwrap().fingerprints(&cached_plugins)
+            }
+        };
+        let fingerprints = Arc::new(fingerprints);
+        let remote_cache = RemoteCache::new(&toml).map(Arc::new);
+
         for plugin in run_plugins {
             let plugin = plugin.clone();
             let toml_clone = toml.clone();
+            let baseline = baseline.clone();
+            let cache = cache.clone();
+            let fingerprints = fingerprints.clone();
+            let remote_cache = remote_cache.clone();
             let report_plugins = Arc::clone(&report_plugins); // Share report plugins across threads
             let pool = self.thread_pool.as_ref().unwrap();
 
             pool.execute(move || {
+                let worker = Worker::new(Task::Test, &plugin);
                 info!("Testing with: {}", plugin.details.id);
-                let result = plugin.run(&toml_clone);
-
-                if let Err(err) = result {
-                    error!("{}", err);
-                    return;
-                }
 
-                let command = result.unwrap();
+                // Plugins whose files haven't changed since their last run aren't run again,
+                // and runs of the same files on CI or by teammates are shared remotely
+                let id = &plugin.details.id;
+                let fingerprint = fingerprints.get(id).cloned();
+                let cached = fingerprint
+                    .as_deref()
+                    .and_then(|fingerprint| cache.lock().unwrap().get(id, fingerprint).cloned());
+                let shared = match (&cached, &fingerprint, &remote_cache) {
+                    (None, Some(fingerprint), Some(remote)) => remote
+                        .get(id, fingerprint)
+                        .inspect_err(|err| warn!("Couldn't read the remote cache: {}", err))
+                        .ok()
+                        .flatten(),
+                    _ => None,
+                };
 
-                let cmd_output = std::process::Command::new(&command[0])
-                    .args(&command[1..])
-                    .current_dir(get_flag!(current_dir).as_path())
-                    .output();
-
-                info!("Running command: {:#?}", command);
+                let mut res = match (cached, shared) {
+                    (Some(output), _) => {
+                        info!(
+                            "Skipping {}, none of its files changed since its last run",
+                            id
+                        );
+                        output
+                    }
+                    (None, Some(output)) => {
+                        info!(
+                            "Skipping {}, the remote cache has a run of the same files",
+                            id
+                        );
+                        record_run(&cache, &plugin, fingerprint, &output);
+                        output
+                    }
+                    (None, None) => {
+                        let Some(output) = execute(&worker, &plugin, &toml_clone) else {
+                            return;
+                        };
+                        let remote = remote_cache.as_ref().filter(|remote| remote.is_writable());
+                        if let Some((remote, fingerprint)) = remote.zip(fingerprint.as_ref()) {
+                            if is_reusable(&plugin, &output) {
+                                if let Err(err) = remote.put(id, fingerprint, &output) {
+                                    warn!(
+                                        "Couldn't write the run of {} to the remote cache: {}",
+                                        id, err
+                                    );
+                                }
+                            }
+                        }
+                        record_run(&cache, &plugin, fingerprint, &output);
+                        output
+                    }
+                };
 
-                if let Err(e) = cmd_output {
-                    error!("Failed to execute command '{}': {}", command[0], e);
-                    return;
+                if plugin.kind == PluginKind::Lint {
+                    let count = res.test_results.len();
+                    suppressions::suppress(&plugin.details.id, &mut res);
+                    let suppressed = count - res.test_results.len();
+                    if suppressed > 0 {
+                        info!(
+                            "Suppressed {} findings of {} with comments",
+                            suppressed, plugin.details.id
+                        );
+                    }
                 }
+                if let Some(baseline) = baseline.filter(|_| plugin.kind == PluginKind::Lint) {
+                    let suppressed = baseline.subtract(&plugin.details.id, &mut res);
+                    if suppressed > 0 {
+                        info!(
+                            "Left out {} findings of {} in the baseline",
+                            suppressed, plugin.details.id
+                        );
+                    }
+                }
+                worker.evaluated(plugin.kind.clone(), &res);
+                for report_plugin in report_plugins.iter() {
+                    match report_plugin.report(&toml_clone, &res) {
+                        Err(e) => {
+                            worker.diagnostic(LogKind::Error, e.to_string());
+                        }
+                        Ok(res) => {
+                            for (file_name, contents) in res {
+                                // Reports aren't written once flint is quitting
+                                if is_cancelled() {
+                                    return;
+                                }
 
-                let output = cmd_output.unwrap();
+                                let flint_path = get_flag!(current_dir);
+                                let file_path = flint_path.join(&file_name);
 
-                let eval_result = plugin.eval(output);
+                                info!("Running report plugin: {}", report_plugin.details.id);
 
-                match eval_result {
-                    Err(e) => error!("Failed to evaluate plugin: {}", e),
-                    Ok(res) => {
-                        for report_plugin in report_plugins.iter() {
-                            match report_plugin.report(&toml_clone, &res) {
-                                Err(e) => {
-                                    error!("Report plugin error: {}", e);
-                                }
-                                Ok(res) => {
-                                    for (file_name, contents) in res {
-                                        let flint_path = get_flag!(current_dir);
-                                        let file_path = flint_path.join(&file_name);
-
-                                        info!(
-                                            "Running report plugin: {}",
-                                            report_plugin.details.id
-                                        );
-
-                                        if let Some(parent) = file_path.parent() {
-                                            if !parent.exists() {
-                                                fs::create_dir_all(parent).unwrap_or_else(|e| {
-                                                    error!(
-                                                        "Failed to create directory for {}: {}",
-                                                        file_name, e
-                                                    );
-                                                });
-                                            }
-                                        }
-
-                                        match std::fs::write(flint_path.join(&file_name), contents)
-                                        {
-                                            Ok(_) => (),
-                                            Err(e) => error!(
-                                                "Failed to write report file {}: {}",
-                                                file_name, e
-                                            ),
-                                        }
-
-                                        success!(
-                                            "Reported {} results to {} successfully",
-                                            plugin.details.id,
-                                            file_name
-                                        );
+                                if let Some(parent) = file_path.parent() {
+                                    if !parent.exists() {
+                                        fs::create_dir_all(parent).unwrap_or_else(|e| {
+                                            worker.diagnostic(
+                                                LogKind::Error,
+                                                format!(
+                                                    "Failed to create directory for {}: {}",
+                                                    file_name, e
+                                                ),
+                                            );
+                                        });
                                     }
                                 }
+
+                                match contents.write_to(&file_path) {
+                                    Ok(_) => worker.file_written(file_path),
+                                    Err(e) => worker.diagnostic(
+                                        LogKind::Error,
+                                        format!("Failed to write report file {}: {}", file_name, e),
+                                    ),
+                                }
+
+                                success!(
+                                    "Reported {} results to {} successfully",
+                                    plugin.details.id,
+                                    file_name
+                                );
                             }
                         }
                     }
@@ -170,25 +563,87 @@ impl AppWidget for TestWidget {
 
         Ok(())
     }
+}
+
+impl AppWidget for TestWidget {
+    fn setup(&mut self) -> AppResult<()> {
+        self.run(|_| true)
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &[RERUN_FAILED_TESTS, RUN_ONE_PLUGIN]
+    }
+
+    fn run_command(&mut self, command: &str) -> AppResult<()> {
+        match command {
+            RUN_ONE_PLUGIN => return self.open_picker(),
+            RERUN_FAILED_TESTS => (),
+            _ => return Ok(()),
+        }
+
+        let failed = self
+            .progress
+            .plugins()
+            .iter()
+            .filter(|progress| progress.status == PluginStatus::Failed)
+            .map(|progress| progress.plugin.clone())
+            .collect::<Vec<_>>();
+        if failed.is_empty() {
+            info!("There are no failed tests to re-run");
+            return Ok(());
+        }
+
+        self.run(|plugin| failed.contains(&plugin.details.id))
+    }
 
     fn set_thread_pool(&mut self, thread_pool: &ThreadPool) {
         self.thread_pool = Some(thread_pool.clone());
     }
 
-    fn handle_events(&mut self, event: crossterm::event::Event) -> AppResult<()> {
-        let _ = handle_key_events(event.clone(), |_, key_code| match key_code {
-            KeyCode::Up => {
-                self.logs_state.borrow_mut().scroll_up(1);
-                Ok(())
+    fn handle_worker_event(&mut self, event: &WorkerEvent) {
+        self.progress.handle_event(event);
+        self.tool_problems.handle_event(event);
+        self.plugin_errors.handle_event(event);
+    }
+
+    fn handle_tick(&mut self, tick: usize) {
+        self.progress.handle_tick(tick);
+    }
+
+    fn claims_key(&self, _key_event: &KeyEvent) -> bool {
+        // Every key goes to the picker while it's open
+        self.picker.is_some()
+    }
+
+    fn handle_events(&mut self, event: Event) -> AppResult<()> {
+        if let Some(picker) = self.picker.as_mut() {
+            if let Event::Key(KeyEvent {
+                code: KeyCode::Esc, ..
+            }) = event
+            {
+                self.picker = None;
+            } else if let Some(index) = picker.handle_event(&event) {
+                let id = picker.items()[index].clone();
+                self.picker = None;
+                self.run(|plugin| plugin.details.id == id)?;
             }
-            KeyCode::Down => {
-                self.logs_state.borrow_mut().scroll_down(1);
-                Ok(())
+            return Ok(());
+        }
+
+        if self.progress.handle_input(&event) {
+            return Ok(());
+        }
+
+        let _ = handle_key_events(event.clone(), |key_event, _| {
+            match get_action(&key_event) {
+                Some(Action::ScrollUp) => self.logs_state.borrow_mut().scroll_up(1),
+                Some(Action::ScrollDown) => self.logs_state.borrow_mut().scroll_down(1),
+                _ => (),
             }
-            _ => Ok(()),
+            Ok(())
         });
 
-        handle_mouse_event(event.clone(), |mouse_event| match mouse_event {
+        handle_mouse_event(event.clone(), |_, kind| match kind {
             MouseEventKind::ScrollUp => {
                 self.logs_state.borrow_mut().scroll_up(1);
                 Ok(())
@@ -202,12 +657,156 @@ impl AppWidget for TestWidget {
     }
 }
 
+/// Caches the output of the plugin's run in the project, if its inputs are known
+fn record_run(
+    cache: &Mutex<RunCache>,
+    plugin: &Plugin,
+    fingerprint: Option<String>,
+    output: &PluginEvalOutput,
+) {
+    let Some(fingerprint) = fingerprint else {
+        return;
+    };
+    let mut cache = cache.lock().unwrap();
+    cache.record(plugin, fingerprint, output);
+    if let Err(err) = cache.write() {
+        warn!("Couldn't cache the run of {}: {}", plugin.details.id, err);
+    }
+}
+
+/// Runs the plugin's command and evaluates its output, reporting why to the worker if
+/// it can't. Nothing is returned if it didn't run or flint is quitting.
+fn execute(worker: &Worker, plugin: &Plugin, toml: &Arc<Config>) -> Option<PluginEvalOutput> {
+    // Without its tools the plugin would only fail with an unclear error
+    let problems = plugin.tool_problems();
+    let is_missing_tools = problems.iter().any(|problem| problem.is_missing);
+    for problem in problems {
+        let kind = if problem.is_missing {
+            LogKind::Error
+        } else {
+            LogKind::Warn
+        };
+        worker.tool_problem(kind, problem);
+    }
+    if is_missing_tools || is_cancelled() {
+        return None;
+    }
+
+    // Running includes the command and evaluating its output, but not the reports
+    let timing = timings::time(Phase::Run, &plugin.details.id);
+    let result = plugin.run(toml);
+
+    if let Err(err) = result {
+        worker.diagnostic(LogKind::Error, err.to_string());
+        return None;
+    }
+
+    let command = result.unwrap();
+
+    if !is_allowed_to_run(plugin, &command) {
+        worker.diagnostic(
+            LogKind::Error,
+            format!("{} was not allowed to run", plugin.details.id),
+        );
+        return None;
+    }
+    if is_cancelled() {
+        return None;
+    }
+
+    let cmd_output = std::process::Command::new(&command[0])
+        .args(&command[1..])
+        .current_dir(get_flag!(current_dir).as_path())
+        .output();
+
+    info!("Running command: {:#?}", command);
+
+    if let Err(e) = cmd_output {
+        // Tools missing right after generating configs are the most common cause
+        let hint = match e.kind() {
+            std::io::ErrorKind::NotFound => ". Run flint setup to install it",
+            _ => "",
+        };
+        worker.diagnostic(
+            LogKind::Error,
+            format!("Failed to execute command '{}': {}{}", command[0], e, hint),
+        );
+        return None;
+    }
+
+    let output = cmd_output.unwrap();
+
+    let eval_result = plugin.eval(output);
+    drop(timing);
+
+    match eval_result {
+        Err(e) => {
+            worker.diagnostic(LogKind::Error, e.to_string());
+            None
+        }
+        Ok(output) => Some(output),
+    }
+}
+
+/// Set once every plugin is allowed to run commands for the rest of the session
+static ALLOW_ALL_PLUGINS: AtomicBool = AtomicBool::new(false);
+
+/// Asks whether a plugin may run its command. Plugins run without asking if
+/// there's no UI to ask in.
+fn is_allowed_to_run(plugin: &Plugin, command: &[String]) -> bool {
+    if ALLOW_ALL_PLUGINS.load(Ordering::Relaxed) {
+        return true;
+    }
+
+    let answer = modal::ask(Modal::select(
+        t!("Run plugin"),
+        t!(
+            "{plugin} wants to run:\n{command}",
+            plugin = plugin.details.id,
+            command = command.join(" ")
+        ),
+        vec![
+            t!("Allow").to_string(),
+            t!("Allow all plugins").to_string(),
+            t!("Deny").to_string(),
+        ],
+    ));
+
+    match answer {
+        None | Some(ModalResult::Selected(0)) => true,
+        Some(ModalResult::Selected(1)) => {
+            ALLOW_ALL_PLUGINS.store(true, Ordering::Relaxed);
+            true
+        }
+        _ => false,
+    }
+}
+
 impl WidgetRef for TestWidget {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let area = self.tool_problems.render_above(area, buf);
+        let area = self.plugin_errors.render_below(area, buf);
+        if let Some(picker) = &self.picker {
+            let block = Block::bordered()
+                .title(i18n::text(RUN_ONE_PLUGIN))
+                .title_bottom(t!("[enter to run, esc to close]"))
+                .border_style(theme().border)
+                .border_set(theme().symbols.border);
+            picker.render_ref(block.inner(area), buf);
+            block.render(area, buf);
+            return;
+        }
+
         let mut logs_state = self.logs_state.borrow_mut();
         ui!((area, buf) => {
-            Stateful(&mut logs_state) {
-                { self.logs }
+            Layout(
+                direction: pane_direction(area),
+                constraints: pane_constraints(area, self.progress.width(), self.progress.height())
+            ) {
+                { &self.progress },
+                Stateful(&mut logs_state) {
+                    { self.logs }
+                }
             }
         });
     }
@@ -0,0 +1,133 @@
+use clap::Parser;
+use std::io::{self, Write};
+use std::process::{Command, Output};
+use std::sync::mpsc;
+use std::thread;
+
+use super::{AppResult, FlintError};
+use flint_core::config::Config;
+use flint_core::workspace::{Member, Schedule, Workspace};
+use flint_core::{app_err, error, get_flag, info, success, warn};
+
+#[derive(Parser, Clone)]
+pub struct WorkspaceArgs {
+    /// How many members run at once
+    #[clap(short, long, default_value_t = 4)]
+    jobs: usize,
+
+    /// The flint command run in every member, like `test` or `generate --check`
+    #[clap(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
+
+/// Runs `flint workspace`, running a command in every member of the monorepo. Members
+/// run once the members they depend on succeeded, and members that don't depend on
+/// each other run at the same time.
+pub fn run(args: WorkspaceArgs) -> AppResult<()> {
+    let config = Config::load(get_flag!(config_path))?;
+    let workspace = match Workspace::load(get_flag!(current_dir), &config) {
+        Ok(Some(workspace)) => workspace,
+        Ok(None) => {
+            return Err(app_err!(
+                "flint.toml lists no workspace members, add them to [workspace] members"
+            ))
+        }
+        Err(FlintError::Err(message)) => return Err(app_err!("{}", message)),
+        Err(err) => return Err(app_err!("Unable to read the workspace: {}", err)),
+    };
+
+    let command = args.command.join(" ");
+    let mut schedule = Schedule::new(&workspace);
+    let mut failed = Vec::new();
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        let mut running = 0;
+        loop {
+            while running < args.jobs.max(1) {
+                let Some(member) = schedule.take_ready() else {
+                    break;
+                };
+                info!("{}: flint {}", member.name, command);
+                let sender = sender.clone();
+                let args = &args.command;
+                scope.spawn(move || {
+                    let _ = sender.send((member, run_member(member, args)));
+                });
+                running += 1;
+            }
+            if running == 0 {
+                break;
+            }
+
+            // Members run by the scope always send their output before it ends
+            let (member, output) = receiver.recv().unwrap();
+            running -= 1;
+            let success = match output {
+                Ok(output) => {
+                    // Each member's output is printed once it's done, so outputs of
+                    // members running at the same time aren't mixed
+                    let _ = io::stdout().write_all(&output.stdout);
+                    let _ = io::stderr().write_all(&output.stderr);
+                    output.status.success()
+                }
+                Err(err) => {
+                    error!("Unable to run flint in {}: {}", member.name, err);
+                    false
+                }
+            };
+            match success {
+                true => success!("{}: flint {} succeeded", member.name, command),
+                false => {
+                    error!("{}: flint {} failed", member.name, command);
+                    failed.push(member.name.as_str());
+                }
+            }
+            schedule.finish(member, success);
+        }
+    });
+
+    let skipped = schedule.skipped();
+    for member in skipped {
+        warn!(
+            "Skipped {}, as a member it depends on didn't succeed",
+            member.name
+        );
+    }
+    if !failed.is_empty() || !skipped.is_empty() {
+        return Err(app_err!(
+            "flint {} failed in {}{}",
+            command,
+            failed.join(", "),
+            match skipped.len() {
+                0 => String::new(),
+                skipped => format!(", skipping {} members depending on them", skipped),
+            }
+        ));
+    }
+    success!(
+        "flint {} succeeded in {} members",
+        command,
+        workspace.members.len()
+    );
+    Ok(())
+}
+
+/// Runs flint in the member with its own flint.toml. Global flags are passed on, as
+/// they aren't read from the config.
+fn run_member(member: &Member, args: &[String]) -> io::Result<Output> {
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg("--config-path")
+        .arg(member.path.join("flint.toml"))
+        .arg("--plugins-dir")
+        .arg(get_flag!(plugins_dir))
+        .arg("--color")
+        .arg(if *get_flag!(color) { "always" } else { "never" });
+    if *get_flag!(no_install) {
+        command.arg("--no-install");
+    }
+    if *get_flag!(offline) {
+        command.arg("--offline");
+    }
+    command.args(args).output()
+}
@@ -1,9 +1,14 @@
 use app::{App, AppArgs};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use flint_core::error::FlintError;
+use flint_core::timings::{self, TimingsSort};
+use flint_core::{get_flag, set_flag, trace};
+use std::path::Path;
 use util::flags::handle_global_flags;
+use util::terminal::TerminalGuard;
 
 pub mod app;
-pub mod plugin;
+pub mod report;
 pub mod util;
 pub mod widgets;
 
@@ -12,36 +17,166 @@ fn main() {
 
     let app_args = AppArgs::parse_from(&args);
 
+    trace::init();
     handle_global_flags(&app_args);
 
     // #[cfg(not(debug_assertions))]
     {
-        use app::{
-            help::HelpWidget, install::InstallWidget, test::TestWidget, AppWidget, AppWidgetArgs,
-        };
+        use app::{help::HelpWidget, AppWidget, AppWidgetArgs};
+        use flint_core::events::open_channel;
+        use report::{annotations::is_github_actions, Report};
+        use std::io::{self, IsTerminal};
+        use std::path::Path;
+        use std::time::Duration;
         use threadpool::ThreadPool;
-        let subcommand = args.get(1).unwrap();
+        // Read from the parsed arguments, as global flags like --config-path can come
+        // before the subcommand
+        let matches = AppArgs::command().get_matches_from(&args);
+        let subcommand = matches.subcommand_name().unwrap_or_default();
 
-        if ["test", "install"].contains(&subcommand.as_str()) {
+        if let Some(result) = app_args
+            .command
+            .as_ref()
+            .and_then(AppWidgetArgs::run_standalone)
+        {
+            if let Err(err) = result {
+                exit_with(err);
+            }
+            return;
+        }
+
+        // Checking generated configs only makes sense in scripts, so it never opens the UI
+        let is_check = app_args.is_check();
+        let is_fix_dry_run = app_args.is_fix_dry_run();
+        // The UI needs a terminal, so generating without one, like in a workspace member
+        // or with the output piped, writes the files without it. Screen readers follow
+        // the announced progress better than the UI.
+        let accessible = *get_flag!(accessible);
+        let is_generate_without_ui =
+            subcommand == "generate" && (!io::stdout().is_terminal() || accessible);
+
+        if ["test", "lint", "audit", "ci", "install", "update"].contains(&subcommand)
+            || is_check
+            || is_fix_dry_run
+            || is_generate_without_ui
+        {
             set_flag!(non_interactive, true);
-            let mut non_interactive_widget: Box<dyn AppWidget> = match app_args.command.unwrap() {
-                AppWidgetArgs::Install(args) => Box::new(InstallWidget::new(args)),
-                AppWidgetArgs::Test(args) => Box::new(TestWidget::new(args)),
-                _ => Box::new(HelpWidget::default()),
-            };
 
+            // Formatted findings and fix diffs are read by other tools, so nothing else goes
+            // to stdout
+            let lint_format = app_args.lint_format();
+            set_flag!(logs_on_stderr, lint_format.is_some() || is_fix_dry_run);
+
+            // Runs are sent to the daemon of the project if it's running, which has
+            // the plugins loaded already. Installed plugins are only found by a new daemon.
+            // Timed and exported runs aren't forwarded, as the daemon's time and spans can't
+            // be recorded here, and neither are accessible ones, as the daemon's logs come
+            // once it's done. The daemon found its plugins when it started, so runs with
+            // other plugins aren't forwarded either.
+            if !["install", "update", "fix"].contains(&subcommand)
+                && app_args.plugins_dir.is_none()
+                && !timings::is_enabled()
+                && !trace::is_exporting()
+                && !accessible
+            {
+                if let Some(has_failed) = app::daemon::forward(&args[1..]) {
+                    std::process::exit(has_failed as i32);
+                }
+            }
+
+            let sarif_path = app_args.sarif_path().map(Path::to_path_buf);
+            let trace_path = app_args.trace_path().map(Path::to_path_buf);
+            let failure_threshold = app_args.failure_threshold();
+            let mut non_interactive_widget: Box<dyn AppWidget> = app_args
+                .command
+                .unwrap()
+                .into_non_interactive_widget()
+                .unwrap_or_else(|| Box::new(HelpWidget::default()));
+
+            // Worker events are collected for the report once every plugin finished
+            let worker_events = open_channel();
             let thread_pool = ThreadPool::new(16);
             non_interactive_widget.set_thread_pool(&thread_pool);
 
-            non_interactive_widget.setup().unwrap();
+            if let Err(err) = non_interactive_widget.setup() {
+                exit_with(err);
+            }
 
-            thread_pool.join();
+            // Events are handled while the plugins run, so the report knows when they started
+            let mut report = Report::default();
+            loop {
+                let is_done = thread_pool.active_count() == 0 && thread_pool.queued_count() == 0;
+                while let Ok(event) = worker_events.recv_timeout(Duration::from_millis(50)) {
+                    report.handle_event(&event);
+                    if accessible {
+                        report::announce::announce(&report, &event);
+                    }
+                }
+                if is_done {
+                    break;
+                }
+            }
+            // Failures beyond the threshold exit with an error, so scripts and git hooks
+            // can stop on them
+            let mut has_failed = false;
+            if report.is_finished() {
+                has_failed = report.write().current.has_failed(failure_threshold);
+                report.log_vulnerabilities();
+                if let Some(path) = sarif_path {
+                    report.write_sarif(&path);
+                }
+                if let Some(format) = lint_format {
+                    report.print_lint_findings(format);
+                }
+                if is_github_actions() {
+                    report.print_annotations();
+                }
+                if is_fix_dry_run {
+                    app::fix::print_dry_run(&app::fix::plan(&report));
+                }
+            }
+            finish(app_args.timings_sort, trace_path.as_deref());
+            if has_failed {
+                std::process::exit(1);
+            }
             return;
         }
     }
 
-    let mut terminal = ratatui::init();
+    if let Some(app::AppWidgetArgs::Generate(args)) = &app_args.command {
+        if args.inline {
+            let result = app::inline::run_generate(args.clone());
+            finish(app_args.timings_sort, app_args.trace_path());
+            if let Err(err) = result {
+                exit_with(err);
+            }
+            return;
+        }
+    }
+
+    // The terminal is restored when the guard is dropped, even if the app panics
+    let timings_sort = app_args.timings_sort;
+    let trace_path = app_args.trace_path().map(std::path::Path::to_path_buf);
+    let mut terminal = TerminalGuard::init();
     let app_result = App::new(app_args).run(&mut terminal);
-    app_result.expect("Error while running app");
-    ratatui::restore();
+    // The table is printed once the terminal is restored, so it stays in the scrollback
+    drop(terminal);
+    finish(timings_sort, trace_path.as_deref());
+    if let Err(err) = app_result {
+        exit_with(err.into());
+    }
+}
+
+/// Prints the error that stopped flint, with its causes and how it may be fixed
+fn exit_with(err: FlintError) -> ! {
+    eprintln!("{}", err.details());
+    std::process::exit(1);
+}
+
+/// Prints the timings and exports the spans, once flint is done
+fn finish(sort: TimingsSort, trace_path: Option<&Path>) {
+    timings::finish(sort, trace_path);
+    if let Err(err) = trace::export() {
+        eprintln!("{}", err);
+    }
 }
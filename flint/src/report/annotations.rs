@@ -0,0 +1,96 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use super::{diagnostic_kind_name, evaluated_plugins, Report};
+use flint_core::get_flag;
+use flint_core::plugin::{exec::eval::TestCaseOutput, PluginKind};
+
+/// Whether flint runs in a GitHub Actions job, which shows annotations inline on PR diffs
+pub fn is_github_actions() -> bool {
+    env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true")
+}
+
+/// Renders a workflow command like `::error file=src/a.js,line=3::message` for every
+/// lint finding, failed test and diagnostic reported by a plugin
+pub fn render(report: &Report) -> Vec<String> {
+    let mut commands = Vec::new();
+    for kind in [PluginKind::Lint, PluginKind::Test] {
+        for (plugin, output) in evaluated_plugins(report, kind) {
+            for result in output.test_results.iter().filter(|result| !result.success) {
+                commands.push(result_command(&plugin.plugin, result));
+            }
+        }
+    }
+
+    for plugin in report.plugins() {
+        for (kind, message) in &plugin.diagnostics {
+            let level = match diagnostic_kind_name(*kind) {
+                "error" => "error",
+                "warn" => "warning",
+                _ => "notice",
+            };
+            commands.push(format!(
+                "::{} title={}::{}",
+                level,
+                escape_property(&plugin.plugin),
+                escape_data(message)
+            ));
+        }
+    }
+
+    commands
+}
+
+fn result_command(plugin: &str, result: &TestCaseOutput) -> String {
+    let level = match result.severity.as_deref() {
+        Some("warning") => "warning",
+        Some("note") | Some("info") => "notice",
+        _ => "error",
+    };
+
+    let mut properties = vec![format!(
+        "file={}",
+        escape_property(&workspace_path(Path::new(&result.file_name)))
+    )];
+    if let Some(line) = result.line_no {
+        properties.push(format!("line={}", line));
+    }
+    if let Some(column) = result.column_no {
+        properties.push(format!("col={}", column));
+    }
+    let title = match &result.rule_id {
+        Some(rule) => format!("{} ({})", plugin, rule),
+        None => plugin.to_string(),
+    };
+    properties.push(format!("title={}", escape_property(&title)));
+
+    let message = result.error_message.as_deref().unwrap_or("Test failed");
+    format!(
+        "::{} {}::{}",
+        level,
+        properties.join(","),
+        escape_data(message)
+    )
+}
+
+/// The path of a file relative to the checked out repository, which GitHub needs to
+/// place the annotation on the diff. The project root is used outside of a checkout.
+fn workspace_path(path: &Path) -> String {
+    let root = env::var_os("GITHUB_WORKSPACE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| get_flag!(current_dir).clone());
+    let path = path.strip_prefix(&root).unwrap_or(path);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Escapes the message of a workflow command, which ends at the first line break
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property of a workflow command, which also can't contain `:` or `,`
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
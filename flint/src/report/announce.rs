@@ -0,0 +1,101 @@
+use super::{PluginReport, Report};
+use crate::widgets::progress::PluginStatus;
+use flint_core::events::WorkerEvent;
+use flint_core::plugin::PluginKind;
+use flint_core::{error, info, success, t};
+
+/// Announces what changed in the progress of the plugins with `--accessible`, in a
+/// labeled line screen readers read as it's printed, instead of the progress panel.
+/// Called once the report handled the event.
+pub fn announce(report: &Report, event: &WorkerEvent) {
+    match event {
+        WorkerEvent::PluginStarted { plugin, .. } => {
+            info!("{}", t!("{plugin} started", plugin = plugin))
+        }
+        WorkerEvent::FileWritten { plugin, path, .. } => info!(
+            "{}",
+            t!(
+                "{plugin} wrote {file}",
+                plugin = plugin,
+                file = path.display()
+            )
+        ),
+        WorkerEvent::Finished { task, plugin, .. } => {
+            let plugins = report.plugins();
+            let Some(plugin) = plugins
+                .iter()
+                .find(|report| report.task == *task && report.plugin == *plugin)
+            else {
+                return;
+            };
+            let finished = plugins
+                .iter()
+                .filter(|report| report.status != PluginStatus::Running)
+                .count();
+
+            let duration = format!("{:.1}s", plugin.duration.unwrap_or_default().as_secs_f64());
+            let mut message = match plugin.status {
+                PluginStatus::Failed => t!(
+                    "{plugin} failed after {duration}",
+                    plugin = plugin.plugin,
+                    duration = duration
+                ),
+                _ => t!(
+                    "{plugin} finished in {duration}",
+                    plugin = plugin.plugin,
+                    duration = duration
+                ),
+            };
+            if let Some(results) = results(plugin) {
+                message = format!("{}: {}", message, results);
+            }
+            let progress = t!(
+                "{finished} of {total} plugins done",
+                finished = finished,
+                total = plugins.len()
+            );
+            match plugin.status {
+                PluginStatus::Failed => error!("{} ({})", message, progress),
+                _ => success!("{} ({})", message, progress),
+            }
+        }
+        // Logs are printed already, and diagnostics and tool problems are logged too
+        WorkerEvent::Log(..)
+        | WorkerEvent::Diagnostic { .. }
+        | WorkerEvent::ToolProblem { .. }
+        | WorkerEvent::Evaluated { .. } => (),
+    }
+}
+
+/// What the plugin found, like "2 errors, 1 warnings", if its output was evaluated
+fn results(plugin: &PluginReport) -> Option<String> {
+    let output = plugin.output.as_ref()?;
+    match plugin.kind {
+        PluginKind::Lint => {
+            let findings = output.test_results.iter().filter(|result| !result.success);
+            let (mut errors, mut warnings) = (0, 0);
+            for finding in findings {
+                match finding.severity.as_deref() {
+                    Some("warning") => warnings += 1,
+                    Some("note") | Some("info") => (),
+                    _ => errors += 1,
+                }
+            }
+            Some(format!(
+                "{}, {}",
+                t!("{count} errors", count = errors),
+                t!("{count} warnings", count = warnings)
+            ))
+        }
+        PluginKind::Test => Some(t!(
+            "{passed} of {total} tests passed",
+            passed = output.tests_passed,
+            total = output.total_tests
+        )),
+        PluginKind::Audit => Some(t!(
+            "{count} vulnerabilities",
+            count = output.test_results.len()
+        )),
+        _ => None,
+    }
+}
@@ -0,0 +1,193 @@
+use clap::ValueEnum;
+use std::path::PathBuf;
+
+use super::{escape, lint_findings, Report};
+use crate::util::theme::theme;
+use flint_core::get_flag;
+use flint_core::plugin::exec::eval::TestCaseOutput;
+
+/// The formats lint findings are printed in with `--format`, which are the same as
+/// the formatters of eslint, so problem matchers of editors and parsers of CI servers
+/// read them unchanged
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LintFormat {
+    /// A table of the findings of every file
+    Stylish,
+    /// A line for every finding
+    Compact,
+    /// Checkstyle XML
+    Checkstyle,
+}
+
+impl LintFormat {
+    pub fn render(self, report: &Report) -> String {
+        let files = findings(report);
+        match self {
+            LintFormat::Stylish => stylish(&files),
+            LintFormat::Compact => compact(&files),
+            LintFormat::Checkstyle => checkstyle(&files),
+        }
+    }
+}
+
+/// A lint finding with what every format prints. Lines and columns are 0 when the
+/// plugin didn't report them.
+struct Finding<'a> {
+    line: u32,
+    column: u32,
+    severity: &'static str,
+    message: &'a str,
+    /// The rule the finding broke, or the plugin that found it if it has no rule id
+    rule: &'a str,
+    plugin: &'a str,
+}
+
+impl<'a> Finding<'a> {
+    fn new(plugin: &'a str, finding: &'a TestCaseOutput) -> Self {
+        Self {
+            line: finding.line_no.unwrap_or_default(),
+            column: finding.column_no.unwrap_or_default(),
+            severity: match finding.severity.as_deref() {
+                Some("warning") => "warning",
+                Some("note") | Some("info") => "info",
+                _ => "error",
+            },
+            message: finding.error_message.as_deref().unwrap_or("Lint finding"),
+            rule: finding.rule_id.as_deref().unwrap_or(plugin),
+            plugin,
+        }
+    }
+}
+
+/// The findings of every file by absolute path, ordered by their position
+fn findings(report: &Report) -> Vec<(PathBuf, Vec<Finding<'_>>)> {
+    let current_dir = get_flag!(current_dir).clone();
+    lint_findings(report)
+        .into_iter()
+        .map(|(file_name, findings)| {
+            let mut findings = findings
+                .into_iter()
+                .map(|(plugin, finding)| Finding::new(plugin, finding))
+                .collect::<Vec<_>>();
+            findings.sort_by_key(|finding| (finding.line, finding.column));
+            (current_dir.join(file_name), findings)
+        })
+        .collect()
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+/// Like eslint's stylish: the findings of every file below its path, aligned in
+/// columns, then a count of the problems
+fn stylish(files: &[(PathBuf, Vec<Finding>)]) -> String {
+    let mut output = String::new();
+    for (path, findings) in files {
+        let rows = findings
+            .iter()
+            .map(|finding| {
+                [
+                    format!("{}:{}", finding.line, finding.column),
+                    finding.severity.to_string(),
+                    finding.message.to_string(),
+                    finding.rule.to_string(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let widths = (0..3)
+            .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+            .collect::<Vec<_>>();
+
+        output.push_str(&format!("\n{}\n", path.display()));
+        for [position, severity, message, rule] in rows {
+            output.push_str(&format!(
+                "  {:<w0$}  {:<w1$}  {:<w2$}  {}\n",
+                position,
+                severity,
+                message,
+                rule,
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+            ));
+        }
+    }
+
+    let all = files.iter().flat_map(|(_, findings)| findings);
+    let errors = all.clone().filter(|finding| finding.severity == "error");
+    let warnings = all.clone().filter(|finding| finding.severity == "warning");
+    let problems = all.count();
+    if problems > 0 {
+        output.push_str(&format!(
+            "\n{} {} ({}, {})\n",
+            theme().symbols.failed,
+            plural(problems, "problem"),
+            plural(errors.count(), "error"),
+            plural(warnings.count(), "warning"),
+        ));
+    }
+    output
+}
+
+/// Like eslint's compact: a line for every finding, then a count of the problems
+fn compact(files: &[(PathBuf, Vec<Finding>)]) -> String {
+    let mut output = String::new();
+    let mut problems = 0;
+    for (path, findings) in files {
+        for finding in findings {
+            let mut severity = finding.severity.to_string();
+            severity[..1].make_ascii_uppercase();
+            output.push_str(&format!(
+                "{}: line {}, col {}, {} - {} ({})\n",
+                path.display(),
+                finding.line,
+                finding.column,
+                severity,
+                finding.message,
+                finding.rule,
+            ));
+            problems += 1;
+        }
+    }
+
+    if problems > 0 {
+        output.push_str(&format!("\n{}\n", plural(problems, "problem")));
+    }
+    output
+}
+
+/// Checkstyle XML, with the plugin and rule of a finding as its source like
+/// `eslint.no-unused-vars`
+fn checkstyle(files: &[(PathBuf, Vec<Finding>)]) -> String {
+    let mut output =
+        String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?><checkstyle version=\"4.3\">");
+    for (path, findings) in files {
+        output.push_str(&format!(
+            "<file name=\"{}\">",
+            escape(&path.to_string_lossy())
+        ));
+        for finding in findings {
+            let source = if finding.rule == finding.plugin {
+                finding.plugin.to_string()
+            } else {
+                format!("{}.{}", finding.plugin, finding.rule)
+            };
+            output.push_str(&format!(
+                "<error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"{}\" />",
+                finding.line,
+                finding.column,
+                finding.severity,
+                escape(finding.message),
+                escape(&source),
+            ));
+        }
+        output.push_str("</file>");
+    }
+    output.push_str("</checkstyle>\n");
+    output
+}
@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{evaluated_plugins, task_name, vulnerabilities, Report};
+use crate::widgets::progress::PluginStatus;
+use flint_core::config::FailOn;
+use flint_core::events::Task;
+use flint_core::get_flag;
+use flint_core::logs::LogKind;
+use flint_core::plugin::PluginKind;
+use flint_core::t;
+
+/// The file runs are recorded in, relative to the project root
+pub const HISTORY_FILE: &str = ".flint/history.jsonl";
+
+pub fn history_file() -> PathBuf {
+    get_flag!(current_dir).join(HISTORY_FILE)
+}
+
+/// The findings a run may have without failing, from `[flint] fail_on` and
+/// `--max-warnings`, which fails a run with more warnings whatever `fail_on` is
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FailureThreshold {
+    pub fail_on: FailOn,
+    pub max_warnings: Option<usize>,
+}
+
+/// What a run of the plugins found, recorded as a line of the history file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    /// When the run finished, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// What was run, like `["lint", "test"]`: "generate" for generated configs and the
+    /// kinds of the plugins otherwise, so `flint lint` and `flint test` are told apart.
+    /// Runs are only compared to runs of the same tasks.
+    pub tasks: Vec<String>,
+    pub plugins: usize,
+    pub failed_plugins: usize,
+    /// Errors reported by plugins, lint findings with an error severity and critical or
+    /// high vulnerabilities. Moderate vulnerabilities are warnings.
+    pub errors: usize,
+    pub warnings: usize,
+    pub tests_passed: u32,
+    pub total_tests: u32,
+    pub duration_ms: u64,
+    /// How long every plugin took, by id
+    pub plugin_durations_ms: BTreeMap<String, u64>,
+}
+
+impl RunSummary {
+    pub fn new(report: &Report) -> Self {
+        let plugins = report.plugins();
+        let mut tasks = plugins
+            .iter()
+            .map(|plugin| match plugin.task {
+                Task::Generate => task_name(plugin.task).to_string(),
+                Task::Test => plugin.kind.to_string(),
+            })
+            .collect::<Vec<_>>();
+        tasks.sort();
+        tasks.dedup();
+
+        let mut errors = 0;
+        let mut warnings = 0;
+        for plugin in plugins {
+            for (kind, _) in &plugin.diagnostics {
+                match kind {
+                    LogKind::Error => errors += 1,
+                    LogKind::Warn => warnings += 1,
+                    _ => (),
+                }
+            }
+        }
+        for (_, output) in evaluated_plugins(report, PluginKind::Lint) {
+            for result in output.test_results.iter().filter(|result| !result.success) {
+                match result.severity.as_deref() {
+                    Some("warning") => warnings += 1,
+                    Some("note") | Some("info") => (),
+                    _ => errors += 1,
+                }
+            }
+        }
+        for vulnerability in vulnerabilities(report) {
+            match vulnerability.severity.level() {
+                "error" => errors += 1,
+                "warning" => warnings += 1,
+                _ => (),
+            }
+        }
+
+        let (tests_passed, total_tests) = evaluated_plugins(report, PluginKind::Test).fold(
+            (0, 0),
+            |(passed, total), (_, output)| {
+                (passed + output.tests_passed, total + output.total_tests)
+            },
+        );
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        Self {
+            timestamp,
+            tasks,
+            plugins: plugins.len(),
+            failed_plugins: plugins
+                .iter()
+                .filter(|plugin| plugin.status == PluginStatus::Failed)
+                .count(),
+            errors,
+            warnings,
+            tests_passed,
+            total_tests,
+            duration_ms: report.duration().as_millis() as u64,
+            plugin_durations_ms: plugins
+                .iter()
+                .filter_map(|plugin| {
+                    let duration = plugin.duration?;
+                    Some((plugin.plugin.clone(), duration.as_millis() as u64))
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether a plugin failed, had failing tests or found more than the threshold
+    /// allows, which makes the command exit with an error
+    pub fn has_failed(&self, threshold: FailureThreshold) -> bool {
+        let findings_fail = match threshold.fail_on {
+            FailOn::Error => self.errors > 0,
+            FailOn::Warning => self.errors > 0 || self.warnings > 0,
+            FailOn::Never => false,
+        };
+        let too_many_warnings = threshold
+            .max_warnings
+            .is_some_and(|max_warnings| self.warnings > max_warnings);
+
+        self.failed_plugins > 0
+            || self.tests_passed < self.total_tests
+            || findings_fail
+            || too_many_warnings
+    }
+
+    /// The percentage of tests that passed, if any tests were run
+    pub fn pass_rate(&self) -> Option<f64> {
+        (self.total_tests > 0).then(|| self.tests_passed as f64 / self.total_tests as f64 * 100.0)
+    }
+}
+
+/// A run compared to the previous run of the same tasks
+#[derive(Debug, Clone)]
+pub struct Trend {
+    pub current: RunSummary,
+    pub previous: Option<RunSummary>,
+}
+
+impl Trend {
+    /// The figures of the run with their change since the previous run, like
+    /// `("warnings", "42 (−5)")`
+    pub fn items(&self) -> Vec<(&'static str, String)> {
+        let current = &self.current;
+        let previous = self.previous.as_ref();
+
+        let mut items = vec![
+            (
+                t!("errors"),
+                count(current.errors, previous.map(|run| run.errors)),
+            ),
+            (
+                t!("warnings"),
+                count(current.warnings, previous.map(|run| run.warnings)),
+            ),
+        ];
+        if let Some(pass_rate) = current.pass_rate() {
+            let change = previous
+                .and_then(RunSummary::pass_rate)
+                .map(|previous| format!(" ({})", signed(pass_rate - previous, "%", 1)));
+            items.push((
+                t!("pass rate"),
+                format!("{:.1}%{}", pass_rate, change.unwrap_or_default()),
+            ));
+        }
+
+        let seconds = |run: &RunSummary| run.duration_ms as f64 / 1000.0;
+        let change =
+            previous.map(|run| format!(" ({})", signed(seconds(current) - seconds(run), "s", 1)));
+        items.push((
+            t!("duration"),
+            format!("{:.1}s{}", seconds(current), change.unwrap_or_default()),
+        ));
+        items
+    }
+}
+
+impl Trend {
+    /// The line logged after a run, which only compares it to the previous run if
+    /// the history has one
+    pub fn summary(&self) -> String {
+        match self.previous {
+            Some(_) => t!("Compared to the last run: {trend}", trend = self),
+            None => t!("Summary of the run: {trend}", trend = self),
+        }
+    }
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let items = self
+            .items()
+            .into_iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect::<Vec<_>>();
+        write!(f, "{}", items.join(", "))
+    }
+}
+
+/// A count with its change, like `42 (−5)`, or just the count without a previous run
+fn count(current: usize, previous: Option<usize>) -> String {
+    match previous {
+        Some(previous) => format!(
+            "{} ({})",
+            current,
+            signed(current as f64 - previous as f64, "", 0)
+        ),
+        None => current.to_string(),
+    }
+}
+
+/// A change with its sign, using a proper minus sign, like `+2.5%` or `−5`
+fn signed(change: f64, unit: &str, precision: usize) -> String {
+    let rounded = format!("{:.*}", precision, change.abs());
+    if rounded.trim_start_matches(['0', '.']).is_empty() {
+        format!("±{}{}", rounded, unit)
+    } else if change < 0.0 {
+        format!("−{}{}", rounded, unit)
+    } else {
+        format!("+{}{}", rounded, unit)
+    }
+}
+
+/// The last recorded run of the same tasks as `summary`
+pub fn previous_run(summary: &RunSummary) -> Option<RunSummary> {
+    let history = fs::read_to_string(history_file()).ok()?;
+    history
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<RunSummary>(line).ok())
+        .find(|run| run.tasks == summary.tasks)
+}
+
+/// Appends a run to the history file, creating it if needed
+pub fn record(summary: &RunSummary) -> std::io::Result<()> {
+    let path = history_file();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let line = serde_json::to_string(summary).map_err(std::io::Error::other)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(errors: usize, warnings: usize) -> RunSummary {
+        RunSummary {
+            timestamp: 0,
+            tasks: vec!["lint".to_string()],
+            plugins: 1,
+            failed_plugins: 0,
+            errors,
+            warnings,
+            tests_passed: 0,
+            total_tests: 0,
+            duration_ms: 1500,
+            plugin_durations_ms: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn summarizes_the_first_run_without_comparing() {
+        flint_core::i18n::set_locale(Some("en"));
+        let trend = Trend {
+            current: summary(2, 3),
+            previous: None,
+        };
+        assert_eq!(
+            trend.summary(),
+            "Summary of the run: errors: 2, warnings: 3, duration: 1.5s"
+        );
+    }
+
+    #[test]
+    fn compares_to_the_last_run() {
+        flint_core::i18n::set_locale(Some("en"));
+        let trend = Trend {
+            current: summary(2, 3),
+            previous: Some(summary(4, 3)),
+        };
+        assert_eq!(
+            trend.summary(),
+            "Compared to the last run: errors: 2 (−2), warnings: 3 (±0), duration: 1.5s (±0.0s)"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt::Write;
+
+use super::history::Trend;
+use super::{
+    diagnostic_kind_name, escape, evaluated_plugins, has_audits, lint_findings, location,
+    status_name, task_name, vulnerabilities, Report, ReportOptions,
+};
+use flint_core::get_flag;
+use flint_core::plugin::PluginKind;
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 960px; color: #1f2328; }
+h1 { margin-bottom: 0; }
+.root { color: #656d76; margin-top: 0.25rem; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1.5rem; }
+th, td { border-bottom: 1px solid #d0d7de; padding: 0.4rem 0.6rem; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+pre { margin: 0; white-space: pre-wrap; font-size: 0.85rem; }
+.succeeded, .passed { color: #1a7f37; }
+.failed, .error, .critical, .high { color: #cf222e; }
+.running, .warn, .moderate { color: #9a6700; }
+.bar { background: #d0d7de; height: 0.6rem; width: 12rem; }
+.bar > div { background: #1a7f37; height: 100%; }
+";
+
+/// Renders the report as a standalone HTML page
+pub fn render(report: &Report, options: &ReportOptions, trend: &Trend) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<p class=\"root\">{root}</p>\n",
+        title = escape(&options.title),
+        root = escape(&get_flag!(current_dir).display().to_string()),
+    );
+
+    render_trend(&mut html, trend);
+    render_plugins(&mut html, report);
+    render_diagnostics(&mut html, report);
+    render_vulnerabilities(&mut html, report);
+    render_tests(&mut html, report, options);
+    render_coverage(&mut html, report);
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// The figures of the run, with their change since the previous run of the same tasks
+fn render_trend(html: &mut String, trend: &Trend) {
+    html.push_str("<h2>Summary</h2>\n<table>\n");
+    for (name, value) in trend.items() {
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            name,
+            escape(&value)
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn render_plugins(html: &mut String, report: &Report) {
+    html.push_str("<h2>Plugins</h2>\n<table>\n");
+    html.push_str(
+        "<tr><th>Plugin</th><th>Task</th><th>Status</th><th>Files written</th><th>Diagnostics</th></tr>\n",
+    );
+    for plugin in report.plugins() {
+        let status = status_name(plugin.status);
+        let files = plugin
+            .files_written
+            .iter()
+            .map(|path| escape(&path.display().to_string()))
+            .collect::<Vec<_>>()
+            .join("<br>");
+
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td class=\"{status}\">{status}</td><td>{}</td><td>{}</td></tr>",
+            escape(&plugin.plugin),
+            task_name(plugin.task),
+            files,
+            plugin.diagnostics.len(),
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+/// Lists the failures found by lint plugins by file, followed by the diagnostics
+/// plugins reported while running, which don't belong to a file
+fn render_diagnostics(html: &mut String, report: &Report) {
+    let files = lint_findings(report);
+    let has_diagnostics = report
+        .plugins()
+        .iter()
+        .any(|plugin| !plugin.diagnostics.is_empty());
+
+    html.push_str("<h2>Diagnostics</h2>\n");
+    if files.is_empty() && !has_diagnostics {
+        html.push_str("<p>No diagnostics.</p>\n");
+        return;
+    }
+
+    for (file, results) in files {
+        let _ = writeln!(html, "<h3>{}</h3>\n<table>", escape(file));
+        html.push_str("<tr><th>Location</th><th>Plugin</th><th>Message</th></tr>\n");
+        for (plugin, result) in results {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td><pre>{}</pre></td></tr>",
+                location(result),
+                escape(plugin),
+                escape(result.error_message.as_deref().unwrap_or_default()),
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    if has_diagnostics {
+        html.push_str("<h3>Reported by plugins</h3>\n<table>\n");
+        html.push_str("<tr><th>Plugin</th><th>Kind</th><th>Message</th></tr>\n");
+        for plugin in report.plugins() {
+            for (kind, message) in &plugin.diagnostics {
+                let kind = diagnostic_kind_name(*kind);
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td class=\"{kind}\">{kind}</td><td><pre>{}</pre></td></tr>",
+                    escape(&plugin.plugin),
+                    escape(message),
+                );
+            }
+        }
+        html.push_str("</table>\n");
+    }
+}
+
+/// The vulnerabilities found by every audit plugin in one table, from the most severe
+fn render_vulnerabilities(html: &mut String, report: &Report) {
+    if !has_audits(report) {
+        return;
+    }
+
+    html.push_str("<h2>Vulnerabilities</h2>\n");
+    let vulnerabilities = vulnerabilities(report);
+    if vulnerabilities.is_empty() {
+        html.push_str("<p>No vulnerabilities.</p>\n");
+        return;
+    }
+
+    html.push_str("<table>\n");
+    html.push_str(
+        "<tr><th>Severity</th><th>Advisory</th><th>File</th><th>Message</th><th>Found by</th></tr>\n",
+    );
+    for vulnerability in vulnerabilities {
+        let severity = vulnerability.severity.name();
+        let _ = writeln!(
+            html,
+            "<tr><td class=\"{severity}\">{severity}</td><td>{}</td><td>{}</td><td><pre>{}</pre></td><td>{}</td></tr>",
+            escape(vulnerability.advisory.unwrap_or_default()),
+            escape(vulnerability.file),
+            escape(vulnerability.message.unwrap_or_default()),
+            escape(&vulnerability.plugins.join(", ")),
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn render_tests(html: &mut String, report: &Report, options: &ReportOptions) {
+    html.push_str("<h2>Test results</h2>\n");
+    let mut has_tests = false;
+    for (plugin, output) in evaluated_plugins(report, PluginKind::Test) {
+        has_tests = true;
+
+        let _ = writeln!(
+            html,
+            "<h3>{} ({} of {} passed)</h3>\n<table>",
+            escape(&plugin.plugin),
+            output.tests_passed,
+            output.total_tests
+        );
+        html.push_str("<tr><th>File</th><th>Location</th><th>Status</th><th>Message</th></tr>\n");
+        for result in &output.test_results {
+            if result.success && !options.passed_tests {
+                continue;
+            }
+
+            let status = if result.success { "passed" } else { "failed" };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td class=\"{status}\">{status}</td><td><pre>{}</pre></td></tr>",
+                escape(&result.file_name),
+                location(result),
+                escape(result.error_message.as_deref().unwrap_or_default()),
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    if !has_tests {
+        html.push_str("<p>No tests were run.</p>\n");
+    }
+}
+
+fn render_coverage(html: &mut String, report: &Report) {
+    html.push_str("<h2>Coverage</h2>\n");
+    let outputs = evaluated_plugins(report, PluginKind::Test).collect::<Vec<_>>();
+    if outputs.is_empty() {
+        html.push_str("<p>No tests were run.</p>\n");
+        return;
+    }
+
+    html.push_str("<table>\n<tr><th>Plugin</th><th>Passed</th><th>Passing</th><th></th></tr>\n");
+    for (plugin, output) in outputs {
+        let percentage = output.passing_percentage.clamp(0.0, 100.0);
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{} / {}</td><td>{:.1}%</td>\
+             <td><div class=\"bar\"><div style=\"width: {:.1}%\"></div></div></td></tr>",
+            escape(&plugin.plugin),
+            output.tests_passed,
+            output.total_tests,
+            percentage,
+            percentage,
+        );
+    }
+    html.push_str("</table>\n");
+}
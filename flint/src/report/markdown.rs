@@ -0,0 +1,263 @@
+use std::fmt::Write;
+
+use super::history::Trend;
+use super::{
+    diagnostic_kind_name, evaluated_plugins, has_audits, lint_findings, location, status_name,
+    task_name, vulnerabilities, Report, ReportOptions,
+};
+use crate::widgets::progress::PluginStatus;
+use flint_core::plugin::{exec::eval::TestCaseOutput, PluginKind};
+
+/// Tables with more rows than this are put in a collapsed section
+const COLLAPSE_ROWS: usize = 10;
+
+/// Renders the report as Markdown, for pull requests and CI job summaries. Long
+/// tables and failure output are put in `<details>` sections, which GitHub collapses.
+pub fn render(report: &Report, options: &ReportOptions, trend: &Trend) -> String {
+    let mut markdown = String::new();
+    let _ = writeln!(markdown, "# {}\n", options.title);
+
+    // The figures of the run, with their change since the previous run
+    let summary = trend
+        .items()
+        .into_iter()
+        .map(|(name, value)| format!("**{}:** {}", name, value))
+        .collect::<Vec<_>>();
+    let _ = writeln!(markdown, "{}\n", summary.join(" · "));
+
+    render_plugins(&mut markdown, report);
+    render_lint_findings(&mut markdown, report);
+    render_vulnerabilities(&mut markdown, report);
+    render_tests(&mut markdown, report, options);
+    markdown
+}
+
+fn render_plugins(markdown: &mut String, report: &Report) {
+    let failed = report
+        .plugins()
+        .iter()
+        .filter(|plugin| plugin.status == PluginStatus::Failed)
+        .count();
+    let _ = writeln!(
+        markdown,
+        "{} plugins run, {} failed\n",
+        report.plugins().len(),
+        failed
+    );
+
+    markdown.push_str("| Plugin | Task | Status | Files written | Diagnostics |\n");
+    markdown.push_str("| --- | --- | --- | --- | --- |\n");
+    for plugin in report.plugins() {
+        let status = match plugin.status {
+            PluginStatus::Running => "⏳",
+            PluginStatus::Succeeded => "✅",
+            PluginStatus::Failed => "❌",
+        };
+        let _ = writeln!(
+            markdown,
+            "| {} | {} | {} {} | {} | {} |",
+            cell(&plugin.plugin),
+            task_name(plugin.task),
+            status,
+            status_name(plugin.status),
+            plugin.files_written.len(),
+            plugin.diagnostics.len(),
+        );
+    }
+    markdown.push('\n');
+
+    let diagnostics = report
+        .plugins()
+        .iter()
+        .flat_map(|plugin| {
+            plugin
+                .diagnostics
+                .iter()
+                .map(move |(kind, message)| (plugin, kind, message))
+        })
+        .collect::<Vec<_>>();
+    if diagnostics.is_empty() {
+        return;
+    }
+
+    let mut table = String::from("| Plugin | Kind | Message |\n| --- | --- | --- |\n");
+    for (plugin, kind, message) in &diagnostics {
+        let _ = writeln!(
+            table,
+            "| {} | {} | {} |",
+            cell(&plugin.plugin),
+            diagnostic_kind_name(**kind),
+            cell(message)
+        );
+    }
+    details(
+        markdown,
+        &format!("Diagnostics reported by plugins ({})", diagnostics.len()),
+        &table,
+    );
+}
+
+fn render_lint_findings(markdown: &mut String, report: &Report) {
+    markdown.push_str("## Lint findings\n\n");
+    let files = lint_findings(report);
+    if files.is_empty() {
+        markdown.push_str("No lint findings.\n\n");
+        return;
+    }
+
+    for (file, results) in files {
+        let mut table = String::from("| Location | Plugin | Message |\n| --- | --- | --- |\n");
+        for (plugin, result) in &results {
+            let _ = writeln!(
+                table,
+                "| {} | {} | {} |",
+                location(result),
+                cell(plugin),
+                cell(result.error_message.as_deref().unwrap_or_default())
+            );
+        }
+
+        if results.len() > COLLAPSE_ROWS {
+            let summary = format!("<code>{}</code> ({})", cell(file), results.len());
+            details(markdown, &summary, &table);
+        } else {
+            let _ = writeln!(markdown, "### `{}` ({})\n\n{}", file, results.len(), table);
+        }
+    }
+}
+
+/// The vulnerabilities found by every audit plugin in one table, from the most severe
+fn render_vulnerabilities(markdown: &mut String, report: &Report) {
+    if !has_audits(report) {
+        return;
+    }
+
+    markdown.push_str("## Vulnerabilities\n\n");
+    let vulnerabilities = vulnerabilities(report);
+    if vulnerabilities.is_empty() {
+        markdown.push_str("No vulnerabilities.\n\n");
+        return;
+    }
+
+    let mut table = String::from(
+        "| Severity | Advisory | File | Message | Found by |\n| --- | --- | --- | --- | --- |\n",
+    );
+    for vulnerability in &vulnerabilities {
+        let _ = writeln!(
+            table,
+            "| {} | {} | {} | {} | {} |",
+            vulnerability.severity.name(),
+            cell(vulnerability.advisory.unwrap_or_default()),
+            cell(vulnerability.file),
+            cell(vulnerability.message.unwrap_or_default()),
+            cell(&vulnerability.plugins.join(", ")),
+        );
+    }
+
+    if vulnerabilities.len() > COLLAPSE_ROWS {
+        let summary = format!("Vulnerabilities ({})", vulnerabilities.len());
+        details(markdown, &summary, &table);
+    } else {
+        let _ = writeln!(markdown, "{}", table);
+    }
+}
+
+fn render_tests(markdown: &mut String, report: &Report, options: &ReportOptions) {
+    markdown.push_str("## Test results\n\n");
+    let mut has_tests = false;
+    for (plugin, output) in evaluated_plugins(report, PluginKind::Test) {
+        has_tests = true;
+        let _ = writeln!(
+            markdown,
+            "### {}: {} of {} passed ({:.1}%)\n",
+            plugin.plugin,
+            output.tests_passed,
+            output.total_tests,
+            output.passing_percentage.clamp(0.0, 100.0)
+        );
+
+        let results = output
+            .test_results
+            .iter()
+            .filter(|result| !result.success || options.passed_tests)
+            .collect::<Vec<_>>();
+        if results.is_empty() {
+            continue;
+        }
+
+        let mut table =
+            String::from("| File | Location | Status | Message |\n| --- | --- | --- | --- |\n");
+        for result in &results {
+            let status = if result.success {
+                "✅ passed"
+            } else {
+                "❌ failed"
+            };
+            let message = result.error_message.as_deref().unwrap_or_default();
+            let _ = writeln!(
+                table,
+                "| {} | {} | {} | {} |",
+                cell(&result.file_name),
+                location(result),
+                status,
+                cell(message.lines().next().unwrap_or_default())
+            );
+        }
+
+        if results.len() > COLLAPSE_ROWS {
+            details(markdown, &format!("{} results", results.len()), &table);
+        } else {
+            let _ = writeln!(markdown, "{}", table);
+        }
+
+        for result in results.iter().filter(|result| has_long_output(result)) {
+            let output = result.error_message.as_deref().unwrap_or_default();
+            // A longer fence keeps fences in the output from closing the block
+            let fence = if output.contains("```") {
+                "````"
+            } else {
+                "```"
+            };
+            details(
+                markdown,
+                &format!(
+                    "Output of <code>{}</code> {}",
+                    cell(&result.file_name),
+                    location(result)
+                ),
+                &format!("{fence}\n{}\n{fence}\n", output.trim_end()),
+            );
+        }
+    }
+
+    if !has_tests {
+        markdown.push_str("No tests were run.\n\n");
+    }
+}
+
+/// Whether a failure message is longer than the first line shown in the table
+fn has_long_output(result: &TestCaseOutput) -> bool {
+    result
+        .error_message
+        .as_deref()
+        .is_some_and(|message| message.trim_end().lines().count() > 1)
+}
+
+/// A section GitHub shows collapsed, with the summary as its title
+fn details(markdown: &mut String, summary: &str, contents: &str) {
+    let _ = writeln!(
+        markdown,
+        "<details>\n<summary>{}</summary>\n\n{}\n</details>\n",
+        summary, contents
+    );
+}
+
+/// Text in a table cell, which can't contain line breaks or unescaped pipes
+pub fn cell(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
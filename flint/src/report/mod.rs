@@ -0,0 +1,480 @@
+pub mod annotations;
+pub mod announce;
+pub mod format;
+pub mod history;
+pub mod html;
+pub mod markdown;
+pub mod sarif;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::widgets::progress::PluginStatus;
+use flint_core::audit::{self, Vulnerability};
+use flint_core::config::Config;
+use flint_core::events::{Task, WorkerEvent};
+use flint_core::logs::LogKind;
+use flint_core::plugin::{
+    exec::eval::{PluginEvalOutput, TestCaseOutput},
+    PluginKind,
+};
+use flint_core::{error, get_flag, info, success, t, warn};
+use history::{RunSummary, Trend};
+
+fn default_output_path() -> PathBuf {
+    PathBuf::from(".")
+}
+
+fn default_title() -> String {
+    "flint report".into()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// The formats reports are written in, each configured in its own section
+/// like `[report.html]`. Formats without a section aren't written.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 2] = [ReportFormat::Html, ReportFormat::Markdown];
+
+    /// The name of the format, as used in `[report.<name>]`, which is one of the
+    /// `REPORT_FORMATS` that aren't report plugins
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "markdown",
+        }
+    }
+
+    /// The keys of the format's section, read from its options
+    pub fn options(&self) -> Vec<String> {
+        match self {
+            ReportFormat::Html => option_names::<ReportOptions>(),
+            ReportFormat::Markdown => option_names::<MarkdownReportConfig>(),
+        }
+    }
+
+    /// The name of the file the report is written to, in its `output_path`
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "report.html",
+            ReportFormat::Markdown => "report.md",
+        }
+    }
+}
+
+/// The options every report format has
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReportOptions {
+    /// The directory the report is written to, relative to the project root
+    #[serde(default = "default_output_path")]
+    pub output_path: PathBuf,
+    #[serde(default = "default_title")]
+    pub title: String,
+    /// Whether passed tests are listed, or only counted
+    #[serde(default = "default_true")]
+    pub passed_tests: bool,
+}
+
+/// The options of the Markdown report in `[report.markdown]`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MarkdownReportConfig {
+    #[serde(flatten)]
+    pub options: ReportOptions,
+    /// Whether the report is also appended to `$GITHUB_STEP_SUMMARY`, when it's set
+    #[serde(default = "default_true")]
+    pub step_summary: bool,
+}
+
+/// The keys of a section of options, which all have defaults
+fn option_names<T: DeserializeOwned + Serialize>() -> Vec<String> {
+    let defaults = toml::Value::Table(toml::Table::new()).try_into::<T>();
+    match defaults.map(toml::Value::try_from) {
+        Ok(Ok(toml::Value::Table(options))) => options.keys().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Reads the section of a format, skipping it with a warning if it's invalid
+fn format_config<T: DeserializeOwned>(config: &Config, format: ReportFormat) -> Option<T> {
+    let options = config.report.get(format.name())?.clone();
+    match options.try_into() {
+        Ok(options) => Some(options),
+        Err(err) => {
+            warn!("Ignoring [report.{}]: {}", format.name(), err);
+            None
+        }
+    }
+}
+
+/// Everything a plugin did in a run of generate or test
+#[derive(Clone, Debug)]
+pub struct PluginReport {
+    pub task: Task,
+    pub plugin: String,
+    pub status: PluginStatus,
+    pub kind: PluginKind,
+    pub files_written: Vec<PathBuf>,
+    pub diagnostics: Vec<(LogKind, String)>,
+    pub output: Option<PluginEvalOutput>,
+    /// When the start of the plugin was received, which the duration of the run is
+    /// measured from
+    pub started: Instant,
+    /// How long the plugin ran, once it finished
+    pub duration: Option<Duration>,
+}
+
+/// What the plugins of a session did, built from the worker events like the progress
+/// panel. A plugin that's run again replaces its previous run.
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    plugins: Vec<PluginReport>,
+}
+
+impl Report {
+    pub fn handle_event(&mut self, event: &WorkerEvent) {
+        match event {
+            WorkerEvent::PluginStarted { task, plugin, kind } => {
+                let report = PluginReport {
+                    task: *task,
+                    plugin: plugin.clone(),
+                    status: PluginStatus::Running,
+                    kind: kind.clone(),
+                    files_written: Vec::new(),
+                    diagnostics: Vec::new(),
+                    output: None,
+                    started: Instant::now(),
+                    duration: None,
+                };
+
+                match self.get_mut(*task, plugin) {
+                    Some(previous) => *previous = report,
+                    None => self.plugins.push(report),
+                }
+            }
+            WorkerEvent::FileWritten { task, plugin, path } => {
+                if let Some(report) = self.get_mut(*task, plugin) {
+                    report.files_written.push(path.clone());
+                }
+            }
+            WorkerEvent::Diagnostic {
+                task,
+                plugin,
+                kind,
+                message,
+            } => {
+                if let Some(report) = self.get_mut(*task, plugin) {
+                    report.diagnostics.push((*kind, message.clone()));
+                }
+            }
+            WorkerEvent::Evaluated {
+                task,
+                plugin,
+                output,
+                ..
+            } => {
+                if let Some(report) = self.get_mut(*task, plugin) {
+                    report.output = Some(output.clone());
+                }
+            }
+            WorkerEvent::Finished {
+                task,
+                plugin,
+                success,
+                duration,
+            } => {
+                if let Some(report) = self.get_mut(*task, plugin) {
+                    report.status = if *success {
+                        PluginStatus::Succeeded
+                    } else {
+                        PluginStatus::Failed
+                    };
+                    report.duration = Some(*duration);
+                }
+            }
+            // Tool problems are also sent as diagnostics
+            WorkerEvent::Log(..) | WorkerEvent::ToolProblem { .. } => (),
+        }
+    }
+
+    fn get_mut(&mut self, task: Task, plugin: &str) -> Option<&mut PluginReport> {
+        self.plugins
+            .iter_mut()
+            .find(|report| report.task == task && report.plugin == plugin)
+    }
+
+    pub fn plugins(&self) -> &[PluginReport] {
+        &self.plugins
+    }
+
+    /// Whether any plugin was run and every plugin finished
+    pub fn is_finished(&self) -> bool {
+        !self.plugins.is_empty()
+            && self
+                .plugins
+                .iter()
+                .all(|report| report.status != PluginStatus::Running)
+    }
+
+    /// How long the plugins took, from the first start to the last finish
+    pub fn duration(&self) -> Duration {
+        let Some(started) = self.plugins.iter().map(|report| report.started).min() else {
+            return Duration::ZERO;
+        };
+        self.plugins
+            .iter()
+            .filter_map(|report| Some(report.started + report.duration?))
+            .max()
+            .map_or(Duration::ZERO, |finished| finished - started)
+    }
+
+    /// Records the run in the history, and writes the report in every format
+    /// configured under `[report]`. Returns the run compared to the previous one.
+    pub fn write(&self) -> Trend {
+        let summary = RunSummary::new(self);
+        let trend = Trend {
+            previous: history::previous_run(&summary),
+            current: summary,
+        };
+        if let Err(err) = history::record(&trend.current) {
+            error!(
+                "Failed to record the run in {}: {}",
+                history::HISTORY_FILE,
+                err
+            );
+        }
+        info!("{}", trend.summary());
+
+        let Ok(config) = Config::load(get_flag!(config_path)) else {
+            return trend;
+        };
+
+        if let Some(options) = format_config::<ReportOptions>(&config, ReportFormat::Html) {
+            let html = html::render(self, &options, &trend);
+            self.write_file(ReportFormat::Html, &options, html);
+        }
+
+        if let Some(markdown_config) =
+            format_config::<MarkdownReportConfig>(&config, ReportFormat::Markdown)
+        {
+            let markdown = markdown::render(self, &markdown_config.options, &trend);
+            if markdown_config.step_summary {
+                append_step_summary(&markdown);
+            }
+            self.write_file(ReportFormat::Markdown, &markdown_config.options, markdown);
+        }
+        trend
+    }
+
+    /// Writes the findings of the lint and audit plugins to a SARIF file, for code
+    /// scanning tools
+    pub fn write_sarif(&self, path: &Path) {
+        let sarif =
+            serde_json::to_string_pretty(&sarif::render(self)).expect("SARIF is always valid JSON");
+        let result = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent),
+            _ => Ok(()),
+        }
+        .and_then(|_| fs::write(path, sarif));
+
+        match result {
+            Ok(_) => success!("{}", t!("Wrote SARIF to {path}", path = path.display())),
+            Err(err) => error!(
+                "{}",
+                t!(
+                    "Failed to write SARIF to {path}: {error}",
+                    path = path.display(),
+                    error = err
+                )
+            ),
+        }
+    }
+
+    /// Prints a GitHub Actions workflow command for every finding, so they're shown
+    /// inline on the diff of a pull request
+    /// Prints the lint findings to stdout in the format asked for with `--format`
+    pub fn print_lint_findings(&self, format: format::LintFormat) {
+        print!("{}", format.render(self));
+    }
+
+    /// Logs the vulnerabilities found by the audit plugins, from the most severe
+    pub fn log_vulnerabilities(&self) {
+        let vulnerabilities = vulnerabilities(self);
+        for vulnerability in &vulnerabilities {
+            let message = format!(
+                "{} {} in {}: {} (found by {})",
+                vulnerability.severity.name(),
+                vulnerability.advisory.unwrap_or("vulnerability"),
+                vulnerability.file,
+                vulnerability.message.unwrap_or("Vulnerable dependency"),
+                vulnerability.plugins.join(", ")
+            );
+            match vulnerability.severity.level() {
+                "error" => error!("{}", message),
+                "warning" => warn!("{}", message),
+                _ => info!("{}", message),
+            }
+        }
+        if !vulnerabilities.is_empty() {
+            info!(
+                "{}",
+                t!("Vulnerabilities: {count}", count = vulnerabilities.len())
+            );
+        }
+    }
+
+    pub fn print_annotations(&self) {
+        for command in annotations::render(self) {
+            println!("{}", command);
+        }
+    }
+
+    fn write_file(&self, format: ReportFormat, options: &ReportOptions, contents: String) {
+        let output_path = get_flag!(current_dir).join(&options.output_path);
+        let file_path = output_path.join(format.file_name());
+        let result = fs::create_dir_all(&output_path).and_then(|_| fs::write(&file_path, contents));
+
+        match result {
+            Ok(_) => success!(
+                "{}",
+                t!(
+                    "Wrote {format} report to {path}",
+                    format = format.name(),
+                    path = file_path.display()
+                )
+            ),
+            Err(err) => error!(
+                "{}",
+                t!(
+                    "Failed to write {format} report to {path}: {error}",
+                    format = format.name(),
+                    path = file_path.display(),
+                    error = err
+                )
+            ),
+        }
+    }
+}
+
+/// Appends the report to the summary of the GitHub Actions job, if flint runs in one
+fn append_step_summary(markdown: &str) {
+    let Some(path) = env::var_os("GITHUB_STEP_SUMMARY") else {
+        return;
+    };
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", markdown));
+    if let Err(err) = result {
+        error!(
+            "Failed to append the report to $GITHUB_STEP_SUMMARY: {}",
+            err
+        );
+    }
+}
+
+fn task_name(task: Task) -> &'static str {
+    match task {
+        Task::Generate => "generate",
+        Task::Test => "test",
+    }
+}
+
+fn status_name(status: PluginStatus) -> &'static str {
+    match status {
+        PluginStatus::Running => "running",
+        PluginStatus::Succeeded => "succeeded",
+        PluginStatus::Failed => "failed",
+    }
+}
+
+fn diagnostic_kind_name(kind: LogKind) -> &'static str {
+    match kind {
+        LogKind::Error => "error",
+        LogKind::Warn => "warn",
+        LogKind::Info | LogKind::Success | LogKind::Debug => "info",
+    }
+}
+
+/// The line and column of a result, like `12:4`, if the plugin reported them
+fn location(result: &TestCaseOutput) -> String {
+    match (result.line_no, result.column_no) {
+        (Some(line), Some(column)) => format!("{}:{}", line, column),
+        (Some(line), None) => line.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Escapes text for the HTML report and the XML of checkstyle findings
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+/// The plugins of a kind that had their output evaluated, with their output
+fn evaluated_plugins(
+    report: &Report,
+    kind: PluginKind,
+) -> impl Iterator<Item = (&PluginReport, &PluginEvalOutput)> {
+    report.plugins().iter().filter_map(move |plugin| {
+        let output = plugin.output.as_ref()?;
+        (plugin.kind == kind).then_some((plugin, output))
+    })
+}
+
+/// The failures found by lint plugins by file, with the plugin that found them
+pub fn lint_findings(report: &Report) -> BTreeMap<&str, Vec<(&str, &TestCaseOutput)>> {
+    let mut files: BTreeMap<&str, Vec<(&str, &TestCaseOutput)>> = BTreeMap::new();
+    for (plugin, output) in evaluated_plugins(report, PluginKind::Lint) {
+        for result in output.test_results.iter().filter(|result| !result.success) {
+            files
+                .entry(&result.file_name)
+                .or_default()
+                .push((&plugin.plugin, result));
+        }
+    }
+    files
+}
+
+/// The vulnerabilities found by the audit plugins, ranked from the most severe
+pub fn vulnerabilities(report: &Report) -> Vec<Vulnerability<'_>> {
+    audit::rank(
+        evaluated_plugins(report, PluginKind::Audit).flat_map(|(plugin, output)| {
+            output
+                .test_results
+                .iter()
+                .map(move |result| (plugin.plugin.as_str(), result))
+        }),
+    )
+}
+
+/// Whether an audit plugin was run, so the report has a section for what it found
+fn has_audits(report: &Report) -> bool {
+    evaluated_plugins(report, PluginKind::Audit)
+        .next()
+        .is_some()
+}
@@ -0,0 +1,158 @@
+use serde_json::{json, Value};
+use std::path::Path;
+
+use super::{evaluated_plugins, Report};
+use flint_core::audit::Severity;
+use flint_core::get_flag;
+use flint_core::plugin::{exec::eval::TestCaseOutput, PluginKind};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Renders the findings of the lint and audit plugins as a SARIF 2.1.0 log, with a run
+/// for every plugin. The rules of a run are the rule ids its plugin reported, which are
+/// advisories for audit plugins.
+pub fn render(report: &Report) -> Value {
+    let runs = evaluated_plugins(report, PluginKind::Lint)
+        .chain(evaluated_plugins(report, PluginKind::Audit))
+        .map(|(plugin, output)| {
+            let is_audit = plugin.kind == PluginKind::Audit;
+            let findings = output
+                .test_results
+                .iter()
+                .filter(|result| !result.success)
+                .collect::<Vec<_>>();
+
+            // Findings without a rule id are reported under a rule named after the plugin
+            let mut rules: Vec<&str> = Vec::new();
+            for finding in &findings {
+                let rule = rule_id(finding, &plugin.plugin);
+                if !rules.contains(&rule) {
+                    rules.push(rule);
+                }
+            }
+
+            let results = findings
+                .iter()
+                .map(|finding| {
+                    let rule = rule_id(finding, &plugin.plugin);
+                    let index = rules.iter().position(|id| *id == rule);
+                    result(finding, rule, index.unwrap_or_default(), is_audit)
+                })
+                .collect::<Vec<_>>();
+
+            let rules = rules
+                .iter()
+                .map(|id| match is_audit {
+                    true => {
+                        // The most severe vulnerability of the advisory ranks it
+                        let severity = findings
+                            .iter()
+                            .filter(|finding| rule_id(finding, &plugin.plugin) == *id)
+                            .map(|finding| Severity::parse(finding.severity.as_deref()))
+                            .min()
+                            .unwrap_or(Severity::Unknown);
+                        security_rule(id, severity)
+                    }
+                    false => json!({ "id": id }),
+                })
+                .collect::<Vec<_>>();
+
+            json!({
+                "tool": {
+                    "driver": {
+                        "name": plugin.plugin,
+                        "rules": rules,
+                    }
+                },
+                "originalUriBaseIds": {
+                    "%SRCROOT%": { "uri": format!("{}/", file_uri(get_flag!(current_dir))) }
+                },
+                "results": results,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": runs,
+    })
+}
+
+fn rule_id<'a>(finding: &'a TestCaseOutput, plugin: &'a str) -> &'a str {
+    finding.rule_id.as_deref().unwrap_or(plugin)
+}
+
+/// A rule for an advisory, tagged as a security finding with the score code scanning
+/// tools rank it by
+fn security_rule(id: &str, severity: Severity) -> Value {
+    let mut properties = json!({ "tags": ["security"] });
+    if let Some(score) = severity.score() {
+        properties["security-severity"] = score.into();
+    }
+    json!({ "id": id, "properties": properties })
+}
+
+fn result(finding: &TestCaseOutput, rule: &str, rule_index: usize, is_audit: bool) -> Value {
+    let level = match finding.severity.as_deref() {
+        _ if is_audit => Severity::parse(finding.severity.as_deref()).level(),
+        Some("warning") => "warning",
+        Some("note") | Some("info") => "note",
+        _ => "error",
+    };
+    let default_message = match is_audit {
+        true => "Vulnerable dependency",
+        false => "Lint finding",
+    };
+
+    let mut region = serde_json::Map::new();
+    if let Some(line) = finding.line_no {
+        region.insert("startLine".into(), line.into());
+        if let Some(column) = finding.column_no {
+            region.insert("startColumn".into(), column.into());
+        }
+    }
+
+    let mut physical_location = json!({
+        "artifactLocation": artifact_location(Path::new(&finding.file_name))
+    });
+    if !region.is_empty() {
+        physical_location["region"] = Value::Object(region);
+    }
+
+    json!({
+        "ruleId": rule,
+        "ruleIndex": rule_index,
+        "level": level,
+        "message": {
+            "text": finding.error_message.as_deref().unwrap_or(default_message),
+        },
+        "locations": [{ "physicalLocation": physical_location }],
+    })
+}
+
+/// The location of a file, relative to the project root with forward slashes as SARIF
+/// expects. Plugins report files with absolute paths or relative to the project root.
+fn artifact_location(path: &Path) -> Value {
+    let current_dir = get_flag!(current_dir);
+    let path = path.strip_prefix(current_dir).unwrap_or(path);
+    if path.is_absolute() {
+        return json!({ "uri": file_uri(path) });
+    }
+
+    let uri = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    json!({ "uri": uri, "uriBaseId": "%SRCROOT%" })
+}
+
+fn file_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    }
+}
@@ -1,68 +1,54 @@
-use std::{
-    path::{Path, PathBuf},
-    sync::{LazyLock, RwLock},
-};
+use crossterm::style::Stylize;
+use flint_core::config::Config;
+use flint_core::plugin::exec::vm;
+use flint_core::{get_flag, i18n, logs::set_prefix_style, set_flag, timings};
+use std::path::{Path, PathBuf};
 
 use crate::app::AppArgs;
+use crate::util::theme::{set_theme, theme, ColorChoice};
 
-pub struct Flags {
-    pub non_interactive: bool,
-    pub plugins_dir: PathBuf,
-    pub config_path: PathBuf,
-    pub current_dir: PathBuf,
-    pub no_install: bool,
-}
+pub fn handle_global_flags(app_args: &AppArgs) {
+    set_command_flags(app_args, &std::env::current_dir().unwrap());
 
-// Create a static global instance with RwLock
-pub static GLOBAL_FLAGS: LazyLock<RwLock<Flags>> = LazyLock::new(|| {
-    RwLock::new(Flags {
-        non_interactive: false,
-        plugins_dir: crate::plugin::dir(),
-        config_path: std::env::current_dir().unwrap().join("flint.toml"),
-        current_dir: std::env::current_dir().unwrap(),
-        no_install: false,
-    })
-});
+    // Messages are shown in the locale flint.toml sets, or else the system's
+    let config = Config::load(get_flag!(config_path)).ok();
+    let locale = config
+        .as_ref()
+        .and_then(|config| config.flint.locale.as_deref());
+    i18n::set_locale(locale);
 
-#[macro_export]
-macro_rules! get_flag {
-    ($name:ident) => {{
-        use $crate::util::flags::GLOBAL_FLAGS;
+    if let Some(config) = &config {
+        vm::set_memory_limits(config);
+    }
 
-        // Access the flag value through the RwLock read guard
-        &GLOBAL_FLAGS.read().unwrap().$name.clone()
-    }};
-}
+    let accessible = app_args.accessible || config.is_some_and(|config| config.flint.accessible);
+    set_flag!(accessible, accessible);
+    if accessible {
+        set_theme(theme().accessible());
+    }
 
-#[macro_export]
-macro_rules! set_flag {
-    ($name:ident, $value:expr) => {{
-        use $crate::util::flags::GLOBAL_FLAGS;
+    // crossterm honors NO_COLOR by itself, which --color overrides either way
+    if app_args.color != ColorChoice::Auto {
+        crossterm::style::force_color_output(app_args.color.is_enabled());
+    }
+    // Printed logs have their prefix in the color of their kind in the theme
+    set_prefix_style(|kind, prefix| prefix.with(theme().log_color(kind).into()).to_string());
 
-        // Acquire a write lock to safely modify the flag
-        let mut flags = GLOBAL_FLAGS.write().unwrap();
-        flags.$name = $value;
-    }};
+    if app_args.timings.is_some() {
+        timings::enable();
+    }
 }
 
-pub fn handle_global_flags(app_args: &AppArgs) {
+/// Sets the flags of a command line that only affect the command it runs, with the
+/// paths it gives relative to `working_dir`. The daemon sets them for every run it's
+/// sent, from the command line and directory of the `flint` that sent it.
+pub fn set_command_flags(app_args: &AppArgs, working_dir: &Path) {
     if let Some(ref plugins_dir) = app_args.plugins_dir {
-        let path = Path::new(plugins_dir);
-        let plugins_path = if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            std::env::current_dir().unwrap().join(&path)
-        };
-        set_flag!(plugins_dir, plugins_path);
+        set_flag!(plugins_dir, working_dir.join(plugins_dir));
     }
 
     if let Some(ref config_path) = app_args.config_path {
-        let path = Path::new(config_path);
-        let config_path = if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            std::env::current_dir().unwrap().join(&path)
-        };
+        let config_path = working_dir.join(config_path);
         // Update the current_dir based on the config path's parent directory
         let current_dir = config_path.parent().unwrap_or(Path::new("")).to_path_buf();
         set_flag!(current_dir, current_dir);
@@ -70,4 +56,47 @@ pub fn handle_global_flags(app_args: &AppArgs) {
     }
 
     set_flag!(no_install, app_args.no_install);
+    set_flag!(offline, app_args.offline);
+    set_flag!(color, app_args.color.is_enabled());
+}
+
+/// The flags a run of the daemon sets, which are set back to what they were when it's
+/// dropped, so the next run starts from the daemon's own flags
+pub struct SavedFlags {
+    plugins_dir: PathBuf,
+    config_path: PathBuf,
+    current_dir: PathBuf,
+    no_install: bool,
+    offline: bool,
+    color: bool,
+    non_interactive: bool,
+    logs_on_stderr: bool,
+}
+
+impl SavedFlags {
+    pub fn save() -> Self {
+        Self {
+            plugins_dir: get_flag!(plugins_dir).clone(),
+            config_path: get_flag!(config_path).clone(),
+            current_dir: get_flag!(current_dir).clone(),
+            no_install: *get_flag!(no_install),
+            offline: *get_flag!(offline),
+            color: *get_flag!(color),
+            non_interactive: *get_flag!(non_interactive),
+            logs_on_stderr: *get_flag!(logs_on_stderr),
+        }
+    }
+}
+
+impl Drop for SavedFlags {
+    fn drop(&mut self) {
+        set_flag!(plugins_dir, std::mem::take(&mut self.plugins_dir));
+        set_flag!(config_path, std::mem::take(&mut self.config_path));
+        set_flag!(current_dir, std::mem::take(&mut self.current_dir));
+        set_flag!(no_install, self.no_install);
+        set_flag!(offline, self.offline);
+        set_flag!(color, self.color);
+        set_flag!(non_interactive, self.non_interactive);
+        set_flag!(logs_on_stderr, self.logs_on_stderr);
+    }
 }
@@ -0,0 +1,292 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{LazyLock, RwLock},
+};
+
+use flint_core::{
+    config::{Config, KeyBinding},
+    get_flag, warn,
+};
+
+/// Named actions that keys are bound to. Widgets handle actions instead of
+/// matching on key codes, so every binding can be changed under `[flint.keys]`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Confirm,
+    ScrollUp,
+    ScrollDown,
+    NextView,
+    PreviousView,
+    /// Switches to the view at the given index
+    SelectView(usize),
+    CommandPalette,
+    /// Suspends flint to the background, like ctrl+z in any other terminal program
+    Suspend,
+    /// Shows or hides the hidden files in file pickers
+    ToggleHidden,
+    /// Sorts a table by its focused column, or reverses the order if it's sorted by it
+    SortColumn,
+    WidenColumn,
+    NarrowColumn,
+    /// Copies the details of an error to the clipboard
+    Copy,
+}
+
+/// Names of the SelectView actions, by view index
+const SELECT_VIEW_NAMES: [&str; 6] = ["view_1", "view_2", "view_3", "view_4", "view_5", "view_6"];
+
+impl Action {
+    pub const ALL: [Action; 19] = [
+        Action::Quit,
+        Action::Confirm,
+        Action::ScrollUp,
+        Action::ScrollDown,
+        Action::NextView,
+        Action::PreviousView,
+        Action::SelectView(0),
+        Action::SelectView(1),
+        Action::SelectView(2),
+        Action::SelectView(3),
+        Action::SelectView(4),
+        Action::SelectView(5),
+        Action::CommandPalette,
+        Action::Suspend,
+        Action::ToggleHidden,
+        Action::SortColumn,
+        Action::WidenColumn,
+        Action::NarrowColumn,
+        Action::Copy,
+    ];
+
+    /// The name of the action, as used in `[flint.keys]`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Confirm => "confirm",
+            Action::ScrollUp => "scroll_up",
+            Action::ScrollDown => "scroll_down",
+            Action::NextView => "next_view",
+            Action::PreviousView => "previous_view",
+            Action::SelectView(index) => SELECT_VIEW_NAMES[*index],
+            Action::CommandPalette => "command_palette",
+            Action::Suspend => "suspend",
+            Action::ToggleHidden => "toggle_hidden",
+            Action::SortColumn => "sort_column",
+            Action::WidenColumn => "widen_column",
+            Action::NarrowColumn => "narrow_column",
+            Action::Copy => "copy",
+        }
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "esc", "ctrl+c"],
+            Action::Confirm => &["enter"],
+            Action::ScrollUp => &["up"],
+            Action::ScrollDown => &["down"],
+            Action::NextView => &["tab"],
+            Action::PreviousView => &["shift+tab"],
+            Action::SelectView(index) => [&["1"], &["2"], &["3"], &["4"], &["5"], &["6"]][*index],
+            Action::CommandPalette => &["ctrl+p"],
+            Action::Suspend => &["ctrl+z"],
+            Action::ToggleHidden => &["ctrl+h"],
+            Action::SortColumn => &["s"],
+            Action::WidenColumn => &["+"],
+            Action::NarrowColumn => &["-"],
+            Action::Copy => &["c"],
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == name)
+            .ok_or_else(|| format!("Unknown action '{}'", name))
+    }
+}
+
+/// A key together with the modifiers that must be held, like `ctrl+c`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        // Shift is already part of the character for uppercase letters and symbols,
+        // and of the key code for shift+tab
+        let modifiers = match key_event.code {
+            KeyCode::Char(_) | KeyCode::BackTab => key_event.modifiers - KeyModifiers::SHIFT,
+            _ => key_event.modifiers,
+        };
+        self.code == key_event.code && self.modifiers == modifiers
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    /// Parses a chord like `q`, `esc`, `ctrl+c` or `shift+tab`
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts = chord.split('+').collect::<Vec<_>>();
+        // A trailing "+" is the plus key itself, like in "ctrl++"
+        let key = match parts.pop() {
+            Some("") if chord.ends_with('+') => {
+                parts.pop();
+                "+"
+            }
+            Some(key) => key,
+            None => return Err(format!("Empty key binding '{}'", chord)),
+        };
+
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                "super" | "cmd" => KeyModifiers::SUPER,
+                _ => return Err(format!("Unknown modifier '{}' in '{}'", modifier, chord)),
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" | "return" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            name if name.len() > 1 && name.starts_with('f') => match name[1..].parse::<u8>() {
+                Ok(number) => KeyCode::F(number),
+                Err(_) => return Err(format!("Unknown key '{}' in '{}'", key, chord)),
+            },
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(char), None) => KeyCode::Char(char),
+                    _ => return Err(format!("Unknown key '{}' in '{}'", key, chord)),
+                }
+            }
+        };
+
+        // Shift with a letter is reported as the uppercase letter, and shift+tab as backtab
+        let code = match code {
+            KeyCode::Char(char) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(char.to_ascii_uppercase())
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers -= KeyModifiers::SHIFT;
+        }
+
+        Ok(KeyChord { code, modifiers })
+    }
+}
+
+/// Maps actions to the key chords that trigger them
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyChord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let chords = action
+                    .default_keys()
+                    .iter()
+                    .map(|chord| chord.parse().expect("Invalid default key binding"))
+                    .collect();
+                (action, chords)
+            })
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Creates the default keymap, with the bindings given in `[flint.keys]` replacing
+    /// the default bindings of their actions. Invalid bindings are skipped with a warning.
+    pub fn from_config(keys: &HashMap<String, KeyBinding>) -> Self {
+        let mut keymap = Self::default();
+
+        for (name, binding) in keys {
+            let action = match name.parse::<Action>() {
+                Ok(action) => action,
+                Err(err) => {
+                    warn!("Ignoring key binding in [flint.keys]: {}", err);
+                    continue;
+                }
+            };
+
+            let chords = binding
+                .chords()
+                .into_iter()
+                .filter_map(|chord| match chord.parse::<KeyChord>() {
+                    Ok(chord) => Some(chord),
+                    Err(err) => {
+                        warn!("Ignoring key binding for '{}': {}", name, err);
+                        None
+                    }
+                })
+                .collect();
+
+            keymap.bindings.insert(action, chords);
+        }
+
+        keymap
+    }
+
+    /// Returns the action bound to a key event, if any
+    pub fn action(&self, key_event: &KeyEvent) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| {
+            self.bindings
+                .get(action)
+                .is_some_and(|chords| chords.iter().any(|chord| chord.matches(key_event)))
+        })
+    }
+}
+
+pub static KEYMAP: LazyLock<RwLock<Keymap>> = LazyLock::new(|| RwLock::new(Keymap::default()));
+
+/// Loads the key bindings from the config file, if it exists
+pub fn load_keymap() {
+    let config_path = get_flag!(config_path);
+    if !config_path.exists() {
+        return;
+    }
+
+    match Config::load(config_path) {
+        Ok(config) => *KEYMAP.write().unwrap() = Keymap::from_config(&config.flint.keys),
+        Err(err) => warn!("Could not load key bindings: {}", err),
+    }
+}
+
+/// Returns the action bound to a key event in the global keymap, if any
+pub fn get_action(key_event: &KeyEvent) -> Option<Action> {
+    KEYMAP.read().unwrap().action(key_event)
+}
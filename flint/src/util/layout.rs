@@ -0,0 +1,33 @@
+use ratatui::layout::{Constraint, Direction, Rect};
+
+/// The smallest terminal the views are readable in. Smaller terminals show a notice instead.
+pub const MIN_WIDTH: u16 = 80;
+pub const MIN_HEIGHT: u16 = 24;
+
+/// Panes are placed side by side from this width on, and stacked below it
+pub const WIDE_WIDTH: u16 = 120;
+
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+pub fn is_wide(area: Rect) -> bool {
+    area.width >= WIDE_WIDTH
+}
+
+/// The direction panes are split in: side by side on wide terminals, stacked otherwise
+pub fn pane_direction(area: Rect) -> Direction {
+    if is_wide(area) {
+        Direction::Horizontal
+    } else {
+        Direction::Vertical
+    }
+}
+
+/// The constraints of a pane followed by one that fills the rest of the area. The
+/// pane takes `width` columns when the panes are side by side and `height` rows when
+/// they're stacked.
+pub fn pane_constraints(area: Rect, width: u16, height: u16) -> [Constraint; 2] {
+    let size = if is_wide(area) { width } else { height };
+    [Constraint::Length(size), Constraint::Fill(1)]
+}
@@ -1,11 +1,10 @@
-use crossterm::event::{Event, KeyCode, KeyEvent};
+use crossterm::event::{Event, KeyCode, KeyEvent, MouseEvent, MouseEventKind};
 
 pub mod flags;
-pub mod lang;
-pub mod logs;
-pub mod toml;
-
-pub use lang::{detect_languages, get_language_map};
+pub mod keys;
+pub mod layout;
+pub mod terminal;
+pub mod theme;
 
 use crate::app::AppResult;
 
@@ -21,10 +20,10 @@ pub fn handle_key_events(
 
 pub fn handle_mouse_event(
     event: Event,
-    callback: impl FnOnce(crossterm::event::MouseEventKind) -> AppResult<()>,
+    callback: impl FnOnce(MouseEvent, MouseEventKind) -> AppResult<()>,
 ) -> AppResult<()> {
     if let Event::Mouse(mouse_event) = event {
-        return callback(mouse_event.kind);
+        return callback(mouse_event, mouse_event.kind);
     }
     Ok(())
 }
@@ -0,0 +1,173 @@
+use crossterm::{event::DisableMouseCapture, execute, terminal::disable_raw_mode};
+use ratatui::{DefaultTerminal, TerminalOptions, Viewport};
+use std::io::{self, Write};
+use std::ops::{Deref, DerefMut};
+use std::panic;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::thread;
+
+use flint_core::logs::log_file;
+
+/// The terminal flint draws to. It's restored when the guard is dropped and when
+/// any thread panics, so flint never leaves the terminal in raw mode.
+pub struct TerminalGuard {
+    terminal: DefaultTerminal,
+    is_inline: bool,
+}
+
+impl TerminalGuard {
+    pub fn init() -> Self {
+        let terminal = ratatui::init();
+        set_panic_hook();
+        register_suspend_signal();
+        Self {
+            terminal,
+            is_inline: false,
+        }
+    }
+
+    /// Draws to the given number of lines below the cursor instead of the alternate
+    /// screen. What's drawn stays in the scrollback once the guard is dropped.
+    pub fn inline(height: u16) -> Self {
+        let terminal = ratatui::init_with_options(TerminalOptions {
+            viewport: Viewport::Inline(height),
+        });
+        set_panic_hook();
+        Self {
+            terminal,
+            is_inline: true,
+        }
+    }
+}
+
+impl Deref for TerminalGuard {
+    type Target = DefaultTerminal;
+
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl DerefMut for TerminalGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if self.is_inline {
+            // Leaving the alternate screen would move the cursor, so only raw mode is left.
+            // The cursor is moved below the last frame, for the shell's prompt.
+            let _ = disable_raw_mode();
+            let _ = self.terminal.show_cursor();
+            println!();
+        } else {
+            restore();
+        }
+    }
+}
+
+/// Leaves raw mode and the alternate screen and stops capturing the mouse
+pub fn restore() {
+    let _ = execute!(io::stdout(), DisableMouseCapture);
+    ratatui::restore();
+}
+
+fn set_panic_hook() {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        hook(info);
+        eprintln!("Logs were written to {}", log_file().display());
+
+        // The UI can't keep drawing to a restored terminal, so a panic in a worker
+        // thread exits flint as well
+        if thread::current().name() != Some("main") {
+            process::exit(101);
+        }
+    }));
+}
+
+/// Set when flint should suspend itself, by the suspend action or by a SIGTSTP
+/// sent from outside. Ctrl+z doesn't send SIGTSTP while the terminal is in raw mode.
+static SUSPEND_REQUESTED: LazyLock<Arc<AtomicBool>> =
+    LazyLock::new(|| Arc::new(AtomicBool::new(false)));
+
+fn register_suspend_signal() {
+    #[cfg(unix)]
+    {
+        use signal_hook::{consts::SIGTSTP, flag};
+
+        if let Err(err) = flag::register(SIGTSTP, Arc::clone(&SUSPEND_REQUESTED)) {
+            flint_core::warn!("Could not handle SIGTSTP: {}", err);
+        }
+    }
+}
+
+pub fn request_suspend() {
+    SUSPEND_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Returns true once for every suspend that was requested
+pub fn take_suspend_request() -> bool {
+    SUSPEND_REQUESTED.swap(false, Ordering::Relaxed)
+}
+
+/// Restores the terminal and stops flint until it's resumed with SIGCONT, like
+/// with `fg`, then takes over the terminal again. Does nothing where there's no job control.
+pub fn suspend(terminal: &mut DefaultTerminal, mouse_capture: bool) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use crossterm::{
+            event::EnableMouseCapture,
+            terminal::{enable_raw_mode, EnterAlternateScreen},
+        };
+        use signal_hook::{consts::SIGTSTP, low_level};
+
+        restore();
+        // Stops the process, this only returns once it's continued
+        low_level::emulate_default_handler(SIGTSTP)?;
+
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        if mouse_capture {
+            execute!(io::stdout(), EnableMouseCapture)?;
+        }
+        // The screen was used by the shell meanwhile, so the next frame is drawn in full
+        terminal.clear()?;
+    }
+    #[cfg(not(unix))]
+    let _ = (terminal, mouse_capture);
+
+    Ok(())
+}
+
+/// Puts text in the clipboard with the OSC 52 escape sequence, which the terminal
+/// handles, so it works over SSH too. Terminals that don't support it ignore it.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+/// Encodes bytes as standard base64 with padding, which OSC 52 takes
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | (*byte as u32) << (16 - index * 8)
+        });
+        for index in 0..4 {
+            match index <= chunk.len() {
+                true => encoded.push(ALPHABET[(group >> (18 - index * 6)) as usize & 63] as char),
+                false => encoded.push('='),
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,374 @@
+use clap::ValueEnum;
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::symbols::border;
+use std::{
+    env,
+    io::{self, IsTerminal},
+    str::FromStr,
+    sync::{LazyLock, RwLock},
+};
+
+use flint_core::{config::ThemeConfig, logs::LogKind, warn};
+
+/// The themes flint ships with, which `[flint.theme]` can start from
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    /// No colors, like with `NO_COLOR` or `--color=never`
+    Monochrome,
+}
+
+impl FromStr for ThemePreset {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "dark" => Ok(ThemePreset::Dark),
+            "light" => Ok(ThemePreset::Light),
+            "high-contrast" => Ok(ThemePreset::HighContrast),
+            "monochrome" => Ok(ThemePreset::Monochrome),
+            _ => Err(format!(
+                "Unknown theme preset '{}'. Options: \"dark\", \"light\", \"high-contrast\", \"monochrome\"",
+                name
+            )),
+        }
+    }
+}
+
+/// When colors are used, set with `--color`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// In terminals, unless `NO_COLOR` is set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn is_enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+                !no_color && io::stdout().is_terminal()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// The characters widgets are drawn with. The ASCII ones are for terminals without
+/// Unicode box-drawing characters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Symbols {
+    pub border: border::Set,
+    /// Between the tabs and the parts of the status bar
+    pub divider: &'static str,
+    pub spinner: &'static [&'static str],
+    pub succeeded: &'static str,
+    pub failed: &'static str,
+    /// Before the directories of a tree, like in file pickers
+    pub expanded: &'static str,
+    pub collapsed: &'static str,
+    /// After the title of the column a table is sorted by
+    pub sort_ascending: &'static str,
+    pub sort_descending: &'static str,
+}
+
+impl Symbols {
+    pub const UNICODE: Self = Self {
+        border: border::PLAIN,
+        divider: "│",
+        spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+        succeeded: "✓",
+        failed: "✗",
+        expanded: "▾",
+        collapsed: "▸",
+        sort_ascending: "▲",
+        sort_descending: "▼",
+    };
+
+    pub const ASCII: Self = Self {
+        border: border::Set {
+            top_left: "+",
+            top_right: "+",
+            bottom_left: "+",
+            bottom_right: "+",
+            vertical_left: "|",
+            vertical_right: "|",
+            horizontal_top: "-",
+            horizontal_bottom: "-",
+        },
+        divider: "|",
+        spinner: &["|", "/", "-", "\\"],
+        succeeded: "+",
+        failed: "x",
+        expanded: "v",
+        collapsed: ">",
+        sort_ascending: "^",
+        sort_descending: "v",
+    };
+
+    /// For screen readers, which read out the characters of borders and symbols. States
+    /// are words, and the spinner doesn't spin, so the screen isn't read again.
+    pub const SCREEN_READER: Self = Self {
+        border: border::Set {
+            top_left: " ",
+            top_right: " ",
+            bottom_left: " ",
+            bottom_right: " ",
+            vertical_left: " ",
+            vertical_right: " ",
+            horizontal_top: " ",
+            horizontal_bottom: " ",
+        },
+        divider: ",",
+        spinner: &["running"],
+        succeeded: "passed",
+        failed: "failed",
+        expanded: "open",
+        collapsed: "closed",
+        sort_ascending: "ascending",
+        sort_descending: "descending",
+    };
+
+    /// The ASCII symbols if the locale isn't UTF-8, like `C` or `en_US.ISO-8859-1`,
+    /// and the Unicode ones otherwise
+    pub fn detect() -> Self {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty());
+        match locale {
+            Some(locale) if !is_utf8(&locale) => Self::ASCII,
+            _ => Self::UNICODE,
+        }
+    }
+}
+
+impl FromStr for Symbols {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "auto" => Ok(Self::detect()),
+            "unicode" => Ok(Self::UNICODE),
+            "ascii" => Ok(Self::ASCII),
+            _ => Err(format!(
+                "Unknown symbols '{}'. Options: \"auto\", \"unicode\", \"ascii\"",
+                name
+            )),
+        }
+    }
+}
+
+fn is_utf8(locale: &str) -> bool {
+    let locale = locale.to_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+/// The colors and symbols used by every widget
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub info: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub debug: Color,
+    pub border: Color,
+    /// Highlights what needs attention, like the active tab or a question
+    pub accent: Color,
+    pub selection_fg: Color,
+    pub selection_bg: Color,
+    pub status_bar_fg: Color,
+    pub status_bar_bg: Color,
+    pub symbols: Symbols,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(ThemePreset::default())
+    }
+}
+
+impl Theme {
+    pub fn preset(preset: ThemePreset) -> Self {
+        let symbols = Symbols::detect();
+        match preset {
+            ThemePreset::Dark => Self {
+                info: Color::Blue,
+                success: Color::Green,
+                warning: Color::Yellow,
+                error: Color::Red,
+                debug: Color::White,
+                border: Color::Reset,
+                accent: Color::Yellow,
+                selection_fg: Color::Black,
+                selection_bg: Color::White,
+                status_bar_fg: Color::White,
+                status_bar_bg: Color::DarkGray,
+                symbols,
+            },
+            ThemePreset::Light => Self {
+                info: Color::Blue,
+                success: Color::Green,
+                warning: Color::Rgb(175, 135, 0),
+                error: Color::Red,
+                debug: Color::DarkGray,
+                border: Color::Gray,
+                accent: Color::Magenta,
+                selection_fg: Color::White,
+                selection_bg: Color::Blue,
+                status_bar_fg: Color::Black,
+                status_bar_bg: Color::Gray,
+                symbols,
+            },
+            ThemePreset::HighContrast => Self {
+                info: Color::LightCyan,
+                success: Color::LightGreen,
+                warning: Color::LightYellow,
+                error: Color::LightRed,
+                debug: Color::White,
+                border: Color::White,
+                accent: Color::LightYellow,
+                selection_fg: Color::Black,
+                selection_bg: Color::LightYellow,
+                status_bar_fg: Color::Black,
+                status_bar_bg: Color::White,
+                symbols,
+            },
+            ThemePreset::Monochrome => Self {
+                info: Color::Reset,
+                success: Color::Reset,
+                warning: Color::Reset,
+                error: Color::Reset,
+                debug: Color::Reset,
+                border: Color::Reset,
+                accent: Color::Reset,
+                selection_fg: Color::Reset,
+                selection_bg: Color::Reset,
+                status_bar_fg: Color::Reset,
+                status_bar_bg: Color::Reset,
+                symbols,
+            },
+        }
+    }
+
+    /// Creates the theme of the preset in the config, with the colors in the config
+    /// replacing the colors of the preset. Invalid entries are skipped with a warning.
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        let preset = match config.preset.as_deref().map(str::parse::<ThemePreset>) {
+            Some(Ok(preset)) => preset,
+            Some(Err(err)) => {
+                warn!("Ignoring preset in [flint.theme]: {}", err);
+                ThemePreset::default()
+            }
+            None => ThemePreset::default(),
+        };
+
+        let mut theme = Self::preset(preset);
+        match config.symbols.as_deref().map(str::parse::<Symbols>) {
+            Some(Ok(symbols)) => theme.symbols = symbols,
+            Some(Err(err)) => warn!("Ignoring symbols in [flint.theme]: {}", err),
+            None => (),
+        }
+        for (name, color) in &config.colors {
+            if let Err(err) = theme.set_color(name, color) {
+                warn!("Ignoring color in [flint.theme]: {}", err);
+            }
+        }
+
+        theme
+    }
+
+    fn set_color(&mut self, name: &str, color: &str) -> Result<(), String> {
+        let color = Color::from_str(color)
+            .map_err(|_| format!("Invalid color '{}' for '{}'", color, name))?;
+
+        let field = match name {
+            "info" => &mut self.info,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "debug" => &mut self.debug,
+            "border" => &mut self.border,
+            "accent" => &mut self.accent,
+            "selection_fg" => &mut self.selection_fg,
+            "selection_bg" => &mut self.selection_bg,
+            "status_bar_fg" => &mut self.status_bar_fg,
+            "status_bar_bg" => &mut self.status_bar_bg,
+            _ => return Err(format!("Unknown color '{}'", name)),
+        };
+        *field = color;
+
+        Ok(())
+    }
+
+    /// The monochrome theme with the symbols of this theme, for when colors are disabled
+    pub fn without_colors(self) -> Self {
+        Self {
+            symbols: self.symbols,
+            ..Self::preset(ThemePreset::Monochrome)
+        }
+    }
+
+    /// This theme with the symbols for screen readers, for `--accessible`
+    pub fn accessible(self) -> Self {
+        Self {
+            symbols: Symbols::SCREEN_READER,
+            ..self
+        }
+    }
+
+    pub fn log_color(&self, kind: LogKind) -> Color {
+        match kind {
+            LogKind::Info => self.info,
+            LogKind::Success => self.success,
+            LogKind::Error => self.error,
+            LogKind::Warn => self.warning,
+            LogKind::Debug => self.debug,
+        }
+    }
+
+    pub fn log(&self, kind: LogKind) -> Style {
+        Style::new().fg(self.log_color(kind))
+    }
+
+    /// The style of selected list items and focused buttons
+    pub fn selection(&self) -> Style {
+        highlight(self.selection_fg, self.selection_bg)
+    }
+
+    pub fn accent(&self) -> Style {
+        Style::new().fg(self.accent).bold()
+    }
+
+    pub fn status_bar(&self) -> Style {
+        highlight(self.status_bar_fg, self.status_bar_bg)
+    }
+}
+
+/// Text on a background, which is reversed instead without a background color
+fn highlight(fg: Color, bg: Color) -> Style {
+    let style = Style::new().fg(fg).bg(bg);
+    if bg == Color::Reset {
+        style.reversed()
+    } else {
+        style
+    }
+}
+
+pub static THEME: LazyLock<RwLock<Theme>> = LazyLock::new(|| RwLock::new(Theme::default()));
+
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap() = theme;
+}
+
+/// Returns the theme every widget is rendered with
+pub fn theme() -> Theme {
+    *THEME.read().unwrap()
+}
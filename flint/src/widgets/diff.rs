@@ -0,0 +1,159 @@
+use crossterm::event::{Event, MouseEventKind};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Widget, WidgetRef};
+use std::cell::Cell;
+
+use crate::util::keys::{get_action, Action};
+use crate::util::theme::theme;
+use flint_core::diff::{Diff, DiffLine, LineKind};
+
+/// Unchanged lines shown above the first change when a diff is opened
+const CONTEXT_LINES: usize = 3;
+
+/// Shows the diffs of files side by side, the old lines on the left and the new ones
+/// on the right. Changed lines are colored, with the parts that changed emphasized.
+#[derive(Clone, Debug, Default)]
+pub struct DiffWidget {
+    files: Vec<(String, Diff)>,
+    scroll: usize,
+    /// The height it was last rendered with, so it doesn't scroll past the end
+    area_height: Cell<u16>,
+}
+
+impl DiffWidget {
+    /// Shows the diff of a file, scrolled to its first change
+    pub fn new(file: impl Into<String>, diff: Diff) -> Self {
+        let first_change = diff.rows.iter().position(|row| row.is_changed());
+        Self {
+            scroll: first_change.map_or(0, |row| (row + 1).saturating_sub(CONTEXT_LINES)),
+            files: vec![(file.into(), diff)],
+            area_height: Cell::new(0),
+        }
+    }
+
+    /// Adds the diff of another file below the others
+    pub fn push(&mut self, file: impl Into<String>, diff: Diff) {
+        self.files.push((file.into(), diff));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The height needed to show every diff, with a header above each file
+    pub fn height(&self) -> usize {
+        self.files.iter().map(|(_, diff)| diff.rows.len() + 1).sum()
+    }
+
+    /// Scrolls with the scroll keys and the mouse wheel. Returns whether the event
+    /// was one of them.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        let lines = match event {
+            Event::Key(key_event) => match get_action(key_event) {
+                Some(Action::ScrollUp) => -1,
+                Some(Action::ScrollDown) => 1,
+                _ => return false,
+            },
+            Event::Mouse(mouse_event) => match mouse_event.kind {
+                MouseEventKind::ScrollUp => -1,
+                MouseEventKind::ScrollDown => 1,
+                _ => return false,
+            },
+            _ => return false,
+        };
+
+        let max_scroll = self
+            .height()
+            .saturating_sub(self.area_height.get() as usize);
+        self.scroll = self.scroll.saturating_add_signed(lines).min(max_scroll);
+        true
+    }
+
+    fn lines(&self) -> impl Iterator<Item = DiffWidgetLine<'_>> {
+        self.files.iter().flat_map(|(file, diff)| {
+            // Line numbers are aligned to the longest one of the file
+            let number_width = diff
+                .rows
+                .iter()
+                .flat_map(|row| [&row.old, &row.new])
+                .flatten()
+                .map(|line| line.number.to_string().len())
+                .max()
+                .unwrap_or_default();
+
+            std::iter::once(DiffWidgetLine::Header(file, diff)).chain(diff.rows.iter().map(
+                move |row| DiffWidgetLine::Row(row.old.as_ref(), row.new.as_ref(), number_width),
+            ))
+        })
+    }
+}
+
+/// A line of the widget: the header of a file, or a row of its diff
+enum DiffWidgetLine<'a> {
+    Header(&'a str, &'a Diff),
+    Row(Option<&'a DiffLine>, Option<&'a DiffLine>, usize),
+}
+
+fn side(line: Option<&DiffLine>, number_width: usize) -> Line<'_> {
+    let theme = theme();
+    let Some(line) = line else {
+        return Line::default();
+    };
+
+    let style = match line.kind {
+        LineKind::Unchanged => Style::new(),
+        LineKind::Removed => Style::new().fg(theme.error),
+        LineKind::Added => Style::new().fg(theme.success),
+    };
+    let mut spans = vec![Span::styled(
+        format!("{:>number_width$} ", line.number),
+        Style::new().fg(theme.debug),
+    )];
+    spans.extend(
+        line.segments
+            .iter()
+            .map(|segment| match segment.emphasized {
+                true => Span::styled(
+                    segment.text.as_str(),
+                    style.add_modifier(Modifier::REVERSED),
+                ),
+                false => Span::styled(segment.text.as_str(), style),
+            }),
+    );
+    Line::from(spans)
+}
+
+impl WidgetRef for DiffWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        self.area_height.set(area.height);
+        let theme = theme();
+        let divider = theme.symbols.divider;
+
+        let lines = self.lines().skip(self.scroll).take(area.height as usize);
+        for (line, y) in lines.zip(area.y..area.bottom()) {
+            let row_area = Rect::new(area.x, y, area.width, 1);
+            match line {
+                DiffWidgetLine::Header(file, diff) => {
+                    let header = format!("{} +{} -{}", file, diff.added(), diff.removed());
+                    Line::styled(header, Style::new().fg(theme.accent).bold())
+                        .render(row_area, buf);
+                }
+                DiffWidgetLine::Row(old, new, number_width) => {
+                    let [old_area, divider_area, new_area] = Layout::horizontal([
+                        Constraint::Fill(1),
+                        Constraint::Length(divider.chars().count() as u16 + 2),
+                        Constraint::Fill(1),
+                    ])
+                    .areas(row_area);
+                    side(old, number_width).render(old_area, buf);
+                    Line::styled(format!(" {} ", divider), Style::new().fg(theme.border))
+                        .render(divider_area, buf);
+                    side(new, number_width).render(new_area, buf);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crossterm::event::{Event, MouseButton, MouseEvent, MouseEventKind};
+use flint_macros::ui;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Margin, Position, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, Paragraph, Widget, Wrap};
+use std::cell::Cell;
+
+use crate::util::keys::{get_action, Action};
+use crate::util::theme::theme;
+use flint_core::error::FlintError;
+use flint_core::{i18n, t};
+
+/// The labels of the buttons, in the order they're shown
+const CLOSE_BUTTON: &str = "[ Close ]";
+const COPY_BUTTON: &str = "[ Copy details ]";
+
+/// The space between the buttons
+const BUTTON_GAP: u16 = 2;
+
+/// What the user chose on the error screen
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ErrorScreenAction {
+    Close,
+    /// Copies the details of the error, like to paste them into an issue
+    Copy,
+}
+
+/// Shown over the view when a command fails, with the errors that caused it and how
+/// it may be fixed. It takes the keys and clicks until it's closed.
+#[derive(Clone, Debug)]
+pub struct ErrorScreen {
+    chain: Vec<String>,
+    suggestion: Option<String>,
+    details: String,
+    /// Where the buttons were last rendered, to handle clicks on them
+    buttons_area: Cell<Option<Rect>>,
+}
+
+impl ErrorScreen {
+    pub fn new(error: &FlintError) -> Self {
+        Self {
+            chain: error.chain(),
+            suggestion: error.suggestion(),
+            details: error.details(),
+            buttons_area: Cell::new(None),
+        }
+    }
+
+    /// The error chain and the suggestion as text, which copying puts in the clipboard
+    pub fn details(&self) -> &str {
+        &self.details
+    }
+
+    pub fn handle_event(&self, event: &Event) -> Option<ErrorScreenAction> {
+        match event {
+            Event::Key(key_event) => match get_action(key_event) {
+                Some(Action::Confirm | Action::Quit) => Some(ErrorScreenAction::Close),
+                Some(Action::Copy) => Some(ErrorScreenAction::Copy),
+                _ => None,
+            },
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => self.button_at(*column, *row),
+            _ => None,
+        }
+    }
+
+    /// The button at the given position. The buttons are centered on the last line
+    /// inside the border.
+    fn button_at(&self, column: u16, row: u16) -> Option<ErrorScreenAction> {
+        let area = self.buttons_area.get()?;
+        if !area.contains(Position::new(column, row)) {
+            return None;
+        }
+
+        let close_width = button_width(CLOSE_BUTTON);
+        let x = area.x + area.width.saturating_sub(buttons_width()) / 2;
+        if (x..x + close_width).contains(&column) {
+            return Some(ErrorScreenAction::Close);
+        }
+        let x = x + close_width + BUTTON_GAP;
+        (x..x + button_width(COPY_BUTTON))
+            .contains(&column)
+            .then_some(ErrorScreenAction::Copy)
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let theme = theme();
+        let mut lines = Vec::new();
+        if let Some((error, causes)) = self.chain.split_first() {
+            for line in error.lines() {
+                lines.push(Line::styled(
+                    line.to_string(),
+                    Style::default()
+                        .fg(theme.error)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            // Causes like TOML parse errors span several lines, which are indented
+            // under the first
+            let prefix = format!("  {} ", t!("caused by:"));
+            let indent = " ".repeat(prefix.chars().count());
+            for cause in causes {
+                for (index, line) in cause.lines().enumerate() {
+                    let gutter = if index == 0 { &prefix } else { &indent };
+                    lines.push(Line::from(vec![
+                        Span::styled(gutter.clone(), Style::default().fg(theme.border)),
+                        Span::raw(line.to_string()),
+                    ]));
+                }
+            }
+        }
+        if let Some(suggestion) = &self.suggestion {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(vec![
+                Span::styled(format!("{} ", t!("Suggestion:")), theme.accent()),
+                Span::raw(suggestion.clone()),
+            ]));
+        }
+        lines
+    }
+}
+
+fn button_width(label: &'static str) -> u16 {
+    i18n::text(label).chars().count() as u16
+}
+
+fn buttons_width() -> u16 {
+    button_width(CLOSE_BUTTON) + BUTTON_GAP + button_width(COPY_BUTTON)
+}
+
+impl Widget for &ErrorScreen {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.inner(Margin::new(2, 1));
+        Clear.render(area, buf);
+
+        ui!((area, buf) => {
+            Block::bordered(
+                title: t!("Error occurred"),
+                border_style: theme().error,
+                border_set: theme().symbols.border
+            )
+        });
+
+        // The errors take the lines above the buttons, with a blank line between them
+        let inner = area.inner(Margin::new(1, 1));
+        let [text_area, _, buttons_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+        self.buttons_area.set(Some(buttons_area));
+        ui!((text_area, buf) => {
+            Paragraph::new(self.lines(), wrap: Wrap { trim: false })
+        });
+
+        let gap = " ".repeat(BUTTON_GAP as usize);
+        let buttons = Line::from(format!(
+            "{}{}{}",
+            i18n::text(CLOSE_BUTTON),
+            gap,
+            i18n::text(COPY_BUTTON)
+        ))
+        .centered();
+        buttons.render(buttons_area, buf);
+    }
+}
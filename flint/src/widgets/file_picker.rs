@@ -0,0 +1,300 @@
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
+use ignore::WalkBuilder;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Margin, Position, Rect};
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListState, StatefulWidget, Widget, WidgetRef};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::text_input::TextInput;
+use crate::util::keys::{get_action, Action};
+use crate::util::theme::theme;
+use flint_core::t;
+
+/// The most paths a filter lists, so filtering a big tree stays responsive
+const MAX_MATCHES: usize = 500;
+
+/// What a file picker picks
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PickKind {
+    File,
+    /// Only directories are listed
+    Directory,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    path: PathBuf,
+    /// The name of the entry in the tree, or its path under the root when filtered
+    label: String,
+    depth: usize,
+    is_dir: bool,
+}
+
+/// Picks a file or a directory under a root directory, shown as a tree with the root
+/// at the top. Directories are expanded with the right arrow key and collapsed with
+/// the left one. Typing filters the whole tree by name, leaving out ignored files.
+#[derive(Debug)]
+pub struct FilePicker {
+    root: PathBuf,
+    kind: PickKind,
+    show_hidden: bool,
+    expanded: BTreeSet<PathBuf>,
+    entries: Vec<Entry>,
+    filter: TextInput,
+    list_state: RefCell<ListState>,
+    /// Where the list was last rendered, to select entries by clicking them
+    list_area: Cell<Rect>,
+}
+
+impl FilePicker {
+    pub fn new(root: impl Into<PathBuf>, kind: PickKind) -> Self {
+        let mut picker = Self {
+            root: root.into(),
+            kind,
+            show_hidden: false,
+            expanded: BTreeSet::new(),
+            entries: Vec::new(),
+            filter: TextInput::new().with_placeholder("type to search"),
+            list_state: RefCell::new(ListState::default().with_selected(Some(0))),
+            list_area: Cell::new(Rect::default()),
+        };
+        picker.refresh();
+        picker
+    }
+
+    /// Handles an event, returning the path once one is picked
+    pub fn handle_event(&mut self, event: &Event) -> Option<PathBuf> {
+        match event {
+            Event::Key(key_event) => {
+                // Characters are typed into the filter, even if they're bound to an action
+                let is_char = matches!(key_event.code, KeyCode::Char(_))
+                    && !key_event.modifiers.contains(KeyModifiers::CONTROL);
+                if is_char || key_event.code == KeyCode::Backspace {
+                    self.filter.input(*key_event);
+                    self.refresh();
+                    self.list_state.get_mut().select(Some(0));
+                    return None;
+                }
+
+                match (get_action(key_event), key_event.code) {
+                    (Some(Action::ScrollUp), _) => self.list_state.get_mut().select_previous(),
+                    (Some(Action::ScrollDown), _) => self.list_state.get_mut().select_next(),
+                    (Some(Action::ToggleHidden), _) => {
+                        self.show_hidden = !self.show_hidden;
+                        self.refresh();
+                    }
+                    (Some(Action::Confirm), _) => return self.confirm(),
+                    (_, KeyCode::Right) => self.set_expanded(true),
+                    (_, KeyCode::Left) => self.set_expanded(false),
+                    _ => (),
+                }
+                None
+            }
+            Event::Mouse(mouse_event) => {
+                match mouse_event.kind {
+                    MouseEventKind::ScrollUp => self.list_state.get_mut().select_previous(),
+                    MouseEventKind::ScrollDown => self.list_state.get_mut().select_next(),
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if let Some(index) = self.entry_at(mouse_event.column, mouse_event.row) {
+                            self.list_state.get_mut().select(Some(index));
+                        }
+                    }
+                    _ => (),
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn selected(&self) -> Option<&Entry> {
+        self.entries.get(self.list_state.borrow().selected()?)
+    }
+
+    /// Picks the selected entry. Directories are expanded instead when picking files.
+    fn confirm(&mut self) -> Option<PathBuf> {
+        let entry = self.selected()?;
+        if entry.is_dir && self.kind == PickKind::File {
+            let is_expanded = self.expanded.contains(&entry.path);
+            self.set_expanded(!is_expanded);
+            return None;
+        }
+        Some(entry.path.clone())
+    }
+
+    /// Expands or collapses the selected directory. Collapsing anything else selects
+    /// the directory it's in, like in most file trees.
+    fn set_expanded(&mut self, expanded: bool) {
+        let Some(entry) = self.selected().cloned() else {
+            return;
+        };
+        // The root is always expanded, and filtered entries aren't shown as a tree
+        if entry.path == self.root || !self.query().is_empty() {
+            return;
+        }
+
+        if entry.is_dir && expanded != self.expanded.contains(&entry.path) {
+            match expanded {
+                true => self.expanded.insert(entry.path),
+                false => self.expanded.remove(&entry.path),
+            };
+            self.refresh();
+        } else if !expanded {
+            let parent = self
+                .entries
+                .iter()
+                .position(|parent| Some(parent.path.as_path()) == entry.path.parent());
+            self.list_state.get_mut().select(parent);
+        }
+    }
+
+    fn query(&self) -> String {
+        self.filter.value()
+    }
+
+    /// Lists the entries again, after the tree, the filter or the hidden files changed
+    fn refresh(&mut self) {
+        let root = Entry {
+            path: self.root.clone(),
+            label: self.root.display().to_string(),
+            depth: 0,
+            is_dir: true,
+        };
+        let mut entries = vec![root];
+
+        let query = self.query().to_lowercase();
+        if query.is_empty() {
+            self.list_tree(&self.root, 1, &mut entries);
+        } else {
+            entries.extend(self.search(&query));
+        }
+        self.entries = entries;
+    }
+
+    /// Adds the entries of a directory, and of the expanded directories in it
+    fn list_tree(&self, dir: &Path, depth: usize, entries: &mut Vec<Entry>) {
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            return;
+        };
+
+        let mut children = read_dir
+            .filter_map(Result::ok)
+            .map(|child| (child.path(), child.path().is_dir()))
+            .filter(|(path, is_dir)| {
+                (self.show_hidden || !is_hidden(path)) && (*is_dir || self.kind == PickKind::File)
+            })
+            .collect::<Vec<_>>();
+        // Directories come before files, like in most file trees
+        children.sort_by(|(a, a_is_dir), (b, b_is_dir)| b_is_dir.cmp(a_is_dir).then(a.cmp(b)));
+
+        for (path, is_dir) in children {
+            entries.push(Entry {
+                label: file_name(&path),
+                path: path.clone(),
+                depth,
+                is_dir,
+            });
+            if is_dir && self.expanded.contains(&path) {
+                self.list_tree(&path, depth + 1, entries);
+            }
+        }
+    }
+
+    /// The paths under the root whose name contains the query, ignoring case
+    fn search(&self, query: &str) -> Vec<Entry> {
+        WalkBuilder::new(&self.root)
+            .hidden(!self.show_hidden)
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.depth() > 0)
+            .filter(|entry| {
+                let is_dir = entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_dir());
+                is_dir || self.kind == PickKind::File
+            })
+            .filter(|entry| file_name(entry.path()).to_lowercase().contains(query))
+            .take(MAX_MATCHES)
+            .map(|entry| Entry {
+                label: entry
+                    .path()
+                    .strip_prefix(&self.root)
+                    .unwrap_or(entry.path())
+                    .display()
+                    .to_string(),
+                is_dir: entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_dir()),
+                path: entry.into_path(),
+                depth: 1,
+            })
+            .collect()
+    }
+
+    /// Returns the index of the entry shown at the given position, if any
+    fn entry_at(&self, column: u16, row: u16) -> Option<usize> {
+        let list_area = self.list_area.get();
+        if !list_area.contains(Position::new(column, row)) {
+            return None;
+        }
+
+        let index = self.list_state.borrow().offset() + (row - list_area.y) as usize;
+        (index < self.entries.len()).then_some(index)
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn is_hidden(path: &Path) -> bool {
+    file_name(path).starts_with('.')
+}
+
+impl WidgetRef for FilePicker {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let theme = theme();
+        let title = match self.kind {
+            PickKind::File => t!("Choose a file"),
+            PickKind::Directory => t!("Choose a directory"),
+        };
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(t!(
+                "[right to expand, left to collapse, ctrl+h for hidden files]"
+            ))
+            .border_style(theme.border)
+            .border_set(theme.symbols.border);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        block.render(area, buf);
+
+        let [filter_area, list_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+        let [prompt_area, input_area] =
+            Layout::horizontal([Constraint::Length(8), Constraint::Fill(1)]).areas(filter_area);
+        Line::styled(t!("Filter: "), Style::new().fg(theme.accent)).render(prompt_area, buf);
+        self.filter.render_ref(input_area, buf);
+
+        let items = self.entries.iter().map(|entry| {
+            let symbol = match (entry.is_dir, self.expanded.contains(&entry.path)) {
+                // The root is always expanded
+                (true, expanded) if expanded || entry.depth == 0 => theme.symbols.expanded,
+                (true, _) => theme.symbols.collapsed,
+                (false, _) => " ",
+            };
+            let indent = "  ".repeat(entry.depth);
+            format!("{}{} {}", indent, symbol, entry.label)
+        });
+
+        self.list_area.set(list_area);
+        let list = List::new(items).highlight_style(theme.selection());
+        StatefulWidget::render(list, list_area, buf, &mut self.list_state.borrow_mut());
+    }
+}
@@ -0,0 +1,181 @@
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListState, StatefulWidget, WidgetRef};
+use std::cell::{Cell, RefCell};
+
+use super::text_input::TextInput;
+use crate::util::keys::{get_action, Action};
+use crate::util::theme::theme;
+use flint_core::fuzzy::{fuzzy_matches, FuzzyMatch};
+
+/// The narrowest a searchable list gets, so there's room to type
+const MIN_SEARCH_WIDTH: u16 = 30;
+
+/// A list to choose an item from. A searchable list is filtered while typing, with
+/// the best matches first and the characters that matched highlighted.
+#[derive(Debug)]
+pub struct FuzzyList {
+    items: Vec<String>,
+    /// The text the items are filtered by, if they can be searched
+    filter: Option<TextInput>,
+    matches: Vec<FuzzyMatch>,
+    list_state: RefCell<ListState>,
+    /// Where the items were last rendered, to choose them by clicking them
+    list_area: Cell<Rect>,
+}
+
+impl FuzzyList {
+    pub fn new(items: Vec<String>) -> Self {
+        Self {
+            matches: fuzzy_matches("", &items),
+            items,
+            filter: None,
+            list_state: RefCell::new(ListState::default().with_selected(Some(0))),
+            list_area: Cell::new(Rect::default()),
+        }
+    }
+
+    /// Filters the items by what's typed, shown above them
+    pub fn searchable(mut self) -> Self {
+        self.filter = Some(TextInput::new().with_placeholder("type to search"));
+        self
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn is_searchable(&self) -> bool {
+        self.filter.is_some()
+    }
+
+    /// Handles an event, returning the index of the item once one is chosen
+    pub fn handle_event(&mut self, event: &Event) -> Option<usize> {
+        match event {
+            Event::Key(key_event) => {
+                // Characters are typed into the filter, even if they're bound to an action
+                let is_char = matches!(key_event.code, KeyCode::Char(_))
+                    && !key_event.modifiers.contains(KeyModifiers::CONTROL);
+                if let Some(filter) = self.filter.as_mut() {
+                    if is_char || key_event.code == KeyCode::Backspace {
+                        filter.input(*key_event);
+                        self.matches = fuzzy_matches(&filter.value(), &self.items);
+                        self.list_state.get_mut().select(Some(0));
+                        return None;
+                    }
+                }
+
+                match get_action(key_event) {
+                    Some(Action::ScrollUp) => self.list_state.get_mut().select_previous(),
+                    Some(Action::ScrollDown) => self.list_state.get_mut().select_next(),
+                    Some(Action::Confirm) => return self.selected(),
+                    _ => (),
+                }
+                None
+            }
+            Event::Mouse(mouse_event) => {
+                match mouse_event.kind {
+                    MouseEventKind::ScrollUp => self.list_state.get_mut().select_previous(),
+                    MouseEventKind::ScrollDown => self.list_state.get_mut().select_next(),
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        let list_area = self.list_area.get();
+                        let position = Position::new(mouse_event.column, mouse_event.row);
+                        if !list_area.contains(position) {
+                            return None;
+                        }
+
+                        let row = self.list_state.get_mut().offset()
+                            + (position.y - list_area.y) as usize;
+                        return self.matches.get(row).map(|fuzzy_match| fuzzy_match.index);
+                    }
+                    _ => (),
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// The index of the selected item, if any item matches
+    pub fn selected(&self) -> Option<usize> {
+        let selected = self.list_state.borrow().selected()?;
+        self.matches
+            .get(selected)
+            .map(|fuzzy_match| fuzzy_match.index)
+    }
+
+    /// The height needed to show every item, and the filter
+    pub fn height(&self) -> u16 {
+        self.items.len() as u16 + u16::from(self.is_searchable())
+    }
+
+    /// The width needed to show the longest item next to the highlight symbol
+    pub fn width(&self) -> u16 {
+        self.items
+            .iter()
+            .map(|item| Line::from(item.as_str()).width() as u16 + 2)
+            .chain(self.filter.as_ref().map(|_| MIN_SEARCH_WIDTH))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// An item with the characters that matched the filter highlighted
+fn highlighted<'a>(item: &'a str, fuzzy_match: &FuzzyMatch) -> Line<'a> {
+    let highlight = Style::new().fg(theme().accent).bold();
+    let mut spans = Vec::new();
+    // Where the current run of highlighted or plain characters started
+    let mut run: Option<(usize, bool)> = None;
+
+    for (position, (start, _)) in item.char_indices().enumerate() {
+        let is_match = fuzzy_match.positions.contains(&position);
+        match run {
+            Some((_, was_match)) if was_match == is_match => (),
+            _ => {
+                if let Some((run_start, was_match)) = run {
+                    spans.push(span(&item[run_start..start], was_match, highlight));
+                }
+                run = Some((start, is_match));
+            }
+        }
+    }
+    if let Some((run_start, was_match)) = run {
+        spans.push(span(&item[run_start..], was_match, highlight));
+    }
+    Line::from(spans)
+}
+
+fn span(text: &str, is_match: bool, highlight: Style) -> Span<'_> {
+    match is_match {
+        true => Span::styled(text, highlight),
+        false => Span::raw(text),
+    }
+}
+
+impl WidgetRef for FuzzyList {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        // The filter is shown above the items
+        let area = match &self.filter {
+            Some(filter) => {
+                let [filter_area, area] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+                filter.render_ref(filter_area, buf);
+                area
+            }
+            None => area,
+        };
+
+        self.list_area.set(area);
+        let items = self
+            .matches
+            .iter()
+            .map(|fuzzy_match| highlighted(&self.items[fuzzy_match.index], fuzzy_match));
+        let list = List::new(items)
+            .highlight_style(theme().selection())
+            .highlight_symbol("> ");
+        StatefulWidget::render(list, area, buf, &mut self.list_state.borrow_mut());
+    }
+}
@@ -4,24 +4,19 @@ use ratatui::widgets::Wrap;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style},
     widgets::{Block, Padding, Paragraph, StatefulWidget, Widget},
 };
 
-use crate::util::logs::{get_logs, LogKind};
+use crate::util::theme::theme;
+use flint_core::logs::{dropped_logs, get_logs, show_debug_logs, LogKind, LOG_FILE};
+use flint_core::t;
 
 // Define a state to keep track of scrolling position
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct LogsState {
     scroll: usize,
 }
 
-impl Default for LogsState {
-    fn default() -> Self {
-        Self { scroll: 0 }
-    }
-}
-
 impl LogsState {
     pub fn new() -> Self {
         Self::default()
@@ -52,28 +47,21 @@ impl LogsState {
 #[derive(Debug, Default, Clone, Copy)]
 pub struct LogsWidget;
 
-fn get_style(kind: &LogKind) -> Style {
-    Style::default().fg(match kind {
-        LogKind::Info => Color::Blue,
-        LogKind::Success => Color::Green,
-        LogKind::Error => Color::Red,
-        LogKind::Warn => Color::Yellow,
-        LogKind::Debug => Color::White,
-    })
-}
-
 // Changed from Widget to StatefulWidget for scrolling functionality
 impl StatefulWidget for LogsWidget {
     type State = LogsState;
 
     fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
         let logs = get_logs().unwrap();
+        let show_debug_logs = show_debug_logs();
+        let theme = theme();
 
         let all_log_lines = logs
             .iter()
+            .filter(|(kind, _)| show_debug_logs || !matches!(kind, LogKind::Debug))
             .flat_map(|(kind, log)| {
                 log.split('\n')
-                    .map(|line| Line::from(line.to_string()).style(get_style(kind)))
+                    .map(|line| Line::from(line.to_string()).style(theme.log(*kind)))
                     .collect::<Vec<Line>>()
             })
             .collect::<Vec<Line>>();
@@ -97,17 +85,27 @@ impl StatefulWidget for LogsWidget {
             vec![]
         };
 
+        // Only the most recent logs are kept, the rest are in the log file
+        let older_logs = match dropped_logs() {
+            0 => String::new(),
+            _ => t!(" (older in {file})", file = LOG_FILE),
+        };
+
         let text = Text::from(visible_log_lines);
         let block = widget!({
             Block::bordered(
-                title: format!(
-                    "Logs [{}-{}/{}]",
-                    state.scroll + 1, state.scroll + max_visible_lines.min(total_lines),
-                    total_lines),
-
-                title: Line::from("[Scroll up/down using mouse or arrow keys]").right_aligned(),
-
-                padding: Padding::horizontal(1)
+                title: t!(
+                    "Logs [{first}-{last}/{total}]{older}",
+                    first = state.scroll + 1,
+                    last = state.scroll + max_visible_lines.min(total_lines),
+                    total = total_lines,
+                    older = older_logs),
+
+                title: Line::from(t!("[Scroll up/down using mouse or arrow keys]")).right_aligned(),
+
+                padding: Padding::horizontal(1),
+                border_style: theme.border,
+                border_set: theme.symbols.border
             )
         });
 
@@ -1 +1,13 @@
+pub mod diff;
+pub mod error_screen;
+pub mod file_picker;
+pub mod fuzzy_list;
 pub mod logs;
+pub mod modal;
+pub mod plugin_errors;
+pub mod progress;
+pub mod status_bar;
+pub mod table;
+pub mod text_input;
+pub mod toasts;
+pub mod tool_problems;
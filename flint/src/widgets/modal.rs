@@ -0,0 +1,404 @@
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Flex, Layout, Margin, Position, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Block, Clear, Widget, WidgetRef};
+use std::cell::Cell;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::OnceLock;
+
+use super::diff::DiffWidget;
+use super::fuzzy_list::FuzzyList;
+use super::text_input::TextInput;
+use crate::util::keys::{get_action, Action};
+use crate::util::theme::theme;
+use flint_core::cancel::is_cancelled;
+use flint_core::i18n;
+
+const YES_BUTTON: &str = "[ Yes ]";
+const NO_BUTTON: &str = "[ No ]";
+const STOP_WAITING_HINT: &str = "Quit again to stop waiting";
+
+/// The answer given to a modal
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModalResult {
+    /// Yes or no, for a confirm modal
+    Confirmed(bool),
+    /// The entered text, for a prompt modal
+    Text(String),
+    /// The index of the chosen item, for a select modal
+    Selected(usize),
+    Cancelled,
+}
+
+enum ModalKind {
+    Confirm {
+        yes: bool,
+        buttons: Cell<[Rect; 2]>,
+    },
+    Prompt {
+        input: Box<TextInput>,
+    },
+    Select {
+        list: Box<FuzzyList>,
+    },
+    /// Only shows the message, until whoever opened it closes it
+    Waiting,
+}
+
+/// A dialog shown on top of every view. While a modal is open it receives all
+/// key and mouse events, until it's answered or cancelled.
+pub struct Modal {
+    title: String,
+    message: String,
+    kind: ModalKind,
+    /// Shown below the message, like the changes a confirmed overwrite makes
+    diff: Option<DiffWidget>,
+    reply: Option<Sender<ModalResult>>,
+}
+
+impl Modal {
+    /// Asks a yes or no question, answered with `ModalResult::Confirmed`
+    pub fn confirm(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(
+            title,
+            message,
+            ModalKind::Confirm {
+                yes: true,
+                buttons: Cell::new([Rect::default(); 2]),
+            },
+        )
+    }
+
+    /// Shows a message while something finishes. It's only answered, with
+    /// `ModalResult::Cancelled`, if the user stops waiting.
+    pub fn waiting(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(title, message, ModalKind::Waiting)
+    }
+
+    /// Asks for a line of text, answered with `ModalResult::Text`
+    pub fn prompt(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(
+            title,
+            message,
+            ModalKind::Prompt {
+                input: Box::default(),
+            },
+        )
+    }
+
+    /// Asks to choose one of the items, answered with `ModalResult::Selected`
+    pub fn select(
+        title: impl Into<String>,
+        message: impl Into<String>,
+        items: Vec<String>,
+    ) -> Self {
+        Self::new(
+            title,
+            message,
+            ModalKind::Select {
+                list: Box::new(FuzzyList::new(items)),
+            },
+        )
+    }
+
+    /// Like `select`, but the items can be fuzzy searched by typing
+    pub fn search(
+        title: impl Into<String>,
+        message: impl Into<String>,
+        items: Vec<String>,
+    ) -> Self {
+        Self::new(
+            title,
+            message,
+            ModalKind::Select {
+                list: Box::new(FuzzyList::new(items).searchable()),
+            },
+        )
+    }
+
+    fn new(title: impl Into<String>, message: impl Into<String>, kind: ModalKind) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            kind,
+            diff: None,
+            reply: None,
+        }
+    }
+
+    /// Shows a diff below the message, which takes the whole width of the screen
+    /// and is scrolled with the scroll keys
+    pub fn with_diff(mut self, diff: DiffWidget) -> Self {
+        self.diff = Some(diff);
+        self
+    }
+
+    /// Sends the answer of the modal to whoever opened it
+    pub fn with_reply(mut self, reply: Sender<ModalResult>) -> Self {
+        self.reply = Some(reply);
+        self
+    }
+
+    /// Handles an event, returning the answer once the modal is closed
+    fn handle_event(&mut self, event: &Event) -> Option<ModalResult> {
+        if self
+            .diff
+            .as_mut()
+            .is_some_and(|diff| diff.handle_event(event))
+        {
+            return None;
+        }
+
+        match event {
+            Event::Key(key_event) => {
+                let action = get_action(key_event);
+                let is_char = matches!(key_event.code, KeyCode::Char(_))
+                    && !key_event.modifiers.contains(KeyModifiers::CONTROL);
+
+                match &mut self.kind {
+                    ModalKind::Confirm { yes, .. } => match (action, key_event.code) {
+                        (_, KeyCode::Char('y')) => Some(ModalResult::Confirmed(true)),
+                        (_, KeyCode::Char('n')) => Some(ModalResult::Confirmed(false)),
+                        (Some(Action::Confirm), _) => Some(ModalResult::Confirmed(*yes)),
+                        (Some(Action::Quit), _) => Some(ModalResult::Cancelled),
+                        (_, KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab) => {
+                            *yes = !*yes;
+                            None
+                        }
+                        _ => None,
+                    },
+                    // Characters are typed into the prompt, even if they're bound to an action
+                    ModalKind::Prompt { input } => match action {
+                        Some(Action::Confirm) => input.submit().map(ModalResult::Text),
+                        Some(Action::Quit) if !is_char => Some(ModalResult::Cancelled),
+                        _ => {
+                            input.input(*key_event);
+                            None
+                        }
+                    },
+                    // Characters are typed into the filter, if there is one
+                    ModalKind::Select { list } => match action {
+                        Some(Action::Quit) if !(is_char && list.is_searchable()) => {
+                            Some(ModalResult::Cancelled)
+                        }
+                        _ => list.handle_event(event).map(ModalResult::Selected),
+                    },
+                    ModalKind::Waiting => match action {
+                        Some(Action::Quit) => Some(ModalResult::Cancelled),
+                        _ => None,
+                    },
+                }
+            }
+            Event::Mouse(mouse_event) => {
+                let position = Position::new(mouse_event.column, mouse_event.row);
+                match (&mut self.kind, mouse_event.kind) {
+                    (
+                        ModalKind::Confirm { buttons, .. },
+                        MouseEventKind::Down(MouseButton::Left),
+                    ) => {
+                        let [yes, no] = buttons.get();
+                        if yes.contains(position) {
+                            Some(ModalResult::Confirmed(true))
+                        } else if no.contains(position) {
+                            Some(ModalResult::Confirmed(false))
+                        } else {
+                            None
+                        }
+                    }
+                    (ModalKind::Select { list }, _) => {
+                        list.handle_event(event).map(ModalResult::Selected)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The height of the content below the message
+    fn content_height(&self) -> u16 {
+        match &self.kind {
+            ModalKind::Confirm { .. } | ModalKind::Waiting => 1,
+            ModalKind::Prompt { input } => input.height(),
+            ModalKind::Select { list } => list.height(),
+        }
+    }
+
+    fn content_width(&self) -> u16 {
+        match &self.kind {
+            ModalKind::Confirm { .. } => {
+                (label_width(YES_BUTTON) + label_width(NO_BUTTON) + 2) as u16
+            }
+            ModalKind::Prompt { .. } => 30,
+            ModalKind::Waiting => label_width(STOP_WAITING_HINT) as u16,
+            ModalKind::Select { list } => list.width(),
+        }
+    }
+}
+
+impl WidgetRef for Modal {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let message = Text::from(self.message.as_str());
+        let message_height = message.height() as u16;
+
+        // The message and the content, with a line between them and a border around
+        // them. A diff is shown between them, as tall as it fits.
+        let mut width = (message.width() as u16)
+            .max(self.content_width())
+            .max(Line::from(self.title.as_str()).width() as u16)
+            + 4;
+        let mut height = message_height + self.content_height() + 3;
+        let mut diff_height = 0;
+        if let Some(diff) = &self.diff {
+            width = area.width.saturating_sub(4).max(width);
+            diff_height = (diff.height() as u16).min(area.height.saturating_sub(height + 3));
+            height += diff_height + 1;
+        }
+        let [modal_area] = Layout::horizontal([Constraint::Length(width)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [modal_area] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(modal_area);
+
+        Clear.render(modal_area, buf);
+        let theme = theme();
+        let block = Block::bordered()
+            .title(self.title.as_str())
+            .border_style(theme.border)
+            .border_set(theme.symbols.border);
+        let inner = block.inner(modal_area).inner(Margin::new(1, 0));
+        block.render(modal_area, buf);
+
+        let [message_area, _, diff_area, content_area] = Layout::vertical([
+            Constraint::Length(message_height),
+            Constraint::Length(1),
+            Constraint::Length(diff_height + u16::from(diff_height > 0)),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        message.render(message_area, buf);
+        if let Some(diff) = &self.diff {
+            // The line below the diff separates it from the content
+            let diff_area = Rect {
+                height: diff_height,
+                ..diff_area
+            };
+            diff.render_ref(diff_area, buf);
+        }
+
+        match &self.kind {
+            ModalKind::Confirm { yes, buttons } => {
+                let [yes_area, _, no_area] = Layout::horizontal([
+                    Constraint::Length(label_width(YES_BUTTON) as u16),
+                    Constraint::Length(2),
+                    Constraint::Length(label_width(NO_BUTTON) as u16),
+                ])
+                .flex(Flex::Center)
+                .areas(content_area);
+                buttons.set([yes_area, no_area]);
+
+                let focused = theme.selection();
+                let (yes_style, no_style) = if *yes {
+                    (focused, Style::new())
+                } else {
+                    (Style::new(), focused)
+                };
+                Line::styled(i18n::text(YES_BUTTON), yes_style).render(yes_area, buf);
+                Line::styled(i18n::text(NO_BUTTON), no_style).render(no_area, buf);
+            }
+            ModalKind::Prompt { input } => {
+                input.render_ref(content_area, buf);
+            }
+            ModalKind::Select { list } => list.render_ref(content_area, buf),
+            ModalKind::Waiting => {
+                Line::from(i18n::text(STOP_WAITING_HINT))
+                    .dim()
+                    .centered()
+                    .render(content_area, buf);
+            }
+        }
+    }
+}
+
+/// The open modals, of which only the last one opened is shown
+#[derive(Default)]
+pub struct Modals {
+    stack: Vec<Modal>,
+}
+
+impl Modals {
+    pub fn push(&mut self, modal: Modal) {
+        self.stack.push(modal);
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    /// Handles an event with the top modal, replying to whoever opened it once it's
+    /// answered. Returns whether the event was handled, which it always is while a modal is open.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        let Some(modal) = self.stack.last_mut() else {
+            return false;
+        };
+
+        if let Some(result) = modal.handle_event(event) {
+            let modal = self.stack.pop().expect("Modal was just handled");
+            if let Some(reply) = modal.reply {
+                // Whoever opened the modal may have stopped waiting for the answer
+                let _ = reply.send(result);
+            }
+        }
+
+        true
+    }
+}
+
+impl Extend<Modal> for Modals {
+    fn extend<T: IntoIterator<Item = Modal>>(&mut self, modals: T) {
+        self.stack.extend(modals);
+    }
+}
+
+impl WidgetRef for Modals {
+    /// Dims everything behind the top modal
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(modal) = self.stack.last() {
+            buf.set_style(area, Style::new().dim());
+            modal.render_ref(area, buf);
+        }
+    }
+}
+
+static SENDER: OnceLock<Sender<Modal>> = OnceLock::new();
+
+/// Opens the channel that modals are sent to, so they can be opened from any thread
+pub fn open_channel() -> Receiver<Modal> {
+    let (sender, receiver) = mpsc::channel();
+    SENDER.set(sender).expect("Modal channel is already open");
+    receiver
+}
+
+/// Opens a modal and waits for the answer. Returns `None` if there's no UI to
+/// show it in, like when running non-interactively.
+///
+/// This blocks until the modal is answered, so it must only be called from worker threads.
+pub fn ask(modal: Modal) -> Option<ModalResult> {
+    let sender = SENDER.get()?;
+    if is_cancelled() {
+        return Some(ModalResult::Cancelled);
+    }
+    let (reply, receiver) = mpsc::channel();
+    sender.send(modal.with_reply(reply)).ok()?;
+
+    // The modal is dropped without an answer if the App exits first
+    Some(receiver.recv().unwrap_or(ModalResult::Cancelled))
+}
+
+/// The width of the translation of a label
+fn label_width(label: &'static str) -> usize {
+    i18n::text(label).chars().count()
+}
@@ -0,0 +1,168 @@
+use flint_macros::ui;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Widget};
+use std::path::Path;
+
+use crate::util::theme::theme;
+use flint_core::events::{Task, WorkerEvent};
+use flint_core::logs::LogKind;
+use flint_core::plugin::exec::traceback::LuaTrace;
+use flint_core::{get_flag, t};
+
+/// The lines of the script shown before and after the line an error happened on
+const CONTEXT_LINES: usize = 2;
+
+/// The most rows the panel takes, including the borders, so the logs stay visible
+const MAX_HEIGHT: u16 = 16;
+
+/// The Lua errors of the plugins of a task, with the lines of the scripts they
+/// happened on and their tracebacks, built from the worker events of the task. It's
+/// only shown while a plugin has one, and shows the latest.
+#[derive(Clone, Debug)]
+pub struct PluginErrors {
+    task: Task,
+    errors: Vec<PluginError>,
+}
+
+#[derive(Clone, Debug)]
+struct PluginError {
+    plugin: String,
+    trace: LuaTrace,
+    /// The lines around the line the error happened on, read once it's reported
+    source: Vec<(usize, String)>,
+}
+
+impl PluginErrors {
+    pub fn new(task: Task) -> Self {
+        Self {
+            task,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Updates the errors with an event, ignoring events of other tasks
+    pub fn handle_event(&mut self, event: &WorkerEvent) {
+        match event {
+            // A plugin that's run again may not fail again
+            WorkerEvent::PluginStarted { task, plugin, .. } if *task == self.task => {
+                self.errors.retain(|error| error.plugin != *plugin);
+            }
+            WorkerEvent::Diagnostic {
+                task,
+                plugin,
+                kind: LogKind::Error,
+                message,
+            } if *task == self.task => {
+                if let Some(trace) = LuaTrace::parse(message) {
+                    self.errors.push(PluginError {
+                        plugin: plugin.clone(),
+                        source: trace.location().source(CONTEXT_LINES),
+                        trace,
+                    });
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// The height needed to show the latest error, including the borders. It's 0 if
+    /// there's none, so the panel takes no space.
+    pub fn height(&self) -> u16 {
+        match self.errors.last() {
+            Some(error) => (lines(error).len() as u16 + 2).min(MAX_HEIGHT),
+            None => 0,
+        }
+    }
+
+    /// Renders the latest error at the bottom of the area, returning the rest of it
+    pub fn render_below(&self, area: Rect, buf: &mut Buffer) -> Rect {
+        let [rest, errors_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(self.height())]).areas(area);
+        if self.height() > 0 {
+            self.render(errors_area, buf);
+        }
+        rest
+    }
+}
+
+/// The message, the lines of the script around where the error happened with its line
+/// highlighted, and the traceback
+fn lines(error: &PluginError) -> Vec<Line<'static>> {
+    let theme = theme();
+    let trace = &error.trace;
+    let location = trace.location();
+    let mut lines = vec![Line::styled(
+        trace.message.lines().next().unwrap_or_default().to_string(),
+        Style::default().fg(theme.error),
+    )];
+
+    let number_width = error
+        .source
+        .last()
+        .map_or(0, |(number, _)| number.to_string().len());
+    for (number, code) in &error.source {
+        let is_location = *number == location.line;
+        let marker = if is_location { ">" } else { " " };
+        let gutter = Span::styled(
+            format!(
+                "{} {:>number_width$} {} ",
+                marker, number, theme.symbols.divider
+            ),
+            Style::default().fg(theme.border),
+        );
+        let code = match is_location {
+            true => Span::styled(
+                code.clone(),
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            false => Span::raw(code.clone()),
+        };
+        lines.push(Line::from(vec![gutter, code]));
+    }
+
+    lines.push(Line::raw(t!("Traceback:")));
+    for frame in &trace.frames {
+        let place = format!("  {}:{}", display_path(&frame.path), frame.line);
+        lines.push(match &frame.function {
+            Some(function) => Line::raw(t!(
+                "{place} in {function}",
+                place = place,
+                function = function
+            )),
+            None => Line::raw(place),
+        });
+    }
+    lines
+}
+
+/// The path of a script relative to the plugins directory, like "lint/eslint/run.lua"
+fn display_path(path: &Path) -> String {
+    path.strip_prefix(get_flag!(plugins_dir))
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+impl Widget for &PluginErrors {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let Some(error) = self.errors.last() else {
+            return;
+        };
+
+        ui!((area, buf) => {
+            Paragraph::new(
+                lines(error),
+                block: Block::bordered(
+                    title: t!("{plugin} failed", plugin = error.plugin),
+                    border_style: theme().error,
+                    border_set: theme().symbols.border
+                )
+            )
+        });
+    }
+}
@@ -0,0 +1,199 @@
+use crossterm::event::Event;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::{Widget, WidgetRef};
+use std::time::Duration;
+
+use super::table::{Column, ResultCell, ResultRow, ResultsTable};
+use crate::util::theme::theme;
+use flint_core::events::{Task, WorkerEvent};
+use flint_core::{get_flag, t};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PluginStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Clone, Debug)]
+pub struct PluginProgress {
+    pub plugin: String,
+    pub status: PluginStatus,
+    pub files_written: usize,
+    pub diagnostics: usize,
+    /// How long the plugin ran, once it finished
+    pub duration: Option<Duration>,
+}
+
+/// The progress of every plugin run by a task, built from the worker events of the task.
+/// It's shown as a table that can be sorted, like by how long the plugins took.
+#[derive(Clone, Debug)]
+pub struct TaskProgress {
+    task: Task,
+    plugins: Vec<PluginProgress>,
+    table: ResultsTable,
+    /// Picks the frame of the spinner shown for running plugins
+    tick: usize,
+}
+
+impl TaskProgress {
+    pub fn new(task: Task) -> Self {
+        let columns = vec![
+            // The status is only labeled for screen readers, which read its words
+            Column::new(match *get_flag!(accessible) {
+                true => t!("Status"),
+                false => "",
+            }),
+            Column::new(t!("Plugin")),
+            Column::new(t!("Files")).numeric(),
+            Column::new(t!("Diagnostics")).numeric(),
+            Column::new(t!("Duration")).numeric(),
+        ];
+        let mut progress = Self {
+            task,
+            plugins: Vec::new(),
+            // The scroll keys scroll the logs next to it
+            table: ResultsTable::new("", columns).without_selection(),
+            tick: 0,
+        };
+        progress.update_table();
+        progress
+    }
+
+    /// Updates the progress with an event, ignoring events of other tasks
+    pub fn handle_event(&mut self, event: &WorkerEvent) {
+        match event {
+            WorkerEvent::PluginStarted { task, plugin, .. } if *task == self.task => {
+                let progress = PluginProgress {
+                    plugin: plugin.clone(),
+                    status: PluginStatus::Running,
+                    files_written: 0,
+                    diagnostics: 0,
+                    duration: None,
+                };
+
+                // A plugin that's run again replaces its previous run
+                match self.get_mut(plugin) {
+                    Some(previous) => *previous = progress,
+                    None => self.plugins.push(progress),
+                }
+            }
+            WorkerEvent::FileWritten { task, plugin, .. } if *task == self.task => {
+                if let Some(progress) = self.get_mut(plugin) {
+                    progress.files_written += 1;
+                }
+            }
+            WorkerEvent::Diagnostic { task, plugin, .. } if *task == self.task => {
+                if let Some(progress) = self.get_mut(plugin) {
+                    progress.diagnostics += 1;
+                }
+            }
+            WorkerEvent::Finished {
+                task,
+                plugin,
+                success,
+                duration,
+            } if *task == self.task => {
+                if let Some(progress) = self.get_mut(plugin) {
+                    progress.status = if *success {
+                        PluginStatus::Succeeded
+                    } else {
+                        PluginStatus::Failed
+                    };
+                    progress.duration = Some(*duration);
+                }
+            }
+            _ => return,
+        }
+        self.update_table();
+    }
+
+    /// Sorts the table or moves its columns with a key or a click, returning whether
+    /// the event was used
+    pub fn handle_input(&mut self, event: &Event) -> bool {
+        self.table.handle_event(event)
+    }
+
+    /// Advances the spinner of the running plugins
+    pub fn handle_tick(&mut self, tick: usize) {
+        self.tick = tick;
+        if self
+            .plugins
+            .iter()
+            .any(|progress| progress.status == PluginStatus::Running)
+        {
+            self.update_table();
+        }
+    }
+
+    fn update_table(&mut self) {
+        let rows = self
+            .plugins
+            .iter()
+            .map(|progress| row(progress, self.tick))
+            .collect();
+        self.table.set_rows(rows);
+        self.table.set_title(self.title());
+    }
+
+    fn get_mut(&mut self, plugin: &str) -> Option<&mut PluginProgress> {
+        self.plugins
+            .iter_mut()
+            .find(|progress| progress.plugin == plugin)
+    }
+
+    pub fn plugins(&self) -> &[PluginProgress] {
+        &self.plugins
+    }
+
+    /// The height needed to show every plugin, including the borders
+    pub fn height(&self) -> u16 {
+        self.table.height()
+    }
+
+    /// The width needed to show every column and the title, including the borders
+    pub fn width(&self) -> u16 {
+        self.table.width()
+    }
+
+    fn title(&self) -> String {
+        let running = self
+            .plugins
+            .iter()
+            .filter(|progress| progress.status == PluginStatus::Running)
+            .count();
+        t!("Plugins ({count} running)", count = running)
+    }
+}
+
+fn row(progress: &PluginProgress, tick: usize) -> ResultRow {
+    let theme = theme();
+    let spinner = theme.symbols.spinner;
+    let (symbol, color) = match progress.status {
+        PluginStatus::Running => (spinner[tick % spinner.len()], theme.info),
+        PluginStatus::Succeeded => (theme.symbols.succeeded, theme.success),
+        PluginStatus::Failed => (theme.symbols.failed, theme.error),
+    };
+    let duration = match progress.duration {
+        Some(duration) => ResultCell::new(format!("{:.1}s", duration.as_secs_f64()))
+            .with_key(duration.as_secs_f64()),
+        None => ResultCell::default(),
+    };
+
+    ResultRow::new([
+        ResultCell::new(symbol),
+        ResultCell::new(progress.plugin.clone()),
+        ResultCell::number(progress.files_written),
+        ResultCell::number(progress.diagnostics),
+        duration,
+    ])
+    .style(Style::default().fg(color))
+}
+
+impl Widget for &TaskProgress {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.table.render_ref(area, buf);
+    }
+}
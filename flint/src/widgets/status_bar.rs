@@ -0,0 +1,139 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Widget;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::report::history::Trend;
+use crate::util::theme::theme;
+use flint_core::events::WorkerEvent;
+use flint_core::logs::LogKind;
+use flint_core::t;
+
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A single line summarizing the session: the active command, the project root,
+/// the plugins, whether any are still running, how many errors and warnings were logged
+/// and how the last run compares to the one before it
+#[derive(Clone, Debug, Default)]
+pub struct StatusBar {
+    pub command: String,
+    pub project_root: PathBuf,
+    /// The number of plugins configured in the project
+    pub plugins: usize,
+    running: usize,
+    errors: usize,
+    warnings: usize,
+    tick: usize,
+    last_tick: Option<Instant>,
+    last_run: Option<Trend>,
+}
+
+impl StatusBar {
+    pub fn new(project_root: PathBuf, plugins: usize) -> Self {
+        Self {
+            project_root,
+            plugins,
+            ..Self::default()
+        }
+    }
+
+    /// Counts running plugins and logged errors and warnings
+    pub fn handle_event(&mut self, event: &WorkerEvent) {
+        match event {
+            WorkerEvent::PluginStarted { .. } => self.running += 1,
+            WorkerEvent::Finished { .. } => self.running = self.running.saturating_sub(1),
+            WorkerEvent::Log(LogKind::Error, _) => self.errors += 1,
+            WorkerEvent::Log(LogKind::Warn, _) => self.warnings += 1,
+            _ => (),
+        }
+    }
+
+    /// Shows the figures of a run that finished, with their change since the previous run
+    pub fn set_last_run(&mut self, trend: Trend) {
+        self.last_run = Some(trend);
+    }
+
+    /// Advances the spinner while plugins are running. Returns true if the spinner
+    /// moved, which means the status bar needs to be drawn again.
+    pub fn tick(&mut self) -> bool {
+        if self.running == 0 {
+            self.last_tick = None;
+            return false;
+        }
+
+        let now = Instant::now();
+        if self
+            .last_tick
+            .is_some_and(|last_tick| now.duration_since(last_tick) < SPINNER_INTERVAL)
+        {
+            return false;
+        }
+
+        self.tick = self.tick.wrapping_add(1);
+        self.last_tick = Some(now);
+        true
+    }
+
+    /// The number of times the spinner advanced, which the spinners of the views follow
+    pub fn ticks(&self) -> usize {
+        self.tick
+    }
+}
+
+impl Widget for &StatusBar {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme();
+        let divider = Span::from(format!(" {} ", theme.symbols.divider));
+
+        let activity = if self.running > 0 {
+            let spinner = theme.symbols.spinner;
+            let frame = spinner[self.tick % spinner.len()];
+            Span::from(format!(
+                "{} {}",
+                frame,
+                t!("{count} running", count = self.running)
+            ))
+            .fg(theme.accent)
+        } else {
+            Span::from(t!("idle"))
+        };
+
+        let errors = Span::from(t!("{count} errors", count = self.errors));
+        let errors = if self.errors > 0 {
+            errors.fg(theme.error)
+        } else {
+            errors
+        };
+        let warnings = Span::from(t!("{count} warnings", count = self.warnings));
+        let warnings = if self.warnings > 0 {
+            warnings.fg(theme.warning)
+        } else {
+            warnings
+        };
+
+        let mut spans = vec![
+            Span::from(format!(" {}", self.command)).bold(),
+            divider.clone(),
+            Span::from(self.project_root.display().to_string()),
+            divider.clone(),
+            Span::from(t!("{count} plugins", count = self.plugins)),
+            divider.clone(),
+            activity,
+            divider.clone(),
+            errors,
+            Span::from(", "),
+            warnings,
+        ];
+        if let Some(trend) = &self.last_run {
+            spans.push(divider);
+            spans.push(Span::from(t!("last run: {trend}", trend = trend)));
+        }
+
+        Line::from(spans)
+            .style(theme.status_bar())
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,424 @@
+use crossterm::event::{Event, KeyCode, MouseButton, MouseEventKind};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Margin, Position, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{
+    Block, Cell as TableCell, Row, StatefulWidget, Table, TableState, WidgetRef,
+};
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+
+use crate::util::keys::{get_action, Action};
+use crate::util::theme::theme;
+
+/// Columns are never narrowed below this, so their title stays recognizable
+const MIN_COLUMN_WIDTH: u16 = 3;
+/// How much a column is widened or narrowed at a time
+const RESIZE_STEP: u16 = 2;
+const COLUMN_SPACING: u16 = 1;
+
+#[derive(Clone, Debug)]
+pub struct Column {
+    title: String,
+    /// Fits the title and the longest cell, until the column is resized
+    width: u16,
+    resized: bool,
+    /// Numbers are aligned right and sorted largest first
+    numeric: bool,
+}
+
+impl Column {
+    pub fn new(title: impl Into<String>) -> Self {
+        let title = title.into();
+        Self {
+            width: title_width(&title),
+            title,
+            resized: false,
+            numeric: false,
+        }
+    }
+
+    pub fn numeric(mut self) -> Self {
+        self.numeric = true;
+        self
+    }
+}
+
+/// A cell of a results table. It's sorted by its key if it has one, like the
+/// duration a formatted duration is shown for, and by its text otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct ResultCell {
+    text: String,
+    key: Option<f64>,
+    style: Style,
+}
+
+impl ResultCell {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// A count, sorted by its value rather than its text
+    pub fn number(value: usize) -> Self {
+        Self::new(value.to_string()).with_key(value as f64)
+    }
+
+    pub fn with_key(mut self, key: f64) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.key, other.key) {
+            (Some(key), Some(other)) => key.total_cmp(&other),
+            // Cells without a value, like the duration of a running plugin, come last
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self.text.to_lowercase().cmp(&other.text.to_lowercase()),
+        }
+    }
+}
+
+impl From<String> for ResultCell {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<&str> for ResultCell {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ResultRow {
+    cells: Vec<ResultCell>,
+    style: Style,
+}
+
+impl ResultRow {
+    pub fn new(cells: impl IntoIterator<Item = impl Into<ResultCell>>) -> Self {
+        Self {
+            cells: cells.into_iter().map(Into::into).collect(),
+            style: Style::new(),
+        }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+/// A table of results, like plugins or their runs. It's sorted by the focused column
+/// with the sort key, and the focused column is moved with the left and right arrow
+/// keys, scrolling the columns that don't fit into view. The focused column is
+/// widened and narrowed with the resize keys, and clicking a title sorts by it.
+#[derive(Clone, Debug)]
+pub struct ResultsTable {
+    title: String,
+    columns: Vec<Column>,
+    rows: Vec<ResultRow>,
+    /// The indices of the rows in the order they're shown
+    order: Vec<usize>,
+    /// The column the rows are sorted by, and whether they're in descending order
+    sort: Option<(usize, bool)>,
+    focused: usize,
+    /// The first column shown, as the columns before it are scrolled out of view
+    offset: usize,
+    /// Whether rows are selected with the scroll keys. Otherwise they're left to the view.
+    selectable: bool,
+    table_state: RefCell<TableState>,
+    /// Where the table was last rendered, to scroll the focused column into view and
+    /// to find what's clicked
+    area: Cell<Rect>,
+}
+
+impl ResultsTable {
+    pub fn new(title: impl Into<String>, columns: Vec<Column>) -> Self {
+        Self {
+            title: title.into(),
+            columns,
+            rows: Vec::new(),
+            order: Vec::new(),
+            sort: None,
+            focused: 0,
+            offset: 0,
+            selectable: true,
+            table_state: RefCell::new(TableState::default().with_selected(Some(0))),
+            area: Cell::new(Rect::default()),
+        }
+    }
+
+    /// Leaves the scroll keys to the view, for tables that are only looked at
+    pub fn without_selection(mut self) -> Self {
+        self.selectable = false;
+        *self.table_state.get_mut() = TableState::default();
+        self
+    }
+
+    /// Sorts the rows by a column from the start, in its default order
+    pub fn sorted_by(mut self, column: usize) -> Self {
+        self.sort = Some((column, self.columns[column].numeric));
+        self.focused = column;
+        self
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    /// Replaces the rows, keeping their sorting and the selected row
+    pub fn set_rows(&mut self, rows: Vec<ResultRow>) {
+        for (index, column) in self.columns.iter_mut().enumerate() {
+            if column.resized {
+                continue;
+            }
+            let cells = rows
+                .iter()
+                .filter_map(|row| row.cells.get(index))
+                .map(|cell| Line::from(cell.text.as_str()).width() as u16);
+            column.width = cells.fold(title_width(&column.title), u16::max);
+        }
+
+        self.rows = rows;
+        self.sort_rows();
+    }
+
+    /// The index of the selected row in the rows it was given
+    pub fn selected(&self) -> Option<usize> {
+        let selected = self.table_state.borrow().selected()?;
+        self.order.get(selected).copied()
+    }
+
+    /// The width needed to show every column, including the borders
+    pub fn width(&self) -> u16 {
+        let columns = self.columns.iter().map(|column| column.width).sum::<u16>();
+        let spacing = COLUMN_SPACING * self.columns.len().saturating_sub(1) as u16;
+        let title = Line::from(self.title.as_str()).width() as u16;
+        (columns + spacing).max(title) + 2
+    }
+
+    /// The height needed to show every row, including the titles and the borders
+    pub fn height(&self) -> u16 {
+        self.rows.len() as u16 + 3
+    }
+
+    /// Handles an event, returning whether the table used it
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key(key_event) => match (get_action(key_event), key_event.code) {
+                (Some(Action::ScrollUp), _) if self.selectable => {
+                    self.table_state.get_mut().select_previous()
+                }
+                (Some(Action::ScrollDown), _) if self.selectable => {
+                    self.table_state.get_mut().select_next()
+                }
+                (Some(Action::SortColumn), _) => self.sort_by(self.focused),
+                (Some(Action::WidenColumn), _) => self.resize_focused(RESIZE_STEP as i16),
+                (Some(Action::NarrowColumn), _) => self.resize_focused(-(RESIZE_STEP as i16)),
+                (_, KeyCode::Left) => self.focus(self.focused.saturating_sub(1)),
+                (_, KeyCode::Right) => self.focus(self.focused + 1),
+                _ => return false,
+            },
+            Event::Mouse(mouse_event) => {
+                let position = Position::new(mouse_event.column, mouse_event.row);
+                match mouse_event.kind {
+                    MouseEventKind::ScrollUp if self.selectable => {
+                        self.table_state.get_mut().select_previous()
+                    }
+                    MouseEventKind::ScrollDown if self.selectable => {
+                        self.table_state.get_mut().select_next()
+                    }
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        let inner = self.area.get().inner(Margin::new(1, 1));
+                        if !inner.contains(position) {
+                            return false;
+                        }
+
+                        if position.y == inner.y {
+                            let Some(column) = self.column_at(position.x - inner.x) else {
+                                return false;
+                            };
+                            self.focus(column);
+                            self.sort_by(column);
+                        } else if self.selectable {
+                            let row = self.table_state.get_mut().offset()
+                                + (position.y - inner.y - 1) as usize;
+                            if row >= self.rows.len() {
+                                return false;
+                            }
+                            self.table_state.get_mut().select(Some(row));
+                        } else {
+                            return false;
+                        }
+                    }
+                    _ => return false,
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Sorts by a column, reversing the order if it's already sorted by it
+    fn sort_by(&mut self, column: usize) {
+        let descending = match self.sort {
+            Some((sorted, descending)) if sorted == column => !descending,
+            _ => self.columns[column].numeric,
+        };
+        self.sort = Some((column, descending));
+        self.sort_rows();
+    }
+
+    fn sort_rows(&mut self) {
+        let selected = self.selected();
+
+        self.order = (0..self.rows.len()).collect();
+        if let Some((column, descending)) = self.sort {
+            let empty = ResultCell::default();
+            let cell = |row: usize| self.rows[row].cells.get(column).unwrap_or(&empty);
+            // Stable, so equal rows keep the order they were given in
+            self.order.sort_by(|a, b| match descending {
+                true => cell(*b).cmp(cell(*a)),
+                false => cell(*a).cmp(cell(*b)),
+            });
+        }
+
+        // The same row stays selected after sorting
+        if let Some(selected) = selected {
+            let index = self.order.iter().position(|row| *row == selected);
+            self.table_state.get_mut().select(index);
+        }
+    }
+
+    fn resize_focused(&mut self, change: i16) {
+        if let Some(column) = self.columns.get_mut(self.focused) {
+            column.width = column
+                .width
+                .saturating_add_signed(change)
+                .max(MIN_COLUMN_WIDTH);
+            column.resized = true;
+        }
+        self.scroll_to_focused();
+    }
+
+    fn focus(&mut self, column: usize) {
+        self.focused = column.min(self.columns.len().saturating_sub(1));
+        self.scroll_to_focused();
+    }
+
+    /// Scrolls the columns so the focused one is shown, if the table was rendered
+    fn scroll_to_focused(&mut self) {
+        let width = self.area.get().width.saturating_sub(2);
+        self.offset = self.offset.min(self.focused);
+        while self.offset < self.focused && self.columns_width(self.offset..=self.focused) > width {
+            self.offset += 1;
+        }
+    }
+
+    fn columns_width(&self, columns: impl Iterator<Item = usize>) -> u16 {
+        columns
+            .map(|column| self.columns[column].width + COLUMN_SPACING)
+            .sum::<u16>()
+            .saturating_sub(COLUMN_SPACING)
+    }
+
+    /// Returns the index of the column shown at a column of the table, if any
+    fn column_at(&self, x: u16) -> Option<usize> {
+        let mut start = 0;
+        for column in self.offset..self.columns.len() {
+            let end = start + self.columns[column].width;
+            if x < end {
+                return Some(column);
+            }
+            start = end + COLUMN_SPACING;
+        }
+        None
+    }
+
+    fn header(&self) -> Row<'_> {
+        let theme = theme();
+        let cells = self.columns[self.offset..]
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let index = self.offset + index;
+                let title = match self.sort {
+                    Some((sorted, false)) if sorted == index => {
+                        format!("{} {}", column.title, theme.symbols.sort_ascending)
+                    }
+                    Some((sorted, true)) if sorted == index => {
+                        format!("{} {}", column.title, theme.symbols.sort_descending)
+                    }
+                    _ => column.title.clone(),
+                };
+                let style = match index == self.focused {
+                    true => Style::new().fg(theme.accent).underlined(),
+                    false => Style::new(),
+                };
+                TableCell::from(aligned(title, column)).style(style)
+            });
+        Row::new(cells).bold()
+    }
+}
+
+/// The width of a title, with room for the sort symbol after it
+fn title_width(title: &str) -> u16 {
+    Line::from(title).width() as u16 + 2
+}
+
+fn aligned(text: String, column: &Column) -> Line<'static> {
+    match column.numeric {
+        true => Line::from(text).right_aligned(),
+        false => Line::from(text),
+    }
+}
+
+impl WidgetRef for ResultsTable {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        self.area.set(area);
+        let theme = theme();
+        let columns = &self.columns[self.offset..];
+
+        let rows = self.order.iter().map(|index| {
+            let row = &self.rows[*index];
+            let cells = columns.iter().enumerate().map(|(column_index, column)| {
+                let cell = row
+                    .cells
+                    .get(self.offset + column_index)
+                    .cloned()
+                    .unwrap_or_default();
+                TableCell::from(aligned(cell.text, column)).style(cell.style)
+            });
+            Row::new(cells).style(row.style)
+        });
+        let widths = columns
+            .iter()
+            .map(|column| Constraint::Length(column.width));
+
+        let mut table = Table::new(rows, widths)
+            .header(self.header())
+            .column_spacing(COLUMN_SPACING)
+            .block(
+                Block::bordered()
+                    .title(self.title.as_str())
+                    .border_style(theme.border)
+                    .border_set(theme.symbols.border),
+            );
+        if self.selectable {
+            table = table.row_highlight_style(theme.selection());
+        }
+        StatefulWidget::render(table, area, buf, &mut self.table_state.borrow_mut());
+    }
+}
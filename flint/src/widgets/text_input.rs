@@ -0,0 +1,137 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Widget, WidgetRef};
+use std::fmt;
+use tui_textarea::{CursorMove, TextArea};
+
+use crate::util::theme::theme;
+
+/// Checks a typed value, returning why it's rejected
+type Validator = Box<dyn Fn(&str) -> Result<(), String> + Send>;
+
+/// A single line of text typed in, with the editing keys of a shell: the arrow keys,
+/// home and end move the cursor, and ctrl+w, ctrl+u and ctrl+k delete words and
+/// the text around it.
+///
+/// A validator can reject what's submitted, in which case its message is shown
+/// below the input until the text is fixed.
+#[derive(Default)]
+pub struct TextInput {
+    textarea: TextArea<'static>,
+    validator: Option<Validator>,
+    error: Option<String>,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts with a value typed in, with the cursor at its end
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    /// Shows a hint while nothing is typed in
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.textarea.set_placeholder_text(placeholder);
+        self.textarea
+            .set_placeholder_style(Style::new().fg(theme().debug));
+        self
+    }
+
+    pub fn with_validator(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), String> + Send + 'static,
+    ) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    pub fn value(&self) -> String {
+        self.textarea.lines().concat()
+    }
+
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.textarea = TextArea::new(vec![value.into()]);
+        self.textarea.move_cursor(CursorMove::End);
+        self.error = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_value("");
+    }
+
+    /// Why the submitted value was rejected, while it's not fixed
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// The height it's rendered with, which has a line for the error if there is one
+    pub fn height(&self) -> u16 {
+        1 + u16::from(self.error.is_some())
+    }
+
+    /// Edits the value with a key, returning whether it changed. Enter and tab are
+    /// left to whoever shows the input, since there's only one line.
+    pub fn input(&mut self, key_event: KeyEvent) -> bool {
+        let is_newline = matches!(key_event.code, KeyCode::Enter | KeyCode::Tab)
+            || key_event.modifiers.contains(KeyModifiers::CONTROL)
+                && matches!(key_event.code, KeyCode::Char('m' | 'j'));
+        if is_newline || !self.textarea.input(key_event) {
+            return false;
+        }
+
+        // An error goes away once the value is valid again
+        if self.error.is_some() {
+            self.error = self.check().err();
+        }
+        true
+    }
+
+    /// Returns the value if it's valid, or shows why it's not
+    pub fn submit(&mut self) -> Option<String> {
+        self.error = self.check().err();
+        match self.error {
+            Some(_) => None,
+            None => Some(self.value()),
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        match &self.validator {
+            Some(validator) => validator(&self.value()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for TextInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextInput")
+            .field("value", &self.value())
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WidgetRef for TextInput {
+    /// Renders the value on the first line, and the error on the second if it fits
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let input_area = Rect { height: 1, ..area };
+        self.textarea.render(input_area, buf);
+
+        if let (Some(error), true) = (&self.error, area.height > 1) {
+            let error_area = Rect {
+                y: area.y + 1,
+                height: 1,
+                ..area
+            };
+            Line::styled(error.as_str(), Style::new().fg(theme().error)).render(error_area, buf);
+        }
+    }
+}
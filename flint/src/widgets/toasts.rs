@@ -0,0 +1,113 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Flex, Layout, Margin, Rect};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, Paragraph, Widget};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::util::theme::theme;
+use flint_core::events::{Task, WorkerEvent};
+use flint_core::logs::LogKind;
+use flint_core::t;
+
+/// How long a toast is shown
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+/// The most toasts shown at once. Older ones are dismissed early to make room.
+const MAX_TOASTS: usize = 3;
+
+#[derive(Clone, Debug)]
+struct Toast {
+    kind: LogKind,
+    message: String,
+    shown_at: Instant,
+}
+
+/// Short notifications of what the plugins did, like the configs they generated or
+/// that they failed, shown in the bottom right corner for a few seconds. They're
+/// built from the worker events, so they don't need the logs to be scanned.
+#[derive(Clone, Debug, Default)]
+pub struct Toasts {
+    /// The newest toast last
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn handle_event(&mut self, event: &WorkerEvent) {
+        match event {
+            WorkerEvent::FileWritten { task, plugin, path } => {
+                let file = path
+                    .file_name()
+                    .unwrap_or(path.as_os_str())
+                    .to_string_lossy();
+                let message = match task {
+                    Task::Generate => t!(
+                        "Generated {file} with {plugin}",
+                        file = file,
+                        plugin = plugin
+                    ),
+                    Task::Test => t!("Wrote {file} for {plugin}", file = file, plugin = plugin),
+                };
+                self.push(LogKind::Success, message);
+            }
+            WorkerEvent::Finished {
+                plugin,
+                success: false,
+                ..
+            } => self.push(LogKind::Error, t!("{plugin} failed", plugin = plugin)),
+            _ => (),
+        }
+    }
+
+    pub fn push(&mut self, kind: LogKind, message: impl Into<String>) {
+        self.toasts.push_back(Toast {
+            kind,
+            message: message.into(),
+            shown_at: Instant::now(),
+        });
+        while self.toasts.len() > MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+    }
+
+    /// Dismisses the toasts that were shown long enough. Returns true if any was
+    /// dismissed, which means they need to be drawn again.
+    pub fn tick(&mut self) -> bool {
+        let count = self.toasts.len();
+        self.toasts
+            .retain(|toast| toast.shown_at.elapsed() < TOAST_DURATION);
+        self.toasts.len() != count
+    }
+}
+
+impl Widget for &Toasts {
+    /// Stacks the toasts up from the bottom right corner of the area, the newest
+    /// at the bottom. They're kept off the edges, so the borders of the panes below
+    /// them stay visible.
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme();
+        let area = area.inner(Margin::new(1, 1));
+        let mut bottom = area.bottom();
+
+        for toast in self.toasts.iter().rev() {
+            let width = (Line::from(toast.message.as_str()).width() as u16 + 4).min(area.width);
+            if bottom < area.y + 3 {
+                break;
+            }
+
+            let [toast_area] = Layout::horizontal([Constraint::Length(width)])
+                .flex(Flex::End)
+                .areas(Rect::new(area.x, bottom - 3, area.width, 3));
+            bottom -= 3;
+
+            Clear.render(toast_area, buf);
+            Paragraph::new(toast.message.as_str())
+                .block(
+                    Block::bordered()
+                        .border_style(theme.log(toast.kind))
+                        .border_set(theme.symbols.border),
+                )
+                .centered()
+                .render(toast_area, buf);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use flint_macros::ui;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Widget};
+
+use crate::util::theme::theme;
+use flint_core::events::{Task, WorkerEvent};
+use flint_core::plugin::tools::ToolProblem;
+use flint_core::t;
+
+/// The tools the plugins of a task can't run with and how to install them, built from
+/// the worker events of the task. It's only shown while there are any.
+#[derive(Clone, Debug)]
+pub struct ToolProblems {
+    task: Task,
+    /// The problems by the plugin that reported them
+    problems: Vec<(String, ToolProblem)>,
+}
+
+impl ToolProblems {
+    pub fn new(task: Task) -> Self {
+        Self {
+            task,
+            problems: Vec::new(),
+        }
+    }
+
+    /// Updates the problems with an event, ignoring events of other tasks
+    pub fn handle_event(&mut self, event: &WorkerEvent) {
+        match event {
+            // A plugin that's run again checks its tools again
+            WorkerEvent::PluginStarted { task, plugin, .. } if *task == self.task => {
+                self.problems.retain(|(reporter, _)| reporter != plugin);
+            }
+            WorkerEvent::ToolProblem {
+                task,
+                plugin,
+                problem,
+            } if *task == self.task => self.problems.push((plugin.clone(), problem.clone())),
+            _ => (),
+        }
+    }
+
+    /// The problems of every tool, once even if several plugins run it
+    fn unique(&self) -> Vec<&ToolProblem> {
+        let mut problems: Vec<&ToolProblem> = Vec::new();
+        for (_, problem) in &self.problems {
+            if !problems.iter().any(|known| known.tool == problem.tool) {
+                problems.push(problem);
+            }
+        }
+        problems
+    }
+
+    /// The height needed to show every problem, including the borders. It's 0 if
+    /// there are none, so the panel takes no space.
+    pub fn height(&self) -> u16 {
+        match self.unique().len() {
+            0 => 0,
+            problems => problems as u16 + 2,
+        }
+    }
+
+    /// Renders the problems at the top of the area, returning the rest of it
+    pub fn render_above(&self, area: Rect, buf: &mut Buffer) -> Rect {
+        let [problems_area, rest] =
+            Layout::vertical([Constraint::Length(self.height()), Constraint::Fill(1)]).areas(area);
+        if self.height() > 0 {
+            self.render(problems_area, buf);
+        }
+        rest
+    }
+}
+
+fn line(problem: &ToolProblem) -> Line<'static> {
+    let theme = theme();
+    let color = if problem.is_missing {
+        theme.error
+    } else {
+        theme.warning
+    };
+
+    let mut spans = vec![Span::styled(
+        problem.message.clone(),
+        Style::default().fg(color),
+    )];
+    match &problem.fix {
+        Some(fix) => {
+            spans.push(Span::raw(", install it with "));
+            spans.push(Span::styled(
+                fix.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+        }
+        None => spans.push(Span::raw(", install it and run flint again")),
+    }
+    Line::from(spans)
+}
+
+impl Widget for &ToolProblems {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let problems = self.unique();
+        let lines = problems
+            .iter()
+            .map(|problem| line(problem))
+            .collect::<Vec<_>>();
+
+        ui!((area, buf) => {
+            Paragraph::new(
+                lines,
+                block: Block::bordered(
+                    title: t!("Missing tools ({count})", count = problems.len()),
+                    border_style: theme().warning,
+                    border_set: theme().symbols.border
+                )
+            )
+        });
+    }
+}